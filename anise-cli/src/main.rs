@@ -208,7 +208,7 @@ where
 
     info!("CRC32 checksum: 0x{:X}", fmt.crc32());
     if let Some(comments) = fmt.comments().context(CliDAFSnafu)? {
-        println!("== COMMENTS ==\n{comments}== END ==");
+        println!("== COMMENTS ==\n{comments}\n== END ==");
     } else {
         println!("(File has no comments)");
     }
@@ -76,6 +76,10 @@ class Almanac:
     def __init__(self, path: str) -> Almanac:
         """An Almanac contains all of the loaded SPICE and ANISE data. It is the context for all computations."""
 
+    def add_topocentric_frame(self, name: str, parent_frame: Frame, latitude_deg: float, longitude_deg: float, height_km: float) -> Frame:
+        """Adds a topocentric (South East Zenith) frame to this Almanac, centered at the provided geodetic latitude and longitude in degrees
+and height in km with respect to the ellipsoid of the parent body fixed frame, and returns that new frame."""
+
     def azimuth_elevation_range_sez(self, rx: Orbit, tx: Orbit, obstructing_body: Frame=None, ab_corr: Aberration=None) -> AzElRange:
         """Computes the azimuth (in degrees), elevation (in degrees), and range (in kilometers) of the
 receiver state (`rx`) seen from the transmitter state (`tx`), once converted into the SEZ frame of the transmitter.
@@ -131,9 +135,26 @@ This function performs a memory allocation."""
         """Pretty prints the description of this Almanac, showing everything by default. Default time scale is TDB.
 If any parameter is set to true, then nothing other than that will be printed."""
 
+    def export_subset(self, bodies: typing.List, start: Epoch, end: Epoch, out: str) -> None:
+        """Writes a new SPK to the provided path with only the loaded segments of the provided target IDs, clipped to the window from
+the start to the end epochs. The Chebyshev records within the window are copied as is, and those at its boundaries are refit."""
+
+    def frame_from_name(self, name: str) -> Frame:
+        """Returns the frame with the provided name, with its planetary data if it is loaded. The name is case insensitive, and spaces,
+dashes, and underscores are equivalent. The fixed frames, the loaded Euler parameter and planetary data, the NAIF body names
+(including those of the loaded text kernels), and the built-in names are searched in that order. If no frame has this name, the
+error includes the closest known name."""
+
     def frame_info(self, uid: Frame) -> Frame:
         """Returns the frame information (gravitational param, shape) as defined in this Almanac from an empty frame"""
 
+    def light_time(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, direction: LightTimeDirection) -> typing.Tuple:
+        """Returns the converged one-way light time between the target and the observer at the provided epoch, and the geometric state of
+the target at the light time corrected epoch with respect to the observer at the provided epoch.
+
+In reception, the target emits the signal at the epoch of the returned state, one light time before the observer receives it.
+In transmission, the observer emits the signal at the provided epoch, and the target receives it at the epoch of the returned state."""
+
     def line_of_sight_obstructed(self, observer: Orbit, observed: Orbit, obstructing_body: Frame, ab_corr: Aberration=None) -> bool:
         """Computes whether the line of sight between an observer and an observed Cartesian state is obstructed by the obstructing body.
 Returns true if the obstructing body is in the way, false otherwise.
@@ -171,6 +192,9 @@ Algorithm (source: Algorithm 35 of Vallado, 4th edition, page 308.):
         """Load from the provided MetaFile, downloading it if necessary.
 Set autodelete to true to automatically delete lock files. Lock files are important in multi-threaded loads."""
 
+    def load_mmap(self, path: str) -> Almanac:
+        """Memory maps the provided file and loads it, without copying the SPK and BPC files onto the heap. The file must not be modified while loaded."""
+
     def occultation(self, back_frame: Frame, front_frame: Frame, observer: Orbit, ab_corr: Aberration=None) -> Occultation:
         """Computes the occultation percentage of the `back_frame` object by the `front_frame` object as seen from the observer, when according for the provided aberration correction.
 
@@ -179,6 +203,17 @@ A 100%  percent occultation means that the back object is fully hidden from the
 A value in between means that the back object is partially hidden from the observser (i.e. _penumbra_ if the back object is the Sun).
 Refer to the [MathSpec](https://nyxspace.com/nyxspace/MathSpec/celestial/eclipse/) for modeling details."""
 
+    def occultation_state(self, back_frame: Frame, front_frame: Frame, observer_frame: Frame, epoch: Epoch, ab_corr: Aberration=None) -> OccultationState:
+        """Classifies the occultation of the `back_frame` object by the `front_frame` object as seen from the observer frame at the provided
+epoch, analogous to SPICE's `occult` with ellipsoid shapes.
+
+For a solar eclipse, the back frame is the Sun. For a transit, the front frame is the transiting body.
+The aberration correction should include light time corrections, and is also used to orient each ellipsoid."""
+
+    def one_way_light_time(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, ab_corr: Aberration) -> typing.Tuple:
+        """Returns the one-way light time between the target and the observer at the provided epoch given the aberration correction, along
+with the rate of change of that light time (unitless, i.e. in seconds per second)."""
+
     def rotate(self, from_frame: Frame, to_frame: Frame, epoch: Epoch) -> DCM:
         """Returns the 6x6 DCM needed to rotation the `from_frame` to the `to_frame`.
 
@@ -231,6 +266,12 @@ This function performs a memory allocation."""
 # Note
 The units will be those of the underlying ephemeris data (typically km and km/s)"""
 
+    def sub_observer_point(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, method: SubPointMethod, ab_corr: Aberration=None) -> typing.Tuple:
+        """Returns the geodetic latitude (φ) and longitude (λ) in degrees of the sub-observer point on the tri-axial ellipsoid of the target,
+and the altitude of the observer above that point in km (i.e. the distance between them).
+
+The target frame must be body fixed (e.g. IAU_EARTH_FRAME). Use the Sun as the observer to compute the sub-solar point."""
+
     def sun_angle_deg(self, target_id: int, observer_id: int, epoch: Epoch) -> float:
         """Returns the angle (between 0 and 180 degrees) between the observer and the Sun, and the observer and the target body ID.
 This computes the Sun Probe Earth angle (SPE) if the probe is in a loaded SPK, its ID is the "observer_id", and the target is set to its central body.
@@ -286,6 +327,9 @@ will return exactly the same data as the spkerz SPICE call.
 # Note
 The units will be those of the underlying ephemeris data (typically km and km/s)"""
 
+    def transform_by_name(self, target_name: str, observer_name: str, epoch: Epoch, ab_corr: Aberration=None) -> Orbit:
+        """Returns the Cartesian state needed to transform the target to the observer, both provided by their names, exactly as `transform`."""
+
     def transform_many(self, target_frame: Orbit, observer_frame: Frame, time_series: TimeSeries, ab_corr: Aberration=None) -> List[Orbit]:
         """Returns a chronologically sorted list of the Cartesian states that transform the `from_frame` to the `to_frame` for each epoch of the time series, computed in parallel under the hood.
 Note: if any transformation fails, the error will be printed to the stderr.
@@ -299,6 +343,20 @@ Note: the input ordering is lost: the output states will not be in the same orde
 
 Refer to [transform_to] for details."""
 
+    def transform_state(self, state: Orbit, to_frame: Frame, epoch: Epoch) -> Orbit:
+        """Re-expresses the provided state, valid at the provided epoch, in the `to_frame`: both its center and its orientation are changed.
+
+The velocity includes the transport term of the rotation of the frames, i.e. the ω×r term of a rotating body-fixed frame, so
+transforming a state from an inertial frame into a body-fixed frame and back recovers the original velocity. The state is re-centered
+with the geometric translation between the centers of both frames, without any aberration correction (use [transform_to] for that).
+
+The `epoch` must be the epoch of the state: it is checked so that a state at an unintended epoch is rejected instead of being
+transformed at its own epoch.
+
+# Errors
++ The epoch of the state must be the provided epoch;
++ The translation and the rotation between both frames must be available at that epoch."""
+
     def transform_to(self, state: Orbit, observer_frame: Frame, ab_corr: Aberration=None) -> Orbit:
         """Returns the provided state as seen from the observer frame, given the aberration."""
 
@@ -319,9 +377,19 @@ This function only performs the translation and no rotation whatsoever. Use the
 # Note
 This function performs a recursion of no more than twice the [MAX_TREE_DEPTH]."""
 
+    def translate_by_name(self, target_name: str, observer_name: str, epoch: Epoch, ab_corr: Aberration=None) -> Orbit:
+        """Returns the Cartesian state of the target as seen from the observer, both provided by their names, exactly as `translate`."""
+
     def translate_geometric(self, target_frame: Orbit, observer_frame: Frame, epoch: Epoch) -> Orbit:
         """Returns the geometric position vector, velocity vector, and acceleration vector needed to translate the `from_frame` to the `to_frame`, where the distance is in km, the velocity in km/s, and the acceleration in km/s^2."""
 
+    def translate_many(self, target_frame: Frame, observer_frame: Frame, epochs: typing.List, ab_corr: Aberration=None) -> typing.List:
+        """Returns the Cartesian states of the target frame as seen from the observer frame at each of the provided epochs, in the same
+order, and optionally given the aberration correction. Each state is the same as the one returned by `translate`.
+
+Without aberration correction, the segment used for each translation is reused for the following epochs where the segment
+lookup would return that same segment. If any translation fails, the error reports the index and epoch of the first failure."""
+
     def translate_to(self, state: Orbit, observer_frame: Frame, ab_corr: Aberration=None) -> Orbit:
         """Translates the provided Cartesian state into the requested observer frame
 
@@ -330,12 +398,45 @@ This function performs a recursion of no more than twice the [MAX_TREE_DEPTH].""
     def translate_to_parent(self, source: Frame, epoch: Epoch) -> Orbit:
         """Performs the GEOMETRIC translation to the parent. Use translate_from_to for aberration."""
 
+    def unload(self, source: str) -> Almanac:
+        """Unloads every SPK, BPC, and ANISE data set that was loaded from the provided path, or every SPK and BPC whose internal file name is the provided source."""
+
     def __repr__(self) -> str:
         """Return repr(self)."""
 
     def __str__(self) -> str:
         """Return str(self)."""
 
+@typing.final
+class LightTimeDirection:
+    """Direction of the signal between the observer and the target, used by the light time computations."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    Reception: LightTimeDirection = ...
+    Transmission: LightTimeDirection = ...
+
 @typing.final
 class MetaAlmanac:
     """A structure to set up an Almanac, with automatic downloading, local storage, checksum checking, and more.
@@ -458,6 +559,36 @@ This function modified `self` and changes the URI to be the path to the download
     def __str__(self) -> str:
         """Return str(self)."""
 
+@typing.final
+class SubPointMethod:
+    """Method used to locate the point on the surface of the target below the observer, analogous to the methods of SPICE's `subpnt`."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    Intercept: SubPointMethod = ...
+    NearPoint: SubPointMethod = ...
+
 @typing.final
 class astro:
     @typing.final
@@ -687,6 +818,38 @@ class astro:
         def __str__(self) -> str:
             """Return str(self)."""
 
+    @typing.final
+    class OccultationState:
+        """Classifies how the back object is hidden by the front object as seen from an observer, analogous to the occultation types of SPICE's `occult`."""
+
+        def __eq__(self, value: typing.Any) -> bool:
+            """Return self==value."""
+
+        def __ge__(self, value: typing.Any) -> bool:
+            """Return self>=value."""
+
+        def __gt__(self, value: typing.Any) -> bool:
+            """Return self>value."""
+
+        def __int__(self) -> None:
+            """int(self)"""
+
+        def __le__(self, value: typing.Any) -> bool:
+            """Return self<=value."""
+
+        def __lt__(self, value: typing.Any) -> bool:
+            """Return self<value."""
+
+        def __ne__(self, value: typing.Any) -> bool:
+            """Return self!=value."""
+
+        def __repr__(self) -> str:
+            """Return repr(self)."""
+        Annular: OccultationState = ...
+        NoOccultation: OccultationState = ...
+        Partial: OccultationState = ...
+        Total: OccultationState = ...
+
     @typing.final
     class Orbit:
         """Defines a Cartesian state in a given frame at a given epoch in a given time scale. Radius data is expressed in kilometers. Velocity data is expressed in kilometers per second.
@@ -989,6 +1152,12 @@ class astro:
     NOTE: Mean Brouwer Short are only defined around Earth. However, `nyx` does *not* check the
     main celestial body around which the state is defined (GMAT does perform this verification)."""
 
+        def is_circular(self) -> bool:
+            """Returns true if the eccentricity of this orbit is below [ECC_EPSILON]."""
+
+        def is_equatorial(self) -> bool:
+            """Returns true if the sine of the inclination of this orbit is below [EQUATORIAL_EPSILON], i.e. prograde or retrograde equatorial."""
+
         def latitude_deg(self) -> float:
             """Returns the geodetic latitude (φ) in degrees. Value is between -180 and +180 degrees.
 
@@ -1019,7 +1188,17 @@ class astro:
         def ma_deg(self) -> float:
             """Returns the mean anomaly in degrees
 
-    This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly"""
+    This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly.
+    NOTE: This returns an error for circular orbits, and the hyperbolic anomaly (not the hyperbolic mean anomaly) for hyperbolic
+    orbits: use [Self::mean_anomaly_deg] for a mean anomaly defined on all orbits."""
+
+        def mean_anomaly_deg(self) -> float:
+            """Returns the mean anomaly in degrees, which is defined for all orbits unlike [Self::ma_deg].
+
+    + Elliptical orbits: the mean anomaly between 0 and 360 degrees, as [Self::ma_deg].
+    + Circular orbits: the periapsis is undefined and the motion is uniform, so the mean anomaly is the true anomaly, i.e. the argument
+    of latitude, or the true longitude if the orbit is also equatorial (cf. [Self::ta_deg]).
+    + Hyperbolic orbits: the hyperbolic mean anomaly `e sinh(H) - H`, where H is the hyperbolic anomaly, which is not bounded."""
 
         def periapsis_altitude_km(self) -> float:
             """Returns the altitude of periapsis (or perigee around Earth), in kilometers."""
@@ -1028,7 +1207,31 @@ class astro:
             """Returns the radius of periapsis (or perigee around Earth), in kilometers."""
 
         def period(self) -> Duration:
-            """Returns the period in seconds"""
+            """Returns the period in seconds, or an error if the orbit is not elliptical (hyperbolic orbits have no period)."""
+
+        def propagate_j2(self, dt: Duration, j2: float, req_km: float) -> Orbit:
+            """Propagates this elliptical orbit by the provided duration with the secular rates of the mean elements due to the provided J2
+    zonal harmonic of the center of the frame, whose equatorial radius is `req_km`, returning the new state in the same frame.
+
+    The semi-major axis, eccentricity, and inclination are constant, while the right ascension of the ascending node, the argument of
+    periapsis, and the mean anomaly drift at the first order secular rates:
+    + RAAN: -3/2 n J2 (Req/p)^2 cos(i)
+    + AOP: 3/4 n J2 (Req/p)^2 (5 cos^2(i) - 1)
+    + MA: n (1 + 3/4 J2 (Req/p)^2 sqrt(1 - e^2) (3 cos^2(i) - 1))
+
+    # Astrodynamics note
+    The osculating elements of this state are used as mean elements, so the short periodic effects of J2 are not modeled.
+    Use Nyx for high fidelity propagation."""
+
+        def propagate_two_body(self, dt: Duration) -> Orbit:
+            """Propagates this state by the provided duration assuming two-body motion about the center of its frame, returning the new state in
+    the same frame.
+
+    This uses the universal variable formulation, solved with the Laguerre-Conway iteration, so elliptical, near-parabolic, and hyperbolic
+    trajectories are all supported, and the duration may be negative to propagate backward.
+
+    # Astrodynamics note
+    No other force than the point mass gravity of the center of the frame is modeled. Use Nyx for high fidelity propagation."""
 
         def raan_deg(self) -> float:
             """Returns the right ascension of the ascending node in degrees"""
@@ -2398,6 +2601,25 @@ class rotation:
             """Defines a direction cosine matrix from one frame ID to another frame ID, optionally with its time derivative.
     It provides a number of run-time checks that prevent invalid rotations."""
 
+        def euler_angles(self, seq: EulerSequence) -> typing.List:
+            """Returns the Euler angles in radians of this rotation for the provided sequence, in the order in which the rotations are applied,
+    such that `DCM::from_euler_angles` returns this rotation.
+
+    The first and third angles are in [-pi, pi]. The second angle is in [0, pi] for symmetric sequences (e.g. 3-1-3), and in [-pi/2, pi/2]
+    for the other sequences (e.g. 3-2-1).
+
+    # Gimbal lock
+    When the second angle is zero or pi for a symmetric sequence, or plus or minus pi/2 for the other sequences, the rotations about the
+    first and third axes cannot be distinguished. As in the SPICE `m2eul` routine, the third angle is then set to zero and the first
+    angle holds the whole rotation about that axis."""
+
+        def euler_angles_with_rates(self, seq: EulerSequence) -> typing.Tuple:
+            """Returns the Euler angles in radians of this rotation for the provided sequence, as in `euler_angles`, and their time derivatives in
+    rad/s if the time derivative of this DCM is set.
+
+    At gimbal lock, the rate of the third angle is set to zero like the angle itself, and the rates of the first and second angles are
+    those of the rotations about their axes."""
+
         @staticmethod
         def from_identity(from_id: int, to_id: int) -> DCM:
             """Builds an identity rotation."""
@@ -2459,4 +2681,47 @@ class rotation:
         """Return repr(self)."""
 
     def __str__(self) -> str:
-        """Return str(self)."""
\ No newline at end of file
+        """Return str(self)."""
+
+    @typing.final
+    class EulerSequence:
+        """Sequence of the three axes of an Euler angle rotation, in the order in which the rotations are applied.
+
+    For example, the 3-2-1 sequence `ZYX` (yaw, pitch, and roll) rotates first about the Z axis by the first angle, then about the new Y axis by
+    the second angle, and finally about the new X axis by the third angle, so its rotation matrix is `R1(angle 3) R2(angle 2) R3(angle 1)`."""
+
+        def __eq__(self, value: typing.Any) -> bool:
+            """Return self==value."""
+
+        def __ge__(self, value: typing.Any) -> bool:
+            """Return self>=value."""
+
+        def __gt__(self, value: typing.Any) -> bool:
+            """Return self>value."""
+
+        def __int__(self) -> None:
+            """int(self)"""
+
+        def __le__(self, value: typing.Any) -> bool:
+            """Return self<=value."""
+
+        def __lt__(self, value: typing.Any) -> bool:
+            """Return self<value."""
+
+        def __ne__(self, value: typing.Any) -> bool:
+            """Return self!=value."""
+
+        def __repr__(self) -> str:
+            """Return repr(self)."""
+        XYX: EulerSequence = ...
+        XYZ: EulerSequence = ...
+        XZX: EulerSequence = ...
+        XZY: EulerSequence = ...
+        YXY: EulerSequence = ...
+        YXZ: EulerSequence = ...
+        YZX: EulerSequence = ...
+        YZY: EulerSequence = ...
+        ZXY: EulerSequence = ...
+        ZXZ: EulerSequence = ...
+        ZYX: EulerSequence = ...
+        ZYZ: EulerSequence = ...
\ No newline at end of file
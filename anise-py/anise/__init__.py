@@ -1,8 +1,10 @@
 from anise._anise import (
     Aberration,
     Almanac,
+    LightTimeDirection,
     MetaAlmanac,
     MetaFile,
+    SubPointMethod,
     exec_gui,
     time,
     astro,
@@ -21,8 +23,10 @@ __all__ = [
     # root
     "Aberration",
     "Almanac",
+    "LightTimeDirection",
     "MetaAlmanac",
     "MetaFile",
+    "SubPointMethod",
     # functions
     "exec_gui",
 ]
@@ -73,6 +73,10 @@ class Almanac:
     def __init__(self, path: str) -> Almanac:
         """An Almanac contains all of the loaded SPICE and ANISE data. It is the context for all computations."""
 
+    def add_topocentric_frame(self, name: str, parent_frame: Frame, latitude_deg: float, longitude_deg: float, height_km: float) -> Frame:
+        """Adds a topocentric (South East Zenith) frame to this Almanac, centered at the provided geodetic latitude and longitude in degrees
+and height in km with respect to the ellipsoid of the parent body fixed frame, and returns that new frame."""
+
     def azimuth_elevation_range_sez(self, rx: Orbit, tx: Orbit, obstructing_body: Frame=None, ab_corr: Aberration=None) -> AzElRange:
         """Computes the azimuth (in degrees), elevation (in degrees), and range (in kilometers) of the
 receiver state (`rx`) seen from the transmitter state (`tx`), once converted into the SEZ frame of the transmitter.
@@ -128,9 +132,26 @@ This function performs a memory allocation."""
         """Pretty prints the description of this Almanac, showing everything by default. Default time scale is TDB.
 If any parameter is set to true, then nothing other than that will be printed."""
 
+    def export_subset(self, bodies: typing.List, start: Epoch, end: Epoch, out: str) -> None:
+        """Writes a new SPK to the provided path with only the loaded segments of the provided target IDs, clipped to the window from
+the start to the end epochs. The Chebyshev records within the window are copied as is, and those at its boundaries are refit."""
+
+    def frame_from_name(self, name: str) -> Frame:
+        """Returns the frame with the provided name, with its planetary data if it is loaded. The name is case insensitive, and spaces,
+dashes, and underscores are equivalent. The fixed frames, the loaded Euler parameter and planetary data, the NAIF body names
+(including those of the loaded text kernels), and the built-in names are searched in that order. If no frame has this name, the
+error includes the closest known name."""
+
     def frame_info(self, uid: Frame) -> Frame:
         """Returns the frame information (gravitational param, shape) as defined in this Almanac from an empty frame"""
 
+    def light_time(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, direction: LightTimeDirection) -> typing.Tuple:
+        """Returns the converged one-way light time between the target and the observer at the provided epoch, and the geometric state of
+the target at the light time corrected epoch with respect to the observer at the provided epoch.
+
+In reception, the target emits the signal at the epoch of the returned state, one light time before the observer receives it.
+In transmission, the observer emits the signal at the provided epoch, and the target receives it at the epoch of the returned state."""
+
     def line_of_sight_obstructed(self, observer: Orbit, observed: Orbit, obstructing_body: Frame, ab_corr: Aberration=None) -> bool:
         """Computes whether the line of sight between an observer and an observed Cartesian state is obstructed by the obstructing body.
 Returns true if the obstructing body is in the way, false otherwise.
@@ -168,6 +189,9 @@ Algorithm (source: Algorithm 35 of Vallado, 4th edition, page 308.):
         """Load from the provided MetaFile, downloading it if necessary.
 Set autodelete to true to automatically delete lock files. Lock files are important in multi-threaded loads."""
 
+    def load_mmap(self, path: str) -> Almanac:
+        """Memory maps the provided file and loads it, without copying the SPK and BPC files onto the heap. The file must not be modified while loaded."""
+
     def occultation(self, back_frame: Frame, front_frame: Frame, observer: Orbit, ab_corr: Aberration=None) -> Occultation:
         """Computes the occultation percentage of the `back_frame` object by the `front_frame` object as seen from the observer, when according for the provided aberration correction.
 
@@ -176,6 +200,17 @@ A 100%  percent occultation means that the back object is fully hidden from the
 A value in between means that the back object is partially hidden from the observser (i.e. _penumbra_ if the back object is the Sun).
 Refer to the [MathSpec](https://nyxspace.com/nyxspace/MathSpec/celestial/eclipse/) for modeling details."""
 
+    def occultation_state(self, back_frame: Frame, front_frame: Frame, observer_frame: Frame, epoch: Epoch, ab_corr: Aberration=None) -> OccultationState:
+        """Classifies the occultation of the `back_frame` object by the `front_frame` object as seen from the observer frame at the provided
+epoch, analogous to SPICE's `occult` with ellipsoid shapes.
+
+For a solar eclipse, the back frame is the Sun. For a transit, the front frame is the transiting body.
+The aberration correction should include light time corrections, and is also used to orient each ellipsoid."""
+
+    def one_way_light_time(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, ab_corr: Aberration) -> typing.Tuple:
+        """Returns the one-way light time between the target and the observer at the provided epoch given the aberration correction, along
+with the rate of change of that light time (unitless, i.e. in seconds per second)."""
+
     def rotate(self, from_frame: Frame, to_frame: Frame, epoch: Epoch) -> DCM:
         """Returns the 6x6 DCM needed to rotation the `from_frame` to the `to_frame`.
 
@@ -228,6 +263,12 @@ This function performs a memory allocation."""
 # Note
 The units will be those of the underlying ephemeris data (typically km and km/s)"""
 
+    def sub_observer_point(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch, method: SubPointMethod, ab_corr: Aberration=None) -> typing.Tuple:
+        """Returns the geodetic latitude (φ) and longitude (λ) in degrees of the sub-observer point on the tri-axial ellipsoid of the target,
+and the altitude of the observer above that point in km (i.e. the distance between them).
+
+The target frame must be body fixed (e.g. IAU_EARTH_FRAME). Use the Sun as the observer to compute the sub-solar point."""
+
     def sun_angle_deg(self, target_id: int, observer_id: int, epoch: Epoch) -> float:
         """Returns the angle (between 0 and 180 degrees) between the observer and the Sun, and the observer and the target body ID.
 This computes the Sun Probe Earth angle (SPE) if the probe is in a loaded SPK, its ID is the "observer_id", and the target is set to its central body.
@@ -283,6 +324,9 @@ will return exactly the same data as the spkerz SPICE call.
 # Note
 The units will be those of the underlying ephemeris data (typically km and km/s)"""
 
+    def transform_by_name(self, target_name: str, observer_name: str, epoch: Epoch, ab_corr: Aberration=None) -> Orbit:
+        """Returns the Cartesian state needed to transform the target to the observer, both provided by their names, exactly as `transform`."""
+
     def transform_many(self, target_frame: Frame, observer_frame: Frame, time_series: TimeSeries, ab_corr: Aberration=None) -> typing.List[Orbit]:
         """Returns a chronologically sorted list of the Cartesian states that transform the `from_frame` to the `to_frame` for each epoch of the time series, computed in parallel under the hood.
 Note: if any transformation fails, the error will be printed to the stderr.
@@ -296,6 +340,20 @@ Note: the input ordering is lost: the output states will not be in the same orde
 
 Refer to [transform_to] for details."""
 
+    def transform_state(self, state: Orbit, to_frame: Frame, epoch: Epoch) -> Orbit:
+        """Re-expresses the provided state, valid at the provided epoch, in the `to_frame`: both its center and its orientation are changed.
+
+The velocity includes the transport term of the rotation of the frames, i.e. the ω×r term of a rotating body-fixed frame, so
+transforming a state from an inertial frame into a body-fixed frame and back recovers the original velocity. The state is re-centered
+with the geometric translation between the centers of both frames, without any aberration correction (use [transform_to] for that).
+
+The `epoch` must be the epoch of the state: it is checked so that a state at an unintended epoch is rejected instead of being
+transformed at its own epoch.
+
+# Errors
++ The epoch of the state must be the provided epoch;
++ The translation and the rotation between both frames must be available at that epoch."""
+
     def transform_to(self, state: Orbit, observer_frame: Frame, ab_corr: Aberration=None) -> Orbit:
         """Returns the provided state as seen from the observer frame, given the aberration."""
 
@@ -316,9 +374,19 @@ This function only performs the translation and no rotation whatsoever. Use the
 # Note
 This function performs a recursion of no more than twice the [MAX_TREE_DEPTH]."""
 
+    def translate_by_name(self, target_name: str, observer_name: str, epoch: Epoch, ab_corr: Aberration=None) -> Orbit:
+        """Returns the Cartesian state of the target as seen from the observer, both provided by their names, exactly as `translate`."""
+
     def translate_geometric(self, target_frame: Frame, observer_frame: Frame, epoch: Epoch) -> Orbit:
         """Returns the geometric position vector, velocity vector, and acceleration vector needed to translate the `from_frame` to the `to_frame`, where the distance is in km, the velocity in km/s, and the acceleration in km/s^2."""
 
+    def translate_many(self, target_frame: Frame, observer_frame: Frame, epochs: typing.List, ab_corr: Aberration=None) -> typing.List:
+        """Returns the Cartesian states of the target frame as seen from the observer frame at each of the provided epochs, in the same
+order, and optionally given the aberration correction. Each state is the same as the one returned by `translate`.
+
+Without aberration correction, the segment used for each translation is reused for the following epochs where the segment
+lookup would return that same segment. If any translation fails, the error reports the index and epoch of the first failure."""
+
     def translate_to(self, state: Orbit, observer_frame: Frame, ab_corr: Aberration=None) -> Orbit:
         """Translates the provided Cartesian state into the requested observer frame
 
@@ -327,12 +395,45 @@ This function performs a recursion of no more than twice the [MAX_TREE_DEPTH].""
     def translate_to_parent(self, source: Frame, epoch: Epoch) -> Orbit:
         """Performs the GEOMETRIC translation to the parent. Use translate_from_to for aberration."""
 
+    def unload(self, source: str) -> Almanac:
+        """Unloads every SPK, BPC, and ANISE data set that was loaded from the provided path, or every SPK and BPC whose internal file name is the provided source."""
+
     def __repr__(self) -> str:
         """Return repr(self)."""
 
     def __str__(self) -> str:
         """Return str(self)."""
 
+@typing.final
+class LightTimeDirection:
+    """Direction of the signal between the observer and the target, used by the light time computations."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    Reception: LightTimeDirection = ...
+    Transmission: LightTimeDirection = ...
+
 @typing.final
 class MetaAlmanac:
     """A structure to set up an Almanac, with automatic downloading, local storage, checksum checking, and more.
@@ -455,4 +556,34 @@ This function modified `self` and changes the URI to be the path to the download
     def __str__(self) -> str:
         """Return str(self)."""
 
+@typing.final
+class SubPointMethod:
+    """Method used to locate the point on the surface of the target below the observer, analogous to the methods of SPICE's `subpnt`."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    Intercept: SubPointMethod = ...
+    NearPoint: SubPointMethod = ...
+
 def exec_gui():...
\ No newline at end of file
@@ -232,6 +232,38 @@ Refer to the [MathSpec](https://nyxspace.com/nyxspace/MathSpec/celestial/eclipse
     def __str__(self) -> str:
         """Return str(self)."""
 
+@typing.final
+class OccultationState:
+    """Classifies how the back object is hidden by the front object as seen from an observer, analogous to the occultation types of SPICE's `occult`."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    Annular: OccultationState = ...
+    NoOccultation: OccultationState = ...
+    Partial: OccultationState = ...
+    Total: OccultationState = ...
+
 @typing.final
 class Orbit:
     """Defines a Cartesian state in a given frame at a given epoch in a given time scale. Radius data is expressed in kilometers. Velocity data is expressed in kilometers per second.
@@ -557,6 +589,12 @@ The details are at the log level `info`.
 NOTE: Mean Brouwer Short are only defined around Earth. However, `nyx` does *not* check the
 main celestial body around which the state is defined (GMAT does perform this verification)."""
 
+    def is_circular(self) -> bool:
+        """Returns true if the eccentricity of this orbit is below [ECC_EPSILON]."""
+
+    def is_equatorial(self) -> bool:
+        """Returns true if the sine of the inclination of this orbit is below [EQUATORIAL_EPSILON], i.e. prograde or retrograde equatorial."""
+
     def latitude_deg(self) -> float:
         """Returns the geodetic latitude (φ) in degrees. Value is between -180 and +180 degrees.
 
@@ -590,7 +628,17 @@ This state MUST be in the body fixed frame (e.g. ITRF93) prior to calling this f
     def ma_deg(self) -> float:
         """Returns the mean anomaly in degrees
 
-This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly"""
+This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly.
+NOTE: This returns an error for circular orbits, and the hyperbolic anomaly (not the hyperbolic mean anomaly) for hyperbolic
+orbits: use [Self::mean_anomaly_deg] for a mean anomaly defined on all orbits."""
+
+    def mean_anomaly_deg(self) -> float:
+        """Returns the mean anomaly in degrees, which is defined for all orbits unlike [Self::ma_deg].
+
++ Elliptical orbits: the mean anomaly between 0 and 360 degrees, as [Self::ma_deg].
++ Circular orbits: the periapsis is undefined and the motion is uniform, so the mean anomaly is the true anomaly, i.e. the argument
+of latitude, or the true longitude if the orbit is also equatorial (cf. [Self::ta_deg]).
++ Hyperbolic orbits: the hyperbolic mean anomaly `e sinh(H) - H`, where H is the hyperbolic anomaly, which is not bounded."""
 
     def mean_motion_deg_s(self) -> float:
         """Returns the mean motion in degrees per seconds"""
@@ -602,7 +650,31 @@ This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly"""
         """Returns the radius of periapsis (or perigee around Earth), in kilometers."""
 
     def period(self) -> Duration:
-        """Returns the period in seconds"""
+        """Returns the period in seconds, or an error if the orbit is not elliptical (hyperbolic orbits have no period)."""
+
+    def propagate_j2(self, dt: Duration, j2: float, req_km: float) -> Orbit:
+        """Propagates this elliptical orbit by the provided duration with the secular rates of the mean elements due to the provided J2
+zonal harmonic of the center of the frame, whose equatorial radius is `req_km`, returning the new state in the same frame.
+
+The semi-major axis, eccentricity, and inclination are constant, while the right ascension of the ascending node, the argument of
+periapsis, and the mean anomaly drift at the first order secular rates:
++ RAAN: -3/2 n J2 (Req/p)^2 cos(i)
++ AOP: 3/4 n J2 (Req/p)^2 (5 cos^2(i) - 1)
++ MA: n (1 + 3/4 J2 (Req/p)^2 sqrt(1 - e^2) (3 cos^2(i) - 1))
+
+# Astrodynamics note
+The osculating elements of this state are used as mean elements, so the short periodic effects of J2 are not modeled.
+Use Nyx for high fidelity propagation."""
+
+    def propagate_two_body(self, dt: Duration) -> Orbit:
+        """Propagates this state by the provided duration assuming two-body motion about the center of its frame, returning the new state in
+the same frame.
+
+This uses the universal variable formulation, solved with the Laguerre-Conway iteration, so elliptical, near-parabolic, and hyperbolic
+trajectories are all supported, and the duration may be negative to propagate backward.
+
+# Astrodynamics note
+No other force than the point mass gravity of the center of the frame is modeled. Use Nyx for high fidelity propagation."""
 
     def raan_deg(self) -> float:
         """Returns the right ascension of the ascending node in degrees"""
@@ -14,6 +14,25 @@ It provides a number of run-time checks that prevent invalid rotations."""
         """Defines a direction cosine matrix from one frame ID to another frame ID, optionally with its time derivative.
 It provides a number of run-time checks that prevent invalid rotations."""
 
+    def euler_angles(self, seq: EulerSequence) -> typing.List:
+        """Returns the Euler angles in radians of this rotation for the provided sequence, in the order in which the rotations are applied,
+such that `DCM::from_euler_angles` returns this rotation.
+
+The first and third angles are in [-pi, pi]. The second angle is in [0, pi] for symmetric sequences (e.g. 3-1-3), and in [-pi/2, pi/2]
+for the other sequences (e.g. 3-2-1).
+
+# Gimbal lock
+When the second angle is zero or pi for a symmetric sequence, or plus or minus pi/2 for the other sequences, the rotations about the
+first and third axes cannot be distinguished. As in the SPICE `m2eul` routine, the third angle is then set to zero and the first
+angle holds the whole rotation about that axis."""
+
+    def euler_angles_with_rates(self, seq: EulerSequence) -> typing.Tuple:
+        """Returns the Euler angles in radians of this rotation for the provided sequence, as in `euler_angles`, and their time derivatives in
+rad/s if the time derivative of this DCM is set.
+
+At gimbal lock, the rate of the third angle is set to zero like the angle itself, and the rates of the first and second angles are
+those of the rotations about their axes."""
+
     @staticmethod
     def from_identity(from_id: int, to_id: int) -> DCM:
         """Builds an identity rotation."""
@@ -75,4 +94,47 @@ The criteria for validity are:
         """Return repr(self)."""
 
     def __str__(self) -> str:
-        """Return str(self)."""
\ No newline at end of file
+        """Return str(self)."""
+
+@typing.final
+class EulerSequence:
+    """Sequence of the three axes of an Euler angle rotation, in the order in which the rotations are applied.
+
+For example, the 3-2-1 sequence `ZYX` (yaw, pitch, and roll) rotates first about the Z axis by the first angle, then about the new Y axis by
+the second angle, and finally about the new X axis by the third angle, so its rotation matrix is `R1(angle 3) R2(angle 2) R3(angle 1)`."""
+
+    def __eq__(self, value: typing.Any) -> bool:
+        """Return self==value."""
+
+    def __ge__(self, value: typing.Any) -> bool:
+        """Return self>=value."""
+
+    def __gt__(self, value: typing.Any) -> bool:
+        """Return self>value."""
+
+    def __int__(self) -> None:
+        """int(self)"""
+
+    def __le__(self, value: typing.Any) -> bool:
+        """Return self<=value."""
+
+    def __lt__(self, value: typing.Any) -> bool:
+        """Return self<value."""
+
+    def __ne__(self, value: typing.Any) -> bool:
+        """Return self!=value."""
+
+    def __repr__(self) -> str:
+        """Return repr(self)."""
+    XYX: EulerSequence = ...
+    XYZ: EulerSequence = ...
+    XZX: EulerSequence = ...
+    XZY: EulerSequence = ...
+    YXY: EulerSequence = ...
+    YXZ: EulerSequence = ...
+    YZX: EulerSequence = ...
+    YZY: EulerSequence = ...
+    ZXY: EulerSequence = ...
+    ZXZ: EulerSequence = ...
+    ZYX: EulerSequence = ...
+    ZYZ: EulerSequence = ...
\ No newline at end of file
@@ -10,6 +10,7 @@
 
 use anise::astro::AzElRange;
 use anise::astro::Occultation;
+use anise::astro::OccultationState;
 use anise::structure::planetocentric::ellipsoid::Ellipsoid;
 use pyo3::prelude::*;
 
@@ -24,6 +25,7 @@ pub(crate) fn astro(_py: Python, sm: &Bound<'_, PyModule>) -> PyResult<()> {
     sm.add_class::<Orbit>()?;
     sm.add_class::<AzElRange>()?;
     sm.add_class::<Occultation>()?;
+    sm.add_class::<OccultationState>()?;
 
     // Also add the constants as a submodule to astro for backward compatibility
     sm.add_wrapped(wrap_pymodule!(crate::constants::constants))?;
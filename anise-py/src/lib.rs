@@ -9,8 +9,9 @@
  */
 
 use ::anise::almanac::metaload::{MetaAlmanac, MetaFile};
+use ::anise::almanac::surface::SubPointMethod;
 use ::anise::almanac::Almanac;
-use ::anise::astro::Aberration;
+use ::anise::astro::{Aberration, LightTimeDirection};
 use hifitime::leap_seconds::{LatestLeapSeconds, LeapSecondsFile};
 use hifitime::python::{PyDurationError, PyHifitimeError, PyParsingError};
 use hifitime::ut1::Ut1Provider;
@@ -38,6 +39,8 @@ fn anise(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     m.add_class::<Almanac>()?;
     m.add_class::<Aberration>()?;
+    m.add_class::<LightTimeDirection>()?;
+    m.add_class::<SubPointMethod>()?;
     m.add_class::<MetaAlmanac>()?;
     m.add_class::<MetaFile>()?;
     Ok(())
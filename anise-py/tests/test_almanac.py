@@ -1,19 +1,38 @@
 import os
 from pathlib import Path
 import pickle
-
-from anise import Almanac, MetaAlmanac, MetaFile
+import tempfile
+
+from anise import (
+    Aberration,
+    Almanac,
+    LightTimeDirection,
+    MetaAlmanac,
+    MetaFile,
+    SubPointMethod,
+)
 from anise.astro import *
 from anise.constants import Frames
-from anise.rotation import DCM
+from anise.rotation import DCM, EulerSequence
 from anise.time import Duration, Epoch, TimeSeries, Unit
 from anise.utils import convert_tpc
 
 from os import environ
 
+import pytest
+
 # For compatibility with version 0.6.x, check we can import the constants from anise.astro as well
 from anise.astro.constants import Frames
 
+DATA_PATH = Path(__file__).parent.joinpath("..", "..", "data")
+
+
+def load_almanac() -> Almanac:
+    """Loads the DE440s ephemeris and the planetary constants, which the CI downloads into the data folder"""
+    return Almanac(str(DATA_PATH.joinpath("de440s.bsp"))).load(
+        str(DATA_PATH.joinpath("pck08.pca"))
+    )
+
 
 def test_state_transformation():
     """
@@ -240,6 +259,227 @@ def test_frame_defs():
     assert Frames.EME2000 != Frames.SSB_J2000
 
 
+def test_unload():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+    almanac.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+
+    unloaded = almanac.unload(str(DATA_PATH.joinpath("de440s.bsp")))
+    with pytest.raises(Exception):
+        unloaded.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+    # The original Almanac is unchanged
+    almanac.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+
+    # Nothing else was loaded from that path
+    with pytest.raises(Exception):
+        unloaded.unload(str(DATA_PATH.joinpath("de440s.bsp")))
+
+
+def test_one_way_light_time():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    light_time, rate = almanac.one_way_light_time(
+        Frames.MOON_J2000, Frames.EARTH_J2000, epoch, Aberration("LT")
+    )
+    # The Moon is between 1.2 and 1.4 light seconds away from the Earth
+    assert 1.2 < light_time.to_seconds() < 1.4
+    assert abs(rate) < 1e-5
+
+
+def test_add_topocentric_frame():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    paris = almanac.add_topocentric_frame(
+        "PARIS", Frames.IAU_EARTH_FRAME, 48.8566, 2.3522, 0.4
+    )
+    assert paris != Frames.IAU_EARTH_FRAME
+
+    # The origin of the new frame is on the surface of the Earth
+    state = almanac.translate(paris, Frames.EARTH_J2000, epoch)
+    assert 6350.0 < state.rmag_km() < 6380.0
+
+
+def test_occultation_state():
+    almanac = load_almanac()
+
+    # Total lunar eclipse of 2022 November 08, seen from the Moon as an occultation of the Sun by the Earth (cf. the Rust tests)
+    for epoch, expected in [
+        (Epoch("2022-11-08 07:00:00 UTC"), OccultationState.NoOccultation),
+        (Epoch("2022-11-08 10:59:00 UTC"), OccultationState.Total),
+    ]:
+        state = almanac.occultation_state(
+            Frames.SUN_J2000,
+            Frames.IAU_EARTH_FRAME,
+            Frames.MOON_J2000,
+            epoch,
+            Aberration("LT"),
+        )
+        assert state == expected, f"{epoch}: {state}"
+
+    assert OccultationState.Partial != OccultationState.Annular
+    print(OccultationState.Total)
+
+
+def test_export_subset():
+    almanac = load_almanac()
+    start = Epoch("2021-10-29 12:34:56 TDB")
+    end = Epoch("2021-11-29 12:34:56 TDB")
+
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        out = os.path.join(tmp_dir, "moon-subset.bsp")
+        # The Moon is defined with respect to the Earth Moon barycenter
+        almanac.export_subset([301], start, end, out)
+        assert os.path.getsize(out) < os.path.getsize(DATA_PATH.joinpath("de440s.bsp"))
+
+        subset = Almanac(out)
+        epoch = Epoch("2021-11-12 01:02:03 TDB")
+        expected = almanac.translate(
+            Frames.MOON_J2000, Frames.EARTH_MOON_BARYCENTER_J2000, epoch
+        )
+        state = subset.translate(
+            Frames.MOON_J2000, Frames.EARTH_MOON_BARYCENTER_J2000, epoch
+        )
+        assert state == expected
+
+
+def test_translate_many():
+    almanac = load_almanac()
+    start = Epoch("2021-10-29 12:34:56 TDB")
+    epochs = [start + Unit.Hour * i for i in range(48)]
+
+    states = almanac.translate_many(Frames.MOON_J2000, Frames.EARTH_J2000, epochs)
+    assert len(states) == len(epochs)
+    for epoch, state in zip(epochs, states):
+        assert state == almanac.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+
+
+def test_euler_angles():
+    dcm = DCM.from_r3(0.3, 1, 2)
+    angles = dcm.euler_angles(EulerSequence.ZYX)
+    assert abs(angles[0] - 0.3) < 1e-12
+    assert abs(angles[1]) < 1e-12
+    assert abs(angles[2]) < 1e-12
+
+    # The time derivative of this DCM is not set, so neither are the rates
+    angles, rates = dcm.euler_angles_with_rates(EulerSequence.ZXZ)
+    assert abs(angles[0] - 0.3) < 1e-12
+    assert rates is None
+    print(EulerSequence.ZYX)
+
+
+def test_light_time():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    for direction in [LightTimeDirection.Reception, LightTimeDirection.Transmission]:
+        light_time, state = almanac.light_time(
+            Frames.MOON_J2000, Frames.EARTH_J2000, epoch, direction
+        )
+        assert 1.2 < light_time.to_seconds() < 1.4
+        # The state of the target is at the epoch of emission or reception of the signal
+        delay = epoch.timedelta(state.epoch).to_seconds()
+        if direction == LightTimeDirection.Reception:
+            assert abs(delay - light_time.to_seconds()) < 1e-6
+        else:
+            assert abs(delay + light_time.to_seconds()) < 1e-6
+
+
+def test_sub_observer_point():
+    almanac = load_almanac()
+    # June solstice of 2021
+    epoch = Epoch("2021-06-21 03:32:00 UTC")
+
+    for method in [SubPointMethod.Intercept, SubPointMethod.NearPoint]:
+        lat_deg, long_deg, alt_km = almanac.sub_observer_point(
+            Frames.IAU_EARTH_FRAME, Frames.SUN_J2000, epoch, method
+        )
+        # The sub-solar point is on the tropic of Cancer (the geodetic latitude of the intercept is slightly higher)
+        assert abs(lat_deg - 23.44) < 0.25, f"{method}: {lat_deg}"
+        assert -180.0 <= long_deg <= 360.0
+        assert 1.5e8 < alt_km < 1.53e8
+
+
+def test_frame_from_name():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    # Names are case insensitive and spaces, dashes, and underscores are equivalent
+    for name in ["EARTH_MOON_BARYCENTER", "earth moon barycenter", "Earth-Moon Barycenter"]:
+        frame = almanac.frame_from_name(name)
+        assert frame.ephemeris_id == 3
+        assert frame.orientation_id == 1
+
+    by_name = almanac.translate_by_name("Moon", "Earth", epoch)
+    by_frame = almanac.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+    assert abs(by_name.rmag_km() - by_frame.rmag_km()) < 1e-9
+
+    by_name = almanac.transform_by_name("moon", "earth", epoch)
+    by_frame = almanac.transform(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+    assert abs(by_name.rmag_km() - by_frame.rmag_km()) < 1e-9
+
+    with pytest.raises(Exception):
+        almanac.frame_from_name("not a frame")
+
+
+def test_transform_state():
+    almanac = load_almanac()
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    state = Orbit.from_cartesian(
+        8_191.93, -4_582.16, 1_629.96, 1.215, 5.891, 3.145, epoch, Frames.EARTH_J2000
+    )
+    body_fixed = almanac.transform_state(state, Frames.IAU_EARTH_FRAME, epoch)
+    assert body_fixed.frame.orientation_id == Frames.IAU_EARTH_FRAME.orientation_id
+    # The rotation preserves the position magnitude but not the velocity magnitude (transport term)
+    assert abs(body_fixed.rmag_km() - state.rmag_km()) < 1e-9
+    assert abs(body_fixed.vmag_km_s() - state.vmag_km_s()) > 1e-3
+
+    # Transforming back recovers the original state
+    round_trip = almanac.transform_state(body_fixed, Frames.EARTH_J2000, epoch)
+    for attr in ["x_km", "y_km", "z_km"]:
+        assert abs(getattr(round_trip, attr) - getattr(state, attr)) < 1e-8
+    for attr in ["vx_km_s", "vy_km_s", "vz_km_s"]:
+        assert abs(getattr(round_trip, attr) - getattr(state, attr)) < 1e-9
+
+
+def test_load_mmap():
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+    almanac = load_almanac()
+    mapped = Almanac(str(DATA_PATH.joinpath("pck08.pca"))).load_mmap(
+        str(DATA_PATH.joinpath("de440s.bsp"))
+    )
+
+    # The memory mapped SPK yields the same states as the SPK loaded onto the heap
+    state = almanac.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+    mapped_state = mapped.translate(Frames.MOON_J2000, Frames.EARTH_J2000, epoch)
+    for attr in ["x_km", "y_km", "z_km", "vx_km_s", "vy_km_s", "vz_km_s"]:
+        assert getattr(mapped_state, attr) == getattr(state, attr)
+
+
+def test_propagate():
+    almanac = load_almanac()
+    eme2k = almanac.frame_info(Frames.EME2000)
+    epoch = Epoch("2021-10-29 12:34:56 TDB")
+
+    state = Orbit.from_keplerian(
+        8_191.93, 0.024, 12.85, 306.614, 314.19, 99.887_7, epoch, eme2k
+    )
+
+    # After one period, the two-body motion returns to the initial state
+    later = state.propagate_two_body(state.period())
+    assert later.epoch == epoch + state.period()
+    for attr in ["x_km", "y_km", "z_km"]:
+        assert abs(getattr(later, attr) - getattr(state, attr)) < 1e-6
+
+    # The J2 secular rates keep the shape of the orbit but regress the node of this prograde orbit
+    later = state.propagate_j2(Duration("1 day"), 1.082_63e-3, 6_378.136_3)
+    assert abs(later.sma_km() - state.sma_km()) < 1e-6
+    assert abs(later.ecc() - state.ecc()) < 1e-9
+    assert later.raan_deg() < state.raan_deg()
+
+
 if __name__ == "__main__":
     # test_meta_load()
     # test_exports()
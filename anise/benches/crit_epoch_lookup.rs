@@ -0,0 +1,70 @@
+use anise::{
+    naif::{daf::datatypes::HermiteSetType13, daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    prelude::*,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+/// Number of intervals of the synthetic segment
+const NUM_INTERVALS: usize = 50_000;
+const NUM_QUERIES: usize = 1_000;
+const SAMPLES: usize = 8;
+
+/// Builds the states, epochs, and epoch directory of an unequal step segment of a target moving in a straight line.
+fn synthetic_segment() -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let epochs: Vec<f64> = (0..=NUM_INTERVALS)
+        .map(|i| 60.0 * i as f64 + (i % 3) as f64)
+        .collect();
+    let states = epochs
+        .iter()
+        .flat_map(|et| [7000.0 + et, -0.5 * et, 0.25 * et, 1.0, -0.5, 0.25])
+        .collect();
+    // SPICE stores every 100th epoch in the directory.
+    let directory = epochs.iter().skip(99).step_by(100).copied().collect();
+    (states, epochs, directory)
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let (states, epochs, directory) = synthetic_segment();
+    let data = HermiteSetType13 {
+        samples: SAMPLES,
+        num_records: epochs.len(),
+        state_data: &states,
+        epoch_data: &epochs,
+        epoch_registry: &directory,
+    };
+    let summary = SPKSummaryRecord::default();
+
+    // Spread the queries over the whole segment, alternating between interval boundaries and points within the intervals.
+    let queries: Vec<Epoch> = (0..NUM_QUERIES)
+        .map(|i| {
+            let idx = i * NUM_INTERVALS / NUM_QUERIES;
+            let et = if i % 2 == 0 {
+                epochs[idx]
+            } else {
+                0.5 * (epochs[idx] + epochs[idx + 1])
+            };
+            Epoch::from_et_seconds(et)
+        })
+        .collect();
+
+    c.bench_function("Type 13 evaluation in a 50k-interval segment", |b| {
+        b.iter(|| {
+            for epoch in &queries {
+                black_box(data.evaluate(*epoch, &summary).unwrap());
+            }
+        })
+    });
+
+    c.bench_function("Linear scan of a 50k-interval segment (baseline)", |b| {
+        b.iter(|| {
+            for epoch in &queries {
+                let et = epoch.to_et_seconds();
+                black_box(epochs.iter().position(|&e| e >= et));
+            }
+        })
+    });
+}
+
+criterion_group!(epoch_lookup, criterion_benchmark);
+criterion_main!(epoch_lookup);
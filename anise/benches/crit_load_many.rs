@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use anise::prelude::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn kernels() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("../data/pck08.pca")];
+    for _ in 0..9 {
+        paths.push(PathBuf::from("../data/de440s.bsp"));
+    }
+    paths
+}
+
+fn benchmark_sequential(paths: &[PathBuf]) {
+    let mut almanac = Almanac::default();
+    for path in paths {
+        almanac = almanac.load(path.to_str().unwrap()).unwrap();
+    }
+    black_box(almanac);
+}
+
+fn benchmark_load_many(paths: &[PathBuf]) {
+    black_box(Almanac::default().load_many(paths).unwrap());
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let paths = kernels();
+
+    c.bench_function("Load 10 kernels sequentially", |b| {
+        b.iter(|| benchmark_sequential(&paths))
+    });
+
+    c.bench_function("Load 10 kernels with load_many", |b| {
+        b.iter(|| benchmark_load_many(&paths))
+    });
+}
+
+criterion_group!(load_many, criterion_benchmark);
+criterion_main!(load_many);
@@ -0,0 +1,47 @@
+use anise::{
+    constants::frames::{EARTH_J2000, IAU_MOON_FRAME, VENUS_J2000},
+    prelude::*,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const NUM_QUERIES: f64 = 100.0;
+
+fn benchmark_transform(ctx: &Almanac, time_it: TimeSeries, clear_cache: bool) {
+    for epoch in time_it {
+        if clear_cache {
+            ctx.path_cache.clear();
+        }
+        black_box(
+            ctx.transform(VENUS_J2000, IAU_MOON_FRAME, epoch, None)
+                .unwrap(),
+        );
+        black_box(
+            ctx.translate_geometric(EARTH_J2000, VENUS_J2000, epoch)
+                .unwrap(),
+        );
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let start_epoch = Epoch::from_gregorian_at_noon(2025, 1, 1, TimeScale::ET);
+    let end_epoch = Epoch::from_gregorian_at_noon(2025, 2, 1, TimeScale::ET);
+    let time_step = ((end_epoch - start_epoch).to_seconds() / NUM_QUERIES).seconds();
+    let time_it = TimeSeries::exclusive(start_epoch, end_epoch - time_step, time_step);
+
+    let ctx = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    c.bench_function("Repeated transforms with the path cache", |b| {
+        b.iter(|| benchmark_transform(&ctx, time_it.clone(), false))
+    });
+
+    c.bench_function("Repeated transforms without the path cache", |b| {
+        b.iter(|| benchmark_transform(&ctx, time_it.clone(), true))
+    });
+}
+
+criterion_group!(path_cache, criterion_benchmark);
+criterion_main!(path_cache);
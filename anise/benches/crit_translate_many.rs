@@ -0,0 +1,59 @@
+use anise::{
+    constants::frames::{EARTH_J2000, MOON_J2000, VENUS_J2000},
+    prelude::*,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const NUM_QUERIES: f64 = 10_000.0;
+
+fn benchmark_translate_loop(ctx: &Almanac, epochs: &[Epoch]) {
+    for epoch in epochs {
+        black_box(
+            ctx.translate(MOON_J2000, EARTH_J2000, *epoch, None)
+                .unwrap(),
+        );
+        black_box(
+            ctx.translate(VENUS_J2000, EARTH_J2000, *epoch, None)
+                .unwrap(),
+        );
+    }
+}
+
+fn benchmark_translate_many(ctx: &Almanac, epochs: &[Epoch]) {
+    black_box(
+        ctx.translate_many(MOON_J2000, EARTH_J2000, epochs, None)
+            .unwrap(),
+    );
+    black_box(
+        ctx.translate_many(VENUS_J2000, EARTH_J2000, epochs, None)
+            .unwrap(),
+    );
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let start_epoch = Epoch::from_gregorian_at_noon(2025, 1, 1, TimeScale::ET);
+    let end_epoch = Epoch::from_gregorian_at_noon(2025, 2, 1, TimeScale::ET);
+    let time_step = ((end_epoch - start_epoch).to_seconds() / NUM_QUERIES).seconds();
+    let epochs =
+        TimeSeries::exclusive(start_epoch, end_epoch - time_step, time_step).collect::<Vec<_>>();
+
+    // Load several SPKs, since the segment lookup goes through each of them.
+    let ctx = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/de440s.bsp")
+        .unwrap();
+
+    c.bench_function("Translations in a loop", |b| {
+        b.iter(|| benchmark_translate_loop(&ctx, &epochs))
+    });
+
+    c.bench_function("Batch translations", |b| {
+        b.iter(|| benchmark_translate_many(&ctx, &epochs))
+    });
+}
+
+criterion_group!(translate_many, criterion_benchmark);
+criterion_main!(translate_many);
@@ -7,5 +7,8 @@ fuzz_target!(|data: &[u8]| {
     // create default almanac to serve as test env
     let almanac = Almanac::default();
     // convert fuzzed data into Bytes object, matching _load_from_bytes function
-    let _ = almanac.load_from_bytes(Bytes::copy_from_slice(data));
+    if let Ok(almanac) = almanac.load_from_bytes(Bytes::copy_from_slice(data)) {
+        // malformed kernels must be reported, not panic
+        let _ = almanac.validate();
+    }
 });
@@ -9,8 +9,8 @@
  */
 
 use crate::{
-    astro::{Aberration, AzElRange},
-    constants::SPEED_OF_LIGHT_KM_S,
+    astro::{Aberration, AzElRange, AzElRangeRates},
+    constants::{orientations::J2000, SPEED_OF_LIGHT_KM_S},
     ephemerides::{EphemerisError, EphemerisPhysicsSnafu},
     errors::{AlmanacError, EphemerisSnafu, PhysicsError},
     frames::Frame,
@@ -21,7 +21,7 @@ use crate::{
 use super::Almanac;
 use crate::errors::AlmanacResult;
 
-use hifitime::TimeUnits;
+use hifitime::{Epoch, TimeUnits};
 use log::warn;
 
 use snafu::ResultExt;
@@ -48,6 +48,24 @@ impl Almanac {
         obstructing_body: Option<Frame>,
         ab_corr: Option<Aberration>,
     ) -> AlmanacResult<AzElRange> {
+        Ok(self
+            .azimuth_elevation_range_rates_sez(rx, tx, obstructing_body, ab_corr)?
+            .aer)
+    }
+
+    /// Computes the azimuth, elevation, and range of the receiver state (`rx`) seen from the transmitter state (`tx`) exactly
+    /// like [Self::azimuth_elevation_range_sez], and also returns the azimuth and elevation rates (in degrees per second).
+    ///
+    /// # Rates
+    /// The rates are computed from the velocity of the receiver in the body fixed frame of the transmitter, rotated into the SEZ frame.
+    /// As for the range rate, the transmitter is considered fixed in its body fixed frame.
+    pub fn azimuth_elevation_range_rates_sez(
+        &self,
+        rx: Orbit,
+        tx: Orbit,
+        obstructing_body: Option<Frame>,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<AzElRangeRates> {
         if tx.epoch != rx.epoch {
             return Err(AlmanacError::Ephemeris {
                 action: "",
@@ -116,16 +134,74 @@ impl Almanac {
         // For the elevation, we need to perform a quadrant check because it's measured from 0 to 360 degrees.
         let azimuth_deg = between_0_360((rho_sez.y.atan2(-rho_sez.x)).to_degrees());
 
-        Ok(AzElRange {
-            epoch: tx.epoch,
-            azimuth_deg,
-            elevation_deg,
-            range_km: rho_sez.norm(),
-            range_rate_km_s,
-            obstructed_by,
-            light_time: (rho_sez.norm() / SPEED_OF_LIGHT_KM_S).seconds(),
+        // Rotate the relative velocity into the SEZ frame to compute the rates.
+        // Source: Vallado, section 4.4.3
+        let rho_dot_sez = sez_dcm.rot_mat.transpose() * rx_in_tx_frame.velocity_km_s;
+        let rho_xy_sq = rho_sez.x.powi(2) + rho_sez.y.powi(2);
+        let azimuth_rate_deg_s =
+            ((rho_sez.y * rho_dot_sez.x - rho_sez.x * rho_dot_sez.y) / rho_xy_sq).to_degrees();
+        let elevation_rate_deg_s = ((rho_dot_sez.z - range_rate_km_s * rho_sez.z / rho_sez.norm())
+            / rho_xy_sq.sqrt())
+        .to_degrees();
+
+        Ok(AzElRangeRates {
+            aer: AzElRange {
+                epoch: tx.epoch,
+                azimuth_deg,
+                elevation_deg,
+                range_km: rho_sez.norm(),
+                range_rate_km_s,
+                obstructed_by,
+                light_time: (rho_sez.norm() / SPEED_OF_LIGHT_KM_S).seconds(),
+            },
+            azimuth_rate_deg_s,
+            elevation_rate_deg_s,
         })
     }
+
+    /// Computes the azimuth (in degrees), elevation (in degrees), range (in kilometers), and their rates of the `target`
+    /// frame seen from the `observer` frame (e.g. a ground station), in the SEZ frame of the observer, without any aberration correction.
+    ///
+    /// # Observer frame
+    /// The ephemeris of the observer must be loaded and its parent must be the body on which the observer is fixed (as in
+    /// the usual ground station SPKs), and the orientation of the observer frame must be the body fixed frame of that body, e.g. IAU_EARTH.
+    /// The geodetic latitude and longitude of the observer are computed from the shape of that body fetched from the planetary data.
+    ///
+    /// # Algorithm
+    /// 1. Find the parent body of the observer and fetch its tri-axial ellipsoid shape.
+    /// 2. Compute the state of the observer in the body fixed frame, and the state of the target with respect to the parent body.
+    /// 3. Call [Self::azimuth_elevation_range_rates_sez] with these states.
+    pub fn azimuth_elevation_range_sez_from_frames(
+        &self,
+        target: Frame,
+        observer: Frame,
+        epoch: Epoch,
+        obstructing_body: Option<Frame>,
+    ) -> AlmanacResult<AzElRangeRates> {
+        let parent = self
+            .translate_to_parent(observer, epoch)
+            .context(EphemerisSnafu {
+                action: "fetching the parent of the observer for AER",
+            })?
+            .frame;
+
+        let body_fixed = self
+            .frame_from_uid(Frame::new(parent.ephemeris_id, observer.orientation_id))
+            .map_err(|e| AlmanacError::GenericError {
+                err: format!("{e} when fetching the shape of {parent:e} for AER"),
+            })?;
+
+        // Translations are computed in J2000, so rotate from there into the body fixed frame.
+        let tx = self.transform(observer.with_orient(J2000), body_fixed, epoch, None)?;
+        let rx = self.transform(
+            target.with_orient(J2000),
+            body_fixed.with_orient(J2000),
+            epoch,
+            None,
+        )?;
+
+        self.azimuth_elevation_range_rates_sez(rx, tx, obstructing_body, None)
+    }
 }
 
 #[cfg(test)]
@@ -136,10 +212,13 @@ mod ut_aer {
 
     use crate::astro::orbit::Orbit;
     use crate::astro::AzElRange;
-    use crate::constants::frames::{EARTH_ITRF93, EARTH_J2000, IAU_EARTH_FRAME};
+    use crate::constants::frames::{EARTH_ITRF93, EARTH_J2000, IAU_EARTH_FRAME, MOON_J2000};
     use crate::constants::usual_planetary_constants::MEAN_EARTH_ANGULAR_VELOCITY_DEG_S;
     use crate::math::cartesian::CartesianState;
-    use crate::prelude::{Almanac, Epoch, MetaAlmanac};
+    use crate::math::Vector3;
+    use crate::naif::spk::writer::SPKWriter;
+    use crate::prelude::{Almanac, Epoch, Frame, MetaAlmanac};
+    use hifitime::TimeUnits;
 
     #[test]
     fn verif_edge_case() {
@@ -391,4 +470,125 @@ mod ut_aer {
             );
         }
     }
+
+    /// Builds a ground station SPK and checks the frame based AER against an independent topocentric computation,
+    /// in the spirit of SPICE's `azlcpo`, for an Earth station observing the Moon.
+    #[test]
+    fn aer_from_frames() {
+        let almanac = Almanac::new("../data/de440s.bsp")
+            .unwrap()
+            .load("../data/pck08.pca")
+            .unwrap();
+        let iau_earth = almanac.frame_from_uid(IAU_EARTH_FRAME).unwrap();
+
+        // Madrid DSN ground station
+        let latitude_deg = 40.427_222;
+        let longitude_deg = 4.250_556;
+        let height_km = 0.834_939;
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 14);
+
+        // Build the J2000 ephemeris of the station fixed on the Earth.
+        let station_id = 399_901;
+        let states = (-120_i32..=120)
+            .map(|minute| {
+                let station = Orbit::try_latlongalt(
+                    latitude_deg,
+                    longitude_deg,
+                    height_km,
+                    0.0,
+                    epoch + Unit::Minute * f64::from(minute),
+                    iau_earth,
+                )
+                .unwrap();
+                almanac.transform_to(station, EARTH_J2000, None).unwrap()
+            })
+            .collect::<Vec<CartesianState>>();
+
+        let mut writer = SPKWriter::new("ANISE AER test");
+        writer
+            .add_type2_segment(&states, station_id, 399, 1, 11, 1.hours(), "MADRID")
+            .unwrap();
+        let almanac = almanac.with_spk(writer.build().unwrap()).unwrap();
+
+        let observer = Frame::new(station_id, iau_earth.orientation_id);
+        let aer_at = |epoch: Epoch| {
+            almanac
+                .azimuth_elevation_range_sez_from_frames(MOON_J2000, observer, epoch, None)
+                .unwrap()
+        };
+
+        let aer = aer_at(epoch);
+        assert!(aer.aer.is_valid());
+        assert!(!aer.aer.is_obstructed());
+
+        // Independent topocentric computation from the East-North-Up basis of the station.
+        let station = Orbit::try_latlongalt(
+            latitude_deg,
+            longitude_deg,
+            height_km,
+            0.0,
+            epoch,
+            iau_earth,
+        )
+        .unwrap();
+        let moon = almanac
+            .transform(MOON_J2000, iau_earth, epoch, None)
+            .unwrap();
+        let rho = moon.radius_km - station.radius_km;
+        let (sin_lat, cos_lat) = latitude_deg.to_radians().sin_cos();
+        let (sin_long, cos_long) = longitude_deg.to_radians().sin_cos();
+        let east = Vector3::new(-sin_long, cos_long, 0.0);
+        let north = Vector3::new(-sin_lat * cos_long, -sin_lat * sin_long, cos_lat);
+        let up = Vector3::new(cos_lat * cos_long, cos_lat * sin_long, sin_lat);
+
+        let expected_az_deg = rho.dot(&east).atan2(rho.dot(&north)).to_degrees();
+        let expected_az_deg = (expected_az_deg + 360.0) % 360.0;
+        let expected_el_deg = (rho.dot(&up) / rho.norm()).asin().to_degrees();
+
+        assert!(
+            (aer.aer.azimuth_deg - expected_az_deg).abs() < 1e-6,
+            "az: {} != {expected_az_deg}",
+            aer.aer.azimuth_deg
+        );
+        assert!(
+            (aer.aer.elevation_deg - expected_el_deg).abs() < 1e-6,
+            "el: {} != {expected_el_deg}",
+            aer.aer.elevation_deg
+        );
+        assert!((aer.aer.range_km - rho.norm()).abs() < 1e-5);
+
+        // The frame based and the state based computations must match.
+        let rx = almanac
+            .transform(MOON_J2000, EARTH_J2000, epoch, None)
+            .unwrap();
+        let from_states = almanac
+            .azimuth_elevation_range_rates_sez(rx, station, None, None)
+            .unwrap();
+        assert!((aer.aer.azimuth_deg - from_states.aer.azimuth_deg).abs() < 1e-6);
+        assert!((aer.aer.elevation_deg - from_states.aer.elevation_deg).abs() < 1e-6);
+        assert!((aer.aer.range_km - from_states.aer.range_km).abs() < 1e-5);
+
+        // Check the rates against central differences.
+        let before = aer_at(epoch - 1.seconds());
+        let after = aer_at(epoch + 1.seconds());
+        let az_rate_deg_s = 0.5 * (after.aer.azimuth_deg - before.aer.azimuth_deg);
+        let el_rate_deg_s = 0.5 * (after.aer.elevation_deg - before.aer.elevation_deg);
+        let range_rate_km_s = 0.5 * (after.aer.range_km - before.aer.range_km);
+
+        assert!(
+            (aer.azimuth_rate_deg_s - az_rate_deg_s).abs() < 1e-7,
+            "az rate: {} != {az_rate_deg_s}",
+            aer.azimuth_rate_deg_s
+        );
+        assert!(
+            (aer.elevation_rate_deg_s - el_rate_deg_s).abs() < 1e-7,
+            "el rate: {} != {el_rate_deg_s}",
+            aer.elevation_rate_deg_s
+        );
+        assert!(
+            (aer.aer.range_rate_km_s - range_rate_km_s).abs() < 1e-5,
+            "range rate: {} != {range_rate_km_s}",
+            aer.aer.range_rate_km_s
+        );
+    }
 }
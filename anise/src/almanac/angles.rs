@@ -0,0 +1,73 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+
+use crate::{
+    astro::Aberration, constants::orientations::J2000, ephemerides::EphemerisError,
+    math::angles::Angle, prelude::Frame,
+};
+
+use super::Almanac;
+
+impl Almanac {
+    /// Returns the apparent angular separation between the two targets as seen from the observer at the provided epoch, where the
+    /// position of each target is corrected for the light time (and optionally the stellar aberration) between it and the observer.
+    ///
+    /// # SPICE Compatibility
+    /// This is the equivalent of `trgsep` with the `POINT` shape for both targets.
+    pub fn angular_separation(
+        &self,
+        target_a: Frame,
+        target_b: Frame,
+        observer: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Angle, EphemerisError> {
+        let observer = observer.with_orient(J2000);
+
+        let obs_to_a = self.translate(target_a.with_orient(J2000), observer, epoch, ab_corr)?;
+        let obs_to_b = self.translate(target_b.with_orient(J2000), observer, epoch, ab_corr)?;
+
+        Ok(Angle::between(&obs_to_a.radius_km, &obs_to_b.radius_km))
+    }
+
+    /// Returns the phase angle at the target between the observer and the illuminator (typically the Sun) at the provided epoch, i.e.
+    /// the angle between the target-observer and the target-illuminator vectors.
+    ///
+    /// With aberration corrections, the target is seen from the observer at its light time corrected epoch, and the illuminator is
+    /// seen from the target at that epoch, corrected for the light time between the illuminator and the target.
+    ///
+    /// # SPICE Compatibility
+    /// This is the equivalent of `phaseq`, where the illuminator is provided instead of always being the Sun.
+    pub fn phase_angle(
+        &self,
+        target: Frame,
+        observer: Frame,
+        illuminator: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Angle, EphemerisError> {
+        let target = target.with_orient(J2000);
+        let observer = observer.with_orient(J2000);
+
+        let obs_to_tgt = self.translate(target, observer, epoch, ab_corr)?;
+
+        let epoch_tgt = self.light_time_corrected_epoch(target, observer, epoch, ab_corr)?;
+
+        let tgt_to_illum =
+            self.translate(illuminator.with_orient(J2000), target, epoch_tgt, ab_corr)?;
+
+        Ok(Angle::between(
+            &-obs_to_tgt.radius_km,
+            &tgt_to_illum.radius_km,
+        ))
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use bytes::Bytes;
+use log::{info, warn};
+use snafu::ResultExt;
+use zip::ZipArchive;
+
+use super::Almanac;
+use crate::errors::{AlmanacError, AlmanacResult, LoadingDataSnafu, TLDataSetSnafu};
+use crate::naif::kpl::fk::FKItem;
+use crate::naif::kpl::parser::{convert_fk_items, convert_tpc_items, parse_bytes};
+use crate::naif::kpl::tpc::TPCItem;
+use crate::naif::kpl::Parameter;
+
+impl Almanac {
+    /// Loads all of the kernels bundled in the provided zip archive into a clone of this Almanac.
+    ///
+    /// Each entry is identified from its first bytes, regardless of its name:
+    /// + DAF files (`DAF/SPK` and `DAF/PCK`) and ANISE data sets are loaded exactly as with [Self::load_from_bytes], in the order of the archive;
+    /// + KPL/PCK text kernels are merged together and converted into the planetary data of the Almanac. As with
+    ///   [convert_tpc](crate::naif::kpl::parser::convert_tpc), the kernels which only define gravitational parameters (e.g. `gm_de431.tpc`)
+    ///   provide the GM of the bodies defined in the other kernels, and bodies without a GM are skipped;
+    /// + KPL/FK text kernels are merged together and converted into the Euler parameter data of the Almanac.
+    ///
+    /// Entries which are not recognized as kernels, like a README, are skipped with a warning.
+    ///
+    /// # Errors
+    /// + The archive cannot be read or decompressed;
+    /// + An entry is recognized as a kernel but cannot be parsed, or cannot be added to the Almanac (e.g. too many SPKs loaded).
+    pub fn load_from_zip(&self, bytes: Bytes) -> AlmanacResult<Self> {
+        let mut archive =
+            ZipArchive::new(Cursor::new(bytes)).map_err(|e| AlmanacError::GenericError {
+                err: format!("reading zip archive: {e}"),
+            })?;
+
+        let mut me = self.clone();
+        let mut planetary_items = HashMap::<i32, TPCItem>::new();
+        let mut gravity_items = HashMap::<i32, TPCItem>::new();
+        let mut frame_items = HashMap::<i32, FKItem>::new();
+
+        for idx in 0..archive.len() {
+            let mut entry = archive
+                .by_index(idx)
+                .map_err(|e| AlmanacError::GenericError {
+                    err: format!("reading entry #{idx} of zip archive: {e}"),
+                })?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            let name = entry.name().to_string();
+            let mut contents = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut contents)
+                .map_err(|e| AlmanacError::GenericError {
+                    err: format!("decompressing {name} from zip archive: {e}"),
+                })?;
+
+            if contents.starts_with(b"KPL/PCK") {
+                info!("Loading {name} as KPL/PCK");
+                let items = parse_bytes::<_, TPCItem>(&mut contents.as_slice(), false)
+                    .context(TLDataSetSnafu {
+                        action: "parsing KPL/PCK from zip archive",
+                    })
+                    .context(LoadingDataSnafu { path: name.clone() })?;
+
+                let gravity_only = items.values().all(|item| {
+                    item.data
+                        .keys()
+                        .all(|param| *param == Parameter::GravitationalParameter)
+                });
+
+                let merged = if gravity_only {
+                    &mut gravity_items
+                } else {
+                    &mut planetary_items
+                };
+                for (id, item) in items {
+                    merged.entry(id).or_default().data.extend(item.data);
+                }
+            } else if contents.starts_with(b"KPL/FK") {
+                info!("Loading {name} as KPL/FK");
+                let items = parse_bytes::<_, FKItem>(&mut contents.as_slice(), false)
+                    .context(TLDataSetSnafu {
+                        action: "parsing KPL/FK from zip archive",
+                    })
+                    .context(LoadingDataSnafu { path: name.clone() })?;
+
+                for (id, item) in items {
+                    let merged = frame_items.entry(id).or_default();
+                    merged.body_id = merged.body_id.or(item.body_id);
+                    merged.name = item.name.or(merged.name.take());
+                    merged.data.extend(item.data);
+                }
+            } else {
+                match Self::parse_bytes(Bytes::from(contents), Some(&name)) {
+                    Ok(data) => {
+                        me = me
+                            .with_loaded_data(data, Some(&name))
+                            .context(LoadingDataSnafu { path: name })?;
+                    }
+                    // Only the unrecognized files are reported as generic errors.
+                    Err(AlmanacError::GenericError { err }) => {
+                        warn!("skipping {name} from zip archive: {err}");
+                    }
+                    Err(e) => {
+                        return Err(e).context(LoadingDataSnafu { path: name });
+                    }
+                }
+            }
+        }
+
+        if !planetary_items.is_empty() {
+            let dataset =
+                convert_tpc_items(planetary_items, gravity_items).context(TLDataSetSnafu {
+                    action: "converting KPL/PCK from zip archive",
+                })?;
+            me = me.with_planetary_data(dataset);
+        } else if !gravity_items.is_empty() {
+            warn!("zip archive only provides gravitational parameters without any planetary constants, ignoring them");
+        }
+
+        if !frame_items.is_empty() {
+            let dataset = convert_fk_items(frame_items).context(TLDataSetSnafu {
+                action: "converting KPL/FK from zip archive",
+            })?;
+            me = me.with_euler_parameters(dataset);
+        }
+
+        Ok(me)
+    }
+}
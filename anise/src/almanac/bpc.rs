@@ -118,15 +118,10 @@ impl Almanac {
         let (start, end) = self.bpc_domain(id)?;
         error!("Almanac: summary {id} valid from {start} to {end} but not at requested {epoch}");
         // If we're reached this point, there is no relevant summary at this epoch.
-        Err(OrientationError::BPC {
-            action: "searching for SPK summary",
-            source: DAFError::SummaryIdAtEpochError {
-                kind: "BPC",
-                id,
-                epoch,
-                start,
-                end,
-            },
+        Err(OrientationError::EpochOutOfCoverage {
+            id,
+            requested: epoch,
+            available: (start, end),
         })
     }
 
@@ -0,0 +1,160 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::{Duration, Epoch};
+
+use super::Almanac;
+use crate::naif::daf::NAIFSummaryRecord;
+use crate::prelude::Frame;
+
+impl Almanac {
+    /// Returns the time intervals, in chronological order and merged when they touch, over which the translation between the target
+    /// and the observer frames can be computed.
+    ///
+    /// # Algorithm
+    /// The ephemeris path between two frames only changes at the boundaries of the loaded SPK segments. Between each pair of
+    /// consecutive boundaries, the path is computed in the middle of that span: if every hop of the path has data, then the whole span
+    /// is covered. Hence, the gaps between the segments of a single SPK, and the intersection of the coverage of each hop in the path,
+    /// are accounted for.
+    ///
+    /// # Note
+    /// Coverage that is not bounded (e.g. both frames have the same ephemeris center) starts at `Duration::MIN` and/or ends at
+    /// `Duration::MAX` past the TDB reference epoch.
+    pub fn ephemeris_coverage(&self, target: Frame, observer: Frame) -> Vec<(Epoch, Epoch)> {
+        let boundaries_et_s = self
+            .spk_data
+            .iter()
+            .take(self.num_loaded_spk())
+            .flatten()
+            .flat_map(|spk| spk.data_summaries().unwrap_or(&[]).iter())
+            .filter(|summary| !summary.is_empty())
+            .flat_map(|summary| [summary.start_epoch_et_s, summary.end_epoch_et_s])
+            .collect();
+
+        coverage_intervals(boundaries_et_s, |epoch| {
+            self.common_ephemeris_path(observer, target, epoch).is_ok()
+        })
+    }
+
+    /// Returns the time intervals, in chronological order and merged when they touch, over which the rotation between the from
+    /// and the to frames can be computed.
+    ///
+    /// This follows the same algorithm as [Self::ephemeris_coverage] using the boundaries of the loaded BPC segments. The orientations
+    /// defined by the planetary data and the Euler parameters are not bounded in time.
+    pub fn orientation_coverage(&self, from_frame: Frame, to_frame: Frame) -> Vec<(Epoch, Epoch)> {
+        let boundaries_et_s = self
+            .bpc_data
+            .iter()
+            .take(self.num_loaded_bpc())
+            .flatten()
+            .flat_map(|bpc| bpc.data_summaries().unwrap_or(&[]).iter())
+            .filter(|summary| !summary.is_empty())
+            .flat_map(|summary| [summary.start_epoch_et_s, summary.end_epoch_et_s])
+            .collect();
+
+        coverage_intervals(boundaries_et_s, |epoch| {
+            self.common_orientation_path(from_frame, to_frame, epoch)
+                .is_ok()
+        })
+    }
+}
+
+/// Returns the merged intervals between the provided boundaries (in ET seconds) over which `is_covered` is true in the middle.
+///
+/// The spans before the first boundary and after the last one are checked one second past that boundary, and are unbounded if covered.
+fn coverage_intervals(
+    mut boundaries_et_s: Vec<f64>,
+    is_covered: impl Fn(Epoch) -> bool,
+) -> Vec<(Epoch, Epoch)> {
+    boundaries_et_s.sort_by(|a, b| a.total_cmp(b));
+    boundaries_et_s.dedup();
+
+    let (first_et_s, last_et_s) = match (boundaries_et_s.first(), boundaries_et_s.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        // Nothing bounds the coverage: either nothing is available or the data is not time dependent.
+        _ => {
+            return if is_covered(Epoch::from_tdb_duration(Duration::ZERO)) {
+                vec![(
+                    Epoch::from_tdb_duration(Duration::MIN),
+                    Epoch::from_tdb_duration(Duration::MAX),
+                )]
+            } else {
+                Vec::new()
+            };
+        }
+    };
+
+    let mut spans = Vec::with_capacity(boundaries_et_s.len() + 1);
+    spans.push((f64::NEG_INFINITY, first_et_s, first_et_s - 1.0));
+    for pair in boundaries_et_s.windows(2) {
+        spans.push((pair[0], pair[1], 0.5 * (pair[0] + pair[1])));
+    }
+    spans.push((last_et_s, f64::INFINITY, last_et_s + 1.0));
+
+    let mut intervals: Vec<(f64, f64)> = Vec::new();
+    for (start_et_s, end_et_s, probe_et_s) in spans {
+        if !is_covered(Epoch::from_et_seconds(probe_et_s)) {
+            continue;
+        }
+        match intervals.last_mut() {
+            // Contiguous with the previous span, so extend it.
+            Some(last) if last.1 == start_et_s => last.1 = end_et_s,
+            _ => intervals.push((start_et_s, end_et_s)),
+        }
+    }
+
+    let to_epoch = |et_s: f64| {
+        if et_s == f64::NEG_INFINITY {
+            Epoch::from_tdb_duration(Duration::MIN)
+        } else if et_s == f64::INFINITY {
+            Epoch::from_tdb_duration(Duration::MAX)
+        } else {
+            Epoch::from_et_seconds(et_s)
+        }
+    };
+
+    intervals
+        .into_iter()
+        .map(|(start_et_s, end_et_s)| (to_epoch(start_et_s), to_epoch(end_et_s)))
+        .collect()
+}
+
+#[cfg(test)]
+mod ut_coverage {
+    use super::coverage_intervals;
+    use hifitime::{Duration, Epoch};
+
+    #[test]
+    fn merge_and_gaps() {
+        // Covered over [0, 10] and [20, 40], with a boundary in the middle of the second span.
+        let covered = |epoch: Epoch| {
+            let et_s = epoch.to_et_seconds();
+            (0.0..=10.0).contains(&et_s) || (20.0..=40.0).contains(&et_s)
+        };
+        let intervals = coverage_intervals(vec![30.0, 0.0, 10.0, 20.0, 40.0, 20.0], covered);
+        assert_eq!(
+            intervals,
+            vec![
+                (Epoch::from_et_seconds(0.0), Epoch::from_et_seconds(10.0)),
+                (Epoch::from_et_seconds(20.0), Epoch::from_et_seconds(40.0))
+            ]
+        );
+
+        assert!(coverage_intervals(vec![], |_| false).is_empty());
+
+        // Always covered, regardless of the boundaries.
+        let unbounded = vec![(
+            Epoch::from_tdb_duration(Duration::MIN),
+            Epoch::from_tdb_duration(Duration::MAX),
+        )];
+        assert_eq!(coverage_intervals(vec![], |_| true), unbounded);
+        assert_eq!(coverage_intervals(vec![10.0, 20.0], |_| true), unbounded);
+    }
+}
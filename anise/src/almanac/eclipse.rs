@@ -8,14 +8,16 @@
  * Documentation: https://nyxspace.com/
  */
 
+use hifitime::Epoch;
 use log::error;
 
 use crate::{
-    astro::{Aberration, Occultation},
+    astro::{Aberration, Occultation, OccultationState},
     constants::{frames::SUN_J2000, orientations::J2000},
     ephemerides::EphemerisPhysicsSnafu,
     errors::{AlmanacError, EphemerisSnafu, OrientationSnafu},
     frames::Frame,
+    math::{Matrix3, Vector3},
     prelude::Orbit,
 };
 
@@ -272,6 +274,150 @@ impl Almanac {
         }
     }
 
+    /// Classifies the occultation of the `back_frame` object by the `front_frame` object as seen from the observer frame at the provided
+    /// epoch, analogous to SPICE's `occult` with ellipsoid shapes.
+    ///
+    /// For a solar eclipse, the back frame is the Sun (e.g. a lunar eclipse is a total occultation of the Sun by the Earth as seen
+    /// from the Moon). For a transit, the front frame is the transiting body (e.g. a transit of Phobos across the Sun as seen from Mars
+    /// is an annular occultation).
+    ///
+    /// The position of each object is corrected with the provided aberration, which should include light time corrections (e.g.
+    /// `Aberration::LT`) for the objects to be seen where they were when the light left them. The orientation of each ellipsoid is
+    /// also computed at that light time corrected epoch.
+    ///
+    /// # Algorithm
+    /// 1. The shape of each object is projected onto the plane orthogonal to its line of sight, i.e. the apparent disk of each ellipsoid
+    ///    is an ellipse, whose smallest and largest angular radii bound that disk. This orthographic projection assumes that the
+    ///    objects are far from the observer compared to their size.
+    /// 2. If the angular separation between both centers is greater than the sum of the angular extent of both disks in the direction
+    ///    of the other object, then the disks do not overlap and there is no occultation.
+    /// 3. If the back disk fits inside the smallest radius of the front disk, the occultation is total; conversely, if the front disk
+    ///    fits inside the smallest radius of the back disk, the occultation is annular. Otherwise, it is partial.
+    ///
+    /// # Note
+    /// The ellipsoid is oriented by the orientation of the provided frame: use the body fixed frame of the object (e.g. `IAU_EARTH`)
+    /// for the oblateness to be accounted for correctly.
+    ///
+    /// # Errors
+    /// + The shape of both objects must be available in the loaded planetary data, or in the provided frames.
+    pub fn occultation_state(
+        &self,
+        back_frame: Frame,
+        front_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<OccultationState> {
+        let back = self.apparent_disk(back_frame, observer_frame, epoch, ab_corr)?;
+        let front = self.apparent_disk(front_frame, observer_frame, epoch, ab_corr)?;
+
+        if front.distance_km >= back.distance_km {
+            // The front object is behind the back object, so it cannot hide it.
+            return Ok(OccultationState::None);
+        }
+
+        let separation_rad = back.direction.angle(&front.direction);
+
+        let (back_toward_front_rad, front_toward_back_rad) = if separation_rad > f64::EPSILON {
+            (
+                back.angular_radius_toward(front.direction),
+                front.angular_radius_toward(back.direction),
+            )
+        } else {
+            (back.max_angular_radius_rad, front.max_angular_radius_rad)
+        };
+
+        if separation_rad >= back_toward_front_rad + front_toward_back_rad {
+            Ok(OccultationState::None)
+        } else if separation_rad + back.max_angular_radius_rad <= front.min_angular_radius_rad {
+            Ok(OccultationState::Total)
+        } else if separation_rad + front.max_angular_radius_rad <= back.min_angular_radius_rad {
+            Ok(OccultationState::Annular)
+        } else {
+            Ok(OccultationState::Partial)
+        }
+    }
+
+    /// Returns the apparent disk of the ellipsoid of this frame as seen from the observer, in the J2000 orientation.
+    fn apparent_disk(
+        &self,
+        frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<ApparentDisk> {
+        let (frame, shape) = self.frame_with_shape(frame, "computing occultation state")?;
+
+        let position_km = self
+            .translate(frame.with_orient(J2000), observer_frame, epoch, ab_corr)
+            .context(EphemerisSnafu {
+                action: "computing occultation state",
+            })?
+            .radius_km;
+
+        // The object is oriented where it was when the light left it (or will be when the light reaches it).
+        let body_epoch = match ab_corr {
+            Some(ab_corr) => {
+                let (one_way_lt, _) = self
+                    .one_way_light_time(frame, observer_frame, epoch, ab_corr)
+                    .context(EphemerisSnafu {
+                        action: "computing light time for occultation state",
+                    })?;
+                if ab_corr.transmit_mode {
+                    epoch + one_way_lt
+                } else {
+                    epoch - one_way_lt
+                }
+            }
+            None => epoch,
+        };
+
+        let j2000_to_body = self
+            .rotate(frame.with_orient(J2000), frame, body_epoch)
+            .context(OrientationSnafu {
+                action: "computing occultation state",
+            })?
+            .rot_mat;
+
+        let semi_axes_km = Matrix3::from_diagonal(&Vector3::new(
+            shape.semi_major_equatorial_radius_km,
+            shape.semi_minor_equatorial_radius_km,
+            shape.polar_radius_km,
+        ));
+        let semi_axes_sq = j2000_to_body.transpose() * semi_axes_km * semi_axes_km * j2000_to_body;
+
+        let distance_km = position_km.norm();
+        let direction = position_km / distance_km;
+
+        // Build a basis of the plane orthogonal to the line of sight, using the axis least aligned with that line.
+        let least_aligned = if direction.x.abs() < 0.5 {
+            Vector3::x()
+        } else if direction.y.abs() < 0.5 {
+            Vector3::y()
+        } else {
+            Vector3::z()
+        };
+        let e1 = direction.cross(&least_aligned).normalize();
+        let e2 = direction.cross(&e1);
+
+        // The squared radii of the projected ellipse are the eigenvalues of the 2x2 projection of the ellipsoid.
+        let q11 = e1.dot(&(semi_axes_sq * e1));
+        let q22 = e2.dot(&(semi_axes_sq * e2));
+        let q12 = e1.dot(&(semi_axes_sq * e2));
+        let mean = 0.5 * (q11 + q22);
+        let spread = (0.25 * (q11 - q22).powi(2) + q12.powi(2)).sqrt();
+
+        let angular_radius = |radius_km: f64| (radius_km / distance_km).min(1.0).asin();
+
+        Ok(ApparentDisk {
+            direction,
+            distance_km,
+            semi_axes_sq,
+            min_angular_radius_rad: angular_radius((mean - spread).max(0.0).sqrt()),
+            max_angular_radius_rad: angular_radius((mean + spread).sqrt()),
+        })
+    }
+
     /// Computes the solar eclipsing of the observer due to the eclipsing_frame.
     ///
     /// This function calls `occultation` where the back object is the Sun in the J2000 frame, and the front object
@@ -311,6 +457,28 @@ impl Almanac {
     }
 }
 
+/// Apparent disk of an ellipsoid as seen from an observer, in the J2000 orientation.
+struct ApparentDisk {
+    /// Unit vector from the observer to the center of the object
+    direction: Vector3,
+    /// Distance from the observer to the center of the object
+    distance_km: f64,
+    /// Ellipsoid matrix whose quadratic form is the squared extent of the ellipsoid along a direction
+    semi_axes_sq: Matrix3,
+    min_angular_radius_rad: f64,
+    max_angular_radius_rad: f64,
+}
+
+impl ApparentDisk {
+    /// Returns the angular extent of this disk from its center toward the provided direction of another object.
+    fn angular_radius_toward(&self, other_direction: Vector3) -> f64 {
+        let towards =
+            (other_direction - self.direction * self.direction.dot(&other_direction)).normalize();
+        let radius_km = towards.dot(&(self.semi_axes_sq * towards)).sqrt();
+        (radius_km / self.distance_km).min(1.0).asin()
+    }
+}
+
 /// Compute the area of the circular segment of radius r and chord length d
 fn circ_seg_area(r: f64, d: f64) -> f64 {
     r.powi(2) * (d / r).acos() - d * (r.powi(2) - d.powi(2)).sqrt()
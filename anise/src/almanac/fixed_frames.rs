@@ -0,0 +1,227 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::{Epoch, Unit};
+use snafu::ResultExt;
+
+use super::Almanac;
+use crate::constants::orientations::J2000;
+use crate::ephemerides::{EphemerisError, FixedFrameRotationSnafu};
+use crate::errors::{AlmanacError, AlmanacResult};
+use crate::math::rotation::DCM;
+use crate::math::{Matrix3, Vector3};
+use crate::naif::daf::NAIFSummaryRecord;
+use crate::prelude::Frame;
+use crate::NaifId;
+
+/// First NAIF ID assigned to the frames added at runtime, in the range that NAIF reserves for user defined frames.
+pub const FIXED_FRAME_FIRST_ID: NaifId = 1_400_000;
+/// Last NAIF ID which may be assigned to the frames added at runtime.
+pub const FIXED_FRAME_LAST_ID: NaifId = 2_000_000;
+
+/// A frame whose origin and axes are fixed with respect to a parent frame, typically the body fixed frame of a planet (e.g. a
+/// ground station), and which is added to an Almanac at runtime instead of being loaded from a kernel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FixedFrame {
+    /// Name of this frame
+    pub name: String,
+    /// Frame in which this frame is fixed: its ephemeris ID is the center of this frame, and its orientation ID is the parent of its axes.
+    pub parent: Frame,
+    /// Position of the origin of this frame in the parent frame, in km
+    pub offset_km: Vector3,
+    /// Rotation matrix from this frame into the parent frame
+    pub rot_mat: Matrix3,
+}
+
+impl Almanac {
+    /// Adds a topocentric frame to this Almanac, centered at the provided geodetic latitude and longitude in degrees and height in km
+    /// with respect to the ellipsoid of the parent body fixed frame, and whose axes are South, East, and Zenith (SEZ) as in
+    /// [Self::azimuth_elevation_range_sez]. The returned frame can then be used in any translation, rotation, or transformation.
+    ///
+    /// # Errors
+    /// + The shape of the parent frame must be available in the loaded planetary data, or in the parent frame itself.
+    /// + Refer to [Self::add_fixed_frame].
+    pub fn add_topocentric_frame(
+        &mut self,
+        name: &str,
+        parent_frame: Frame,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        height_km: f64,
+    ) -> AlmanacResult<Frame> {
+        let parent_frame = self.frame_from_uid(parent_frame).unwrap_or(parent_frame);
+
+        let offset_km = parent_frame
+            .geodetic_to_cartesian(latitude_deg, longitude_deg, height_km)
+            .map_err(|e| AlmanacError::GenericError {
+                err: format!("{e} when computing the origin of topocentric frame {name}"),
+            })?;
+
+        // The zenith is the normal to the ellipsoid, so it's defined by the geodetic latitude and longitude.
+        let (sin_lat, cos_lat) = latitude_deg.to_radians().sin_cos();
+        let (sin_long, cos_long) = longitude_deg.to_radians().sin_cos();
+        let zenith = Vector3::new(cos_lat * cos_long, cos_lat * sin_long, sin_lat);
+        let east = Vector3::new(-sin_long, cos_long, 0.0);
+        let south = east.cross(&zenith);
+
+        self.add_fixed_frame(
+            name,
+            parent_frame,
+            offset_km,
+            Matrix3::from_columns(&[south, east, zenith]),
+        )
+    }
+
+    /// Adds a frame to this Almanac whose origin is at the provided offset in km in the parent frame, and whose axes are rotated from
+    /// the parent frame by the provided rotation matrix, which rotates vectors from the new frame into the parent frame.
+    ///
+    /// The new frame is assigned the first available NAIF ID starting at [FIXED_FRAME_FIRST_ID], used as both its ephemeris and
+    /// orientation IDs, and it is kept when loading or unloading kernels.
+    ///
+    /// # Errors
+    /// + The rotation matrix must be orthonormal.
+    /// + There are more than a half million IDs available, so this only fails if the loaded data uses all of them.
+    pub fn add_fixed_frame(
+        &mut self,
+        name: &str,
+        parent_frame: Frame,
+        offset_km: Vector3,
+        rot_mat: Matrix3,
+    ) -> AlmanacResult<Frame> {
+        if ((rot_mat * rot_mat.transpose()) - Matrix3::identity()).norm() > 1e-9
+            || (rot_mat.determinant() - 1.0).abs() > 1e-9
+        {
+            return Err(AlmanacError::GenericError {
+                err: format!("rotation of fixed frame {name} is not orthonormal: {rot_mat}"),
+            });
+        }
+
+        let id = (FIXED_FRAME_FIRST_ID..=FIXED_FRAME_LAST_ID)
+            .find(|id| !self.uses_naif_id(*id))
+            .ok_or(AlmanacError::GenericError {
+                err: format!("no NAIF ID available to add fixed frame {name}"),
+            })?;
+
+        self.fixed_frames.insert(
+            id,
+            FixedFrame {
+                name: name.to_string(),
+                parent: parent_frame,
+                offset_km,
+                rot_mat,
+            },
+        );
+
+        // The paths between frames may now go through this frame.
+        self.path_cache.clear();
+
+        Ok(Frame::new(id, id))
+    }
+
+    /// Returns true if this NAIF ID is used by any of the loaded data.
+    fn uses_naif_id(&self, id: NaifId) -> bool {
+        if self.fixed_frames.contains_key(&id)
+            || self.planetary_data.get_by_id(id).is_ok()
+            || self.euler_param_data.get_by_id(id).is_ok()
+        {
+            return true;
+        }
+
+        let in_spks = self
+            .spk_data
+            .iter()
+            .take(self.num_loaded_spk())
+            .flatten()
+            .flat_map(|spk| spk.data_summaries().unwrap_or(&[]).iter())
+            .any(|summary| {
+                !summary.is_empty() && (summary.target_id == id || summary.center_id == id)
+            });
+
+        let in_bpcs = self
+            .bpc_data
+            .iter()
+            .take(self.num_loaded_bpc())
+            .flatten()
+            .flat_map(|bpc| bpc.data_summaries().unwrap_or(&[]).iter())
+            .any(|summary| {
+                !summary.is_empty() && (summary.frame_id == id || summary.inertial_frame_id == id)
+            });
+
+        in_spks || in_bpcs
+    }
+
+    /// Returns the position and velocity of this fixed frame with respect to its center, in the J2000 orientation, along with the
+    /// frame of its center.
+    pub(crate) fn fixed_frame_translation_parts(
+        &self,
+        source: Frame,
+        fixed: &FixedFrame,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3, Frame), EphemerisError> {
+        let dcm = self.fixed_frame_parent_to_j2000(source, fixed, epoch)?;
+
+        // The offset is constant in the parent frame, so its velocity is only due to the rotation of that frame.
+        let pos_km = dcm.rot_mat * fixed.offset_km;
+        let vel_km_s = dcm.rot_mat_dt.unwrap_or_else(Matrix3::zeros) * fixed.offset_km;
+
+        Ok((
+            pos_km,
+            vel_km_s,
+            source.with_ephem(fixed.parent.ephemeris_id),
+        ))
+    }
+
+    /// Returns the position, velocity, and acceleration of this fixed frame with respect to its center, in the J2000 orientation, along
+    /// with the frame of its center. The acceleration is computed by central finite differencing of the rotation rate over one second.
+    pub(crate) fn fixed_frame_translation_parts_with_acceleration(
+        &self,
+        source: Frame,
+        fixed: &FixedFrame,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3, Vector3, Frame), EphemerisError> {
+        let (pos_km, vel_km_s, frame) = self.fixed_frame_translation_parts(source, fixed, epoch)?;
+
+        let (_, vel_before_km_s, _) =
+            self.fixed_frame_translation_parts(source, fixed, epoch - Unit::Second * 1)?;
+        let (_, vel_after_km_s, _) =
+            self.fixed_frame_translation_parts(source, fixed, epoch + Unit::Second * 1)?;
+
+        Ok((
+            pos_km,
+            vel_km_s,
+            0.5 * (vel_after_km_s - vel_before_km_s),
+            frame,
+        ))
+    }
+
+    /// Returns the rotation between this fixed frame and its parent frame, from the parent into this frame like for the BPC and
+    /// planetary data orientations.
+    pub(crate) fn fixed_frame_rotation_to_parent(id: NaifId, fixed: &FixedFrame) -> DCM {
+        DCM {
+            rot_mat: fixed.rot_mat.transpose(),
+            rot_mat_dt: None,
+            from: fixed.parent.orientation_id,
+            to: id,
+        }
+    }
+
+    /// Returns the rotation from the parent of this fixed frame into the J2000 frame.
+    fn fixed_frame_parent_to_j2000(
+        &self,
+        source: Frame,
+        fixed: &FixedFrame,
+        epoch: Epoch,
+    ) -> Result<DCM, EphemerisError> {
+        self.rotate(fixed.parent, fixed.parent.with_orient(J2000), epoch)
+            .context(FixedFrameRotationSnafu {
+                id: source.ephemeris_id,
+            })
+    }
+}
@@ -15,7 +15,7 @@ use serde_derive::{Deserialize, Serialize};
 use serde_dhall::StaticType;
 use std::env;
 use std::fs::{create_dir_all, remove_file, File};
-use std::io::Write;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
 use std::path::Path;
 use std::thread;
 use std::time::Duration;
@@ -28,7 +28,6 @@ use pyo3::prelude::*;
 #[cfg(feature = "python")]
 use pyo3::pyclass::CompareOp;
 
-use crate::file2heap;
 use crate::prelude::InputOutputError;
 
 use super::MetaAlmanacError;
@@ -37,7 +36,7 @@ use super::MetaAlmanacError;
 ///
 /// The data is stored in the user's local temp directory (i.e. `~/.local/share/nyx-space/anise/` on Linux and `AppData/Local/nyx-space/anise/` on Windows).
 /// Prior to loading a remote resource, if the local resource exists, its CRC32 will be computed: if it matches the CRC32 of this instance of MetaFile,
-/// then the file will not be downloaded a second time.
+/// then the file will not be downloaded a second time. If the CRC32 is provided, the downloaded file must match it, or it is deleted and an error is returned.
 ///
 /// :type uri: str
 /// :type crc32: int, optional
@@ -48,7 +47,7 @@ use super::MetaAlmanacError;
 pub struct MetaFile {
     /// URI of this meta file
     pub uri: String,
-    /// Optionally specify the CRC32 of this file, which will be checked prior to loading.
+    /// Optionally specify the CRC32 of this file, which will be checked prior to loading and after downloading.
     pub crc32: Option<u32>,
 }
 
@@ -138,9 +137,9 @@ impl MetaFile {
                                         if dest_path.exists() {
                                             if let Some(crc32) = self.crc32 {
                                                 // Open the file and check the CRC32
-                                                let dest_path_c = dest_path.clone(); // macro token issue
-                                                if let Ok(bytes) = file2heap!(dest_path_c) {
-                                                    let computed_crc32 = crc32fast::hash(&bytes);
+                                                if let Ok(computed_crc32) =
+                                                    crc32_of_file(&dest_path)
+                                                {
                                                     let dest_path_s =
                                                         dest_path.to_str().unwrap().to_string();
                                                     if computed_crc32 == crc32 {
@@ -185,56 +184,31 @@ impl MetaFile {
                                         match client.get(self.uri.clone()).call() {
                                             Ok(mut resp) => {
                                                 if resp.status().is_success() {
-                                                    // Downloaded the file, let's store it locally.
-                                                    match File::create(&dest_path) {
-                                                        Err(e) => {
-                                                            del_lock_file();
-                                                            Err(MetaAlmanacError::MetaIO {
-                                                                path: dest_path
-                                                                    .to_str()
-                                                                    .unwrap()
-                                                                    .into(),
-                                                                what: "creating file for storage",
-                                                                source: InputOutputError::IOError {
-                                                                    kind: e.kind(),
-                                                                },
-                                                            })
-                                                        }
-                                                        Ok(mut file) => {
-                                                            // Created the file, let's write the bytes.
-                                                            let bytes = resp
-                                                                .body_mut()
-                                                                .with_config()
-                                                                .limit(1024 * 1024 * 200) // 200 MB limit
-                                                                .read_to_vec()
-                                                                .map_err(|e| {
-                                                                    MetaAlmanacError::FetchError {
-                                                                        error: format!("{e:?}"),
-                                                                        uri: self.uri.clone(),
-                                                                    }
-                                                                })?;
-                                                            let crc32 = crc32fast::hash(&bytes);
-                                                            file.write_all(&bytes).unwrap();
-
-                                                            info!(
-                                                                 "Saved {url} to {} (CRC32 = 0x{crc32:x})",
-                                                                 dest_path.to_str().unwrap()
-                                                             );
-
-                                                            // Set the URI for loading
-                                                            self.uri = dest_path
-                                                                .to_str()
-                                                                .unwrap()
-                                                                .to_string();
-
-                                                            // Set the CRC32
-                                                            self.crc32 = Some(crc32);
-
-                                                            del_lock_file();
-
-                                                            Ok(())
-                                                        }
-                                                    }
+                                                    // Stream the download to the file, checking its CRC32 along the way.
+                                                    let reader = resp
+                                                        .body_mut()
+                                                        .with_config()
+                                                        .limit(1024 * 1024 * 200) // 200 MB limit
+                                                        .reader();
+                                                    let rslt = store_verified(
+                                                        reader, &dest_path, self.crc32, &self.uri,
+                                                    );
+                                                    del_lock_file();
+                                                    let crc32 = rslt?;
+
+                                                    info!(
+                                                        "Saved {url} to {} (CRC32 = 0x{crc32:x})",
+                                                        dest_path.to_str().unwrap()
+                                                    );
+
+                                                    // Set the URI for loading
+                                                    self.uri =
+                                                        dest_path.to_str().unwrap().to_string();
+
+                                                    // Set the CRC32
+                                                    self.crc32 = Some(crc32);
+
+                                                    Ok(())
                                                 } else {
                                                     del_lock_file();
                                                     Err(MetaAlmanacError::FetchError {
@@ -339,6 +313,70 @@ impl MetaFile {
     }
 }
 
+/// Streams all of the bytes of the reader into the writer, returning the CRC32 of these bytes.
+fn copy_with_crc32<R: Read, W: Write>(mut reader: R, mut writer: W) -> std::io::Result<u32> {
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0_u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => {
+                hasher.update(&buf[..len]);
+                writer.write_all(&buf[..len])?;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    writer.flush()?;
+    Ok(hasher.finalize())
+}
+
+/// Computes the CRC32 of the file without loading it all in memory.
+fn crc32_of_file(path: &Path) -> std::io::Result<u32> {
+    copy_with_crc32(BufReader::new(File::open(path)?), std::io::sink())
+}
+
+/// Stores the data of the reader in the destination path, and returns its CRC32.
+///
+/// If the expected CRC32 is provided and does not match, the stored file is deleted and an error is returned.
+fn store_verified<R: Read>(
+    reader: R,
+    dest_path: &Path,
+    expected_crc32: Option<u32>,
+    uri: &str,
+) -> Result<u32, MetaAlmanacError> {
+    let file = File::create(dest_path).map_err(|e| MetaAlmanacError::MetaIO {
+        path: dest_path.to_str().unwrap().into(),
+        what: "creating file for storage",
+        source: InputOutputError::IOError { kind: e.kind() },
+    })?;
+
+    let computed = match copy_with_crc32(reader, BufWriter::new(file)) {
+        Ok(computed) => computed,
+        Err(e) => {
+            // Do not leave a partial file behind.
+            let _ = remove_file(dest_path);
+            return Err(MetaAlmanacError::FetchError {
+                error: format!("{e}"),
+                uri: uri.to_string(),
+            });
+        }
+    };
+
+    match expected_crc32 {
+        Some(expected) if expected != computed => {
+            let _ = remove_file(dest_path);
+            Err(MetaAlmanacError::ChecksumMismatch {
+                uri: uri.to_string(),
+                expected,
+                computed,
+            })
+        }
+        _ => Ok(computed),
+    }
+}
+
 fn replace_env_vars(input: &str) -> String {
     let re = Regex::new(r"env:([A-Z_][A-Z0-9_]*)").unwrap();
     re.replace_all(input, |caps: &regex::Captures| {
@@ -350,7 +388,11 @@ fn replace_env_vars(input: &str) -> String {
 
 #[cfg(test)]
 mod ut_metafile {
-    use super::MetaFile;
+    use super::{store_verified, MetaFile};
+    use crate::almanac::metaload::MetaAlmanacError;
+    use platform_dirs::AppDirs;
+    use std::env;
+    use std::fs::{create_dir_all, read, remove_file, write};
 
     #[test]
     fn abs_paths() {
@@ -403,4 +445,81 @@ mod ut_metafile {
             "env:BLAH_BLAH_NO_EXIST/.cargo/env".to_string()
         );
     }
+
+    #[test]
+    fn crc32_cache_hit() {
+        let data_dir = AppDirs::new(Some("nyx-space/anise"), true)
+            .unwrap()
+            .data_dir;
+        create_dir_all(&data_dir).unwrap();
+        let cached_path = data_dir.join("anise-ut-crc32-cache-hit.bin");
+
+        // The cache is the user's data directory, so the cached file (and any lock file) is removed even if the test fails.
+        struct RemoveOnDrop(std::path::PathBuf);
+        impl Drop for RemoveOnDrop {
+            fn drop(&mut self) {
+                let _ = remove_file(&self.0);
+                let _ = remove_file(self.0.with_extension("bin.lock"));
+            }
+        }
+        let _cleanup = RemoveOnDrop(cached_path.clone());
+
+        let bytes = b"cached ANISE data";
+        write(&cached_path, bytes).unwrap();
+
+        // Nothing listens on this port, so the file must be loaded from cache.
+        let mut cached = MetaFile {
+            uri: "http://127.0.0.1:9/anise-ut-crc32-cache-hit.bin".to_string(),
+            crc32: Some(crc32fast::hash(bytes)),
+        };
+        cached.process(false).unwrap();
+        assert_eq!(cached.uri, cached_path.to_str().unwrap());
+
+        // If the cached copy does not match, it is not used and the download is attempted.
+        let mut corrupted = MetaFile {
+            uri: "http://127.0.0.1:9/anise-ut-crc32-cache-hit.bin".to_string(),
+            crc32: Some(crc32fast::hash(bytes) ^ 0xFF),
+        };
+        assert!(matches!(
+            corrupted.process(false),
+            Err(MetaAlmanacError::CnxError { .. })
+        ));
+    }
+
+    #[test]
+    fn crc32_mismatch() {
+        let path = env::temp_dir().join("anise-ut-crc32-mismatch.bin");
+        let bytes = b"downloaded ANISE data";
+        let expected = crc32fast::hash(bytes) ^ 0xFF;
+
+        assert_eq!(
+            store_verified(&bytes[..], &path, Some(expected), "test"),
+            Err(MetaAlmanacError::ChecksumMismatch {
+                uri: "test".to_string(),
+                expected,
+                computed: crc32fast::hash(bytes),
+            })
+        );
+        // Corrupted data must not be left behind.
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn crc32_missing() {
+        let path = env::temp_dir().join("anise-ut-crc32-missing.bin");
+        // Larger than the copy buffer to check the streaming.
+        let bytes: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+
+        let computed = store_verified(&bytes[..], &path, None, "test").unwrap();
+        assert_eq!(computed, crc32fast::hash(&bytes));
+        assert_eq!(read(&path).unwrap(), bytes);
+
+        // And with the correct CRC32
+        assert_eq!(
+            store_verified(&bytes[..], &path, Some(computed), "test"),
+            Ok(computed)
+        );
+
+        remove_file(&path).unwrap();
+    }
 }
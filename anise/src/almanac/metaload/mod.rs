@@ -44,6 +44,14 @@ pub enum MetaAlmanacError {
     },
     #[snafu(display("fetching {uri} returned {error}"))]
     FetchError { error: String, uri: String },
+    #[snafu(display(
+        "CRC32 of {uri} is 0x{computed:x} but 0x{expected:x} was expected (data corrupted?)"
+    ))]
+    ChecksumMismatch {
+        uri: String,
+        expected: u32,
+        computed: u32,
+    },
     #[snafu(display("connection {uri} returned {error}"))]
     CnxError { uri: String, error: String },
     #[snafu(display("error parsing `{path}` as Dhall config: {err}"))]
@@ -9,24 +9,34 @@
  */
 
 use bytes::Bytes;
-use hifitime::TimeScale;
+use hifitime::{Duration, TimeScale};
 use log::info;
+use memmap2::MmapOptions;
 use snafu::ResultExt;
 use zerocopy::FromBytes;
 
 use crate::ephemerides::SPKSnafu;
 use crate::errors::{
-    AlmanacError, AlmanacResult, EphemerisSnafu, LoadingSnafu, OrientationSnafu, TLDataSetSnafu,
+    AlmanacError, AlmanacResult, EphemerisSnafu, FrameNameSnafu, InputOutputError,
+    LoadingDataSnafu, LoadingSnafu, OrientationSnafu, TLDataSetSnafu,
 };
 use crate::file2heap;
 use crate::naif::daf::{FileRecord, NAIFRecord};
+use crate::naif::kpl::parser::Assignment;
 use crate::naif::pretty_print::NAIFPrettyPrint;
 use crate::naif::{BPC, SPK};
 use crate::orientations::BPCSnafu;
 use crate::structure::dataset::DataSetType;
 use crate::structure::metadata::Metadata;
 use crate::structure::{EulerParameterDataSet, PlanetaryDataSet, SpacecraftDataSet};
+use crate::NaifId;
 use core::fmt;
+use fixed_frames::FixedFrame;
+use names::{body_definitions, NaifIds};
+use path_cache::PathCache;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
 
 // TODO: Switch these to build constants so that it's configurable when building the library.
 pub const MAX_LOADED_SPKS: usize = 32;
@@ -35,12 +45,26 @@ pub const MAX_SPACECRAFT_DATA: usize = 16;
 pub const MAX_PLANETARY_DATA: usize = 128;
 
 pub mod aer;
+pub mod angles;
+#[cfg(feature = "zip")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+pub mod archive;
 pub mod bpc;
+pub mod coverage;
 pub mod eclipse;
+pub mod fixed_frames;
+pub mod names;
+pub mod path_cache;
 pub mod planetary;
+pub mod remap;
 pub mod solar;
 pub mod spk;
+pub mod subset;
+pub mod summary;
+pub mod surface;
 pub mod transform;
+pub mod validate;
+pub mod visibility;
 
 #[cfg(feature = "metaload")]
 pub mod metaload;
@@ -73,6 +97,36 @@ pub struct Almanac {
     pub spacecraft_data: SpacecraftDataSet,
     /// Dataset of euler parameters
     pub euler_param_data: EulerParameterDataSet,
+    /// Cache of the paths between frames, emptied whenever new data is loaded
+    pub path_cache: PathCache,
+    /// Frames added at runtime, e.g. topocentric frames, indexed by their NAIF ID
+    pub fixed_frames: HashMap<NaifId, FixedFrame>,
+    /// Names of the bodies, from the standard NAIF list and the `NAIF_BODY_CODE`/`NAIF_BODY_NAME` definitions of the loaded text kernels
+    pub naif_ids: NaifIds,
+    /// Path from which each SPK was loaded, if it was loaded from a file
+    pub(crate) spk_sources: [Option<String>; MAX_LOADED_SPKS],
+    /// Path from which each BPC was loaded, if it was loaded from a file
+    pub(crate) bpc_sources: [Option<String>; MAX_LOADED_BPCS],
+    /// Path from which the planetary data was loaded, if it was loaded from a file
+    pub(crate) planetary_source: Option<String>,
+    /// Path from which the spacecraft data was loaded, if it was loaded from a file
+    pub(crate) spacecraft_source: Option<String>,
+    /// Path from which the Euler parameters were loaded, if they were loaded from a file
+    pub(crate) euler_param_source: Option<String>,
+    /// Longest gap between the segments of the same ephemeris ID which is bridged by extrapolation, cf. [Self::with_gap_tolerance]
+    pub(crate) gap_tolerance: Option<Duration>,
+}
+
+/// Data parsed from a file or bytes, prior to being added to an Almanac.
+#[allow(clippy::large_enum_variant)]
+enum LoadedData {
+    SPK(SPK),
+    BPC(BPC),
+    PlanetaryData(PlanetaryDataSet),
+    SpacecraftData(SpacecraftDataSet),
+    EulerParameterData(EulerParameterDataSet),
+    /// Body definitions of a text kernel
+    BodyNames(Vec<Assignment>),
 }
 
 impl fmt::Display for Almanac {
@@ -106,6 +160,7 @@ impl Almanac {
     pub fn with_spacecraft_data(&self, spacecraft_data: SpacecraftDataSet) -> Self {
         let mut me = self.clone();
         me.spacecraft_data = spacecraft_data;
+        me.spacecraft_source = None;
         me
     }
 
@@ -113,6 +168,7 @@ impl Almanac {
     pub fn with_euler_parameters(&self, ep_dataset: EulerParameterDataSet) -> Self {
         let mut me = self.clone();
         me.euler_param_data = ep_dataset;
+        me.euler_param_source = None;
         me
     }
 
@@ -122,6 +178,11 @@ impl Almanac {
     }
 
     fn _load_from_bytes(&self, bytes: Bytes, path: Option<&str>) -> AlmanacResult<Self> {
+        self.with_loaded_data(Self::parse_bytes(bytes, path)?, path)
+    }
+
+    /// Parses the provided bytes as one of the data types supported in ANISE, without adding them to an Almanac.
+    fn parse_bytes(bytes: Bytes, path: Option<&str>) -> AlmanacResult<LoadedData> {
         // Try to load as a SPICE DAF first (likely the most typical use case)
 
         // Load the header only
@@ -138,9 +199,7 @@ impl Almanac {
                             .context(OrientationSnafu {
                                 action: "from generic loading",
                             })?;
-                        self.with_bpc(bpc).context(OrientationSnafu {
-                            action: "adding BPC file to context",
-                        })
+                        Ok(LoadedData::BPC(bpc))
                     }
                     "SPK" => {
                         info!("Loading {} as DAF/SPK", path.unwrap_or("bytes"));
@@ -151,9 +210,7 @@ impl Almanac {
                             .context(EphemerisSnafu {
                                 action: "from generic loading",
                             })?;
-                        self.with_spk(spk).context(EphemerisSnafu {
-                            action: "adding SPK file to context",
-                        })
+                        Ok(LoadedData::SPK(spk))
                     }
                     fileid => Err(AlmanacError::GenericError {
                         err: format!("DAF/{fileid} is not yet supported"),
@@ -163,6 +220,11 @@ impl Almanac {
             // Fall through to try to load as an ANISE file
         }
 
+        if let Some(assignments) = body_definitions(&bytes) {
+            info!("Loading {} as KPL body names", path.unwrap_or("bytes"));
+            return Ok(LoadedData::BodyNames(assignments));
+        }
+
         if let Ok(metadata) = Metadata::decode_header(&bytes) {
             // Use `try_from` to validate the dataset type
             let dataset_type =
@@ -191,7 +253,7 @@ impl Almanac {
                         "Loading {} as ANISE spacecraft data",
                         path.unwrap_or("bytes")
                     );
-                    Ok(self.with_spacecraft_data(dataset))
+                    Ok(LoadedData::SpacecraftData(dataset))
                 }
                 DataSetType::PlanetaryData => {
                     // Decode as planetary data
@@ -201,7 +263,7 @@ impl Almanac {
                         }
                     })?;
                     info!("Loading {} as ANISE/PCA", path.unwrap_or("bytes"));
-                    Ok(self.with_planetary_data(dataset))
+                    Ok(LoadedData::PlanetaryData(dataset))
                 }
                 DataSetType::EulerParameterData => {
                     // Decode as euler parameter data
@@ -211,7 +273,7 @@ impl Almanac {
                         }
                     })?;
                     info!("Loading {} as ANISE/EPA", path.unwrap_or("bytes"));
-                    Ok(self.with_euler_parameters(dataset))
+                    Ok(LoadedData::EulerParameterData(dataset))
                 }
             }
         } else {
@@ -221,6 +283,83 @@ impl Almanac {
         }
     }
 
+    /// Adds the parsed data into a clone of this Almanac, recording the path of the SPK and BPC files so they can be unloaded.
+    fn with_loaded_data(&self, data: LoadedData, path: Option<&str>) -> AlmanacResult<Self> {
+        match data {
+            LoadedData::SPK(spk) => {
+                let mut me = self.with_spk(spk).context(EphemerisSnafu {
+                    action: "adding SPK file to context",
+                })?;
+                me.spk_sources[me.num_loaded_spk() - 1] = path.map(|path| path.to_string());
+                Ok(me)
+            }
+            LoadedData::BPC(bpc) => {
+                let mut me = self.with_bpc(bpc).context(OrientationSnafu {
+                    action: "adding BPC file to context",
+                })?;
+                me.bpc_sources[me.num_loaded_bpc() - 1] = path.map(|path| path.to_string());
+                Ok(me)
+            }
+            LoadedData::PlanetaryData(dataset) => {
+                let mut me = self.with_planetary_data(dataset);
+                me.planetary_source = path.map(|path| path.to_string());
+                Ok(me)
+            }
+            LoadedData::SpacecraftData(dataset) => {
+                let mut me = self.with_spacecraft_data(dataset);
+                me.spacecraft_source = path.map(|path| path.to_string());
+                Ok(me)
+            }
+            LoadedData::EulerParameterData(dataset) => {
+                let mut me = self.with_euler_parameters(dataset);
+                me.euler_param_source = path.map(|path| path.to_string());
+                Ok(me)
+            }
+            LoadedData::BodyNames(assignments) => {
+                let mut me = self.clone();
+                me.naif_ids.apply(&assignments).context(FrameNameSnafu)?;
+                Ok(me)
+            }
+        }
+    }
+
+    /// Loads all of the provided paths into a clone of this Almanac, guessing at the file type of each.
+    ///
+    /// As with [Self::load], each file is memory mapped and its data is copied onto the heap (cf. [file2heap!](crate::file2heap)), so
+    /// the returned Almanac does not depend on the files remaining unchanged. Use [Self::load_mmap] to keep the mapping instead.
+    ///
+    /// When the `rayon` feature is enabled, the files are read and parsed in parallel. In all cases, the parsed data is added to the
+    /// Almanac in the order of the provided paths, exactly as if each path was loaded in turn with [Self::load]. Therefore, when several
+    /// files define the same data (e.g. the same SPK target), the last one in the list takes precedence regardless of thread scheduling.
+    ///
+    /// Any error is attributed to the path of the file that caused it, and the first error in the order of the paths is returned.
+    pub fn load_many(&self, paths: &[PathBuf]) -> AlmanacResult<Self> {
+        let parse = |path: &PathBuf| -> AlmanacResult<LoadedData> {
+            let path_s = path.to_string_lossy().to_string();
+            let bytes = file2heap!(path).context(LoadingSnafu {
+                path: path_s.clone(),
+            })?;
+            Self::parse_bytes(bytes, Some(&path_s)).context(LoadingDataSnafu { path: path_s })
+        };
+
+        #[cfg(feature = "rayon")]
+        let parsed = {
+            use rayon::prelude::*;
+            paths.par_iter().map(parse).collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let parsed = paths.iter().map(parse).collect::<Vec<_>>();
+
+        let mut me = self.clone();
+        for (path, data) in paths.iter().zip(parsed) {
+            let path_s = path.to_string_lossy().to_string();
+            me = me
+                .with_loaded_data(data?, Some(&path_s))
+                .context(LoadingDataSnafu { path: path_s })?;
+        }
+        Ok(me)
+    }
+
     /// Generic function that tries to load the provided path guessing to the file type.
     pub fn load(&self, path: &str) -> AlmanacResult<Self> {
         // Load the data onto the heap
@@ -240,6 +379,165 @@ impl Almanac {
             })
     }
 
+    /// Memory maps the provided file and loads it into a clone of this Almanac, exactly as [Self::load], but without copying the SPK and BPC
+    /// files onto the heap: their summaries are read from the memory map, and the data of their segments is only read from the file when
+    /// these segments are evaluated, which suits very large kernels on memory constrained machines. The ANISE data sets are decoded as usual.
+    ///
+    /// # Safety of the memory map
+    /// The file must not be modified or truncated while it is loaded, since the data is read directly from it.
+    pub fn load_mmap(&self, path: &str) -> AlmanacResult<Self> {
+        let file = File::open(path)
+            .map_err(|e| InputOutputError::IOError { kind: e.kind() })
+            .context(LoadingSnafu {
+                path: path.to_string(),
+            })?;
+        // SAFETY: the file is mapped read only, and the data is only valid as long as the file is unchanged (cf. the docs).
+        let mmap = unsafe { MmapOptions::new().map(&file) }
+            .map_err(|_| InputOutputError::IOUnknownError)
+            .context(LoadingSnafu {
+                path: path.to_string(),
+            })?;
+
+        // Only read the file record to check the kind of DAF, and otherwise fall back onto the generic loading.
+        let fileid = mmap
+            .get(..FileRecord::SIZE)
+            .and_then(|bytes| FileRecord::read_from_bytes(bytes).ok())
+            .and_then(|file_record| file_record.identification().ok().map(str::to_string));
+
+        let data = match fileid.as_deref() {
+            Some("PCK") => {
+                info!("Memory mapping {path} as DAF/PCK");
+                LoadedData::BPC(
+                    BPC::from_mmap(mmap)
+                        .context(BPCSnafu {
+                            action: "memory mapping",
+                        })
+                        .context(OrientationSnafu {
+                            action: "from memory mapped loading",
+                        })?,
+                )
+            }
+            Some("SPK") => {
+                info!("Memory mapping {path} as DAF/SPK");
+                LoadedData::SPK(
+                    SPK::from_mmap(mmap)
+                        .context(SPKSnafu {
+                            action: "memory mapping",
+                        })
+                        .context(EphemerisSnafu {
+                            action: "from memory mapped loading",
+                        })?,
+                )
+            }
+            _ => Self::parse_bytes(Bytes::from_owner(mmap), Some(path))?,
+        };
+
+        self.with_loaded_data(data, Some(path))
+    }
+
+    /// Unloads every SPK, BPC, and ANISE data set that was loaded from the provided source into a clone of this Almanac.
+    ///
+    /// The source is either the path used to load the file (e.g. with [Self::load] or [Self::load_many]), or the internal file name
+    /// of the DAF, which is how the files loaded from bytes or with [Self::with_spk] and [Self::with_bpc] are identified.
+    /// The ANISE data sets (planetary data, spacecraft data, and Euler parameters) are only identified by their path, and unloading
+    /// one leaves that data set empty, since each one replaces the previously loaded one of the same kind.
+    ///
+    /// # Overlapping data
+    /// The most recently loaded data takes precedence, so if another file provides the same segments as the one that is unloaded,
+    /// then the data of that other file is used again. The order of the remaining files is unchanged, and the cached paths are reset.
+    ///
+    /// # Errors
+    /// Returns an error if no SPK, BPC, nor ANISE data set matches that source.
+    pub fn unload(&self, source: &str) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+
+        let spks = self
+            .spk_data
+            .iter()
+            .zip(self.spk_sources.iter())
+            .take(self.num_loaded_spk())
+            .filter(|(spk, loaded_from)| {
+                !Self::is_source(
+                    spk.as_ref().unwrap().file_record().ok(),
+                    loaded_from,
+                    source,
+                )
+            });
+        let num_spks = spks.clone().count();
+        me.spk_data = Default::default();
+        me.spk_sources = Default::default();
+        for (idx, (spk, loaded_from)) in spks.enumerate() {
+            me.spk_data[idx] = spk.clone();
+            me.spk_sources[idx] = loaded_from.clone();
+        }
+
+        let bpcs = self
+            .bpc_data
+            .iter()
+            .zip(self.bpc_sources.iter())
+            .take(self.num_loaded_bpc())
+            .filter(|(bpc, loaded_from)| {
+                !Self::is_source(
+                    bpc.as_ref().unwrap().file_record().ok(),
+                    loaded_from,
+                    source,
+                )
+            });
+        let num_bpcs = bpcs.clone().count();
+        me.bpc_data = Default::default();
+        me.bpc_sources = Default::default();
+        for (idx, (bpc, loaded_from)) in bpcs.enumerate() {
+            me.bpc_data[idx] = bpc.clone();
+            me.bpc_sources[idx] = loaded_from.clone();
+        }
+
+        let mut num_datasets = 0;
+        if self.planetary_source.as_deref() == Some(source) {
+            me.planetary_data = Default::default();
+            me.planetary_source = None;
+            num_datasets += 1;
+        }
+        if self.spacecraft_source.as_deref() == Some(source) {
+            me.spacecraft_data = Default::default();
+            me.spacecraft_source = None;
+            num_datasets += 1;
+        }
+        if self.euler_param_source.as_deref() == Some(source) {
+            me.euler_param_data = Default::default();
+            me.euler_param_source = None;
+            num_datasets += 1;
+        }
+
+        if num_spks == self.num_loaded_spk()
+            && num_bpcs == self.num_loaded_bpc()
+            && num_datasets == 0
+        {
+            return Err(AlmanacError::GenericError {
+                err: format!("no loaded SPK, BPC, nor ANISE data set matches {source}"),
+            });
+        }
+
+        info!(
+            "Unloaded {} SPK, {} BPC, and {num_datasets} ANISE data sets from {source}",
+            self.num_loaded_spk() - num_spks,
+            self.num_loaded_bpc() - num_bpcs
+        );
+
+        Ok(me)
+    }
+
+    /// Returns true if the file was loaded from the provided source, or if its internal file name is that source.
+    fn is_source(
+        file_record: Option<FileRecord>,
+        loaded_from: &Option<String>,
+        source: &str,
+    ) -> bool {
+        loaded_from.as_deref() == Some(source)
+            || file_record
+                .and_then(|record| record.internal_filename().ok().map(|name| name == source))
+                .unwrap_or(false)
+    }
+
     /// Pretty prints the description of this Almanac, showing everything by default. Default time scale is TDB.
     /// If any parameter is set to true, then nothing other than that will be printed.
     pub fn describe(
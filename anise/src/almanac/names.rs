@@ -0,0 +1,647 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use hifitime::Epoch;
+use snafu::prelude::*;
+
+use crate::{
+    astro::Aberration,
+    constants::{celestial_objects::*, frames::*, orientations::*},
+    errors::{AlmanacResult, EphemerisSnafu, FrameNameSnafu},
+    math::cartesian::CartesianState,
+    naif::kpl::parser::{parse_assignments, Assignment},
+    prelude::Frame,
+    structure::lookuptable::LookUpTable,
+    NaifId,
+};
+
+use super::Almanac;
+
+#[derive(Debug, PartialEq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[non_exhaustive]
+pub enum FrameNameError {
+    #[snafu(display(
+        "no frame named `{name}` is built in or loaded{}",
+        closest.as_ref().map(|closest| format!(", did you mean `{closest}`?")).unwrap_or_default()
+    ))]
+    UnknownFrameName {
+        name: String,
+        closest: Option<String>,
+    },
+    #[snafu(display("text kernel defines {codes} NAIF_BODY_CODE but {names} NAIF_BODY_NAME"))]
+    MismatchedBodyDefinitions { codes: usize, names: usize },
+    #[snafu(display("text kernel has invalid NAIF_BODY_CODE `{value}`"))]
+    InvalidBodyCode { value: String },
+}
+
+/// Frames which can be resolved by their name without loading any kernel: the ephemeris names use the J2000 orientation, and the
+/// orientation names are centered on their body (or on the solar system barycenter for the inertial orientations).
+pub const BUILT_IN_FRAME_NAMES: &[(&str, Frame)] = &[
+    ("SOLAR_SYSTEM_BARYCENTER", SSB_J2000),
+    ("SSB", SSB_J2000),
+    ("MERCURY", MERCURY_J2000),
+    ("VENUS", VENUS_J2000),
+    ("EARTH_MOON_BARYCENTER", EARTH_MOON_BARYCENTER_J2000),
+    ("EMB", EARTH_MOON_BARYCENTER_J2000),
+    ("EARTH_BARYCENTER", EARTH_MOON_BARYCENTER_J2000),
+    ("MARS_BARYCENTER", MARS_BARYCENTER_J2000),
+    ("JUPITER_BARYCENTER", JUPITER_BARYCENTER_J2000),
+    ("SATURN_BARYCENTER", SATURN_BARYCENTER_J2000),
+    ("URANUS_BARYCENTER", URANUS_BARYCENTER_J2000),
+    ("NEPTUNE_BARYCENTER", NEPTUNE_BARYCENTER_J2000),
+    ("PLUTO_BARYCENTER", PLUTO_BARYCENTER_J2000),
+    ("SUN", SUN_J2000),
+    ("MOON", MOON_J2000),
+    ("EARTH", EARTH_J2000),
+    ("MARS", MARS_J2000),
+    ("JUPITER", Frame::new(JUPITER, J2000)),
+    ("SATURN", Frame::new(SATURN, J2000)),
+    ("URANUS", Frame::new(URANUS, J2000)),
+    ("NEPTUNE", Frame::new(NEPTUNE, J2000)),
+    ("PLUTO", Frame::new(PLUTO, J2000)),
+    ("J2000", SSB_J2000),
+    ("ICRF", SSB_J2000),
+    ("EME2000", EME2000),
+    ("B1950", Frame::new(SOLAR_SYSTEM_BARYCENTER, B1950)),
+    ("FK4", Frame::new(SOLAR_SYSTEM_BARYCENTER, FK4)),
+    ("GALACTIC", Frame::new(SOLAR_SYSTEM_BARYCENTER, GALACTIC)),
+    ("MARSIAU", Frame::new(MARS, MARSIAU)),
+    (
+        "ECLIPJ2000",
+        Frame::new(SOLAR_SYSTEM_BARYCENTER, ECLIPJ2000),
+    ),
+    (
+        "ECLIPB1950",
+        Frame::new(SOLAR_SYSTEM_BARYCENTER, ECLIPB1950),
+    ),
+    ("IAU_MERCURY", IAU_MERCURY_FRAME),
+    ("IAU_VENUS", IAU_VENUS_FRAME),
+    ("IAU_EARTH", IAU_EARTH_FRAME),
+    ("ITRF93", EARTH_ITRF93),
+    ("IAU_MOON", IAU_MOON_FRAME),
+    ("MOON_ME", MOON_ME_FRAME),
+    ("MOON_PA", MOON_PA_FRAME),
+    ("IAU_MARS", IAU_MARS_FRAME),
+    ("IAU_JUPITER", IAU_JUPITER_FRAME),
+    ("IAU_SATURN", IAU_SATURN_FRAME),
+    ("IAU_URANUS", IAU_URANUS_FRAME),
+    ("IAU_NEPTUNE", IAU_NEPTUNE_FRAME),
+];
+
+/// The standard NAIF body list, as ID and name pairs. When an ID has several names, the first one is its name in [NaifIds::name_from_id].
+///
+/// Source: <https://naif.jpl.nasa.gov/pub/naif/toolkit_docs/C/req/naif_ids.html>
+pub const STANDARD_NAIF_IDS: &[(NaifId, &str)] = &[
+    (0, "SOLAR SYSTEM BARYCENTER"),
+    (0, "SSB"),
+    (0, "SOLAR_SYSTEM_BARYCENTER"),
+    (1, "MERCURY BARYCENTER"),
+    (1, "MERCURY_BARYCENTER"),
+    (2, "VENUS BARYCENTER"),
+    (2, "VENUS_BARYCENTER"),
+    (3, "EARTH BARYCENTER"),
+    (3, "EMB"),
+    (3, "EARTH MOON BARYCENTER"),
+    (3, "EARTH-MOON BARYCENTER"),
+    (3, "EARTH_BARYCENTER"),
+    (4, "MARS BARYCENTER"),
+    (4, "MARS_BARYCENTER"),
+    (5, "JUPITER BARYCENTER"),
+    (5, "JUPITER_BARYCENTER"),
+    (6, "SATURN BARYCENTER"),
+    (6, "SATURN_BARYCENTER"),
+    (7, "URANUS BARYCENTER"),
+    (7, "URANUS_BARYCENTER"),
+    (8, "NEPTUNE BARYCENTER"),
+    (8, "NEPTUNE_BARYCENTER"),
+    (9, "PLUTO BARYCENTER"),
+    (9, "PLUTO_BARYCENTER"),
+    (10, "SUN"),
+    (199, "MERCURY"),
+    (299, "VENUS"),
+    (399, "EARTH"),
+    (301, "MOON"),
+    (499, "MARS"),
+    (401, "PHOBOS"),
+    (402, "DEIMOS"),
+    (599, "JUPITER"),
+    (501, "IO"),
+    (502, "EUROPA"),
+    (503, "GANYMEDE"),
+    (504, "CALLISTO"),
+    (505, "AMALTHEA"),
+    (699, "SATURN"),
+    (601, "MIMAS"),
+    (602, "ENCELADUS"),
+    (603, "TETHYS"),
+    (604, "DIONE"),
+    (605, "RHEA"),
+    (606, "TITAN"),
+    (607, "HYPERION"),
+    (608, "IAPETUS"),
+    (609, "PHOEBE"),
+    (799, "URANUS"),
+    (701, "ARIEL"),
+    (702, "UMBRIEL"),
+    (703, "TITANIA"),
+    (704, "OBERON"),
+    (705, "MIRANDA"),
+    (899, "NEPTUNE"),
+    (801, "TRITON"),
+    (802, "NEREID"),
+    (999, "PLUTO"),
+    (901, "CHARON"),
+    (902, "NIX"),
+    (903, "HYDRA"),
+    (904, "KERBEROS"),
+    (905, "STYX"),
+    (2000001, "CERES"),
+    (2000004, "VESTA"),
+    (-31, "VOYAGER 1"),
+    (-31, "VG1"),
+    (-32, "VOYAGER 2"),
+    (-32, "VG2"),
+    (-61, "JUNO"),
+    (-64, "OSIRIS-REX"),
+    (-74, "MARS RECON ORBITER"),
+    (-74, "MRO"),
+    (-82, "CASSINI"),
+    (-85, "LUNAR RECONNAISSANCE ORBITER"),
+    (-85, "LRO"),
+    (-96, "PARKER SOLAR PROBE"),
+    (-96, "SPP"),
+    (-98, "NEW HORIZONS"),
+    (-170, "JAMES WEBB SPACE TELESCOPE"),
+    (-170, "JWST"),
+    (-125544, "INTERNATIONAL SPACE STATION"),
+    (-125544, "ISS"),
+];
+
+/// Bidirectional lookup table between the NAIF IDs and the names of the bodies, with the same normalization of the names as the frames
+/// (cf. [normalize_frame_name]).
+///
+/// The table is seeded from the [STANDARD_NAIF_IDS], and the `NAIF_BODY_CODE` and `NAIF_BODY_NAME` assignments of the text kernels loaded
+/// in the Almanac (or with [Self::load_kernel]) add or override entries. As in SPICE, the later definitions override the earlier ones,
+/// both for the name of an ID and for the ID of a name, and a direct assignment (`=`) replaces the definitions of the previously loaded
+/// kernels whereas an incremental assignment (`+=`) extends them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NaifIds {
+    /// Body codes defined by the loaded kernels, paired with `kernel_names`
+    kernel_codes: Vec<NaifId>,
+    /// Body names defined by the loaded kernels, paired with `kernel_codes`
+    kernel_names: Vec<String>,
+    by_name: HashMap<String, NaifId>,
+    by_id: HashMap<NaifId, String>,
+}
+
+impl Default for NaifIds {
+    fn default() -> Self {
+        let mut me = Self {
+            kernel_codes: Vec::new(),
+            kernel_names: Vec::new(),
+            by_name: HashMap::new(),
+            by_id: HashMap::new(),
+        };
+        me.rebuild();
+        me
+    }
+}
+
+impl NaifIds {
+    /// Returns the NAIF ID of the provided name, normalized with [normalize_frame_name].
+    pub fn id_from_name(&self, name: &str) -> Option<NaifId> {
+        self.by_name.get(&normalize_frame_name(name)).copied()
+    }
+
+    /// Returns the name of the provided NAIF ID, i.e. the last name defined for this ID.
+    pub fn name_from_id(&self, id: NaifId) -> Option<&str> {
+        self.by_id.get(&id).map(String::as_str)
+    }
+
+    /// Adds or overrides the provided ID and name pair, as an incremental assignment of a text kernel.
+    pub fn insert(&mut self, id: NaifId, name: &str) {
+        self.kernel_codes.push(id);
+        self.kernel_names.push(name.trim().to_string());
+        self.define(id, name.trim());
+    }
+
+    /// Applies the `NAIF_BODY_CODE` and `NAIF_BODY_NAME` assignments of the provided text kernel, and returns the number of body definitions
+    /// of this table from all of the loaded kernels.
+    ///
+    /// # Errors
+    /// + Each code must be an integer;
+    /// + The kernels must define as many codes as names.
+    ///
+    /// The table is unchanged if the kernel is invalid.
+    pub fn load_kernel<R: BufRead>(&mut self, reader: &mut R) -> Result<usize, FrameNameError> {
+        self.apply(&parse_assignments(reader, false))
+    }
+
+    /// Applies the `NAIF_BODY_CODE` and `NAIF_BODY_NAME` assignments among the provided ones, cf. [Self::load_kernel].
+    pub(crate) fn apply(&mut self, assignments: &[Assignment]) -> Result<usize, FrameNameError> {
+        let mut codes = self.kernel_codes.clone();
+        let mut names = self.kernel_names.clone();
+
+        for assignment in assignments {
+            let Some((keyword, incremental)) = body_keyword(assignment) else {
+                continue;
+            };
+
+            if keyword == NAIF_BODY_CODE {
+                if !incremental {
+                    codes.clear();
+                }
+                for value in assignment
+                    .value
+                    .split(|c: char| c.is_whitespace() || "(),".contains(c))
+                    .filter(|value| !value.is_empty())
+                {
+                    codes.push(value.parse().map_err(|_| FrameNameError::InvalidBodyCode {
+                        value: value.to_string(),
+                    })?);
+                }
+            } else {
+                if !incremental {
+                    names.clear();
+                }
+                // Names are quoted and may contain spaces; the odd pieces are within quotes.
+                names.extend(
+                    assignment
+                        .value
+                        .split('\'')
+                        .skip(1)
+                        .step_by(2)
+                        .map(|name| name.trim().to_string()),
+                );
+            }
+        }
+
+        ensure!(
+            codes.len() == names.len(),
+            MismatchedBodyDefinitionsSnafu {
+                codes: codes.len(),
+                names: names.len()
+            }
+        );
+
+        self.kernel_codes = codes;
+        self.kernel_names = names;
+        self.rebuild();
+
+        Ok(self.kernel_codes.len())
+    }
+
+    /// Rebuilds the lookups from the standard list and the definitions of the loaded kernels, in order.
+    fn rebuild(&mut self) {
+        self.by_name.clear();
+        self.by_id.clear();
+        for (id, name) in STANDARD_NAIF_IDS {
+            self.by_name.insert(normalize_frame_name(name), *id);
+            // The first name of the standard list is the preferred one.
+            self.by_id.entry(*id).or_insert_with(|| name.to_string());
+        }
+        for i in 0..self.kernel_codes.len() {
+            let (id, name) = (self.kernel_codes[i], self.kernel_names[i].clone());
+            self.define(id, &name);
+        }
+    }
+
+    fn define(&mut self, id: NaifId, name: &str) {
+        self.by_name.insert(normalize_frame_name(name), id);
+        self.by_id.insert(id, name.to_string());
+    }
+}
+
+const NAIF_BODY_CODE: &str = "NAIF_BODY_CODE";
+const NAIF_BODY_NAME: &str = "NAIF_BODY_NAME";
+
+/// Returns the keyword of this assignment if it defines body codes or names, and whether it is incremental (`+=`).
+fn body_keyword(assignment: &Assignment) -> Option<(&str, bool)> {
+    let (keyword, incremental) = match assignment.keyword.strip_suffix('+') {
+        Some(keyword) => (keyword.trim(), true),
+        None => (assignment.keyword.trim(), false),
+    };
+    [NAIF_BODY_CODE, NAIF_BODY_NAME]
+        .contains(&keyword)
+        .then_some((keyword, incremental))
+}
+
+/// Returns the body definitions of the text kernel in the provided bytes, or None if these bytes are not a text kernel or it does not
+/// define any body.
+pub(crate) fn body_definitions(bytes: &[u8]) -> Option<Vec<Assignment>> {
+    if !bytes.starts_with(b"KPL/") {
+        return None;
+    }
+    let assignments: Vec<Assignment> = parse_assignments(&mut &bytes[..], false)
+        .into_iter()
+        .filter(|assignment| body_keyword(assignment).is_some())
+        .collect();
+    (!assignments.is_empty()).then_some(assignments)
+}
+
+impl Almanac {
+    /// Returns the frame with the provided name, with its planetary data if it is loaded. The name is case insensitive, and spaces,
+    /// dashes, and underscores are equivalent (e.g. "Earth-Moon Barycenter" is "EARTH_MOON_BARYCENTER").
+    ///
+    /// The names are looked up in this order:
+    /// 1. the fixed frames added to this Almanac (e.g. ground stations);
+    /// 2. the frames of the loaded Euler parameter data (i.e. the frames of the converted FK text kernels), centered on the body whose
+    ///    name starts theirs (e.g. the Moon for MOON_ME_DE421), or on the solar system barycenter otherwise;
+    /// 3. the loaded planetary data, in the J2000 orientation;
+    /// 4. the body names of [Self::naif_ids], i.e. the standard NAIF bodies and those defined by the loaded text kernels, in the J2000
+    ///    orientation;
+    /// 5. the built-in names in [BUILT_IN_FRAME_NAMES].
+    ///
+    /// # Errors
+    /// If no frame has this name, the error includes the closest known name.
+    pub fn frame_from_name(&self, name: &str) -> Result<Frame, FrameNameError> {
+        let key = normalize_frame_name(name);
+
+        let frame = self
+            .fixed_frames
+            .iter()
+            .find(|(_, fixed)| normalize_frame_name(&fixed.name) == key)
+            .map(|(id, _)| Frame::new(*id, *id))
+            .or_else(|| {
+                lut_id_by_name(&self.euler_param_data.lut, &key)
+                    .map(|id| Frame::new(body_of_frame_name(&key), id))
+            })
+            .or_else(|| {
+                lut_id_by_name(&self.planetary_data.lut, &key).map(|id| Frame::new(id, J2000))
+            })
+            .or_else(|| {
+                self.naif_ids
+                    .by_name
+                    .get(&key)
+                    .map(|id| Frame::new(*id, J2000))
+            })
+            .or_else(|| {
+                BUILT_IN_FRAME_NAMES
+                    .iter()
+                    .find(|(built_in, _)| *built_in == key)
+                    .map(|(_, frame)| *frame)
+            });
+
+        match frame {
+            Some(frame) => Ok(self.frame_from_uid(frame).unwrap_or(frame)),
+            None => UnknownFrameNameSnafu {
+                name,
+                closest: self.closest_frame_name(&key),
+            }
+            .fail(),
+        }
+    }
+
+    /// Returns the NAIF ID of the body with the provided name, from the standard NAIF bodies and those defined by the loaded text kernels.
+    pub fn body_id_from_name(&self, name: &str) -> Option<NaifId> {
+        self.naif_ids.id_from_name(name)
+    }
+
+    /// Returns the name of the body with the provided NAIF ID, from the standard NAIF bodies and those defined by the loaded text kernels.
+    pub fn body_name_from_id(&self, id: NaifId) -> Option<&str> {
+        self.naif_ids.name_from_id(id)
+    }
+
+    /// Returns the Cartesian state of the target frame as seen from the observer frame, both provided by their names, exactly as
+    /// [Self::translate]. Refer to [Self::frame_from_name] for the names.
+    pub fn translate_by_name(
+        &self,
+        target_name: &str,
+        observer_name: &str,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        let target_frame = self.frame_from_name(target_name).context(FrameNameSnafu)?;
+        let observer_frame = self
+            .frame_from_name(observer_name)
+            .context(FrameNameSnafu)?;
+
+        self.translate(target_frame, observer_frame, epoch, ab_corr)
+            .context(EphemerisSnafu {
+                action: "translating by name",
+            })
+    }
+
+    /// Returns the Cartesian state needed to transform the target frame to the observer frame, both provided by their names, exactly as
+    /// [Self::transform]. Refer to [Self::frame_from_name] for the names.
+    pub fn transform_by_name(
+        &self,
+        target_name: &str,
+        observer_name: &str,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        let target_frame = self.frame_from_name(target_name).context(FrameNameSnafu)?;
+        let observer_frame = self
+            .frame_from_name(observer_name)
+            .context(FrameNameSnafu)?;
+
+        self.transform(target_frame, observer_frame, epoch, ab_corr)
+    }
+
+    /// Returns the known name closest to the provided normalized name, by edit distance.
+    fn closest_frame_name(&self, key: &str) -> Option<String> {
+        let fixed = self.fixed_frames.values().map(|fixed| fixed.name.clone());
+        let euler = self
+            .euler_param_data
+            .lut
+            .by_name
+            .keys()
+            .map(|name| name.to_string());
+        let planetary = self
+            .planetary_data
+            .lut
+            .by_name
+            .keys()
+            .map(|name| name.to_string());
+        let bodies = self.naif_ids.by_name.keys().cloned();
+        let built_in = BUILT_IN_FRAME_NAMES
+            .iter()
+            .map(|(name, _)| name.to_string());
+
+        fixed
+            .chain(euler)
+            .chain(planetary)
+            .chain(bodies)
+            .chain(built_in)
+            .min_by_key(|name| edit_distance(key, &normalize_frame_name(name)))
+    }
+}
+
+/// Returns the provided frame or body name in upper case, where each run of spaces and each dash is replaced by an underscore
+/// (e.g. " Earth-Moon  Barycenter" is "EARTH_MOON_BARYCENTER"). All of the names of this module are compared once normalized.
+pub fn normalize_frame_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join("_")
+        .chars()
+        .map(|c| match c {
+            '-' => '_',
+            _ => c.to_ascii_uppercase(),
+        })
+        .collect()
+}
+
+/// Returns the ID of the entry with the provided normalized name in this look up table.
+fn lut_id_by_name<const ENTRIES: usize>(lut: &LookUpTable<ENTRIES>, key: &str) -> Option<NaifId> {
+    let (_, index) = lut
+        .by_name
+        .iter()
+        .find(|(name, _)| normalize_frame_name(name) == key)?;
+
+    lut.by_id
+        .iter()
+        .find(|(_, id_index)| *id_index == index)
+        .map(|(id, _)| *id)
+}
+
+/// Returns the body whose built-in name is the longest prefix of the provided normalized frame name (e.g. MOON for MOON_ME_DE421),
+/// or the solar system barycenter if there is none.
+fn body_of_frame_name(key: &str) -> NaifId {
+    let prefixes = key.match_indices('_').map(|(idx, _)| &key[..idx]).rev();
+
+    core::iter::once(key)
+        .chain(prefixes)
+        .find_map(|prefix| {
+            BUILT_IN_FRAME_NAMES
+                .iter()
+                .find(|(name, frame)| *name == prefix && frame.orient_origin_id_match(J2000))
+                .map(|(_, frame)| frame.ephemeris_id)
+        })
+        .unwrap_or(SOLAR_SYSTEM_BARYCENTER)
+}
+
+/// Levenshtein distance between the two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod ut_names {
+    use super::*;
+
+    #[test]
+    fn normalized_names() {
+        assert_eq!(
+            normalize_frame_name(" Earth-Moon Barycenter"),
+            "EARTH_MOON_BARYCENTER"
+        );
+        assert_eq!(body_of_frame_name("MOON_ME_DE421"), MOON);
+        assert_eq!(
+            body_of_frame_name("EARTH_MOON_BARYCENTER_X"),
+            EARTH_MOON_BARYCENTER
+        );
+        assert_eq!(body_of_frame_name("DSS_65_TOPO"), SOLAR_SYSTEM_BARYCENTER);
+        assert_eq!(edit_distance("IAU_MRAS", "IAU_MARS"), 2);
+        assert_eq!(edit_distance("", "SUN"), 3);
+        assert_eq!(edit_distance("EARTH", "EARTH"), 0);
+    }
+
+    #[test]
+    fn standard_bodies() {
+        let ids = NaifIds::default();
+        assert_eq!(ids.id_from_name("EARTH"), Some(399));
+        assert_eq!(ids.id_from_name(" mars "), Some(499));
+        assert_eq!(ids.id_from_name("solar  system barycenter"), Some(0));
+        assert_eq!(ids.id_from_name("Earth-Moon Barycenter"), Some(3));
+        assert_eq!(ids.id_from_name("NOT A BODY"), None);
+        assert_eq!(ids.name_from_id(399), Some("EARTH"));
+        assert_eq!(ids.name_from_id(0), Some("SOLAR SYSTEM BARYCENTER"));
+        assert_eq!(ids.name_from_id(123_456), None);
+
+        for (id, name) in STANDARD_NAIF_IDS {
+            assert_eq!(ids.id_from_name(name), Some(*id));
+            assert!(ids.name_from_id(*id).is_some());
+        }
+    }
+
+    #[test]
+    fn kernel_definitions() {
+        let mut ids = NaifIds::default();
+        let kernel = "KPL/FK
+
+\\begindata
+
+NAIF_BODY_CODE += -999999
+NAIF_BODY_NAME += 'MY_SAT'
+
+\\begintext
+";
+
+        assert_eq!(ids.load_kernel(&mut kernel.as_bytes()), Ok(1));
+        assert_eq!(ids.id_from_name("MY_SAT"), Some(-999999));
+        assert_eq!(ids.id_from_name("my_sat"), Some(-999999));
+        assert_eq!(ids.name_from_id(-999999), Some("MY_SAT"));
+        // The standard bodies are still available.
+        assert_eq!(ids.id_from_name("EARTH"), Some(399));
+
+        // Later definitions win, in both directions.
+        let kernel = "\\begindata
+NAIF_BODY_CODE += ( -999999, 399 )
+NAIF_BODY_NAME += ( 'MY SAT RENAMED', 'HOME' )
+";
+        assert_eq!(ids.load_kernel(&mut kernel.as_bytes()), Ok(3));
+        assert_eq!(ids.name_from_id(-999999), Some("MY SAT RENAMED"));
+        assert_eq!(ids.id_from_name("my  sat renamed"), Some(-999999));
+        // The older name still points to that ID.
+        assert_eq!(ids.id_from_name("MY_SAT"), Some(-999999));
+        assert_eq!(ids.name_from_id(399), Some("HOME"));
+        assert_eq!(ids.id_from_name("EARTH"), Some(399));
+
+        // A direct assignment replaces the previous kernel definitions.
+        let kernel = "\\begindata
+NAIF_BODY_CODE = -42
+NAIF_BODY_NAME = 'OTHER_SAT'
+";
+        assert_eq!(ids.load_kernel(&mut kernel.as_bytes()), Ok(1));
+        assert_eq!(ids.id_from_name("MY_SAT"), None);
+        assert_eq!(ids.name_from_id(399), Some("EARTH"));
+        assert_eq!(ids.id_from_name("OTHER_SAT"), Some(-42));
+
+        // Invalid kernels leave the table unchanged.
+        let before = ids.clone();
+        let kernel = "\\begindata
+NAIF_BODY_CODE += ( -1, -2 )
+NAIF_BODY_NAME += 'ONLY ONE'
+";
+        assert_eq!(
+            ids.load_kernel(&mut kernel.as_bytes()),
+            Err(FrameNameError::MismatchedBodyDefinitions { codes: 3, names: 2 })
+        );
+        let kernel = "\\begindata
+NAIF_BODY_CODE += SAT
+NAIF_BODY_NAME += 'SAT'
+";
+        assert_eq!(
+            ids.load_kernel(&mut kernel.as_bytes()),
+            Err(FrameNameError::InvalidBodyCode {
+                value: "SAT".to_string()
+            })
+        );
+        assert_eq!(ids, before);
+
+        ids.insert(-43, "THIRD SAT");
+        assert_eq!(ids.name_from_id(-43), Some("THIRD SAT"));
+    }
+}
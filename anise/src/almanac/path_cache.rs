@@ -0,0 +1,180 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use hifitime::Epoch;
+
+use crate::ephemerides::paths::MAX_TREE_DEPTH;
+use crate::NaifId;
+
+/// A path between two frames: the number of nodes, the nodes themselves, and the common node.
+pub(crate) type FramePath = (usize, [Option<NaifId>; MAX_TREE_DEPTH], NaifId);
+
+#[derive(Copy, Clone, Debug)]
+struct CachedPath {
+    path: FramePath,
+    /// Start of the validity of this path, in ET seconds (inclusive)
+    start_et_s: f64,
+    /// End of the validity of this path, in ET seconds (inclusive)
+    end_et_s: f64,
+}
+
+type PathMap = RwLock<HashMap<(NaifId, NaifId), CachedPath>>;
+
+/// Cache of the ephemeris and orientation paths between pairs of frames, keyed by the (from, to) IDs.
+///
+/// Each path is only valid over the time span where the segments that define it do not change. Only the graph traversal is cached:
+/// the states and rotations are always computed at the requested epoch.
+///
+/// # Invalidation
+/// Loading data creates a new Almanac, and a clone of an Almanac always starts with an empty cache, so loading any new kernel invalidates
+/// all of the cached paths. If the loaded data is modified directly (e.g. by setting the `planetary_data` field), call [PathCache::clear].
+#[derive(Debug, Default)]
+pub struct PathCache {
+    ephemeris: PathMap,
+    orientation: PathMap,
+}
+
+impl Clone for PathCache {
+    /// Clones are empty because cloning an Almanac is how new data is loaded.
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PathCache {
+    /// Removes all of the cached paths.
+    pub fn clear(&self) {
+        for map in [&self.ephemeris, &self.orientation] {
+            if let Ok(mut map) = map.write() {
+                map.clear();
+            }
+        }
+    }
+
+    /// Returns the number of cached ephemeris and orientation paths.
+    pub fn len(&self) -> usize {
+        [&self.ephemeris, &self.orientation]
+            .iter()
+            .map(|map| map.read().map(|map| map.len()).unwrap_or(0))
+            .sum()
+    }
+
+    /// Returns true if no path is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn ephemeris_path(
+        &self,
+        from_id: NaifId,
+        to_id: NaifId,
+        epoch: Epoch,
+    ) -> Option<FramePath> {
+        Self::get(&self.ephemeris, from_id, to_id, epoch)
+    }
+
+    pub(crate) fn insert_ephemeris_path(
+        &self,
+        from_id: NaifId,
+        to_id: NaifId,
+        path: FramePath,
+        validity_et_s: (f64, f64),
+    ) {
+        Self::insert(&self.ephemeris, from_id, to_id, path, validity_et_s)
+    }
+
+    pub(crate) fn orientation_path(
+        &self,
+        from_id: NaifId,
+        to_id: NaifId,
+        epoch: Epoch,
+    ) -> Option<FramePath> {
+        Self::get(&self.orientation, from_id, to_id, epoch)
+    }
+
+    pub(crate) fn insert_orientation_path(
+        &self,
+        from_id: NaifId,
+        to_id: NaifId,
+        path: FramePath,
+        validity_et_s: (f64, f64),
+    ) {
+        Self::insert(&self.orientation, from_id, to_id, path, validity_et_s)
+    }
+
+    fn get(map: &PathMap, from_id: NaifId, to_id: NaifId, epoch: Epoch) -> Option<FramePath> {
+        let cached = *map.read().ok()?.get(&(from_id, to_id))?;
+        let et_s = epoch.to_et_seconds();
+        (cached.start_et_s <= et_s && et_s <= cached.end_et_s).then_some(cached.path)
+    }
+
+    fn insert(
+        map: &PathMap,
+        from_id: NaifId,
+        to_id: NaifId,
+        path: FramePath,
+        (start_et_s, end_et_s): (f64, f64),
+    ) {
+        if let Ok(mut map) = map.write() {
+            map.insert(
+                (from_id, to_id),
+                CachedPath {
+                    path,
+                    start_et_s,
+                    end_et_s,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod ut_path_cache {
+    use super::PathCache;
+    use hifitime::Epoch;
+
+    #[test]
+    fn validity_and_clear() {
+        let cache = PathCache::default();
+        assert!(cache.is_empty());
+
+        let path = (1, [Some(3), None, None, None, None, None, None, None], 3);
+        cache.insert_ephemeris_path(399, 301, path, (-10.0, 10.0));
+        assert_eq!(cache.len(), 1);
+
+        assert_eq!(
+            cache.ephemeris_path(399, 301, Epoch::from_et_seconds(5.0)),
+            Some(path)
+        );
+        // Not valid outside of its span
+        assert_eq!(
+            cache.ephemeris_path(399, 301, Epoch::from_et_seconds(11.0)),
+            None
+        );
+        // Keyed by the ordered pair of IDs, and split between ephemeris and orientation.
+        assert_eq!(
+            cache.ephemeris_path(301, 399, Epoch::from_et_seconds(5.0)),
+            None
+        );
+        assert_eq!(
+            cache.orientation_path(399, 301, Epoch::from_et_seconds(5.0)),
+            None
+        );
+
+        // Clones never share the cached paths
+        assert!(cache.clone().is_empty());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
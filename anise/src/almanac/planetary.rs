@@ -12,8 +12,10 @@ use snafu::prelude::*;
 use tabled::{settings::Style, Table, Tabled};
 
 use crate::{
+    ephemerides::EphemerisPhysicsSnafu,
+    errors::{AlmanacError, AlmanacResult, EphemerisSnafu, PhysicsError},
     prelude::{Frame, FrameUid},
-    structure::{dataset::DataSetError, PlanetaryDataSet},
+    structure::{dataset::DataSetError, planetocentric::ellipsoid::Ellipsoid, PlanetaryDataSet},
 };
 
 #[derive(Debug, Snafu, PartialEq)]
@@ -40,10 +42,38 @@ impl Almanac {
             .to_frame(uid))
     }
 
+    /// Returns the provided frame with its planetary data, fetched from the loaded planetary data if its shape is not set, and its shape.
+    pub(crate) fn frame_with_shape(
+        &self,
+        mut frame: Frame,
+        action: &'static str,
+    ) -> AlmanacResult<(Frame, Ellipsoid)> {
+        if frame.shape.is_none() {
+            frame = self
+                .frame_from_uid(frame)
+                .map_err(|e| AlmanacError::GenericError {
+                    err: format!("{e} when fetching {frame:e} frame data"),
+                })?;
+        }
+
+        let shape = frame
+            .shape
+            .ok_or(PhysicsError::MissingFrameData {
+                action,
+                data: "shape",
+                frame: frame.into(),
+            })
+            .context(EphemerisPhysicsSnafu { action })
+            .context(EphemerisSnafu { action })?;
+
+        Ok((frame, shape))
+    }
+
     /// Loads the provided planetary data into a clone of this original Almanac.
     pub fn with_planetary_data(&self, planetary_data: PlanetaryDataSet) -> Self {
         let mut me = self.clone();
         me.planetary_data = planetary_data;
+        me.planetary_source = None;
         me
     }
 }
@@ -9,11 +9,13 @@
  */
 
 use super::{
+    names::FrameNameError,
     planetary::{PlanetaryDataError, PlanetaryDataSetSnafu},
+    surface::SubPointMethod,
     Almanac,
 };
 use crate::{
-    astro::{Aberration, AzElRange, Occultation},
+    astro::{Aberration, AzElRange, LightTimeDirection, Occultation, OccultationState},
     ephemerides::EphemerisError,
     errors::AlmanacResult,
     math::{cartesian::CartesianState, rotation::DCM},
@@ -21,7 +23,7 @@ use crate::{
     prelude::{Frame, Orbit},
     NaifId,
 };
-use hifitime::{Epoch, TimeScale, TimeSeries};
+use hifitime::{Duration, Epoch, TimeScale, TimeSeries};
 use pyo3::prelude::*;
 use rayon::prelude::*;
 use snafu::prelude::*;
@@ -94,6 +96,64 @@ impl Almanac {
         self.load(path)
     }
 
+    /// Memory maps the provided file and loads it, without copying the SPK and BPC files onto the heap. The file must not be modified while loaded.
+    ///
+    /// :type path: str
+    /// :rtype: Almanac
+    #[pyo3(name = "load_mmap")]
+    fn py_load_mmap(&self, path: &str) -> AlmanacResult<Self> {
+        self.load_mmap(path)
+    }
+
+    /// Unloads every SPK, BPC, and ANISE data set that was loaded from the provided path, or every SPK and BPC whose internal file name is the provided source.
+    ///
+    /// :type source: str
+    /// :rtype: Almanac
+    #[pyo3(name = "unload")]
+    fn py_unload(&self, source: &str) -> AlmanacResult<Self> {
+        self.unload(source)
+    }
+
+    /// Adds a topocentric (South East Zenith) frame to this Almanac, centered at the provided geodetic latitude and longitude in degrees
+    /// and height in km with respect to the ellipsoid of the parent body fixed frame, and returns that new frame.
+    ///
+    /// :type name: str
+    /// :type parent_frame: Frame
+    /// :type latitude_deg: float
+    /// :type longitude_deg: float
+    /// :type height_km: float
+    /// :rtype: Frame
+    #[pyo3(name = "add_topocentric_frame")]
+    fn py_add_topocentric_frame(
+        &mut self,
+        name: &str,
+        parent_frame: Frame,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        height_km: f64,
+    ) -> AlmanacResult<Frame> {
+        self.add_topocentric_frame(name, parent_frame, latitude_deg, longitude_deg, height_km)
+    }
+
+    /// Writes a new SPK to the provided path with only the loaded segments of the provided target IDs, clipped to the window from
+    /// the start to the end epochs. The Chebyshev records within the window are copied as is, and those at its boundaries are refit.
+    ///
+    /// :type bodies: typing.List
+    /// :type start: Epoch
+    /// :type end: Epoch
+    /// :type out: str
+    /// :rtype: None
+    #[pyo3(name = "export_subset")]
+    fn py_export_subset(
+        &self,
+        bodies: Vec<NaifId>,
+        start: Epoch,
+        end: Epoch,
+        out: &str,
+    ) -> AlmanacResult<()> {
+        self.export_subset(&bodies, (start, end), out)
+    }
+
     /// Computes the azimuth (in degrees), elevation (in degrees), and range (in kilometers) of the
     /// receiver state (`rx`) seen from the transmitter state (`tx`), once converted into the SEZ frame of the transmitter.
     ///
@@ -245,6 +305,36 @@ impl Almanac {
         self.occultation(back_frame, front_frame, observer, ab_corr)
     }
 
+    /// Classifies the occultation of the `back_frame` object by the `front_frame` object as seen from the observer frame at the provided
+    /// epoch, analogous to SPICE's `occult` with ellipsoid shapes.
+    ///
+    /// For a solar eclipse, the back frame is the Sun. For a transit, the front frame is the transiting body.
+    /// The aberration correction should include light time corrections, and is also used to orient each ellipsoid.
+    ///
+    /// :type back_frame: Frame
+    /// :type front_frame: Frame
+    /// :type observer_frame: Frame
+    /// :type epoch: Epoch
+    /// :type ab_corr: Aberration, optional
+    /// :rtype: OccultationState
+    #[pyo3(name = "occultation_state", signature=(
+        back_frame,
+        front_frame,
+        observer_frame,
+        epoch,
+        ab_corr=None,
+    ))]
+    fn py_occultation_state(
+        &self,
+        back_frame: Frame,
+        front_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<OccultationState> {
+        self.occultation_state(back_frame, front_frame, observer_frame, epoch, ab_corr)
+    }
+
     /// Computes the solar eclipsing of the observer due to the eclipsing_frame.
     ///
     /// This function calls `occultation` where the back object is the Sun in the J2000 frame, and the front object
@@ -428,6 +518,30 @@ impl Almanac {
         self.transform_to(state, observer_frame, ab_corr)
     }
 
+    /// Re-expresses the provided state, valid at the provided epoch, in the `to_frame`: both its center and its orientation are changed.
+    ///
+    /// The velocity includes the transport term of the rotation of the frames, i.e. the ω×r term of a rotating body-fixed frame, so
+    /// transforming a state from an inertial frame into a body-fixed frame and back recovers the original velocity. The state is re-centered
+    /// with the geometric translation between the centers of both frames, without any aberration correction (use [transform_to] for that).
+    ///
+    /// :type state: Orbit
+    /// :type to_frame: Frame
+    /// :type epoch: Epoch
+    /// :rtype: Orbit
+    #[pyo3(name = "transform_state", signature=(
+        state,
+        to_frame,
+        epoch,
+    ))]
+    fn py_transform_state(
+        &self,
+        state: CartesianState,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> AlmanacResult<CartesianState> {
+        self.transform_state(state, to_frame, epoch)
+    }
+
     /// Returns a chronologically sorted list of the provided states as seen from the observer frame, given the aberration.
     /// Note: if any transformation fails, the error will be printed to the stderr.
     /// Note: the input ordering is lost: the output states will not be in the same order as the input states if these are not chronologically sorted!
@@ -558,6 +672,171 @@ impl Almanac {
         self.translate(target_frame, observer_frame, epoch, ab_corr)
     }
 
+    /// Returns the Cartesian states of the target frame as seen from the observer frame at each of the provided epochs, in the same
+    /// order, and optionally given the aberration correction. Each state is the same as the one returned by `translate`.
+    ///
+    /// Without aberration correction, the segment used for each translation is reused for the following epochs where the segment
+    /// lookup would return that same segment. If any translation fails, the error reports the index and epoch of the first failure.
+    ///
+    /// :type target_frame: Frame
+    /// :type observer_frame: Frame
+    /// :type epochs: typing.List
+    /// :type ab_corr: Aberration, optional
+    /// :rtype: typing.List
+    #[pyo3(name = "translate_many", signature=(
+        target_frame,
+        observer_frame,
+        epochs,
+        ab_corr=None,
+    ))]
+    fn py_translate_many(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epochs: Vec<Epoch>,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Vec<CartesianState>, EphemerisError> {
+        self.translate_many(target_frame, observer_frame, &epochs, ab_corr)
+    }
+
+    /// Returns the geodetic latitude (φ) and longitude (λ) in degrees of the sub-observer point on the tri-axial ellipsoid of the target,
+    /// and the altitude of the observer above that point in km (i.e. the distance between them).
+    ///
+    /// The target frame must be body fixed (e.g. IAU_EARTH_FRAME). Use the Sun as the observer to compute the sub-solar point.
+    ///
+    /// :type target_frame: Frame
+    /// :type observer_frame: Frame
+    /// :type epoch: Epoch
+    /// :type method: SubPointMethod
+    /// :type ab_corr: Aberration, optional
+    /// :rtype: typing.Tuple
+    #[pyo3(name = "sub_observer_point", signature=(
+        target_frame,
+        observer_frame,
+        epoch,
+        method,
+        ab_corr=None,
+    ))]
+    fn py_sub_observer_point(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        method: SubPointMethod,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<(f64, f64, f64)> {
+        self.sub_observer_point(target_frame, observer_frame, epoch, method, ab_corr)
+    }
+
+    /// Returns the frame with the provided name, with its planetary data if it is loaded. The name is case insensitive, and spaces,
+    /// dashes, and underscores are equivalent. The fixed frames, the loaded Euler parameter and planetary data, the NAIF body names
+    /// (including those of the loaded text kernels), and the built-in names are searched in that order. If no frame has this name, the
+    /// error includes the closest known name.
+    ///
+    /// :type name: str
+    /// :rtype: Frame
+    #[pyo3(name = "frame_from_name")]
+    fn py_frame_from_name(&self, name: &str) -> Result<Frame, FrameNameError> {
+        self.frame_from_name(name)
+    }
+
+    /// Returns the Cartesian state of the target as seen from the observer, both provided by their names, exactly as `translate`.
+    ///
+    /// :type target_name: str
+    /// :type observer_name: str
+    /// :type epoch: Epoch
+    /// :type ab_corr: Aberration, optional
+    /// :rtype: Orbit
+    #[pyo3(name = "translate_by_name", signature=(
+        target_name,
+        observer_name,
+        epoch,
+        ab_corr=None,
+    ))]
+    fn py_translate_by_name(
+        &self,
+        target_name: &str,
+        observer_name: &str,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        self.translate_by_name(target_name, observer_name, epoch, ab_corr)
+    }
+
+    /// Returns the Cartesian state needed to transform the target to the observer, both provided by their names, exactly as `transform`.
+    ///
+    /// :type target_name: str
+    /// :type observer_name: str
+    /// :type epoch: Epoch
+    /// :type ab_corr: Aberration, optional
+    /// :rtype: Orbit
+    #[pyo3(name = "transform_by_name", signature=(
+        target_name,
+        observer_name,
+        epoch,
+        ab_corr=None,
+    ))]
+    fn py_transform_by_name(
+        &self,
+        target_name: &str,
+        observer_name: &str,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        self.transform_by_name(target_name, observer_name, epoch, ab_corr)
+    }
+
+    /// Returns the converged one-way light time between the target and the observer at the provided epoch, and the geometric state of
+    /// the target at the light time corrected epoch with respect to the observer at the provided epoch.
+    ///
+    /// In reception, the target emits the signal at the epoch of the returned state, one light time before the observer receives it.
+    /// In transmission, the observer emits the signal at the provided epoch, and the target receives it at the epoch of the returned state.
+    ///
+    /// :type target_frame: Frame
+    /// :type observer_frame: Frame
+    /// :type epoch: Epoch
+    /// :type direction: LightTimeDirection
+    /// :rtype: typing.Tuple
+    #[pyo3(name = "light_time", signature=(
+        target_frame,
+        observer_frame,
+        epoch,
+        direction,
+    ))]
+    fn py_light_time(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        direction: LightTimeDirection,
+    ) -> Result<(Duration, CartesianState), EphemerisError> {
+        self.light_time(target_frame, observer_frame, epoch, direction)
+    }
+
+    /// Returns the one-way light time between the target and the observer at the provided epoch given the aberration correction, along
+    /// with the rate of change of that light time (unitless, i.e. in seconds per second).
+    ///
+    /// :type target_frame: Frame
+    /// :type observer_frame: Frame
+    /// :type epoch: Epoch
+    /// :type ab_corr: Aberration
+    /// :rtype: typing.Tuple
+    #[pyo3(name = "one_way_light_time", signature=(
+        target_frame,
+        observer_frame,
+        epoch,
+        ab_corr,
+    ))]
+    fn py_one_way_light_time(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Aberration,
+    ) -> Result<(Duration, f64), EphemerisError> {
+        self.one_way_light_time(target_frame, observer_frame, epoch, ab_corr)
+    }
+
     /// Returns the geometric position vector, velocity vector, and acceleration vector needed to translate the `from_frame` to the `to_frame`, where the distance is in km, the velocity in km/s, and the acceleration in km/s^2.
     ///
     /// :type target_frame: Frame
@@ -0,0 +1,188 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bytes::Bytes;
+use snafu::ResultExt;
+
+use super::{Almanac, LoadedData};
+use crate::ephemerides::SPKSnafu;
+use crate::errors::{AlmanacError, AlmanacResult, EphemerisSnafu, OrientationSnafu};
+use crate::naif::daf::{DAFError, NAIFSummaryRecord, DAF};
+use crate::naif::pck::BPCSummaryRecord;
+use crate::naif::spk::summary::SPKSummaryRecord;
+use crate::orientations::BPCSnafu;
+use crate::NaifId;
+
+/// Summaries whose IDs can be rewritten when loading a DAF.
+trait RemapSummary: NAIFSummaryRecord {
+    /// Replaces each of the IDs of this summary which is a key of the remapping by its value.
+    fn remap_ids(&mut self, remap: &HashMap<NaifId, NaifId>);
+}
+
+impl RemapSummary for SPKSummaryRecord {
+    fn remap_ids(&mut self, remap: &HashMap<NaifId, NaifId>) {
+        for id in [&mut self.target_id, &mut self.center_id, &mut self.frame_id] {
+            if let Some(new_id) = remap.get(id) {
+                *id = *new_id;
+            }
+        }
+    }
+}
+
+impl RemapSummary for BPCSummaryRecord {
+    fn remap_ids(&mut self, remap: &HashMap<NaifId, NaifId>) {
+        for id in [&mut self.frame_id, &mut self.inertial_frame_id] {
+            if let Some(new_id) = remap.get(id) {
+                *id = *new_id;
+            }
+        }
+    }
+}
+
+impl Almanac {
+    /// Loads the provided bytes into a clone of this Almanac exactly as [Self::load_from_bytes], but rewriting the IDs of each segment
+    /// of an SPK or BPC with the provided remapping, e.g. to load the kernel of a spacecraft under another ID for a parallel scenario.
+    ///
+    /// The remapping applies to all of the IDs of the segments: the target, the center (observer), and the frame of the SPK segments,
+    /// and the frame and the inertial frame of the BPC segments. Only the loaded copy is rewritten, the original bytes are untouched.
+    ///
+    /// # Errors
+    /// The IDs of the data of the segments (the targets of the SPK and the frames of the BPC) are checked for collisions before loading,
+    /// returning a remap collision error if any remapped ID:
+    /// + is already provided by the data loaded in this Almanac;
+    /// + is also provided by this kernel, and not remapped itself;
+    /// + is the remapping of several IDs of this kernel.
+    ///
+    /// The ANISE data sets (planetary data, spacecraft data, and Euler parameters) cannot be remapped, so this returns an error if the
+    /// remapping is not empty for such data.
+    pub fn load_with_remap(
+        &self,
+        bytes: Bytes,
+        remap: &HashMap<NaifId, NaifId>,
+    ) -> AlmanacResult<Self> {
+        let data = match Self::parse_bytes(bytes, None)? {
+            LoadedData::SPK(spk) => {
+                let loaded = self
+                    .spk_data
+                    .iter()
+                    .take(self.num_loaded_spk())
+                    .flatten()
+                    .flat_map(|spk| spk.data_summaries().unwrap_or(&[]))
+                    .filter(|summary| !summary.is_empty())
+                    .map(|summary| summary.id())
+                    .collect();
+                check_remap(&spk, &loaded, remap)?;
+                LoadedData::SPK(
+                    remapped(&spk, remap)
+                        .context(SPKSnafu {
+                            action: "remapping IDs",
+                        })
+                        .context(EphemerisSnafu {
+                            action: "loading with remapping",
+                        })?,
+                )
+            }
+            LoadedData::BPC(bpc) => {
+                let loaded = self
+                    .bpc_data
+                    .iter()
+                    .take(self.num_loaded_bpc())
+                    .flatten()
+                    .flat_map(|bpc| bpc.data_summaries().unwrap_or(&[]))
+                    .filter(|summary| !summary.is_empty())
+                    .map(|summary| summary.id())
+                    .collect();
+                check_remap(&bpc, &loaded, remap)?;
+                LoadedData::BPC(
+                    remapped(&bpc, remap)
+                        .context(BPCSnafu {
+                            action: "remapping IDs",
+                        })
+                        .context(OrientationSnafu {
+                            action: "loading with remapping",
+                        })?,
+                )
+            }
+            data => {
+                if !remap.is_empty() {
+                    return Err(AlmanacError::GenericError {
+                        err: "only the IDs of SPK and BPC segments can be remapped".to_string(),
+                    });
+                }
+                data
+            }
+        };
+
+        self.with_loaded_data(data, None)
+    }
+}
+
+/// Checks that remapping the IDs of the segments of this DAF does not collide with the IDs already loaded, nor with one another.
+fn check_remap<R: RemapSummary>(
+    daf: &DAF<R>,
+    loaded: &HashSet<NaifId>,
+    remap: &HashMap<NaifId, NaifId>,
+) -> AlmanacResult<()> {
+    let in_kernel: HashSet<NaifId> = daf
+        .data_summaries()
+        .unwrap_or(&[])
+        .iter()
+        .filter(|summary| !summary.is_empty())
+        .map(|summary| summary.id())
+        .collect();
+
+    // Sorted so that the reported collision does not depend on the order of the hash map.
+    let mut remapped_to = HashMap::new();
+    for (from, to) in remap.iter().collect::<BTreeMap<_, _>>() {
+        if !in_kernel.contains(from) || from == to {
+            continue;
+        }
+
+        let reason = if loaded.contains(to) {
+            Some("that ID is already loaded")
+        } else if in_kernel.contains(to) && !remap.contains_key(to) {
+            Some("that ID is also in this kernel")
+        } else if remapped_to.insert(*to, *from).is_some() {
+            Some("another ID of this kernel is remapped to it")
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            return Err(AlmanacError::RemapCollision {
+                from: *from,
+                to: *to,
+                reason,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a copy of this DAF whose summaries are remapped.
+fn remapped<R: RemapSummary>(
+    daf: &DAF<R>,
+    remap: &HashMap<NaifId, NaifId>,
+) -> Result<DAF<R>, DAFError> {
+    let mut mut_daf = daf.to_mutable();
+    for (idx, summary) in daf.data_summaries()?.iter().enumerate() {
+        if summary.is_empty() {
+            continue;
+        }
+        let mut summary = *summary;
+        summary.remap_ids(remap);
+        mut_daf.set_nth_summary(idx, summary)?;
+    }
+
+    DAF::parse(mut_daf.bytes)
+}
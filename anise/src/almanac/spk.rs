@@ -10,7 +10,7 @@
 
 use std::collections::HashMap;
 
-use hifitime::Epoch;
+use hifitime::{Duration, Epoch};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
@@ -27,6 +27,18 @@ use log::error;
 use super::{Almanac, MAX_LOADED_SPKS};
 
 impl Almanac {
+    /// Returns a clone of this Almanac where the gaps between the segments of the same ephemeris ID which are no longer than the provided
+    /// tolerance do not cause coverage errors. By default, there is no tolerance and any gap causes an error.
+    ///
+    /// When the requested epoch falls in such a gap, the segment whose boundary is nearer to that epoch is evaluated at its boundary,
+    /// and its position is extrapolated linearly with the velocity at that boundary, so the extrapolation is never longer than half of
+    /// the tolerance. A warning is logged whenever this happens. Epochs before the first segment or after the last one are still errors.
+    pub fn with_gap_tolerance(&self, tolerance: Duration) -> Self {
+        let mut me = self.clone();
+        me.gap_tolerance = Some(tolerance);
+        me
+    }
+
     pub fn from_spk(spk: SPK) -> Result<Almanac, EphemerisError> {
         let me = Self::default();
         me.with_spk(spk)
@@ -120,22 +132,63 @@ impl Almanac {
             }
         }
 
+        if let Some(found) = self.spk_summary_across_gap(id, epoch) {
+            return Ok(found);
+        }
+
         // If the ID is not present at all, spk_domain will report it.
         let (start, end) = self.spk_domain(id)?;
         error!("Almanac: summary {id} valid from {start} to {end} but not at requested {epoch}");
         // If we're reached this point, there is no relevant summary at this epoch.
-        Err(EphemerisError::SPK {
-            action: "searching for SPK summary",
-            source: DAFError::SummaryIdAtEpochError {
-                kind: "SPK",
-                id,
-                epoch,
-                start,
-                end,
-            },
+        Err(EphemerisError::EpochOutOfCoverage {
+            id,
+            requested: epoch,
+            available: (start, end),
         })
     }
 
+    /// Returns the segment of this ID whose boundary is the nearest to the provided epoch, if that epoch falls in a gap between two segments
+    /// of this ID which is no longer than the gap tolerance. Returns None if the gap tolerance is not set.
+    fn spk_summary_across_gap(
+        &self,
+        id: i32,
+        epoch: Epoch,
+    ) -> Option<(&SPKSummaryRecord, usize, usize)> {
+        let tolerance = self.gap_tolerance?;
+
+        // Segments ending before and starting after the epoch, where the most recently loaded ones take precedence.
+        let mut before: Option<(&SPKSummaryRecord, usize, usize)> = None;
+        let mut after: Option<(&SPKSummaryRecord, usize, usize)> = None;
+        for (spk_no, maybe_spk) in self.spk_data.iter().take(self.num_loaded_spk()).enumerate() {
+            let spk = maybe_spk.as_ref().unwrap();
+            for (idx_in_spk, summary) in spk.data_summaries().ok()?.iter().enumerate() {
+                if summary.target_id != id || summary.is_empty() {
+                    continue;
+                }
+                let (start, end) = (summary.start_epoch(), summary.end_epoch());
+                if end < epoch && !matches!(before, Some((other, _, _)) if end < other.end_epoch())
+                {
+                    before = Some((summary, spk_no, idx_in_spk));
+                } else if start > epoch
+                    && !matches!(after, Some((other, _, _)) if start > other.start_epoch())
+                {
+                    after = Some((summary, spk_no, idx_in_spk));
+                }
+            }
+        }
+
+        let (before, after) = (before?, after?);
+        if after.0.start_epoch() - before.0.end_epoch() > tolerance {
+            return None;
+        }
+
+        if epoch - before.0.end_epoch() <= after.0.start_epoch() - epoch {
+            Some(before)
+        } else {
+            Some(after)
+        }
+    }
+
     /// Returns the most recently loaded summary by its name, if any with that ID are available
     pub fn spk_summary_from_name(
         &self,
@@ -0,0 +1,452 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::path::Path;
+
+use hifitime::{Epoch, Unit};
+use snafu::ResultExt;
+
+use super::Almanac;
+use crate::ephemerides::{EphemInterpolationSnafu, EphemerisError, SPKSnafu};
+use crate::errors::{AlmanacError, AlmanacResult, EphemerisSnafu};
+use crate::math::Vector3;
+use crate::naif::daf::datatypes::{Type2ChebyshevSet, Type3ChebyshevSet};
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::naif::spk::summary::SPKSummaryRecord;
+use crate::naif::spk::writer::{fit_type2_records, FitSample, SPKWriter};
+use crate::NaifId;
+
+impl Almanac {
+    /// Writes a new SPK to the provided path with only the loaded segments of the provided target IDs, clipped to the provided window.
+    ///
+    /// The records of the Chebyshev segments (Type 2 and Type 3) that are fully within the window are copied as is, so the subset
+    /// returns exactly the same states. The partial records at the boundaries of the window are refit with Chebyshev polynomials (Type 2)
+    /// of the same degree, each as their own segment. The segments of the other data types are copied as is if they overlap the window.
+    ///
+    /// The segments of the most recently loaded SPKs are written first, such that the subset uses the same segments as this Almanac.
+    ///
+    /// # Errors
+    /// + The window must not be empty, and at least one segment of the requested bodies must overlap it.
+    /// + The subset must fit in a single summary record, i.e. at most [crate::naif::spk::writer::MAX_SEGMENTS] segments, noting that
+    ///   each clipped Chebyshev segment may require up to three segments.
+    pub fn export_subset<P: AsRef<Path>>(
+        &self,
+        bodies: &[NaifId],
+        window: (Epoch, Epoch),
+        out: P,
+    ) -> AlmanacResult<()> {
+        let (start, end) = window;
+        if start >= end {
+            return Err(AlmanacError::GenericError {
+                err: format!("subset window from {start} to {end} is empty"),
+            });
+        }
+        let window_et_s = (start.to_et_seconds(), end.to_et_seconds());
+
+        let mut writer = SPKWriter::new("ANISE SPK SUBSET");
+
+        // The last loaded SPK has precedence, and the first matching segment of an SPK is used, so the most recent SPKs are written first.
+        for spk in self
+            .spk_data
+            .iter()
+            .take(self.num_loaded_spk())
+            .rev()
+            .flatten()
+        {
+            let summary_size = spk
+                .file_record()
+                .context(SPKSnafu {
+                    action: "fetching file record for subset",
+                })
+                .context(EphemerisSnafu {
+                    action: "exporting subset",
+                })?
+                .summary_size();
+            let name_record = spk
+                .name_record()
+                .context(SPKSnafu {
+                    action: "fetching name record for subset",
+                })
+                .context(EphemerisSnafu {
+                    action: "exporting subset",
+                })?;
+            let summaries = spk
+                .data_summaries()
+                .context(SPKSnafu {
+                    action: "fetching summaries for subset",
+                })
+                .context(EphemerisSnafu {
+                    action: "exporting subset",
+                })?;
+
+            for (idx, summary) in summaries.iter().enumerate() {
+                if summary.is_empty()
+                    || !bodies.contains(&summary.target_id)
+                    || summary.end_epoch_et_s <= window_et_s.0
+                    || summary.start_epoch_et_s >= window_et_s.1
+                {
+                    continue;
+                }
+
+                let name = name_record.nth_name(idx, summary_size);
+                let clipped_et_s = (
+                    summary.start_epoch_et_s.max(window_et_s.0),
+                    summary.end_epoch_et_s.min(window_et_s.1),
+                );
+
+                match summary.data_type().context(EphemerisSnafu {
+                    action: "exporting subset",
+                })? {
+                    DafDataType::Type2ChebyshevTriplet => {
+                        let data = spk
+                            .nth_data::<Type2ChebyshevSet>(idx)
+                            .context(SPKSnafu {
+                                action: "fetching data for subset",
+                            })
+                            .context(EphemerisSnafu {
+                                action: "exporting subset",
+                            })?;
+                        let records = ChebyshevRecords {
+                            init_et_s: data.init_epoch.to_et_seconds(),
+                            interval_s: data.interval_length.to_seconds(),
+                            rsize: data.rsize,
+                            num_records: data.num_records,
+                            record_data: data.record_data,
+                            degree: data.degree(),
+                        };
+                        subset_chebyshev(
+                            &mut writer,
+                            summary,
+                            name,
+                            clipped_et_s,
+                            records,
+                            |epoch| {
+                                data.evaluate(epoch, summary)
+                                    .context(EphemInterpolationSnafu)
+                            },
+                        )?;
+                    }
+                    DafDataType::Type3ChebyshevSextuplet => {
+                        let data = spk
+                            .nth_data::<Type3ChebyshevSet>(idx)
+                            .context(SPKSnafu {
+                                action: "fetching data for subset",
+                            })
+                            .context(EphemerisSnafu {
+                                action: "exporting subset",
+                            })?;
+                        let records = ChebyshevRecords {
+                            init_et_s: data.init_epoch.to_et_seconds(),
+                            interval_s: data.interval_length.to_seconds(),
+                            rsize: data.rsize,
+                            num_records: data.num_records,
+                            record_data: data.record_data,
+                            degree: data.degree(),
+                        };
+                        subset_chebyshev(
+                            &mut writer,
+                            summary,
+                            name,
+                            clipped_et_s,
+                            records,
+                            |epoch| {
+                                data.evaluate(epoch, summary)
+                                    .context(EphemInterpolationSnafu)
+                            },
+                        )?;
+                    }
+                    _ => {
+                        let data = spk
+                            .nth_data_f64(idx)
+                            .context(SPKSnafu {
+                                action: "fetching data for subset",
+                            })
+                            .context(EphemerisSnafu {
+                                action: "exporting subset",
+                            })?;
+                        writer
+                            .add_segment_data(*summary, name, data.to_vec())
+                            .map_err(|e| AlmanacError::GenericError {
+                                err: format!("{e} when copying segment {name} into subset"),
+                            })?;
+                    }
+                }
+            }
+        }
+
+        if writer.num_segments() == 0 {
+            return Err(AlmanacError::GenericError {
+                err: format!("no segment of {bodies:?} loaded between {start} and {end}"),
+            });
+        }
+
+        writer.write(out).map_err(|e| AlmanacError::GenericError {
+            err: format!("{e} when exporting subset"),
+        })
+    }
+}
+
+/// Records of a Chebyshev segment (Type 2 or Type 3), all of the same size and length.
+struct ChebyshevRecords<'a> {
+    init_et_s: f64,
+    interval_s: f64,
+    rsize: usize,
+    num_records: usize,
+    record_data: &'a [f64],
+    degree: usize,
+}
+
+/// Adds the records of a Chebyshev segment that are within the clipped window to the writer, and refits the partial records at the
+/// boundaries of that window as their own Type 2 segments.
+fn subset_chebyshev(
+    writer: &mut SPKWriter,
+    summary: &SPKSummaryRecord,
+    name: &str,
+    (start_et_s, end_et_s): (f64, f64),
+    records: ChebyshevRecords,
+    evaluate: impl Fn(Epoch) -> Result<(Vector3, Vector3), EphemerisError>,
+) -> AlmanacResult<()> {
+    // Tolerate the rounding of the window onto the boundaries of the records.
+    let tol_s = 1e-6 * records.interval_s;
+    let first_full = (((start_et_s - records.init_et_s - tol_s) / records.interval_s)
+        .ceil()
+        .max(0.0) as usize)
+        .min(records.num_records);
+    let end_full = (((end_et_s - records.init_et_s + tol_s) / records.interval_s)
+        .floor()
+        .max(0.0) as usize)
+        .min(records.num_records);
+
+    let (head_end_et_s, tail_start_et_s) = if first_full < end_full {
+        let copy_start_et_s = records.init_et_s + (first_full as f64) * records.interval_s;
+        let copy_end_et_s = records.init_et_s + (end_full as f64) * records.interval_s;
+
+        let mut data =
+            records.record_data[first_full * records.rsize..end_full * records.rsize].to_vec();
+        // Both Type 2 and Type 3 end with the initial epoch, the interval length, the record size, and the number of records.
+        data.extend([
+            copy_start_et_s,
+            records.interval_s,
+            records.rsize as f64,
+            (end_full - first_full) as f64,
+        ]);
+
+        writer
+            .add_segment_data(
+                SPKSummaryRecord {
+                    start_epoch_et_s: copy_start_et_s,
+                    end_epoch_et_s: copy_end_et_s,
+                    ..*summary
+                },
+                name,
+                data,
+            )
+            .map_err(|e| AlmanacError::GenericError {
+                err: format!("{e} when copying segment {name} into subset"),
+            })?;
+
+        (copy_start_et_s, copy_end_et_s)
+    } else {
+        // The window is within a single record.
+        (end_et_s, end_et_s)
+    };
+
+    if head_end_et_s - start_et_s > tol_s {
+        refit_type2(
+            writer,
+            summary,
+            name,
+            (start_et_s, head_end_et_s),
+            records.degree,
+            &evaluate,
+        )?;
+    }
+
+    if end_et_s - tail_start_et_s > tol_s {
+        refit_type2(
+            writer,
+            summary,
+            name,
+            (tail_start_et_s, end_et_s),
+            records.degree,
+            &evaluate,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Fits the original data over the provided span with a single Chebyshev record of the provided degree, and adds it as a Type 2 segment.
+fn refit_type2(
+    writer: &mut SPKWriter,
+    summary: &SPKSummaryRecord,
+    name: &str,
+    (start_et_s, end_et_s): (f64, f64),
+    degree: usize,
+    evaluate: impl Fn(Epoch) -> Result<(Vector3, Vector3), EphemerisError>,
+) -> AlmanacResult<()> {
+    // The original polynomial restricted to this span is of the same degree, so twice as many states fit it exactly.
+    let num_samples = 2 * (degree + 1);
+    let samples = (0..num_samples)
+        .map(|i| {
+            let epoch = Epoch::from_et_seconds(
+                start_et_s + (end_et_s - start_et_s) * (i as f64) / ((num_samples - 1) as f64),
+            );
+            let (pos_km, vel_km_s) = evaluate(epoch).context(EphemerisSnafu {
+                action: "evaluating segment to refit for subset",
+            })?;
+            Ok(FitSample {
+                et_s: epoch.to_et_seconds(),
+                values: pos_km,
+                rates: Some(vel_km_s),
+            })
+        })
+        .collect::<AlmanacResult<Vec<FitSample>>>()?;
+
+    let init_et_s = samples[0].et_s;
+    let final_et_s = samples[num_samples - 1].et_s;
+    // Pad the interval by a nanosecond such that all of the samples are fit in a single record, regardless of rounding.
+    let interval_length = Unit::Second * (final_et_s - init_et_s) + Unit::Nanosecond;
+
+    let (data, _) = fit_type2_records(&samples, degree, interval_length).map_err(|e| {
+        AlmanacError::GenericError {
+            err: format!("{e} when refitting segment {name} for subset"),
+        }
+    })?;
+
+    writer
+        .add_segment_data(
+            SPKSummaryRecord {
+                start_epoch_et_s: init_et_s,
+                end_epoch_et_s: final_et_s,
+                data_type_i: 2,
+                ..*summary
+            },
+            name,
+            data,
+        )
+        .map_err(|e| AlmanacError::GenericError {
+            err: format!("{e} when refitting segment {name} for subset"),
+        })
+}
+
+#[cfg(test)]
+mod ut_subset {
+    use std::env;
+
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        almanac::Almanac,
+        astro::utils::propagate_universal_variable,
+        constants::frames::EARTH_J2000,
+        math::{cartesian::CartesianState, Vector3},
+        naif::{daf::NAIFSummaryRecord, spk::writer::SPKWriter},
+        prelude::Frame,
+    };
+
+    #[test]
+    fn subset_type2() {
+        let start = Epoch::from_et_seconds(0.0);
+        let states = (0..=12 * 60)
+            .map(|i| {
+                let dt_s = i as f64 * 60.0;
+                let (radius_km, velocity_km_s) = propagate_universal_variable(
+                    Vector3::new(7000.0, 100.0, -50.0),
+                    Vector3::new(0.1, 7.2, 1.5),
+                    398600.4418,
+                    dt_s,
+                )
+                .unwrap();
+                CartesianState {
+                    radius_km,
+                    velocity_km_s,
+                    epoch: start + dt_s.seconds(),
+                    frame: EARTH_J2000,
+                }
+            })
+            .collect::<Vec<CartesianState>>();
+
+        let mut writer = SPKWriter::new("ANISE subset test");
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+        // Other bodies which are not exported.
+        for id in -10000005..=-10000002 {
+            writer
+                .add_type2_segment(&states, id, 399, 1, 13, 1.hours(), "OTHER SC")
+                .unwrap();
+        }
+        let spk = writer.build().unwrap();
+        let original_len = spk.bytes.len();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let path = env::temp_dir().join("anise-subset-ut.bsp");
+        let window = (start + 1.5.hours(), start + 9.25.hours());
+        almanac.export_subset(&[-10000001], window, &path).unwrap();
+
+        // One of the five bodies over two thirds of the span is well below the original size.
+        let subset_len = std::fs::metadata(&path).unwrap().len() as usize;
+        assert!(
+            subset_len * 2 < original_len,
+            "subset of {subset_len} bytes is not well below the original {original_len} bytes"
+        );
+
+        let subset_almanac = Almanac::default().load(&path.to_string_lossy()).unwrap();
+        let spk = subset_almanac.spk_data[0].as_ref().unwrap();
+        let summaries = spk.data_summaries().unwrap();
+        // The full records, then the head and the tail refit.
+        assert_eq!(summaries.len(), 3);
+        assert!(summaries
+            .iter()
+            .all(|summary| summary.target_id == -10000001));
+        assert_eq!(summaries[0].start_epoch(), start + 2.hours());
+        assert_eq!(summaries[0].end_epoch(), start + 9.hours());
+        assert_eq!(summaries[1].start_epoch(), window.0);
+        assert_eq!(summaries[2].end_epoch(), window.1);
+
+        let sc = Frame::new(-10000001, 1);
+        for step in 0..=(7.75 * 60.0) as i64 {
+            let epoch = window.0 + step.minutes();
+            let expected = almanac.translate_to_parent(sc, epoch).unwrap();
+            let state = subset_almanac.translate_to_parent(sc, epoch).unwrap();
+            if (start + 2.hours()..start + 9.hours()).contains(&epoch) {
+                // Copied records are identical (at 9 hours, the original SPK uses the next record).
+                assert_eq!(state.radius_km, expected.radius_km, "{epoch}");
+                assert_eq!(state.velocity_km_s, expected.velocity_km_s, "{epoch}");
+            } else {
+                assert!(
+                    (state.radius_km - expected.radius_km).norm() < 1e-5,
+                    "{epoch}: {}",
+                    (state.radius_km - expected.radius_km).norm()
+                );
+                assert!(
+                    (state.velocity_km_s - expected.velocity_km_s).norm() < 1e-8,
+                    "{epoch}: {}",
+                    (state.velocity_km_s - expected.velocity_km_s).norm()
+                );
+            }
+        }
+
+        // Outside of the window
+        assert!(subset_almanac
+            .translate_to_parent(sc, start + 1.hours())
+            .is_err());
+        assert!(subset_almanac
+            .translate_to_parent(Frame::new(-10000002, 1), start + 5.hours())
+            .is_err());
+
+        // Nothing to export
+        assert!(almanac.export_subset(&[-10000003], window, &path).is_err());
+        assert!(almanac
+            .export_subset(&[-10000001], (window.1, window.0), &path)
+            .is_err());
+    }
+}
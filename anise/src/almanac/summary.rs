@@ -0,0 +1,320 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use hifitime::{Epoch, TimeScale};
+
+use super::Almanac;
+use crate::naif::daf::{DafDataType, NAIFSummaryRecord, DAF};
+use crate::NaifId;
+
+/// Kind of kernel in which a segment is defined
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KernelKind {
+    /// Ephemeris data (DAF/SPK), i.e. translations
+    SPK,
+    /// Binary planetary constants (DAF/PCK), i.e. rotations
+    BPC,
+}
+
+impl fmt::Display for KernelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SPK => write!(f, "SPK"),
+            Self::BPC => write!(f, "BPC"),
+        }
+    }
+}
+
+/// Description of one segment of a loaded SPK or BPC, as listed by [Almanac::summary].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentDescriptor {
+    /// Kind of kernel in which this segment is defined
+    pub kind: KernelKind,
+    /// Index of the kernel in the loading order of its kind
+    pub kernel_no: usize,
+    /// Path from which the kernel was loaded, if it was loaded from a file
+    pub source: Option<String>,
+    /// Index of the segment in its kernel
+    pub segment_no: usize,
+    /// Name of the segment
+    pub name: String,
+    /// Target ID of an SPK segment, or frame ID of a BPC segment
+    pub target_id: NaifId,
+    /// Center ID of an SPK segment, or inertial frame ID of a BPC segment
+    pub observer_id: NaifId,
+    /// Orientation ID in which the data is expressed, i.e. the frame ID of an SPK segment, or the inertial frame ID of a BPC segment
+    pub frame_id: NaifId,
+    /// Data type of this segment, or its raw identifier if ANISE does not support it
+    pub data_type: Result<DafDataType, i32>,
+    /// Start epoch of the coverage of this segment
+    pub start_epoch: Epoch,
+    /// End epoch of the coverage of this segment
+    pub end_epoch: Epoch,
+}
+
+impl fmt::Display for SegmentDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data_type = match self.data_type {
+            Ok(data_type) => data_type.to_string(),
+            Err(data_type_i) => format!("unsupported type {data_type_i}"),
+        };
+        write!(
+            f,
+            "{} #{} segment #{} `{}`: {} wrt {} in {}\t{}\t{} to {}",
+            self.kind,
+            self.kernel_no,
+            self.segment_no,
+            self.name,
+            self.target_id,
+            self.observer_id,
+            self.frame_id,
+            data_type,
+            self.start_epoch.to_gregorian_str(TimeScale::TDB),
+            self.end_epoch.to_gregorian_str(TimeScale::TDB),
+        )
+    }
+}
+
+impl Almanac {
+    /// Returns the description of every segment of the loaded SPKs then BPCs, sorted by kernel in the loading order, then by the index of
+    /// the segment in its kernel. Empty segments are skipped.
+    pub fn summary(&self) -> Vec<SegmentDescriptor> {
+        let mut segments = Vec::new();
+
+        for (kernel_no, spk) in self.spk_data.iter().take(self.num_loaded_spk()).enumerate() {
+            let spk = spk.as_ref().unwrap();
+            for (segment_no, name, summary) in named_summaries(spk) {
+                segments.push(SegmentDescriptor {
+                    kind: KernelKind::SPK,
+                    kernel_no,
+                    source: self.spk_sources[kernel_no].clone(),
+                    segment_no,
+                    name,
+                    target_id: summary.target_id,
+                    observer_id: summary.center_id,
+                    frame_id: summary.frame_id,
+                    data_type: summary.data_type().map_err(|_| summary.data_type_i),
+                    start_epoch: summary.start_epoch(),
+                    end_epoch: summary.end_epoch(),
+                });
+            }
+        }
+
+        for (kernel_no, bpc) in self.bpc_data.iter().take(self.num_loaded_bpc()).enumerate() {
+            let bpc = bpc.as_ref().unwrap();
+            for (segment_no, name, summary) in named_summaries(bpc) {
+                segments.push(SegmentDescriptor {
+                    kind: KernelKind::BPC,
+                    kernel_no,
+                    source: self.bpc_sources[kernel_no].clone(),
+                    segment_no,
+                    name,
+                    target_id: summary.frame_id,
+                    observer_id: summary.inertial_frame_id,
+                    frame_id: summary.inertial_frame_id,
+                    data_type: summary.data_type().map_err(|_| summary.data_type_i),
+                    start_epoch: summary.start_epoch(),
+                    end_epoch: summary.end_epoch(),
+                });
+            }
+        }
+
+        segments
+    }
+
+    /// Returns a report of everything loaded in this Almanac, to be pasted in bug reports: each kernel with its segments as listed by
+    /// [Self::summary], the planetary and Euler parameter data, the fixed frames, and the edges of the translation and rotation graphs
+    /// derived from all of them, where `A -> B` means that A is defined with respect to B.
+    ///
+    /// The ordering is deterministic, so this report can be used in snapshot tests.
+    pub fn summary_report(&self) -> String {
+        let segments = self.summary();
+        let mut translations = BTreeSet::new();
+        let mut rotations = BTreeSet::new();
+        let mut report = String::new();
+
+        for kind in [KernelKind::SPK, KernelKind::BPC] {
+            let num_loaded = match kind {
+                KernelKind::SPK => self.num_loaded_spk(),
+                KernelKind::BPC => self.num_loaded_bpc(),
+            };
+            for kernel_no in 0..num_loaded {
+                let kernel_segments = segments
+                    .iter()
+                    .filter(|segment| segment.kind == kind && segment.kernel_no == kernel_no)
+                    .collect::<Vec<_>>();
+
+                let source = match kind {
+                    KernelKind::SPK => &self.spk_sources[kernel_no],
+                    KernelKind::BPC => &self.bpc_sources[kernel_no],
+                };
+                writeln!(
+                    report,
+                    "=== {kind} #{kernel_no} ({}) with {} segments ===",
+                    source.as_deref().unwrap_or("loaded from bytes"),
+                    kernel_segments.len()
+                )
+                .unwrap();
+
+                for segment in kernel_segments {
+                    writeln!(report, "{segment}").unwrap();
+                    match kind {
+                        KernelKind::SPK => {
+                            translations.insert((segment.target_id, segment.observer_id))
+                        }
+                        KernelKind::BPC => {
+                            rotations.insert((segment.target_id, segment.observer_id))
+                        }
+                    };
+                }
+            }
+        }
+
+        let mut planetary_ids = self
+            .planetary_data
+            .lut
+            .by_id
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        planetary_ids.sort_unstable();
+        writeln!(
+            report,
+            "=== PLANETARY DATA with {} entries ===",
+            planetary_ids.len()
+        )
+        .unwrap();
+        for id in planetary_ids {
+            if let Ok(planetary) = self.planetary_data.get_by_id(id) {
+                writeln!(report, "{planetary}").unwrap();
+                if planetary.pole_right_ascension.is_some() || planetary.prime_meridian.is_some() {
+                    rotations.insert((planetary.object_id, planetary.parent_id));
+                }
+            }
+        }
+
+        let mut euler_ids = self
+            .euler_param_data
+            .lut
+            .by_id
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        euler_ids.sort_unstable();
+        writeln!(
+            report,
+            "=== EULER PARAMETER DATA with {} entries ===",
+            euler_ids.len()
+        )
+        .unwrap();
+        for id in euler_ids {
+            if let Ok(quaternion) = self.euler_param_data.get_by_id(id) {
+                writeln!(report, "{id}: {quaternion}").unwrap();
+                rotations.insert((quaternion.from, quaternion.to));
+            }
+        }
+
+        let mut fixed_ids = self.fixed_frames.keys().copied().collect::<Vec<_>>();
+        fixed_ids.sort_unstable();
+        writeln!(
+            report,
+            "=== FIXED FRAMES with {} entries ===",
+            fixed_ids.len()
+        )
+        .unwrap();
+        for id in fixed_ids {
+            let fixed = &self.fixed_frames[&id];
+            writeln!(report, "{id} `{}` fixed in {:e}", fixed.name, fixed.parent).unwrap();
+            translations.insert((id, fixed.parent.ephemeris_id));
+            rotations.insert((id, fixed.parent.orientation_id));
+        }
+
+        writeln!(
+            report,
+            "=== TRANSLATION GRAPH with {} edges ===",
+            translations.len()
+        )
+        .unwrap();
+        for (child, parent) in translations {
+            writeln!(report, "{child} -> {parent}").unwrap();
+        }
+        writeln!(
+            report,
+            "=== ROTATION GRAPH with {} edges ===",
+            rotations.len()
+        )
+        .unwrap();
+        for (child, parent) in rotations {
+            writeln!(report, "{child} -> {parent}").unwrap();
+        }
+
+        report
+    }
+}
+
+/// Returns the index, name, and summary of each non-empty segment of this DAF file, in the order of the file.
+fn named_summaries<R: NAIFSummaryRecord>(daf: &DAF<R>) -> Vec<(usize, String, R)> {
+    let (Ok(summaries), Ok(name_record), Ok(file_record)) =
+        (daf.data_summaries(), daf.name_record(), daf.file_record())
+    else {
+        return Vec::new();
+    };
+
+    summaries
+        .iter()
+        .enumerate()
+        .filter(|(_, summary)| !summary.is_empty())
+        .map(|(segment_no, summary)| {
+            let name = name_record.nth_name(segment_no, file_record.summary_size());
+            (segment_no, name.trim().to_string(), *summary)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod ut_summary {
+    use super::*;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::{Matrix3, Vector3};
+
+    #[test]
+    fn summary_report_is_deterministic() {
+        let mut almanac = Almanac::default();
+        assert!(almanac.summary().is_empty());
+        assert!(almanac
+            .summary_report()
+            .contains("TRANSLATION GRAPH with 0 edges"));
+
+        // Fixed frames add edges to both graphs.
+        let frame = almanac
+            .add_fixed_frame(
+                "TEST_FIXED",
+                EARTH_J2000,
+                Vector3::new(1.0, 2.0, 3.0),
+                Matrix3::identity(),
+            )
+            .unwrap();
+        let report = almanac.summary_report();
+        assert!(report.contains("FIXED FRAMES with 1 entries"), "{report}");
+        assert!(
+            report.contains(&format!("{} -> 399", frame.ephemeris_id)),
+            "{report}"
+        );
+        assert!(
+            report.contains(&format!("{} -> 1", frame.orientation_id)),
+            "{report}"
+        );
+        assert_eq!(report, almanac.summary_report());
+    }
+}
@@ -0,0 +1,271 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+use hifitime::{Epoch, Unit as TimeUnit};
+use snafu::ResultExt;
+
+use crate::{
+    astro::{Aberration, PhysicsResult},
+    constants::{frames::SSB_J2000, orientations::J2000, SPEED_OF_LIGHT_KM_S},
+    ephemerides::EphemerisPhysicsSnafu,
+    errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu, PhysicsError},
+    math::Vector3,
+    prelude::Frame,
+};
+
+use super::Almanac;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Method used to locate the point on the surface of the target below the observer, analogous to the methods of SPICE's `subpnt`.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[cfg_attr(feature = "python", pyo3(module = "anise"))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SubPointMethod {
+    /// Intersection of the surface with the ray from the observer to the center of the target (`INTERCEPT/ELLIPSOID`).
+    Intercept,
+    /// Point of the surface nearest to the observer, i.e. where the normal to the surface points to the observer (`NEAR POINT/ELLIPSOID`).
+    #[default]
+    NearPoint,
+}
+
+impl fmt::Display for SubPointMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Intercept => write!(f, "intercept"),
+            Self::NearPoint => write!(f, "near point"),
+        }
+    }
+}
+
+/// The point where a ray from an observer hits the surface of a target, cf. [Almanac::surface_intercept].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SurfaceIntercept {
+    /// Intercept point in km, in the body fixed frame of the target
+    pub point_km: Vector3,
+    /// Epoch of the position and orientation of the target, i.e. the light time corrected epoch of the intercept point
+    pub epoch: Epoch,
+    /// Vector from the observer to the intercept point in km, in the body fixed frame of the target
+    pub surface_vector_km: Vector3,
+    /// Body fixed frame of the target, with its planetary data
+    pub frame: Frame,
+}
+
+impl SurfaceIntercept {
+    /// Returns the geodetic latitude (φ) and longitude (λ) in degrees and height in km (typically zero) of the intercept point.
+    pub fn geodetic(&self) -> PhysicsResult<(f64, f64, f64)> {
+        self.frame.cartesian_to_geodetic(self.point_km)
+    }
+}
+
+impl fmt::Display for SurfaceIntercept {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "intercept of {:x} at {}: {} km ({:.3} km from observer)",
+            self.frame,
+            self.epoch,
+            self.point_km,
+            self.surface_vector_km.norm()
+        )
+    }
+}
+
+impl Almanac {
+    /// Returns the geodetic latitude (φ) and longitude (λ) in degrees of the sub-observer point on the tri-axial ellipsoid of the target,
+    /// and the altitude of the observer above that point in km (i.e. the distance between them).
+    ///
+    /// The target frame must be body fixed (e.g. IAU_EARTH_FRAME), and its shape is fetched from the loaded planetary data if it is not
+    /// set. Use the Sun as the observer to compute the sub-solar point.
+    ///
+    /// # SPICE Compatibility
+    /// This is the equivalent of `subpnt` with the `INTERCEPT/ELLIPSOID` or `NEAR POINT/ELLIPSOID` method, followed by `recgeo` on the
+    /// sub-observer point. With light time corrections, the position and the orientation of the target are those at the light time
+    /// corrected epoch, as in SPICE.
+    ///
+    /// # Errors
+    /// + The target frame must have a shape.
+    /// + With the intercept method, the observer must be outside of the ellipsoid.
+    pub fn sub_observer_point(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        method: SubPointMethod,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<(f64, f64, f64)> {
+        let action = "computing sub-observer point";
+
+        let (target_frame, obs_km) =
+            self.observer_in_body_fixed(target_frame, observer_frame, epoch, ab_corr, action)?;
+
+        let to_geodetic = |radius_km: Vector3| {
+            target_frame
+                .cartesian_to_geodetic(radius_km)
+                .context(EphemerisPhysicsSnafu { action })
+                .context(EphemerisSnafu { action })
+        };
+
+        match method {
+            SubPointMethod::NearPoint => to_geodetic(obs_km),
+            SubPointMethod::Intercept => {
+                // From inside the ellipsoid, the ray toward the center would only find where it exits the surface behind the observer.
+                let (_, _, height_km) = to_geodetic(obs_km)?;
+
+                let surface_km = target_frame
+                    .shape
+                    .and_then(|shape| shape.intersect_ray(obs_km, -obs_km))
+                    .filter(|_| height_km > 0.0)
+                    .ok_or(PhysicsError::RadiusError {
+                        action: "observer is inside the target ellipsoid",
+                    })
+                    .context(EphemerisPhysicsSnafu { action })
+                    .context(EphemerisSnafu { action })?;
+
+                let (latitude_deg, longitude_deg, _) = to_geodetic(surface_km)?;
+
+                Ok((latitude_deg, longitude_deg, (obs_km - surface_km).norm()))
+            }
+        }
+    }
+
+    /// Returns the target frame with its planetary data, and the position in km of the observer in that body fixed frame, where the
+    /// position and the orientation of the target are computed at the light time corrected epoch.
+    pub(crate) fn observer_in_body_fixed(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+        action: &'static str,
+    ) -> AlmanacResult<(Frame, Vector3)> {
+        let (target_frame, _) = self.frame_with_shape(target_frame, action)?;
+
+        let target_inertial = target_frame.with_orient(J2000);
+        let observer_inertial = observer_frame.with_orient(J2000);
+
+        let tgt_wrt_obs = self
+            .translate(target_inertial, observer_inertial, epoch, ab_corr)
+            .context(EphemerisSnafu { action })?;
+
+        let epoch_lt = self
+            .light_time_corrected_epoch(target_inertial, observer_inertial, epoch, ab_corr)
+            .context(EphemerisSnafu { action })?;
+
+        let dcm = self
+            .rotate(target_inertial, target_frame, epoch_lt)
+            .context(OrientationSnafu { action })?;
+
+        Ok((target_frame, -(dcm.rot_mat * tgt_wrt_obs.radius_km)))
+    }
+
+    /// Returns the surface point where the ray from the observer along the provided direction hits the tri-axial ellipsoid of the target,
+    /// or None if the ray misses it. The direction is expressed in the orientation of the direction frame (e.g. the body fixed frame of
+    /// the target, or the inertial frame of a spacecraft attitude), and need not be normalized.
+    ///
+    /// The target frame must be body fixed (e.g. IAU_EARTH_FRAME), and its shape is fetched from the loaded planetary data if it is not
+    /// set. If the observer is inside the ellipsoid, the intercept is where the ray exits it.
+    ///
+    /// # SPICE Compatibility
+    /// This is the equivalent of `sincpt` with the `ELLIPSOID` method. With light time corrections, the position and the orientation of
+    /// the target are those at the epoch when the light left the intercept point (or reached it in transmission mode), iterated as in
+    /// [Self::translate], and the direction is that of the ray at the observer epoch.
+    ///
+    /// # Errors
+    /// + The target frame must have a shape.
+    /// + Stellar aberration corrections of the ray are not supported.
+    pub fn surface_intercept(
+        &self,
+        observer_frame: Frame,
+        target_frame: Frame,
+        epoch: Epoch,
+        direction_frame: Frame,
+        direction: Vector3,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Option<SurfaceIntercept>> {
+        let action = "computing surface intercept";
+
+        let (target_frame, shape) = self.frame_with_shape(target_frame, action)?;
+
+        if ab_corr.is_some_and(|ab_corr| ab_corr.stellar) {
+            return Err(PhysicsError::AberrationError {
+                action: "stellar aberration is not supported for surface intercepts",
+            })
+            .context(EphemerisPhysicsSnafu { action })
+            .context(EphemerisSnafu { action });
+        }
+
+        let dir_inertial = self
+            .rotate(direction_frame, direction_frame.with_orient(J2000), epoch)
+            .context(OrientationSnafu { action })?
+            .rot_mat
+            * direction;
+
+        let obs_ssb_km = self
+            .translate(observer_frame.with_orient(J2000), SSB_J2000, epoch, None)
+            .context(EphemerisSnafu { action })?
+            .radius_km;
+
+        let intercept_at = |epoch_tgt: Epoch| -> AlmanacResult<Option<SurfaceIntercept>> {
+            let target_inertial = target_frame.with_orient(J2000);
+            let tgt_ssb_km = self
+                .translate(target_inertial, SSB_J2000, epoch_tgt, None)
+                .context(EphemerisSnafu { action })?
+                .radius_km;
+            let dcm = self
+                .rotate(target_inertial, target_frame, epoch_tgt)
+                .context(OrientationSnafu { action })?;
+
+            let obs_km = dcm.rot_mat * (obs_ssb_km - tgt_ssb_km);
+
+            Ok(shape
+                .intersect_ray(obs_km, dcm.rot_mat * dir_inertial)
+                .map(|point_km| SurfaceIntercept {
+                    point_km,
+                    epoch: epoch_tgt,
+                    surface_vector_km: point_km - obs_km,
+                    frame: target_frame,
+                }))
+        };
+
+        let mut intercept = intercept_at(epoch)?;
+
+        let Some(ab_corr) = ab_corr else {
+            return Ok(intercept);
+        };
+
+        let num_it = if ab_corr.converged {
+            Aberration::MAX_CONVERGED_ITERATIONS
+        } else {
+            1
+        };
+        let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
+
+        let mut one_way_lt_s = 0.0;
+        for _ in 0..num_it {
+            let Some(found) = intercept else {
+                break;
+            };
+
+            let prev_lt_s = one_way_lt_s;
+            one_way_lt_s = found.surface_vector_km.norm() / SPEED_OF_LIGHT_KM_S;
+            if (one_way_lt_s - prev_lt_s).abs() <= Aberration::CONVERGED_LT_REL_TOL * one_way_lt_s {
+                break;
+            }
+
+            intercept = intercept_at(epoch + lt_sign * one_way_lt_s * TimeUnit::Second)?;
+        }
+
+        Ok(intercept)
+    }
+}
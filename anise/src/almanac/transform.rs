@@ -12,12 +12,13 @@ use hifitime::{Epoch, Unit as TimeUnit};
 use snafu::ResultExt;
 
 use crate::{
+    astro::{AberrationConfig, LightTimeConvergence},
     constants::{
         frames::{EARTH_J2000, SUN_J2000},
         orientations::J2000,
     },
-    errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu},
-    math::{cartesian::CartesianState, units::LengthUnit, Vector3},
+    errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu, PhysicsError},
+    math::{cartesian::CartesianState, units::LengthUnit, Matrix6, Vector3},
     orientations::OrientationPhysicsSnafu,
     prelude::{Aberration, Frame},
     NaifId,
@@ -45,9 +46,30 @@ impl Almanac {
         epoch: Epoch,
         ab_corr: Option<Aberration>,
     ) -> AlmanacResult<CartesianState> {
+        self.transform_cfg(
+            target_frame,
+            observer_frame,
+            epoch,
+            ab_corr,
+            AberrationConfig::default(),
+        )
+        .map(|(state, _)| state)
+    }
+
+    /// Returns the Cartesian state needed to transform the `from_frame` to the `to_frame` exactly as [Self::transform], but iterating the
+    /// light time of the aberration correction with the provided configuration, along with the outcome of these iterations (None without
+    /// aberration correction). Check that outcome to catch geometries where the light time did not converge.
+    pub fn transform_cfg(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+        cfg: AberrationConfig,
+    ) -> AlmanacResult<(CartesianState, Option<LightTimeConvergence>)> {
         // Translate
-        let state = self
-            .translate(target_frame, observer_frame, epoch, ab_corr)
+        let (state, convergence) = self
+            .translate_cfg(target_frame, observer_frame, epoch, ab_corr, cfg)
             .context(EphemerisSnafu {
                 action: "transform from/to",
             })?;
@@ -58,11 +80,14 @@ impl Almanac {
                 action: "transform from/to",
             })?;
 
-        (dcm * state)
-            .context(OrientationPhysicsSnafu {})
-            .context(OrientationSnafu {
-                action: "transform from/to",
-            })
+        let state =
+            (dcm * state)
+                .context(OrientationPhysicsSnafu {})
+                .context(OrientationSnafu {
+                    action: "transform from/to",
+                })?;
+
+        Ok((state, convergence))
     }
 
     /// Returns the provided state as seen from the observer frame, given the aberration.
@@ -96,6 +121,128 @@ impl Almanac {
             })
     }
 
+    /// Re-expresses the provided state, valid at the provided epoch, in the `to_frame`: both its center and its orientation are changed.
+    ///
+    /// The velocity includes the transport term of the rotation of the frames, i.e. the ω×r term of a rotating body-fixed frame, so
+    /// transforming a state from an inertial frame into a body-fixed frame and back recovers the original velocity. The state is re-centered
+    /// with the geometric translation between the centers of both frames, without any aberration correction (use [Self::transform_to] for that).
+    ///
+    /// The `epoch` must be the epoch of the state: it is checked so that a state at an unintended epoch is rejected instead of being
+    /// transformed at its own epoch.
+    ///
+    /// # Errors
+    /// + The epoch of the state must be the provided epoch;
+    /// + The translation and the rotation between both frames must be available at that epoch.
+    pub fn transform_state(
+        &self,
+        state: CartesianState,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> AlmanacResult<CartesianState> {
+        if state.epoch != epoch {
+            return Err(PhysicsError::EpochMismatch {
+                action: "transforming state to another frame",
+                epoch1: state.epoch,
+                epoch2: epoch,
+            })
+            .context(OrientationPhysicsSnafu {})
+            .context(OrientationSnafu {
+                action: "transform state",
+            });
+        }
+
+        self.transform_to(state, to_frame, None)
+    }
+
+    /// Re-expresses the provided state and its acceleration, valid at the provided epoch, in the `to_frame`, exactly as [Self::transform_state].
+    ///
+    /// The acceleration includes the acceleration of the center of the `to_frame` with respect to the center of the state, and the fictitious
+    /// accelerations of the rotation between both frames: the Coriolis term `2 C' v`, and the centripetal and Euler terms `C'' r`.
+    ///
+    /// # Errors
+    /// + The epoch of the state must be the provided epoch;
+    /// + All of the rotations and translations along the path must provide their second time derivative, cf. [Self::rotation_to_parent_with_acceleration]
+    ///   and [Self::translate_with_derivatives].
+    pub fn transform_state_with_acceleration(
+        &self,
+        state: CartesianState,
+        acceleration_km_s2: Vector3,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> AlmanacResult<(CartesianState, Vector3)> {
+        if state.epoch != epoch {
+            return Err(PhysicsError::EpochMismatch {
+                action: "transforming state and acceleration to another frame",
+                epoch1: state.epoch,
+                epoch2: epoch,
+            })
+            .context(OrientationPhysicsSnafu {})
+            .context(OrientationSnafu {
+                action: "transform state with acceleration",
+            });
+        }
+
+        // If the input and final rotations differ, rotate into J2000 first
+        let (mut state, mut acceleration_km_s2) = if state.frame.orient_origin_match(to_frame) {
+            (state, acceleration_km_s2)
+        } else {
+            self.rotate_to_with_acceleration(
+                state,
+                acceleration_km_s2,
+                state.frame.with_orient(J2000),
+            )
+            .context(OrientationSnafu {
+                action: "transform state with acceleration dcm",
+            })?
+        };
+
+        // Translate in the base frame (J2000) or the common frame
+        let (frame_state, frame_acc_km_s2) = self
+            .translate_with_derivatives(state.frame, to_frame, epoch)
+            .context(EphemerisSnafu {
+                action: "transform state with acceleration",
+            })?;
+        let orientation_id = state.frame.orientation_id;
+        state = state.add_unchecked(&frame_state);
+        state.frame = self
+            .frame_from_uid(to_frame)
+            .unwrap_or(to_frame)
+            .with_orient(orientation_id);
+        acceleration_km_s2 += frame_acc_km_s2;
+
+        // Rotate into the final frame
+        self.rotate_to_with_acceleration(state, acceleration_km_s2, to_frame)
+            .context(OrientationSnafu {
+                action: "transform state with acceleration",
+            })
+    }
+
+    /// Rotates the provided 6x6 covariance of a Cartesian state, expressed in the `from_frame`, into the orientation of the `to_frame` at the
+    /// provided epoch, consistently with [Self::transform_state].
+    ///
+    /// The Jacobian of the transformation is the 6x6 DCM of the rotation (cf. [DCM::state_dcm](crate::math::rotation::DCM::state_dcm)),
+    /// whose lower left block is the time derivative of the rotation, i.e. the coupling of the velocity to the position through the ω×r
+    /// term. The covariance is then `J C Jᵀ`. Changing the center of the frame is a translation by a known state, so it does not change the covariance.
+    ///
+    /// # Errors
+    /// + The rotation between both frames must be available at that epoch.
+    pub fn transform_covariance(
+        &self,
+        covariance: Matrix6,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> AlmanacResult<Matrix6> {
+        let jacobian = self
+            .rotate(from_frame, to_frame, epoch)
+            .context(OrientationSnafu {
+                action: "transform covariance",
+            })?
+            .state_dcm();
+
+        Ok(jacobian * covariance * jacobian.transpose())
+    }
+
     /// Returns the Cartesian state of the object as seen from the provided observer frame (essentially `spkezr`).
     ///
     /// # Note
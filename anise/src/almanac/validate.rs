@@ -0,0 +1,664 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use std::collections::HashMap;
+
+use hifitime::Duration;
+
+use super::summary::KernelKind;
+use super::Almanac;
+use crate::naif::daf::datatypes::directory::DIRECTORY_STEP;
+use crate::naif::daf::datatypes::{
+    DiscreteStatesType5, HermiteSetType12, HermiteSetType13, LagrangeSetType8, LagrangeSetType9,
+    Type1ModifiedDifferenceSet, Type2ChebyshevSet, Type3ChebyshevSet,
+};
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord, DAF};
+use crate::DBL_SIZE;
+
+/// Number of doubles of the position and velocity records of the Lagrange and Hermite types (8, 9, 12, and 13) and of the Type 5.
+const STATE_RECORD_LEN: usize = 6;
+
+/// Anomaly found in a loaded kernel by [Almanac::validate].
+#[derive(Clone, Debug, PartialEq)]
+pub enum KernelAnomaly {
+    /// The file record or the summary record cannot be decoded, so none of the segments could be checked
+    UnreadableSummaries { reason: String },
+    /// The summary record declares more summaries than it can hold
+    SummaryCount { declared: usize, capacity: usize },
+    /// The summary record points to another summary record, whose segments are not read by ANISE. This is valid in a DAF (e.g. in SPKs
+    /// of more than 25 segments), so it is reported in the [ValidationReport::limitations] instead of the issues.
+    UnreadSummaryRecord { next_record: usize },
+    /// The data addresses of the segment are not a range of double words (DAF addresses start at 1)
+    InvalidDataPointers { start_idx: usize, end_idx: usize },
+    /// The bytes of the segment data extend past the end of the file
+    DataOutOfBounds {
+        start_byte: usize,
+        end_byte: usize,
+        file_size: usize,
+    },
+    /// The coverage of the segment is not finite, or it ends before it starts
+    InvalidCoverage { start_et_s: f64, end_et_s: f64 },
+    /// The segment starts before the previous segment of the same ID in this kernel
+    NonMonotonicCoverage { previous_segment_no: usize },
+    /// The segment starts before the end of the previous segment of the same ID in this kernel, the latter being used in the overlap
+    OverlappingCoverage {
+        previous_segment_no: usize,
+        overlap: Duration,
+    },
+    /// The data type of the segment is not a NAIF data type
+    UnknownDataType { reason: String },
+    /// The data of the segment cannot be decoded, or it holds invalid values
+    DataDecoding { reason: String },
+    /// The number of doubles of the records does not match the number of records declared by the segment
+    RecordCount {
+        declared_records: usize,
+        expected_len: usize,
+        stored_len: usize,
+    },
+    /// The epochs of the records are not in chronological order, starting at this record
+    UnsortedEpochs { record_no: usize },
+    /// The epoch directory does not hold every 100th epoch of the records, starting at this entry
+    EpochDirectory { entry_no: usize },
+}
+
+impl fmt::Display for KernelAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnreadableSummaries { reason } => write!(f, "unreadable summaries: {reason}"),
+            Self::SummaryCount { declared, capacity } => write!(
+                f,
+                "summary record declares {declared} summaries but holds at most {capacity}"
+            ),
+            Self::UnreadSummaryRecord { next_record } => write!(
+                f,
+                "summary record points to the summary record #{next_record}, whose segments are not read"
+            ),
+            Self::InvalidDataPointers { start_idx, end_idx } => {
+                write!(f, "invalid data addresses from {start_idx} to {end_idx}")
+            }
+            Self::DataOutOfBounds {
+                start_byte,
+                end_byte,
+                file_size,
+            } => write!(
+                f,
+                "data from byte {start_byte} to {end_byte} exceeds the file size of {file_size} bytes"
+            ),
+            Self::InvalidCoverage {
+                start_et_s,
+                end_et_s,
+            } => write!(
+                f,
+                "invalid coverage from {start_et_s} to {end_et_s} ET seconds"
+            ),
+            Self::NonMonotonicCoverage {
+                previous_segment_no,
+            } => write!(
+                f,
+                "starts before the previous segment #{previous_segment_no} of the same ID"
+            ),
+            Self::OverlappingCoverage {
+                previous_segment_no,
+                overlap,
+            } => write!(
+                f,
+                "overlaps the previous segment #{previous_segment_no} of the same ID by {overlap}"
+            ),
+            Self::UnknownDataType { reason } => write!(f, "unknown data type: {reason}"),
+            Self::DataDecoding { reason } => write!(f, "data cannot be decoded: {reason}"),
+            Self::RecordCount {
+                declared_records,
+                expected_len,
+                stored_len,
+            } => write!(
+                f,
+                "{declared_records} records need {expected_len} doubles but {stored_len} are stored"
+            ),
+            Self::UnsortedEpochs { record_no } => {
+                write!(f, "epochs are not sorted from record #{record_no}")
+            }
+            Self::EpochDirectory { entry_no } => {
+                write!(f, "epoch directory is inconsistent from entry #{entry_no}")
+            }
+        }
+    }
+}
+
+/// An anomaly and where it was found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KernelIssue {
+    /// Kind of kernel in which the anomaly was found
+    pub kind: KernelKind,
+    /// Index of the kernel in the loading order of its kind
+    pub kernel_no: usize,
+    /// Path from which the kernel was loaded, if it was loaded from a file
+    pub source: Option<String>,
+    /// Index of the segment in its kernel, or None if the anomaly is that of the whole kernel
+    pub segment_no: Option<usize>,
+    /// Anomaly found
+    pub anomaly: KernelAnomaly,
+}
+
+impl fmt::Display for KernelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.kind, self.kernel_no)?;
+        if let Some(source) = &self.source {
+            write!(f, " ({source})")?;
+        }
+        if let Some(segment_no) = self.segment_no {
+            write!(f, " segment #{segment_no}")?;
+        }
+        write!(f, ": {}", self.anomaly)
+    }
+}
+
+/// Report of [Almanac::validate] over all of the loaded SPKs and BPCs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Number of kernels checked
+    pub num_kernels: usize,
+    /// Number of non-empty segments checked
+    pub num_segments: usize,
+    /// Anomalies found, sorted by kernel in the loading order (SPKs then BPCs), then by segment
+    pub issues: Vec<KernelIssue>,
+    /// Valid structures of the kernels which ANISE does not read, e.g. the segments of the chained summary records, sorted by kernel
+    /// in the loading order. These do not make the report invalid.
+    pub limitations: Vec<KernelIssue>,
+}
+
+impl ValidationReport {
+    /// Returns true if no anomaly was found, regardless of the limitations.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} anomalies in {} segments of {} kernels",
+            self.issues.len(),
+            self.num_segments,
+            self.num_kernels
+        )?;
+        for issue in &self.issues {
+            write!(f, "\n{issue}")?;
+        }
+        for limitation in &self.limitations {
+            write!(f, "\nlimitation of {limitation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Almanac {
+    /// Checks the structure of every segment of the loaded SPKs and BPCs and returns the anomalies found, without panicking on
+    /// malformed kernels. For each kernel, this checks that:
+    ///
+    /// + the summary record can be decoded and holds the number of summaries it declares. The segments of the chained summary records are not
+    ///   read, as when loading the kernel, so these are reported in the limitations of the report;
+    /// + the data addresses of each segment are a valid range within the file;
+    /// + the coverage of each segment is valid, and the segments of the same ID are in chronological order and do not overlap;
+    /// + the data of each segment of a supported type can be decoded, and its record counts match the size of the stored data;
+    /// + the epochs of the unequal step types are sorted, and their epoch directory holds every 100th epoch.
+    ///
+    /// # Note
+    /// SPICE allows overlapping segments, in which case the last one takes precedence, so these are reported but need not be errors.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        for (kernel_no, spk) in self.spk_data.iter().take(self.num_loaded_spk()).enumerate() {
+            let spk = spk.as_ref().unwrap();
+            let source = &self.spk_sources[kernel_no];
+            report.add_kernel(KernelKind::SPK, kernel_no, source, spk);
+        }
+
+        for (kernel_no, bpc) in self.bpc_data.iter().take(self.num_loaded_bpc()).enumerate() {
+            let bpc = bpc.as_ref().unwrap();
+            let source = &self.bpc_sources[kernel_no];
+            report.add_kernel(KernelKind::BPC, kernel_no, source, bpc);
+        }
+
+        report
+    }
+}
+
+impl ValidationReport {
+    fn add_kernel<R: NAIFSummaryRecord>(
+        &mut self,
+        kind: KernelKind,
+        kernel_no: usize,
+        source: &Option<String>,
+        daf: &DAF<R>,
+    ) {
+        self.num_kernels += 1;
+        let mut push = |segment_no, anomaly| {
+            self.issues.push(KernelIssue {
+                kind,
+                kernel_no,
+                source: source.clone(),
+                segment_no,
+                anomaly,
+            })
+        };
+
+        // DAF records are one-indexed, so a null forward pointer cannot be followed.
+        match daf.file_record() {
+            Ok(file_record) if file_record.fwrd_idx() == 0 => {
+                push(
+                    None,
+                    KernelAnomaly::UnreadableSummaries {
+                        reason: "the file record does not point to a summary record".to_string(),
+                    },
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        let (daf_summary, summaries) = match daf.daf_summary().and_then(|daf_summary| {
+            daf.data_summaries()
+                .map(|summaries| (daf_summary, summaries))
+        }) {
+            Ok(found) => found,
+            Err(e) => {
+                push(
+                    None,
+                    KernelAnomaly::UnreadableSummaries {
+                        reason: e.to_string(),
+                    },
+                );
+                return;
+            }
+        };
+
+        if daf_summary.num_summaries() > summaries.len() {
+            push(
+                None,
+                KernelAnomaly::SummaryCount {
+                    declared: daf_summary.num_summaries(),
+                    capacity: summaries.len(),
+                },
+            );
+        }
+        if !daf_summary.is_final_record() {
+            self.limitations.push(KernelIssue {
+                kind,
+                kernel_no,
+                source: source.clone(),
+                segment_no: None,
+                anomaly: KernelAnomaly::UnreadSummaryRecord {
+                    next_record: daf_summary.next_record(),
+                },
+            });
+        }
+
+        let mut num_segments = 0;
+        // Last segment of each ID, with its coverage
+        let mut previous: HashMap<i32, (usize, f64, f64)> = HashMap::new();
+
+        for (segment_no, summary) in summaries.iter().enumerate() {
+            if summary.is_empty() {
+                continue;
+            }
+            num_segments += 1;
+
+            let (start_et_s, end_et_s) = (summary.start_epoch_et_s(), summary.end_epoch_et_s());
+            if !start_et_s.is_finite() || !end_et_s.is_finite() || end_et_s < start_et_s {
+                push(
+                    Some(segment_no),
+                    KernelAnomaly::InvalidCoverage {
+                        start_et_s,
+                        end_et_s,
+                    },
+                );
+            } else {
+                if let Some(&(previous_segment_no, prev_start_et_s, prev_end_et_s)) =
+                    previous.get(&summary.id())
+                {
+                    if start_et_s < prev_start_et_s {
+                        push(
+                            Some(segment_no),
+                            KernelAnomaly::NonMonotonicCoverage {
+                                previous_segment_no,
+                            },
+                        );
+                    } else if start_et_s < prev_end_et_s {
+                        push(
+                            Some(segment_no),
+                            KernelAnomaly::OverlappingCoverage {
+                                previous_segment_no,
+                                overlap: Duration::from_seconds(
+                                    prev_end_et_s.min(end_et_s) - start_et_s,
+                                ),
+                            },
+                        );
+                    }
+                }
+                previous.insert(summary.id(), (segment_no, start_et_s, end_et_s));
+            }
+
+            // Only check the data if it can be accessed.
+            let (start_idx, end_idx) = (summary.start_index(), summary.end_index());
+            if start_idx < 1 || end_idx < start_idx {
+                push(
+                    Some(segment_no),
+                    KernelAnomaly::InvalidDataPointers { start_idx, end_idx },
+                );
+                continue;
+            }
+            let start_byte = (start_idx - 1).saturating_mul(DBL_SIZE);
+            let end_byte = end_idx.saturating_mul(DBL_SIZE);
+            if end_byte > daf.bytes.len() {
+                push(
+                    Some(segment_no),
+                    KernelAnomaly::DataOutOfBounds {
+                        start_byte,
+                        end_byte,
+                        file_size: daf.bytes.len(),
+                    },
+                );
+                continue;
+            }
+
+            match summary.data_type() {
+                Ok(data_type) => {
+                    for anomaly in data_anomalies(daf, segment_no, data_type) {
+                        push(Some(segment_no), anomaly);
+                    }
+                }
+                Err(e) => push(
+                    Some(segment_no),
+                    KernelAnomaly::UnknownDataType {
+                        reason: e.to_string(),
+                    },
+                ),
+            }
+        }
+
+        self.num_segments += num_segments;
+    }
+}
+
+/// Decodes the data of the n-th segment and checks the integrity of its values.
+fn decode<'a, R: NAIFSummaryRecord, S: NAIFDataSet<'a>>(
+    daf: &'a DAF<R>,
+    idx: usize,
+) -> Result<S, KernelAnomaly> {
+    let data = daf
+        .nth_data::<S>(idx)
+        .map_err(|e| KernelAnomaly::DataDecoding {
+            reason: e.to_string(),
+        })?;
+    data.check_integrity()
+        .map_err(|e| KernelAnomaly::DataDecoding {
+            reason: e.to_string(),
+        })?;
+    Ok(data)
+}
+
+/// Returns the anomalies of the data of the n-th segment, which is of a supported type.
+fn data_anomalies<R: NAIFSummaryRecord>(
+    daf: &DAF<R>,
+    idx: usize,
+    data_type: DafDataType,
+) -> Vec<KernelAnomaly> {
+    let checked = match data_type {
+        DafDataType::Type1ModifiedDifferenceArray => {
+            decode::<_, Type1ModifiedDifferenceSet>(daf, idx)
+                .map(|data| epoch_anomalies(data.epoch_data, data.epoch_registry))
+        }
+        DafDataType::Type2ChebyshevTriplet => decode::<_, Type2ChebyshevSet>(daf, idx)
+            .map(|data| record_count(data.num_records, data.rsize, data.record_data.len())),
+        DafDataType::Type3ChebyshevSextuplet => decode::<_, Type3ChebyshevSet>(daf, idx)
+            .map(|data| record_count(data.num_records, data.rsize, data.record_data.len())),
+        DafDataType::Type5DiscreteStates => decode::<_, DiscreteStatesType5>(daf, idx)
+            .map(|data| epoch_anomalies(data.epoch_data, data.epoch_registry)),
+        DafDataType::Type8LagrangeEqualStep => decode::<_, LagrangeSetType8>(daf, idx)
+            .map(|data| record_count(data.num_records, STATE_RECORD_LEN, data.record_data.len())),
+        DafDataType::Type9LagrangeUnequalStep => decode::<_, LagrangeSetType9>(daf, idx)
+            .map(|data| epoch_anomalies(data.epoch_data, data.epoch_registry)),
+        DafDataType::Type12HermiteEqualStep => decode::<_, HermiteSetType12>(daf, idx)
+            .map(|data| record_count(data.num_records, STATE_RECORD_LEN, data.record_data.len())),
+        DafDataType::Type13HermiteUnequalStep => decode::<_, HermiteSetType13>(daf, idx)
+            .map(|data| epoch_anomalies(data.epoch_data, data.epoch_registry)),
+        // The other types cannot be decoded by ANISE.
+        _ => Ok(Vec::new()),
+    };
+
+    checked.unwrap_or_else(|anomaly| vec![anomaly])
+}
+
+/// Returns the anomaly of a segment whose records are not `record_len` doubles each, if any.
+fn record_count(
+    declared_records: usize,
+    record_len: usize,
+    stored_len: usize,
+) -> Vec<KernelAnomaly> {
+    let expected_len = declared_records.saturating_mul(record_len);
+    if expected_len == stored_len {
+        Vec::new()
+    } else {
+        vec![KernelAnomaly::RecordCount {
+            declared_records,
+            expected_len,
+            stored_len,
+        }]
+    }
+}
+
+/// Returns the anomalies of the epochs of an unequal step segment and of its epoch directory, on which the interval lookup relies.
+fn epoch_anomalies(epoch_data: &[f64], epoch_registry: &[f64]) -> Vec<KernelAnomaly> {
+    let mut anomalies = Vec::new();
+
+    if let Some(record_no) = epoch_data
+        .windows(2)
+        .position(|pair| pair[1] < pair[0])
+        .map(|idx| idx + 1)
+    {
+        anomalies.push(KernelAnomaly::UnsortedEpochs { record_no });
+    }
+
+    // Each entry is the last epoch of its block, and all blocks but the last one must have an entry.
+    let num_entries = epoch_data.len().saturating_sub(1) / DIRECTORY_STEP;
+    let first_bad_entry = (0..epoch_registry.len().max(num_entries)).find(|&entry_no| {
+        epoch_registry.get(entry_no) != epoch_data.get((entry_no + 1) * DIRECTORY_STEP - 1)
+    });
+    if let Some(entry_no) = first_bad_entry {
+        anomalies.push(KernelAnomaly::EpochDirectory { entry_no });
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod ut_validate {
+    use super::*;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::cartesian::CartesianState;
+    use crate::math::Vector3;
+    use crate::naif::daf::RCRD_LEN;
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use crate::naif::spk::writer::SPKWriter;
+    use crate::naif::SPK;
+    use hifitime::{Epoch, TimeUnits};
+
+    /// Returns an SPK with two consecutive segments of the same spacecraft.
+    fn two_segment_spk(second_start_s: f64) -> SPK {
+        let states = |start_s: f64| -> Vec<CartesianState> {
+            (0..=60)
+                .map(|i| {
+                    let et_s = start_s + 60.0 * i as f64;
+                    CartesianState {
+                        radius_km: Vector3::new(7000.0 + et_s, -0.5 * et_s, 10.0),
+                        velocity_km_s: Vector3::new(1.0, -0.5, 0.0),
+                        epoch: Epoch::from_et_seconds(et_s),
+                        frame: EARTH_J2000,
+                    }
+                })
+                .collect()
+        };
+
+        let mut writer = SPKWriter::new("ANISE validation test");
+        writer
+            .add_type2_segment(&states(0.0), -10000001, 399, 1, 7, 10.minutes(), "FIRST")
+            .unwrap();
+        writer
+            .add_type2_segment(
+                &states(second_start_s),
+                -10000001,
+                399,
+                1,
+                7,
+                10.minutes(),
+                "SECOND",
+            )
+            .unwrap();
+        writer.build().unwrap()
+    }
+
+    #[test]
+    fn clean_kernel() {
+        let almanac = Almanac::default()
+            .with_spk(two_segment_spk(3600.0))
+            .unwrap();
+
+        let report = almanac.validate();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(report.num_kernels, 1);
+        assert_eq!(report.num_segments, 2);
+        assert!(Almanac::default().validate().is_valid());
+    }
+
+    #[test]
+    fn data_beyond_file() {
+        let spk = two_segment_spk(3600.0);
+        let file_size = spk.bytes.len();
+
+        // Point the data of the second segment past the end of the file.
+        let summary = spk.data_summaries().unwrap()[1];
+        let mut corrupted = spk.to_mutable();
+        corrupted
+            .set_nth_summary(
+                1,
+                SPKSummaryRecord {
+                    end_idx: summary.end_idx + 1_000_000,
+                    ..summary
+                },
+            )
+            .unwrap();
+        let corrupted = SPK::parse(corrupted.bytes.to_vec()).unwrap();
+
+        let report = Almanac::default()
+            .with_spk(two_segment_spk(3600.0))
+            .unwrap()
+            .with_spk(corrupted)
+            .unwrap()
+            .validate();
+
+        assert_eq!(report.num_kernels, 2);
+        assert_eq!(report.num_segments, 4);
+        assert_eq!(report.issues.len(), 1, "{report}");
+        let issue = &report.issues[0];
+        assert_eq!(issue.kind, KernelKind::SPK);
+        assert_eq!(issue.kernel_no, 1);
+        assert_eq!(issue.segment_no, Some(1));
+        assert_eq!(
+            issue.anomaly,
+            KernelAnomaly::DataOutOfBounds {
+                start_byte: (summary.start_idx as usize - 1) * DBL_SIZE,
+                end_byte: (summary.end_idx as usize + 1_000_000) * DBL_SIZE,
+                file_size,
+            }
+        );
+        assert!(issue
+            .to_string()
+            .starts_with("SPK #1 segment #1: data from byte"));
+    }
+
+    #[test]
+    fn chained_summary_record() {
+        let spk = two_segment_spk(3600.0);
+
+        // Point the summary record to a next summary record, as in the SPKs of more than 25 segments.
+        let mut bytes = spk.bytes.to_vec();
+        let rcrd_start = (spk.file_record().unwrap().fwrd_idx() - 1) * RCRD_LEN;
+        bytes[rcrd_start..rcrd_start + DBL_SIZE].copy_from_slice(&7.0_f64.to_ne_bytes());
+        let chained = SPK::parse(bytes).unwrap();
+
+        let report = Almanac::default().with_spk(chained).unwrap().validate();
+
+        // A chained summary record is valid, but its segments are not read.
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(report.num_segments, 2);
+        assert_eq!(report.limitations.len(), 1);
+        let limitation = &report.limitations[0];
+        assert_eq!(limitation.segment_no, None);
+        assert_eq!(
+            limitation.anomaly,
+            KernelAnomaly::UnreadSummaryRecord { next_record: 7 }
+        );
+        assert!(report.to_string().contains("limitation of SPK #0"));
+    }
+
+    #[test]
+    fn overlapping_segments() {
+        let report = Almanac::default()
+            .with_spk(two_segment_spk(1800.0))
+            .unwrap()
+            .validate();
+
+        assert_eq!(
+            report.issues,
+            vec![KernelIssue {
+                kind: KernelKind::SPK,
+                kernel_no: 0,
+                source: None,
+                segment_no: Some(1),
+                anomaly: KernelAnomaly::OverlappingCoverage {
+                    previous_segment_no: 0,
+                    overlap: 30.minutes(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn epoch_directory() {
+        let epochs: Vec<f64> = (0..250).map(|i| i as f64).collect();
+        let directory = [99.0, 199.0];
+        assert!(epoch_anomalies(&epochs, &directory).is_empty());
+        assert!(epoch_anomalies(&epochs[..200], &directory[..1]).is_empty());
+        assert!(epoch_anomalies(&epochs[..100], &[]).is_empty());
+
+        // Missing, extra, and wrong entries
+        assert_eq!(
+            epoch_anomalies(&epochs, &directory[..1]),
+            vec![KernelAnomaly::EpochDirectory { entry_no: 1 }]
+        );
+        assert_eq!(
+            epoch_anomalies(&epochs[..150], &directory),
+            vec![KernelAnomaly::EpochDirectory { entry_no: 1 }]
+        );
+        assert_eq!(
+            epoch_anomalies(&epochs, &[100.0, 199.0]),
+            vec![KernelAnomaly::EpochDirectory { entry_no: 0 }]
+        );
+
+        let mut unsorted = epochs.clone();
+        unsorted.swap(10, 11);
+        assert_eq!(
+            epoch_anomalies(&unsorted, &directory),
+            vec![KernelAnomaly::UnsortedEpochs { record_no: 11 }]
+        );
+    }
+}
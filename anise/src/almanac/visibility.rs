@@ -0,0 +1,247 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+use hifitime::{Duration, Epoch, Unit};
+
+use super::Almanac;
+use crate::errors::{AlmanacError, AlmanacResult};
+use crate::frames::Frame;
+use crate::prelude::Orbit;
+
+/// The crossings of the minimum elevation are located to within this many seconds.
+pub const ELEVATION_CROSSING_TOLERANCE_S: f64 = 1e-3;
+
+/// Whether the target rises above or sets below the minimum elevation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VisibilityEventKind {
+    Rise,
+    Set,
+}
+
+/// A crossing of the minimum elevation by the target, as seen from a station.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VisibilityEvent {
+    /// Epoch of the crossing
+    pub epoch: Epoch,
+    /// Whether the target rises or sets at this epoch
+    pub kind: VisibilityEventKind,
+    /// Elevation of the target at this epoch, in degrees, which is the minimum elevation except at the edges of the search interval
+    pub elevation_deg: f64,
+    /// Peak elevation of the target between this event and the matching one of the same pass (the following set for a rise, the preceding rise for a set), in degrees
+    pub peak_elevation_deg: f64,
+    /// Epoch of the peak elevation
+    pub peak_epoch: Epoch,
+    /// Set if this event is the edge of the search interval instead of a crossing, because the target is already visible at the start or still visible at the end
+    pub at_interval_edge: bool,
+}
+
+impl fmt::Display for VisibilityEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at {} (el = {:.3} deg, peak of {:.3} deg at {}){}",
+            self.kind,
+            self.epoch,
+            self.elevation_deg,
+            self.peak_elevation_deg,
+            self.peak_epoch,
+            if self.at_interval_edge {
+                " at the edge of the interval"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+impl Almanac {
+    /// Returns the rises and sets of the `target` frame above the minimum elevation (in degrees) seen from the `station` between
+    /// the start and stop epochs, in chronological order. Each rise is followed by its set, and both include the peak elevation of that pass.
+    ///
+    /// The station must be defined in a body fixed frame, e.g. with [Orbit::try_latlongalt], and its epoch is ignored.
+    ///
+    /// # Algorithm
+    /// 1. Sample the elevation of the target every `step` from the start to the stop epoch.
+    /// 2. Bisect each sign change of the elevation minus the minimum elevation to locate the crossing within [ELEVATION_CROSSING_TOLERANCE_S].
+    /// 3. Locate the peak elevation of each pass with a golden section search around the highest sample of that pass.
+    ///
+    /// If the target is already visible at the start (or still visible at the stop), the pass starts with a rise at the start epoch
+    /// (or ends with a set at the stop epoch) marked as `at_interval_edge`. Passes shorter than the step may be missed.
+    ///
+    /// # Errors
+    /// + The step must be positive and the stop epoch must be after the start epoch.
+    /// + The target must be available in the frame of the station over the whole interval.
+    pub fn elevation_crossings(
+        &self,
+        target: Frame,
+        station: Orbit,
+        (start, stop): (Epoch, Epoch),
+        min_el_deg: f64,
+        step: Duration,
+    ) -> AlmanacResult<Vec<VisibilityEvent>> {
+        if step <= Duration::ZERO || stop <= start {
+            return Err(AlmanacError::GenericError {
+                err: format!(
+                    "elevation crossings require a positive step and interval, got step of {step} from {start} to {stop}"
+                ),
+            });
+        }
+
+        let elevation_deg = |epoch: Epoch| -> AlmanacResult<f64> {
+            let tx = Orbit { epoch, ..station };
+            let rx = self.transform(target, station.frame, epoch, None)?;
+            Ok(self
+                .azimuth_elevation_range_sez(rx, tx, None, None)?
+                .elevation_deg)
+        };
+
+        let tolerance = Unit::Second * ELEVATION_CROSSING_TOLERANCE_S;
+        // Bisects the bracket, where the elevation is below the minimum at the `below` epoch and above it at the `above` epoch.
+        let crossing = |mut below: Epoch, mut above: Epoch| -> AlmanacResult<(Epoch, f64)> {
+            while (above - below).abs() > tolerance {
+                let mid = below + (above - below) * 0.5;
+                if elevation_deg(mid)? >= min_el_deg {
+                    above = mid;
+                } else {
+                    below = mid;
+                }
+            }
+            Ok((above, elevation_deg(above)?))
+        };
+
+        let mut samples = Vec::new();
+        let mut epoch = start;
+        loop {
+            samples.push((epoch, elevation_deg(epoch)?));
+            if epoch >= stop {
+                break;
+            }
+            epoch = (epoch + step).min(stop);
+        }
+
+        let mut events = Vec::new();
+        // Epoch and elevation of the rise of the current pass, whether it is an edge, and the highest sample of the pass.
+        let mut pass: Option<((Epoch, f64), bool, (Epoch, f64))> = None;
+
+        if samples[0].1 >= min_el_deg {
+            pass = Some((samples[0], true, samples[0]));
+        }
+
+        for window in samples.windows(2) {
+            let (prev, next) = (window[0], window[1]);
+
+            match pass {
+                None if next.1 >= min_el_deg => {
+                    pass = Some((crossing(prev.0, next.0)?, false, next));
+                }
+                Some((rise, rise_edge, highest)) if next.1 < min_el_deg => {
+                    let set = crossing(next.0, prev.0)?;
+                    events.extend(pass_events(
+                        &elevation_deg,
+                        (rise, rise_edge),
+                        (set, false),
+                        highest,
+                        step,
+                    )?);
+                    pass = None;
+                }
+                Some((rise, rise_edge, highest)) if next.1 > highest.1 => {
+                    pass = Some((rise, rise_edge, next));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((rise, rise_edge, highest)) = pass {
+            let set = *samples.last().unwrap();
+            events.extend(pass_events(
+                &elevation_deg,
+                (rise, rise_edge),
+                (set, true),
+                highest,
+                step,
+            )?);
+        }
+
+        Ok(events)
+    }
+}
+
+/// Returns the rise and set events of a pass, after locating its peak elevation around its highest sample.
+fn pass_events(
+    elevation_deg: &dyn Fn(Epoch) -> AlmanacResult<f64>,
+    ((rise, rise_el_deg), rise_edge): ((Epoch, f64), bool),
+    ((set, set_el_deg), set_edge): ((Epoch, f64), bool),
+    (highest, highest_el_deg): (Epoch, f64),
+    step: Duration,
+) -> AlmanacResult<[VisibilityEvent; 2]> {
+    // The elevation is unimodal around the highest sample, so the peak is within a step of it.
+    let mut lower = (highest - step).max(rise);
+    let mut upper = (highest + step).min(set);
+    let tolerance = Unit::Second * ELEVATION_CROSSING_TOLERANCE_S;
+    let inv_phi = (5.0_f64.sqrt() - 1.0) / 2.0;
+
+    let mut left = upper - (upper - lower) * inv_phi;
+    let mut right = lower + (upper - lower) * inv_phi;
+    let mut left_el_deg = elevation_deg(left)?;
+    let mut right_el_deg = elevation_deg(right)?;
+    while upper - lower > tolerance {
+        if left_el_deg > right_el_deg {
+            upper = right;
+            right = left;
+            right_el_deg = left_el_deg;
+            left = upper - (upper - lower) * inv_phi;
+            left_el_deg = elevation_deg(left)?;
+        } else {
+            lower = left;
+            left = right;
+            left_el_deg = right_el_deg;
+            right = lower + (upper - lower) * inv_phi;
+            right_el_deg = elevation_deg(right)?;
+        }
+    }
+
+    // The peak may also be the highest sample itself, or at the edges of the pass if it is cut by the search interval.
+    let (peak_epoch, peak_elevation_deg) = [
+        (left, left_el_deg),
+        (right, right_el_deg),
+        (rise, rise_el_deg),
+        (set, set_el_deg),
+    ]
+    .into_iter()
+    .fold((highest, highest_el_deg), |best, candidate| {
+        if candidate.1 > best.1 {
+            candidate
+        } else {
+            best
+        }
+    });
+
+    Ok([
+        VisibilityEvent {
+            epoch: rise,
+            kind: VisibilityEventKind::Rise,
+            elevation_deg: rise_el_deg,
+            peak_elevation_deg,
+            peak_epoch,
+            at_interval_edge: rise_edge,
+        },
+        VisibilityEvent {
+            epoch: set,
+            kind: VisibilityEventKind::Set,
+            elevation_deg: set_el_deg,
+            peak_elevation_deg,
+            peak_epoch,
+            at_interval_edge: set_edge,
+        },
+    ])
+}
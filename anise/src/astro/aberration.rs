@@ -47,7 +47,7 @@ use crate::errors::PhysicsError;
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", pyo3(module = "anise"))]
 pub struct Aberration {
-    /// Indicates whether the light time calculations should be iterated upon (more precise but three times as many CPU cycles).
+    /// Indicates whether the light time calculations should be iterated upon until convergence (more precise but up to five times as many CPU cycles).
     pub converged: bool,
     /// Flag to denote if stellar aberration correction is applied. Stellar aberration is due to the motion of the observer (caused by Earth's orbit around the Sun).
     pub stellar: bool,
@@ -56,6 +56,12 @@ pub struct Aberration {
 }
 
 impl Aberration {
+    /// Maximum number of light time iterations of the converged corrections (CN, CN+S, XCN, XCN+S).
+    pub const MAX_CONVERGED_ITERATIONS: usize = 5;
+    /// The converged corrections stop iterating once the relative change in one-way light time is less than this tolerance, i.e. at machine precision,
+    /// unless a looser tolerance is configured (cf. [AberrationConfig]).
+    pub const CONVERGED_LT_REL_TOL: f64 = 1e-17;
+
     /// Disables aberration corrections, e.g. all translations are geometric only (typical use case).
     pub const NONE: Option<Self> = None;
     /// Unconverged light time correction in reception mode without stellar aberration (e.g. a ground station targeting a spacecraft near the Moon)
@@ -246,6 +252,92 @@ impl fmt::Display for Aberration {
     }
 }
 
+/// Configuration of the light time iterations of the aberration corrections.
+///
+/// The default configuration is that used by [crate::almanac::Almanac::translate]: the converged corrections iterate at most
+/// [Aberration::MAX_CONVERGED_ITERATIONS] times, until the light time no longer changes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AberrationConfig {
+    /// Maximum number of light time iterations of the converged corrections (CN, CN+S, XCN, XCN+S). The unconverged corrections always
+    /// perform exactly one iteration.
+    pub max_iter: usize,
+    /// The iterations stop once the change in the light time distance (i.e. the light time times the speed of light) between two
+    /// iterations is at most this tolerance, in km. Zero iterates until the light time no longer changes.
+    pub tol_km: f64,
+    /// Whether to add the Shapiro delay due to the gravity of the Sun to the geometric light time, cf. [shapiro_delay_s]. This delay is
+    /// of the order of ten microseconds between the planets and exceeds a hundred microseconds for the signals grazing the Sun.
+    pub shapiro: bool,
+}
+
+impl Default for AberrationConfig {
+    fn default() -> Self {
+        Self {
+            max_iter: Aberration::MAX_CONVERGED_ITERATIONS,
+            tol_km: 0.0,
+            shapiro: false,
+        }
+    }
+}
+
+/// Outcome of the light time iterations of an aberration correction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LightTimeConvergence {
+    /// Number of light time iterations performed
+    pub iterations: usize,
+    /// Whether the change in the light time distance of the last iteration is within the configured tolerance
+    pub converged: bool,
+    /// Change in the light time distance of the last iteration, in km
+    pub residual_km: f64,
+}
+
+impl fmt::Display for LightTimeConvergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} after {} iteration(s) (residual: {:e} km)",
+            if self.converged {
+                "converged"
+            } else {
+                "not converged"
+            },
+            self.iterations,
+            self.residual_km
+        )
+    }
+}
+
+/// Direction of the signal between the observer and the target, used by the light time computations.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[cfg_attr(feature = "python", pyo3(module = "anise"))]
+pub enum LightTimeDirection {
+    /// The observer receives at the epoch a signal that the target emitted one light time earlier (as in `LT` and `CN`).
+    #[default]
+    Reception,
+    /// The observer transmits at the epoch a signal that the target receives one light time later (as in `XLT` and `XCN`).
+    Transmission,
+}
+
+impl LightTimeDirection {
+    /// Returns the converged light time correction, without stellar aberration, in this direction.
+    pub const fn converged_aberration(&self) -> Aberration {
+        Aberration {
+            converged: true,
+            stellar: false,
+            transmit_mode: matches!(self, Self::Transmission),
+        }
+    }
+}
+
+impl fmt::Display for LightTimeDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reception => write!(f, "reception"),
+            Self::Transmission => write!(f, "transmission"),
+        }
+    }
+}
+
 /// Returns the provided target [Orbit] with respect to any observer corrected for steller aberration.
 ///
 /// # Arguments
@@ -324,6 +416,24 @@ pub fn stellar_aberration(
     Ok(app_target_pos_km)
 }
 
+/// Returns the one-way Shapiro delay in seconds of a signal between two points due to the gravity of a body, given the positions of both
+/// points with respect to that body and its gravitational parameter:
+///
+/// `dt = (1 + gamma) GM / c^3 ln((r1 + r2 + r12) / (r1 + r2 - r12))`
+///
+/// where `r1` and `r2` are the distances of both points to the body, `r12` is the distance between both points, and the PPN parameter
+/// `gamma` is one in general relativity.
+///
+/// Source: Moyer, Formulation for Observed and Computed Values of Deep Space Network Data Types for Navigation, JPL, 2000, eq. (8-38).
+pub fn shapiro_delay_s(pos1_km: Vector3, pos2_km: Vector3, mu_km3_s2: f64) -> f64 {
+    let r1_km = pos1_km.norm();
+    let r2_km = pos2_km.norm();
+    let r12_km = (pos2_km - pos1_km).norm();
+
+    2.0 * mu_km3_s2 / SPEED_OF_LIGHT_KM_S.powi(3)
+        * ((r1_km + r2_km + r12_km) / (r1_km + r2_km - r12_km)).ln()
+}
+
 #[cfg(test)]
 mod ut_aberration {
     #[test]
@@ -340,4 +450,32 @@ mod ut_aberration {
         assert_eq!(format!("{:?}", Aberration::XCN.unwrap()), "XCN");
         assert_eq!(format!("{:?}", Aberration::XCN_S.unwrap()), "XCN+S");
     }
+
+    #[test]
+    fn test_shapiro_delay() {
+        use super::shapiro_delay_s;
+        use crate::constants::{SPEED_OF_LIGHT_KM_S, SUN_GM_KM3_S2};
+        use crate::math::Vector3;
+
+        const AU_KM: f64 = 149_597_870.7;
+        const SUN_RADIUS_KM: f64 = 696_000.0;
+
+        // The classic round trip delay of about 250 microseconds for a signal between the Earth and Mars grazing the limb of the Sun.
+        let earth_km = Vector3::new(-AU_KM, SUN_RADIUS_KM, 0.0);
+        let mars_km = Vector3::new(1.524 * AU_KM, SUN_RADIUS_KM, 0.0);
+        let delay_s = shapiro_delay_s(earth_km, mars_km, SUN_GM_KM3_S2);
+        assert!((2.0 * delay_s - 250e-6).abs() < 10e-6, "{delay_s}");
+        // Symmetric in both points.
+        assert_eq!(delay_s, shapiro_delay_s(mars_km, earth_km, SUN_GM_KM3_S2));
+
+        // Far from the body, the impact parameter is large and the delay approaches its small angle limit.
+        let farther_km = Vector3::new(1.524 * AU_KM, 0.5 * AU_KM, 0.0);
+        let far_delay_s = shapiro_delay_s(earth_km, farther_km, SUN_GM_KM3_S2);
+        assert!(far_delay_s < delay_s / 3.0);
+        assert!(far_delay_s > 0.0);
+        // Without gravity, there is no delay.
+        assert_eq!(shapiro_delay_s(earth_km, mars_km, 0.0), 0.0);
+        // Twice GM over c^3 is about ten microseconds for the Sun.
+        assert!((2.0 * SUN_GM_KM3_S2 / SPEED_OF_LIGHT_KM_S.powi(3) - 9.85e-6).abs() < 1e-8);
+    }
 }
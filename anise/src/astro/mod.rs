@@ -25,10 +25,12 @@ use pyo3::pyclass::CompareOp;
 pub mod utils;
 
 pub(crate) mod aberration;
-pub use aberration::Aberration;
+pub use aberration::{
+    shapiro_delay_s, Aberration, AberrationConfig, LightTimeConvergence, LightTimeDirection,
+};
 
 pub(crate) mod occultation;
-pub use occultation::Occultation;
+pub use occultation::{Occultation, OccultationState};
 
 pub mod orbit;
 pub mod orbit_geodetic;
@@ -218,6 +220,29 @@ impl AzElRange {
     }
 }
 
+/// A structure that stores the result of an Azimuth, Elevation, Range, Range rate calculation along with the azimuth and elevation rates.
+///
+/// The rates are those of the receiver as seen from the transmitter fixed in its body fixed frame, like the range rate.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AzElRangeRates {
+    /// Azimuth, elevation, range, and range rate.
+    pub aer: AzElRange,
+    /// Rate of the azimuth in degrees per second, positive when the azimuth increases (i.e. clockwise from North).
+    pub azimuth_rate_deg_s: f64,
+    /// Rate of the elevation in degrees per second, positive when rising.
+    pub elevation_rate_deg_s: f64,
+}
+
+impl Display for AzElRangeRates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}    az. rate: {:.6e} deg/s    el. rate: {:.6e} deg/s",
+            self.aer, self.azimuth_rate_deg_s, self.elevation_rate_deg_s
+        )
+    }
+}
+
 impl Display for AzElRange {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let obs = match self.obstructed_by {
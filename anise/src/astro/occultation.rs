@@ -166,3 +166,31 @@ impl PartialOrd for Occultation {
         }
     }
 }
+
+/// Classifies how the back object is hidden by the front object as seen from an observer, analogous to the occultation types of SPICE's `occult`.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OccultationState {
+    /// The back object is fully visible: the disks of both objects do not overlap, or the front object is further than the back object.
+    /// Named `NoOccultation` in Python because `None` is a reserved keyword.
+    #[cfg_attr(feature = "python", pyo3(name = "NoOccultation"))]
+    None,
+    /// The disks of both objects overlap but neither is fully inside the other (i.e. _penumbra_ if the back object is the Sun).
+    Partial,
+    /// The disk of the front object is fully inside the disk of the back object (e.g. a transit of Phobos across the Sun).
+    Annular,
+    /// The back object is fully hidden by the front object (i.e. _umbra_ if the back object is the Sun).
+    Total,
+}
+
+impl fmt::Display for OccultationState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "no occultation"),
+            Self::Partial => write!(f, "partial occultation"),
+            Self::Annular => write!(f, "annular occultation"),
+            Self::Total => write!(f, "total occultation"),
+        }
+    }
+}
@@ -12,10 +12,11 @@ use super::utils::compute_mean_to_true_anomaly_rad;
 use super::PhysicsResult;
 
 use crate::{
-    astro::utils::true_anomaly_to_mean_anomaly_rad,
+    astro::utils::{propagate_universal_variable, true_anomaly_to_mean_anomaly_rad},
     errors::{
-        HyperbolicTrueAnomalySnafu, InfiniteValueSnafu, MathError, ParabolicEccentricitySnafu,
-        ParabolicSemiParamSnafu, PhysicsError, RadiusSnafu, VelocitySnafu,
+        AppliedMathSnafu, HyperbolicTrueAnomalySnafu, InfiniteValueSnafu, MathError,
+        NotEllipticalSnafu, ParabolicEccentricitySnafu, ParabolicSemiParamSnafu, PhysicsError,
+        RadiusSnafu, VelocitySnafu,
     },
     math::{
         angles::{between_0_360, between_pm_180},
@@ -31,7 +32,7 @@ use core::f64::consts::{PI, TAU};
 use core::fmt;
 use hifitime::{Duration, Epoch, TimeUnits, Unit};
 use log::{error, info, warn};
-use snafu::ensure;
+use snafu::{ensure, ResultExt};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
@@ -41,6 +42,9 @@ use pyo3::types::PyType;
 /// If an orbit has an eccentricity below the following value, it is considered circular.
 pub const ECC_EPSILON: f64 = 1e-11;
 
+/// If an orbit has a sine of inclination below the following value, it is considered equatorial.
+pub const EQUATORIAL_EPSILON: f64 = 1e-11;
+
 /// A helper type alias, but no assumptions are made on the underlying validity of the frame.
 pub type Orbit = CartesianState;
 
@@ -317,6 +321,17 @@ impl Orbit {
                 / self.frame.mu_km3_s2()?,
         )
     }
+
+    /// Returns the angle in degrees of the provided vector from the X axis in the direction of motion, for equatorial orbits.
+    fn equatorial_longitude_deg(&self, vec: Vector3) -> f64 {
+        let longitude_deg = vec.y.atan2(vec.x).to_degrees();
+        if self.radius_km.cross(&self.velocity_km_s).z >= 0.0 {
+            between_0_360(longitude_deg)
+        } else {
+            // Retrograde: the angles increase clockwise about the Z axis.
+            between_0_360(-longitude_deg)
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -785,10 +800,12 @@ impl Orbit {
         Ok(me)
     }
 
-    /// Returns the period in seconds
+    /// Returns the period in seconds, or an error if the orbit is not elliptical (hyperbolic orbits have no period).
     ///
     /// :rtype: Duration
     pub fn period(&self) -> PhysicsResult<Duration> {
+        let ecc = self.ecc()?;
+        ensure!(ecc < 1.0, NotEllipticalSnafu { ecc });
         Ok(TAU
             * (self.sma_km()?.powi(3) / self.frame.mu_km3_s2()?)
                 .sqrt()
@@ -898,10 +915,34 @@ impl Orbit {
         Ok(me)
     }
 
+    /// Returns true if the eccentricity of this orbit is below [ECC_EPSILON].
+    ///
+    /// :rtype: bool
+    pub fn is_circular(&self) -> PhysicsResult<bool> {
+        Ok(self.ecc()? < ECC_EPSILON)
+    }
+
+    /// Returns true if the sine of the inclination of this orbit is below [EQUATORIAL_EPSILON], i.e. prograde or retrograde equatorial.
+    ///
+    /// :rtype: bool
+    pub fn is_equatorial(&self) -> PhysicsResult<bool> {
+        let hvec = self.hvec()?;
+        Ok(hvec.xy().norm() < EQUATORIAL_EPSILON * hvec.norm())
+    }
+
     /// Returns the argument of periapsis in degrees
     ///
+    /// NOTE: The periapsis of a circular orbit is undefined, so the AOP is zero and the true anomaly is the argument of latitude.
+    /// The node of an equatorial orbit is undefined, so the AOP is the longitude of periapsis.
+    ///
     /// :rtype: float
     pub fn aop_deg(&self) -> PhysicsResult<f64> {
+        if self.is_circular()? {
+            return Ok(0.0);
+        } else if self.is_equatorial()? {
+            let evec = self.evec()?;
+            return Ok(self.equatorial_longitude_deg(evec));
+        }
         let n = Vector3::new(0.0, 0.0, 1.0).cross(&self.hvec()?);
         let cos_aop = n.dot(&self.evec()?) / (n.norm() * self.ecc()?);
         let aop = cos_aop.acos();
@@ -961,8 +1002,13 @@ impl Orbit {
 
     /// Returns the right ascension of the ascending node in degrees
     ///
+    /// NOTE: The node of an equatorial orbit is undefined, so its RAAN is zero.
+    ///
     /// :rtype: float
     pub fn raan_deg(&self) -> PhysicsResult<f64> {
+        if self.is_equatorial()? {
+            return Ok(0.0);
+        }
         let n = Vector3::new(0.0, 0.0, 1.0).cross(&self.hvec()?);
         let cos_raan = n[0] / n.norm();
         let raan = cos_raan.acos();
@@ -1029,13 +1075,23 @@ impl Orbit {
     /// This is due to the precision of the cosine calculation: if the arccosine calculation is out of bounds, the sign of the cosine of the true anomaly is used
     /// to determine whether the true anomaly should be 0.0 or 180.0. **In other words**, there is an ambiguity in the computation in the true anomaly exactly at 180.0 and 0.0.
     ///
+    /// NOTE: The periapsis of a circular orbit is undefined, so its true anomaly is measured from the ascending node (i.e. it is the
+    /// argument of latitude), or from the X axis if the orbit is also equatorial (i.e. it is the true longitude).
+    ///
     /// :rtype: float
     pub fn ta_deg(&self) -> PhysicsResult<f64> {
-        if self.ecc()? < ECC_EPSILON {
-            warn!(
-                "true anomaly ill-defined for circular orbit (e = {})",
-                self.ecc()?
-            );
+        if self.is_circular()? {
+            if self.is_equatorial()? {
+                return Ok(self.equatorial_longitude_deg(self.radius_km));
+            }
+            let n = Vector3::new(0.0, 0.0, 1.0).cross(&self.hvec()?);
+            let cos_u = (n.dot(&self.radius_km) / (n.norm() * self.rmag_km())).clamp(-1.0, 1.0);
+            let aol = cos_u.acos();
+            return if self.radius_km.z < 0.0 {
+                Ok((TAU - aol).to_degrees())
+            } else {
+                Ok(aol.to_degrees())
+            };
         }
         let cos_nu = self.evec()?.dot(&self.radius_km) / (self.ecc()? * self.rmag_km());
         // If we're close the valid bounds, let's just do a sign check and return the true anomaly
@@ -1211,7 +1267,9 @@ impl Orbit {
 
     /// Returns the mean anomaly in degrees
     ///
-    /// This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly
+    /// This is a conversion from GMAT's StateConversionUtil::TrueToMeanAnomaly.
+    /// NOTE: This returns an error for circular orbits, and the hyperbolic anomaly (not the hyperbolic mean anomaly) for hyperbolic
+    /// orbits: use [Self::mean_anomaly_deg] for a mean anomaly defined on all orbits.
     ///
     /// :rtype: float
     pub fn ma_deg(&self) -> PhysicsResult<f64> {
@@ -1233,6 +1291,28 @@ impl Orbit {
         }
     }
 
+    /// Returns the mean anomaly in degrees, which is defined for all orbits unlike [Self::ma_deg].
+    ///
+    /// + Elliptical orbits: the mean anomaly between 0 and 360 degrees, as [Self::ma_deg].
+    /// + Circular orbits: the periapsis is undefined and the motion is uniform, so the mean anomaly is the true anomaly, i.e. the argument
+    ///   of latitude, or the true longitude if the orbit is also equatorial (cf. [Self::ta_deg]).
+    /// + Hyperbolic orbits: the hyperbolic mean anomaly `e sinh(H) - H`, where H is the hyperbolic anomaly, which is not bounded.
+    ///
+    /// :rtype: float
+    pub fn mean_anomaly_deg(&self) -> PhysicsResult<f64> {
+        let ecc = self.ecc()?;
+        if self.is_circular()? {
+            self.ta_deg()
+        } else if ecc < 1.0 {
+            self.ma_deg()
+        } else {
+            let (sin_ta, cos_ta) = self.ta_deg()?.to_radians().sin_cos();
+            let hyp_anomaly =
+                ((sin_ta * (ecc.powi(2) - 1.0).sqrt()) / (1.0 + ecc * cos_ta)).asinh();
+            Ok((ecc * hyp_anomaly.sinh() - hyp_anomaly).to_degrees())
+        }
+    }
+
     /// Returns the semi parameter (or semilatus rectum)
     ///
     /// :rtype: float
@@ -1412,6 +1492,79 @@ impl Orbit {
         )
     }
 
+    /// Propagates this state by the provided duration assuming two-body motion about the center of its frame, returning the new state in
+    /// the same frame.
+    ///
+    /// This uses the universal variable formulation, solved with the Laguerre-Conway iteration, so elliptical, near-parabolic, and hyperbolic
+    /// trajectories are all supported, and the duration may be negative to propagate backward.
+    ///
+    /// # Astrodynamics note
+    /// No other force than the point mass gravity of the center of the frame is modeled. Use Nyx for high fidelity propagation.
+    ///
+    /// :type dt: Duration
+    /// :rtype: Orbit
+    pub fn propagate_two_body(&self, dt: Duration) -> PhysicsResult<Self> {
+        let (radius_km, velocity_km_s) = propagate_universal_variable(
+            self.radius_km,
+            self.velocity_km_s,
+            self.frame.mu_km3_s2()?,
+            dt.to_seconds(),
+        )
+        .context(AppliedMathSnafu)?;
+
+        let mut me = *self;
+        me.radius_km = radius_km;
+        me.velocity_km_s = velocity_km_s;
+        me.epoch = self.epoch + dt;
+        Ok(me)
+    }
+
+    /// Propagates this elliptical orbit by the provided duration with the secular rates of the mean elements due to the provided J2
+    /// zonal harmonic of the center of the frame, whose equatorial radius is `req_km`, returning the new state in the same frame.
+    ///
+    /// The semi-major axis, eccentricity, and inclination are constant, while the right ascension of the ascending node, the argument of
+    /// periapsis, and the mean anomaly drift at the first order secular rates:
+    /// + RAAN: -3/2 n J2 (Req/p)^2 cos(i)
+    /// + AOP: 3/4 n J2 (Req/p)^2 (5 cos^2(i) - 1)
+    /// + MA: n (1 + 3/4 J2 (Req/p)^2 sqrt(1 - e^2) (3 cos^2(i) - 1))
+    ///
+    /// # Astrodynamics note
+    /// The osculating elements of this state are used as mean elements, so the short periodic effects of J2 are not modeled.
+    /// Use Nyx for high fidelity propagation.
+    ///
+    /// :type dt: Duration
+    /// :type j2: float
+    /// :type req_km: float
+    /// :rtype: Orbit
+    pub fn propagate_j2(&self, dt: Duration, j2: f64, req_km: f64) -> PhysicsResult<Self> {
+        let ecc = self.ecc()?;
+        ensure!(ecc < 1.0, NotEllipticalSnafu { ecc });
+
+        let sma_km = self.sma_km()?;
+        let inc_deg = self.inc_deg()?;
+        let cos_inc = inc_deg.to_radians().cos();
+        let mean_motion_rad_s = (self.frame.mu_km3_s2()? / sma_km.powi(3)).sqrt();
+        let p_km = sma_km * (1.0 - ecc.powi(2));
+        let k = mean_motion_rad_s * j2 * (req_km / p_km).powi(2);
+
+        let raan_rate_rad_s = -1.5 * k * cos_inc;
+        let aop_rate_rad_s = 0.75 * k * (5.0 * cos_inc.powi(2) - 1.0);
+        let ma_rate_rad_s = mean_motion_rad_s
+            + 0.75 * k * (1.0 - ecc.powi(2)).sqrt() * (3.0 * cos_inc.powi(2) - 1.0);
+
+        let dt_s = dt.to_seconds();
+        Self::try_keplerian_mean_anomaly(
+            sma_km,
+            ecc,
+            inc_deg,
+            between_0_360(self.raan_deg()? + (raan_rate_rad_s * dt_s).to_degrees()),
+            between_0_360(self.aop_deg()? + (aop_rate_rad_s * dt_s).to_degrees()),
+            between_0_360(self.ma_deg()? + (ma_rate_rad_s * dt_s).to_degrees()),
+            self.epoch + dt,
+            self.frame,
+        )
+    }
+
     /// Calculates the duration to reach a specific radius in the orbit.
     ///
     /// This function computes the time it will take for the orbiting body to reach
@@ -11,6 +11,7 @@
 use std::f64::consts::{PI, TAU};
 
 use crate::errors::{MathError, PhysicsError};
+use crate::math::Vector3;
 
 use super::PhysicsResult;
 
@@ -345,6 +346,135 @@ pub fn true_anomaly_to_eccentric_anomaly_rad(nu_rad: f64, ecc: f64) -> Result<f6
     }
 }
 
+/// Maximum number of iterations of the universal variable solver
+const UNIVERSAL_VAR_MAX_ITER: usize = 100;
+
+/// Evaluates the Stumpff functions C(z) and S(z), using their series expansions near zero.
+fn stumpff_cs(z: f64) -> (f64, f64) {
+    if z > 1e-6 {
+        let sqrt_z = z.sqrt();
+        (
+            (1.0 - sqrt_z.cos()) / z,
+            (sqrt_z - sqrt_z.sin()) / (sqrt_z * z),
+        )
+    } else if z < -1e-6 {
+        let sqrt_mz = (-z).sqrt();
+        (
+            (sqrt_mz.cosh() - 1.0) / -z,
+            (sqrt_mz.sinh() - sqrt_mz) / (sqrt_mz * -z),
+        )
+    } else {
+        (
+            0.5 - z / 24.0 + z * z / 720.0,
+            1.0 / 6.0 - z / 120.0 + z * z / 5040.0,
+        )
+    }
+}
+
+/// Propagates the provided position and velocity by `dt_s` seconds assuming two-body motion about a body of gravitational parameter `mu_km3_s2`.
+///
+/// This uses the universal variable formulation, solved with the Laguerre-Conway iteration, such that
+/// elliptical, parabolic, and hyperbolic trajectories are all supported.
+///
+/// # Returns
+///
+/// The propagated position in km and velocity in km/s, or a `MathError` if the solver does not converge.
+pub fn propagate_universal_variable(
+    radius_km: Vector3,
+    velocity_km_s: Vector3,
+    mu_km3_s2: f64,
+    dt_s: f64,
+) -> Result<(Vector3, Vector3), MathError> {
+    if dt_s.abs() < f64::EPSILON {
+        return Ok((radius_km, velocity_km_s));
+    }
+
+    if mu_km3_s2 <= 0.0 {
+        return Err(MathError::DomainError {
+            value: mu_km3_s2,
+            msg: "gravitational parameter must be strictly positive",
+        });
+    }
+
+    let r0 = radius_km.norm();
+    if r0 < f64::EPSILON {
+        return Err(MathError::DivisionByZero {
+            action: "propagating a state with a zero radius",
+        });
+    }
+
+    let sqrt_mu = mu_km3_s2.sqrt();
+    let vr0 = radius_km.dot(&velocity_km_s) / r0;
+    let rv_term = r0 * vr0 / sqrt_mu;
+    // Reciprocal of the semi major axis
+    let alpha = 2.0 / r0 - velocity_km_s.norm_squared() / mu_km3_s2;
+    let one_m_alpha_r0 = 1.0 - alpha * r0;
+
+    // Initial guess of the universal anomaly
+    let mut chi = if alpha.abs() > 1e-12 {
+        sqrt_mu * alpha.abs() * dt_s
+    } else {
+        sqrt_mu * dt_s / r0
+    };
+
+    let n = 5.0;
+    let mut converged = false;
+    for _ in 0..UNIVERSAL_VAR_MAX_ITER {
+        let chi2 = chi * chi;
+        let z = alpha * chi2;
+        let (c, s) = stumpff_cs(z);
+
+        let f = rv_term * chi2 * c + one_m_alpha_r0 * chi2 * chi * s + r0 * chi - sqrt_mu * dt_s;
+        let df = rv_term * chi * (1.0 - z * s) + one_m_alpha_r0 * chi2 * c + r0;
+        let ddf = rv_term * (1.0 - z * c) + one_m_alpha_r0 * chi * (1.0 - z * s);
+
+        let denom = df
+            + df.signum()
+                * ((n - 1.0).powi(2) * df * df - n * (n - 1.0) * f * ddf)
+                    .abs()
+                    .sqrt();
+
+        if denom.abs() < f64::EPSILON {
+            return Err(MathError::DivisionByZero {
+                action: "solving for the universal anomaly",
+            });
+        }
+
+        let delta = n * f / denom;
+        chi -= delta;
+
+        if delta.abs() < 1e-12 * chi.abs().max(1.0) {
+            converged = true;
+            break;
+        }
+    }
+
+    if !converged {
+        return Err(MathError::MaxIterationsReached {
+            iter: UNIVERSAL_VAR_MAX_ITER,
+            action: "solving for the universal anomaly",
+        });
+    }
+
+    // Lagrange coefficients
+    let chi2 = chi * chi;
+    let z = alpha * chi2;
+    let (c, s) = stumpff_cs(z);
+
+    let f = 1.0 - chi2 / r0 * c;
+    let g = dt_s - chi2 * chi / sqrt_mu * s;
+
+    let new_radius_km = f * radius_km + g * velocity_km_s;
+    let rn = new_radius_km.norm();
+
+    let fdot = sqrt_mu / (rn * r0) * (z * chi * s - chi);
+    let gdot = 1.0 - chi2 / rn * c;
+
+    let new_velocity_km_s = fdot * radius_km + gdot * velocity_km_s;
+
+    Ok((new_radius_km, new_velocity_km_s))
+}
+
 #[cfg(test)]
 mod ut_utils {
     use super::*;
@@ -564,6 +694,43 @@ mod ut_utils {
         f64_eq_tol!(res.unwrap(), res2.unwrap(), TEST_EPS, ""); // Both should be same due to normalization
     }
 
+    #[test]
+    fn test_universal_variable_circular() {
+        let mu = 398600.4418;
+        let r = 7000.0;
+        let v = (mu / r).sqrt();
+        let period_s = std::f64::consts::TAU * (r.powi(3) / mu).sqrt();
+
+        let (r_q, v_q) = propagate_universal_variable(
+            Vector3::new(r, 0.0, 0.0),
+            Vector3::new(0.0, v, 0.0),
+            mu,
+            period_s / 4.0,
+        )
+        .unwrap();
+        assert!((r_q - Vector3::new(0.0, r, 0.0)).norm() < 1e-6);
+        assert!((v_q - Vector3::new(-v, 0.0, 0.0)).norm() < 1e-9);
+
+        // Backward propagation must return to the initial state
+        let (r_b, v_b) = propagate_universal_variable(r_q, v_q, mu, -period_s / 4.0).unwrap();
+        assert!((r_b - Vector3::new(r, 0.0, 0.0)).norm() < 1e-6);
+        assert!((v_b - Vector3::new(0.0, v, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_universal_variable_hyperbolic() {
+        let mu = 398600.4418;
+        let r0 = Vector3::new(7000.0, 0.0, 0.0);
+        let v0 = Vector3::new(0.0, 12.0, 1.0);
+
+        let energy = |r: Vector3, v: Vector3| v.norm_squared() / 2.0 - mu / r.norm();
+        let (r1, v1) = propagate_universal_variable(r0, v0, mu, 86400.0).unwrap();
+
+        f64_eq_tol!(energy(r1, v1), energy(r0, v0), 1e-9, "energy");
+        assert!((r1.cross(&v1) - r0.cross(&v0)).norm() < 1e-6);
+        assert!(r1.norm() > r0.norm());
+    }
+
     #[test]
     fn test_ta_to_ma_error_propagation() {
         // 6. Error propagation
@@ -11,6 +11,9 @@
 /// Speed of light in kilometers per second (km/s)
 pub const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
 
+/// Gravitational parameter of the Sun in km^3/s^2 (DE440), used for the Shapiro delay when the loaded planetary data does not define it
+pub const SUN_GM_KM3_S2: f64 = 132_712_440_041.279_42;
+
 pub mod celestial_objects {
     use crate::{ephemerides::EphemerisError, NaifId};
 
@@ -249,6 +252,10 @@ pub mod orientations {
     /// Angle between J2000 to solar system ecliptic J2000 ([ECLIPJ2000]), in radians (about 23.43929 degrees). Apply this rotation about the X axis (R1)
     pub const J2000_TO_ECLIPJ2000_ANGLE_RAD: f64 = 0.40909280422232897;
 
+    /// Precession angles z, theta, and zeta from [B1950] to J2000, in arcseconds. The rotation from B1950 to J2000 is R3(-z) R2(theta) R3(-zeta).
+    pub const B1950_TO_J2000_PRECESSION_ARCSEC: (f64, f64, f64) =
+        (1153.04066200330, 1002.26108439117, 1152.84248596724);
+
     /// Given the frame ID, try to return a human name
     /// Source: <https://naif.jpl.nasa.gov/pub/naif/toolkit_docs/C/req/frames.html#Appendix.%20%60%60Built%20in''%20Inertial%20Reference%20Frames>
     pub const fn orientation_name_from_id(id: NaifId) -> Option<&'static str> {
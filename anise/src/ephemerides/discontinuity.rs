@@ -0,0 +1,208 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+use hifitime::{Epoch, Unit};
+
+use super::EphemerisError;
+use crate::almanac::Almanac;
+use crate::math::cartesian::CartesianState;
+use crate::math::Vector3;
+use crate::naif::daf::datatypes::{Type2ChebyshevSet, Type3ChebyshevSet};
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::prelude::Frame;
+use crate::NaifId;
+
+use super::translations::MAX_TREE_DEPTH;
+
+/// Boundaries of segments and records within this many seconds of the requested epoch are considered.
+pub const BOUNDARY_SEARCH_S: f64 = 1.0;
+/// The left and right pieces are evaluated this many seconds away from the boundary, which is more than the tolerance of the segment lookup.
+const BOUNDARY_OFFSET_S: f64 = 1e-5;
+
+/// The jump in the translation between the pieces of ephemeris data on either side of a boundary, i.e. the right piece minus the left piece.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SegmentDiscontinuity {
+    /// Epoch of the boundary
+    pub epoch: Epoch,
+    /// Ephemeris ID whose segments or records meet at this boundary
+    pub id: NaifId,
+    /// Jump in position, in km
+    pub position_km: Vector3,
+    /// Jump in velocity, in km/s
+    pub velocity_km_s: Vector3,
+}
+
+impl fmt::Display for SegmentDiscontinuity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "discontinuity of {} at {}: {:.6e} km\t{:.6e} km/s",
+            self.id,
+            self.epoch,
+            self.position_km.norm(),
+            self.velocity_km_s.norm()
+        )
+    }
+}
+
+impl Almanac {
+    /// Returns the jump in the translation of the target frame as seen from the observer frame at the boundary closest to the provided epoch,
+    /// if any boundary is within [BOUNDARY_SEARCH_S] of it. The boundaries are those between the segments of the same ephemeris ID, and
+    /// between the records of the Chebyshev segments (Types 2 and 3), for each ephemeris along the path between the target and the observer.
+    ///
+    /// The left and right pieces are evaluated just before and just after the boundary, and extrapolated to the boundary with their velocity and
+    /// acceleration, so that the jump is that of the data itself and not due to the motion of the target. A kernel without stitching errors
+    /// has jumps close to the precision of its fit.
+    ///
+    /// Returns None if there is no boundary near this epoch, or if the data is only available on one side of the boundaries (e.g. at the end
+    /// of the coverage).
+    ///
+    /// # Errors
+    /// + The path between the target and the observer must be available at the provided epoch.
+    pub fn segment_boundary_discontinuity(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<Option<SegmentDiscontinuity>, EphemerisError> {
+        if target_frame == observer_frame {
+            return Ok(None);
+        }
+
+        let (_, _, common_node) =
+            self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+
+        let mut boundaries = Vec::new();
+        for frame in [target_frame, observer_frame] {
+            let mut id = frame.ephemeris_id;
+            for _ in 0..MAX_TREE_DEPTH {
+                if id == common_node {
+                    break;
+                }
+                match self.boundaries_near(id, epoch) {
+                    Some((parent_id, near)) => {
+                        boundaries.extend(near.into_iter().map(|boundary| (boundary, id)));
+                        id = parent_id;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        boundaries.sort_by_key(|(boundary, _)| (*boundary - epoch).abs());
+
+        for (boundary, id) in boundaries {
+            let offset = Unit::Second * BOUNDARY_OFFSET_S;
+            let (Some(left), Some(right)) = (
+                self.piece_at_boundary(target_frame, observer_frame, boundary - offset, offset),
+                self.piece_at_boundary(target_frame, observer_frame, boundary + offset, -offset),
+            ) else {
+                continue;
+            };
+
+            return Ok(Some(SegmentDiscontinuity {
+                epoch: boundary,
+                id,
+                position_km: right.radius_km - left.radius_km,
+                velocity_km_s: right.velocity_km_s - left.velocity_km_s,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the parent of this ephemeris ID at the provided epoch, and the boundaries of its segments and of the records of its
+    /// Chebyshev segment within the search window around that epoch.
+    fn boundaries_near(&self, id: NaifId, epoch: Epoch) -> Option<(NaifId, Vec<Epoch>)> {
+        let window = Unit::Second * BOUNDARY_SEARCH_S;
+        let is_near = |boundary: Epoch| (boundary - epoch).abs() <= window;
+
+        let (summary, spk_no, idx_in_spk) = match self.spk_summary_at_epoch(id, epoch) {
+            Ok(found) => found,
+            Err(_) => {
+                // Fixed frames have no boundaries.
+                return self
+                    .fixed_frames
+                    .get(&id)
+                    .map(|fixed| (fixed.parent.ephemeris_id, Vec::new()));
+            }
+        };
+
+        let mut boundaries: Vec<Epoch> = self
+            .spk_data
+            .iter()
+            .take(self.num_loaded_spk())
+            .flatten()
+            .flat_map(|spk| spk.data_summaries().unwrap_or(&[]).iter())
+            .filter(|other| !other.is_empty() && other.target_id == id)
+            .flat_map(|other| [other.start_epoch(), other.end_epoch()])
+            .filter(|boundary| is_near(*boundary))
+            .collect();
+
+        // The records of the Chebyshev segments are of equal length from the start of the segment.
+        let spk = self.spk_data[spk_no].as_ref()?;
+        let records = match summary.data_type() {
+            Ok(DafDataType::Type2ChebyshevTriplet) => spk
+                .nth_data::<Type2ChebyshevSet>(idx_in_spk)
+                .ok()
+                .map(|data| (data.interval_length, data.num_records)),
+            Ok(DafDataType::Type3ChebyshevSextuplet) => spk
+                .nth_data::<Type3ChebyshevSet>(idx_in_spk)
+                .ok()
+                .map(|data| (data.interval_length, data.num_records)),
+            _ => None,
+        };
+
+        if let Some((interval_length, num_records)) = records {
+            let interval_s = interval_length.to_seconds();
+            let record_no =
+                ((epoch.to_et_seconds() - summary.start_epoch_et_s()) / interval_s).round();
+            if record_no >= 1.0 && (record_no as usize) < num_records {
+                let boundary =
+                    Epoch::from_et_seconds(summary.start_epoch_et_s() + record_no * interval_s);
+                if is_near(boundary) {
+                    boundaries.push(boundary);
+                }
+            }
+        }
+
+        Some((summary.center_id, boundaries))
+    }
+
+    /// Returns the translation evaluated at the provided epoch, extrapolated by the provided offset with its velocity and acceleration,
+    /// or None if it is not available.
+    fn piece_at_boundary(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        offset: hifitime::Duration,
+    ) -> Option<CartesianState> {
+        let (mut state, acc_km_s2) =
+            match self.translate_with_derivatives(target_frame, observer_frame, epoch) {
+                Ok(found) => found,
+                // Some data types are not polynomials, their acceleration is neglected over the offset.
+                Err(_) => (
+                    self.translate(target_frame, observer_frame, epoch, None)
+                        .ok()?,
+                    Vector3::zeros(),
+                ),
+            };
+
+        let dt_s = offset.to_seconds();
+        state.radius_km += dt_s * state.velocity_km_s + 0.5 * dt_s.powi(2) * acc_km_s2;
+        state.velocity_km_s += dt_s * acc_km_s2;
+        state.epoch = epoch + offset;
+
+        Some(state)
+    }
+}
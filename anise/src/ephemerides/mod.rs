@@ -12,10 +12,16 @@ use hifitime::Epoch;
 use snafu::prelude::*;
 
 use crate::{
-    astro::Aberration, errors::PhysicsError, math::interpolation::InterpolationError,
-    naif::daf::DAFError, prelude::FrameUid, NaifId,
+    astro::Aberration,
+    errors::PhysicsError,
+    math::interpolation::InterpolationError,
+    naif::daf::{DAFError, DafDataType},
+    orientations::OrientationError,
+    prelude::FrameUid,
+    NaifId,
 };
 
+pub mod discontinuity;
 pub mod paths;
 pub mod translate_to_parent;
 pub mod translations;
@@ -40,6 +46,29 @@ pub enum EphemerisError {
     },
     #[snafu(display("no ephemeris data loaded (must call load_spk)"))]
     NoEphemerisLoaded,
+    #[snafu(display(
+        "no path from {from} to {to}: {id} is neither in the loaded SPKs nor a fixed frame (is a kernel missing?)"
+    ))]
+    NoConnectingPath {
+        from: FrameUid,
+        to: FrameUid,
+        id: NaifId,
+    },
+    #[snafu(display(
+        "{id} is only covered from {} to {} by the loaded SPKs but was requested at {requested}",
+        available.0,
+        available.1
+    ))]
+    EpochOutOfCoverage {
+        id: NaifId,
+        requested: Epoch,
+        /// Earliest start and latest end of the segments of this ID, which may have gaps in between
+        available: (Epoch, Epoch),
+    },
+    #[snafu(display(
+        "{id} is stored as {kind:?} data, which is not supported for this computation"
+    ))]
+    UnsupportedDataType { id: NaifId, kind: DafDataType },
     #[snafu(display("when {action} caused {source}"))]
     SPK {
         action: &'static str,
@@ -65,6 +94,38 @@ pub enum EphemerisError {
         #[snafu(source(from(EphemerisError, Box::new)))] // This ensures the source error is boxed
         source: Box<EphemerisError>,
     },
+    #[snafu(display("when rotating the parent of fixed frame {id} {source}"))]
+    FixedFrameRotation {
+        id: NaifId,
+        #[snafu(source(from(OrientationError, Box::new)))]
+        source: Box<OrientationError>,
+    },
+    #[snafu(display(
+        "when rotating the segment of {id} from its frame {frame_id} into J2000 {source}"
+    ))]
+    SegmentFrameRotation {
+        id: NaifId,
+        frame_id: NaifId,
+        #[snafu(source(from(OrientationError, Box::new)))]
+        source: Box<OrientationError>,
+    },
+    #[snafu(display(
+        "the ephemeris chain of {target} stops at {root} before reaching the solar system barycenter: {source}"
+    ))]
+    ChainToSsb {
+        target: NaifId,
+        /// Last body of the chain, whose parent could not be found
+        root: NaifId,
+        #[snafu(source(from(EphemerisError, Box::new)))]
+        source: Box<EphemerisError>,
+    },
+    #[snafu(display("translation #{index} of the batch at {epoch} failed: {source}"))]
+    BatchTranslation {
+        index: usize,
+        epoch: Epoch,
+        #[snafu(source(from(EphemerisError, Box::new)))]
+        source: Box<EphemerisError>,
+    },
     #[snafu(display("unknown name associated with NAIF ID {id}"))]
     IdToName { id: NaifId },
     #[snafu(display("unknown NAIF ID associated with `{name}`"))]
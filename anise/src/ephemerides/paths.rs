@@ -68,22 +68,34 @@ impl Almanac {
             return Ok((of_path_len, of_path));
         }
 
-        // Grab the summary data, which we use to find the paths
-        let summary = self.spk_summary_at_epoch(source.ephemeris_id, epoch)?.0;
+        // An ID which is not loaded at all means that no kernel connects the source to the root.
+        let center_of = |id: NaifId| {
+            self.ephemeris_center_id(id, epoch).map_err(|e| match e {
+                EphemerisError::SPK {
+                    source: DAFError::SummaryIdError { id, .. },
+                    ..
+                } => EphemerisError::NoConnectingPath {
+                    from: source.into(),
+                    to: Frame::new(common_center, source.orientation_id).into(),
+                    id,
+                },
+                e => e,
+            })
+        };
 
-        let mut center_id = summary.center_id;
+        // Grab the summary data, which we use to find the paths
+        let mut center_id = center_of(source.ephemeris_id)?;
 
-        of_path[of_path_len] = Some(summary.center_id);
+        of_path[of_path_len] = Some(center_id);
         of_path_len += 1;
 
-        if summary.center_id == common_center {
+        if center_id == common_center {
             // Well that was quick!
             return Ok((of_path_len, of_path));
         }
 
         for _ in 0..MAX_TREE_DEPTH {
-            let summary = self.spk_summary_at_epoch(center_id, epoch)?.0;
-            center_id = summary.center_id;
+            center_id = center_of(center_id)?;
             of_path[of_path_len] = Some(center_id);
             of_path_len += 1;
             if center_id == common_center {
@@ -138,9 +150,40 @@ impl Almanac {
             return Ok((0, [None; MAX_TREE_DEPTH], from_frame.ephemeris_id));
         }
 
-        // Grab the paths
-        let (from_len, from_path) = self.ephemeris_path_to_root(from_frame, epoch)?;
-        let (to_len, to_path) = self.ephemeris_path_to_root(to_frame, epoch)?;
+        let ids = (from_frame.ephemeris_id, to_frame.ephemeris_id);
+        if let Some(path) = self.path_cache.ephemeris_path(ids.0, ids.1, epoch) {
+            return Ok(path);
+        }
+
+        let path = self.compute_common_ephemeris_path(from_frame, to_frame, epoch)?;
+        let validity = self.ephemeris_path_validity([ids.0, ids.1], epoch);
+        self.path_cache
+            .insert_ephemeris_path(ids.0, ids.1, path, validity);
+
+        Ok(path)
+    }
+
+    fn compute_common_ephemeris_path(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(usize, [Option<NaifId>; MAX_TREE_DEPTH], NaifId), EphemerisError> {
+        // Grab the paths, and report any missing node as missing between both frames.
+        let between_frames = |e: EphemerisError| match e {
+            EphemerisError::NoConnectingPath { id, .. } => EphemerisError::NoConnectingPath {
+                from: from_frame.into(),
+                to: to_frame.into(),
+                id,
+            },
+            e => e,
+        };
+        let (from_len, from_path) = self
+            .ephemeris_path_to_root(from_frame, epoch)
+            .map_err(between_frames)?;
+        let (to_len, to_path) = self
+            .ephemeris_path_to_root(to_frame, epoch)
+            .map_err(between_frames)?;
 
         // Now that we have the paths, we can find the matching origin.
 
@@ -195,4 +238,66 @@ impl Almanac {
             Err(EphemerisError::Unreachable)
         }
     }
+
+    /// Returns the center of this object at the provided epoch, from the loaded SPKs or else from the fixed frames.
+    fn ephemeris_center_id(&self, id: NaifId, epoch: Epoch) -> Result<NaifId, EphemerisError> {
+        match self.spk_summary_at_epoch(id, epoch) {
+            Ok((summary, _, _)) => Ok(summary.center_id),
+            Err(e) => self
+                .fixed_frames
+                .get(&id)
+                .map(|fixed| fixed.parent.ephemeris_id)
+                .ok_or(e),
+        }
+    }
+
+    /// Returns the span (in ET seconds) around the epoch over which the ephemeris paths of these objects up to the root do not change.
+    ///
+    /// This is the intersection of the coverage of the segments used at this epoch, excluding the coverage of any other segment
+    /// of the same object with a different center, since that segment would change the path.
+    fn ephemeris_path_validity(&self, ids: [NaifId; 2], epoch: Epoch) -> (f64, f64) {
+        let et_s = epoch.to_et_seconds();
+        let mut start_et_s = f64::NEG_INFINITY;
+        let mut end_et_s = f64::INFINITY;
+
+        for mut id in ids {
+            for _ in 0..MAX_TREE_DEPTH {
+                let center_id = match self.spk_summary_at_epoch(id, epoch) {
+                    Ok((summary, _, _)) => {
+                        start_et_s = start_et_s.max(summary.start_epoch_et_s);
+                        end_et_s = end_et_s.min(summary.end_epoch_et_s);
+                        summary.center_id
+                    }
+                    Err(_) => match self.fixed_frames.get(&id) {
+                        Some(fixed) => fixed.parent.ephemeris_id,
+                        // We've reached the root
+                        None => break,
+                    },
+                };
+
+                for spk in self.spk_data.iter().take(self.num_loaded_spk()).flatten() {
+                    for summary in spk
+                        .data_summaries()
+                        .unwrap_or(&[])
+                        .iter()
+                        .filter(|summary| {
+                            !summary.is_empty()
+                                && summary.target_id == id
+                                && summary.center_id != center_id
+                        })
+                    {
+                        if summary.end_epoch_et_s < et_s {
+                            start_et_s = start_et_s.max(summary.end_epoch_et_s);
+                        } else if summary.start_epoch_et_s > et_s {
+                            end_et_s = end_et_s.min(summary.start_epoch_et_s);
+                        }
+                    }
+                }
+
+                id = center_id;
+            }
+        }
+
+        (start_et_s, end_et_s)
+    }
 }
@@ -8,24 +8,88 @@
  * Documentation: https://nyxspace.com/
  */
 
-use log::trace;
+use log::{trace, warn};
 use snafu::ResultExt;
 
-use super::{EphemerisError, SPKSnafu};
+use super::{EphemerisError, SPKSnafu, SegmentFrameRotationSnafu};
 use crate::almanac::Almanac;
+use crate::constants::orientations::{
+    B1950, B1950_TO_J2000_PRECESSION_ARCSEC, ECLIPJ2000, J2000, J2000_TO_ECLIPJ2000_ANGLE_RAD,
+};
 use crate::ephemerides::EphemInterpolationSnafu;
-use crate::hifitime::Epoch;
+use crate::hifitime::{Epoch, Unit};
 use crate::math::cartesian::CartesianState;
-use crate::math::Vector3;
+use crate::math::rotation::{r1, r2, r3};
+use crate::math::{Matrix3, Vector3};
 use crate::naif::daf::datatypes::{
-    HermiteSetType13, LagrangeSetType9, Type2ChebyshevSet, Type3ChebyshevSet,
+    DiscreteStatesType5, HermiteSetType12, HermiteSetType13, LagrangeSetType8, LagrangeSetType9,
+    Type1ModifiedDifferenceSet, Type2ChebyshevSet, Type3ChebyshevSet,
 };
-use crate::naif::daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::naif::spk::summary::SPKSummaryRecord;
 use crate::prelude::Frame;
+use crate::NaifId;
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+/// A segment found by the segment lookup of an ID, and the span over which the lookup of that ID returns that same segment.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SegmentHint {
+    id: NaifId,
+    summary: SPKSummaryRecord,
+    spk_no: usize,
+    idx_in_spk: usize,
+    start: Epoch,
+    end: Epoch,
+}
+
+impl SegmentHint {
+    fn covers(&self, epoch: Epoch) -> bool {
+        epoch >= self.start && epoch <= self.end
+    }
+}
+
+/// Returns the rotation matrix from the provided inertial frame into J2000, for the inertial frames whose rotation is built in:
+/// J2000, the ecliptic J2000 ([ECLIPJ2000]), and [B1950]. Returns None for any other frame.
+pub(crate) fn inertial_frame_to_j2000(frame_id: NaifId) -> Option<Matrix3> {
+    match frame_id {
+        J2000 => Some(Matrix3::identity()),
+        ECLIPJ2000 => Some(r1(-J2000_TO_ECLIPJ2000_ANGLE_RAD)),
+        B1950 => {
+            let (z, theta, zeta) = B1950_TO_J2000_PRECESSION_ARCSEC;
+            let arcsec_to_rad = (1.0_f64 / 3600.0).to_radians();
+            Some(r3(-z * arcsec_to_rad) * r2(theta * arcsec_to_rad) * r3(-zeta * arcsec_to_rad))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the boundary of the segment from which to extrapolate, and the duration in seconds of the extrapolation, if the epoch is
+/// outside of the segment. Only the lookup with a gap tolerance returns a segment which does not cover the epoch, with the same
+/// tolerance as the lookup.
+fn gap_extrapolation(
+    source: Frame,
+    summary: &SPKSummaryRecord,
+    epoch: Epoch,
+) -> Option<(Epoch, f64)> {
+    let tol = Unit::Nanosecond * 100;
+    if epoch >= summary.start_epoch() - tol && epoch <= summary.end_epoch() + tol {
+        return None;
+    }
+
+    let boundary = if epoch < summary.start_epoch() {
+        summary.start_epoch()
+    } else {
+        summary.end_epoch()
+    };
+    let dt_s = (epoch - boundary).to_seconds();
+
+    warn!("extrapolating {source} by {dt_s} s from the segment boundary at {boundary} to bridge a coverage gap");
+
+    Some((boundary, dt_s))
+}
+
 impl Almanac {
     /// Returns the position vector and velocity vector of the `source` with respect to its parent in the ephemeris at the provided epoch,
     /// Units are those used in the SPK, typically distances are in kilometers and velocities in kilometers per second.
@@ -40,15 +104,128 @@ impl Almanac {
         source: Frame,
         epoch: Epoch,
     ) -> Result<(Vector3, Vector3, Frame), EphemerisError> {
-        // First, let's find the SPK summary for this frame.
+        // First, let's find the SPK summary for this frame, or else whether it is a fixed frame.
         let (summary, spk_no, idx_in_spk) =
-            self.spk_summary_at_epoch(source.ephemeris_id, epoch)?;
+            match self.spk_summary_at_epoch(source.ephemeris_id, epoch) {
+                Ok(found) => found,
+                Err(e) => {
+                    return match self.fixed_frames.get(&source.ephemeris_id) {
+                        Some(fixed) => self.fixed_frame_translation_parts(source, fixed, epoch),
+                        None => Err(e),
+                    }
+                }
+            };
+
+        self.translation_parts_from_segment(source, summary, spk_no, idx_in_spk, epoch)
+    }
+
+    /// Returns the position vector and velocity vector of the `source` with respect to its parent at the provided epoch, like
+    /// [Self::translation_parts_to_parent], but reusing the segments found at previous epochs, as long as the segment lookup would
+    /// return the same segment at this epoch.
+    pub(crate) fn translation_parts_to_parent_with_hints(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+        hints: &mut Vec<SegmentHint>,
+    ) -> Result<(Vector3, Vector3, Frame), EphemerisError> {
+        if let Some(hint) = hints
+            .iter()
+            .find(|hint| hint.id == source.ephemeris_id && hint.covers(epoch))
+        {
+            return self.translation_parts_from_segment(
+                source,
+                &hint.summary,
+                hint.spk_no,
+                hint.idx_in_spk,
+                epoch,
+            );
+        }
+
+        match self.spk_summary_at_epoch(source.ephemeris_id, epoch) {
+            Ok((summary, spk_no, idx_in_spk)) => {
+                let hint =
+                    self.segment_hint(source.ephemeris_id, summary, spk_no, idx_in_spk, epoch);
+                hints.retain(|other| other.id != source.ephemeris_id);
+                hints.push(hint);
+                self.translation_parts_from_segment(source, summary, spk_no, idx_in_spk, epoch)
+            }
+            Err(e) => match self.fixed_frames.get(&source.ephemeris_id) {
+                Some(fixed) => self.fixed_frame_translation_parts(source, fixed, epoch),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Returns the span around the epoch over which the segment lookup of this ID returns the provided segment: the coverage of that
+    /// segment, excluding the coverage of the segments of the same ID which have a higher priority, i.e. those of the SPKs loaded
+    /// afterward, and those before it in the same SPK.
+    fn segment_hint(
+        &self,
+        id: NaifId,
+        summary: &SPKSummaryRecord,
+        spk_no: usize,
+        idx_in_spk: usize,
+        epoch: Epoch,
+    ) -> SegmentHint {
+        // Same tolerance as the segment lookup
+        let tol = Unit::Nanosecond * 100;
+        let mut start = summary.start_epoch() - tol;
+        let mut end = summary.end_epoch() + tol;
+
+        for (other_no, spk) in self
+            .spk_data
+            .iter()
+            .enumerate()
+            .take(self.num_loaded_spk())
+            .skip(spk_no)
+        {
+            let Some(spk) = spk else { continue };
+            for (other_idx, other) in spk.data_summaries().unwrap_or(&[]).iter().enumerate() {
+                if other.target_id != id || (other_no == spk_no && other_idx >= idx_in_spk) {
+                    continue;
+                }
+                // The lookup did not return this segment, so it does not cover the epoch. The bounds are moved by one nanosecond
+                // (the resolution of epochs) so that the epochs covered by both segments are excluded.
+                if other.end_epoch() + tol < epoch {
+                    start = start.max(other.end_epoch() + tol + Unit::Nanosecond * 1);
+                } else if other.start_epoch() - tol > epoch {
+                    end = end.min(other.start_epoch() - tol - Unit::Nanosecond * 1);
+                }
+            }
+        }
+
+        SegmentHint {
+            id,
+            summary: *summary,
+            spk_no,
+            idx_in_spk,
+            start,
+            end,
+        }
+    }
+
+    /// Evaluates the position vector and velocity vector of the `source` with respect to its parent at the provided epoch, using
+    /// the provided segment.
+    fn translation_parts_from_segment(
+        &self,
+        source: Frame,
+        summary: &SPKSummaryRecord,
+        spk_no: usize,
+        idx_in_spk: usize,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3, Frame), EphemerisError> {
+        if let Some((boundary, dt_s)) = gap_extrapolation(source, summary, epoch) {
+            let (pos_km, vel_km_s, frame) =
+                self.translation_parts_from_segment(source, summary, spk_no, idx_in_spk, boundary)?;
+
+            return Ok((pos_km + vel_km_s * dt_s, vel_km_s, frame));
+        }
 
         let new_frame = source.with_ephem(summary.center_id);
 
         trace!("translate {source} wrt to {new_frame} @ {epoch:E}");
 
-        // This should not fail because we've fetched the spk_no from above with the spk_summary_at_epoch call.
+        // This should not fail because the segment was found by the spk_summary_at_epoch call.
         let spk_data = self.spk_data[spk_no]
             .as_ref()
             .ok_or(EphemerisError::Unreachable)?;
@@ -56,6 +233,15 @@ impl Almanac {
         // Now let's simply evaluate the data
 
         let (pos_km, vel_km_s) = match summary.data_type()? {
+            DafDataType::Type1ModifiedDifferenceArray => {
+                let data = spk_data
+                    .nth_data::<Type1ModifiedDifferenceSet>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type2ChebyshevTriplet => {
                 let data =
                     spk_data
@@ -76,6 +262,24 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
+            DafDataType::Type5DiscreteStates => {
+                let data = spk_data
+                    .nth_data::<DiscreteStatesType5>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type8LagrangeEqualStep => {
+                let data = spk_data
+                    .nth_data::<LagrangeSetType8>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type9LagrangeUnequalStep => {
                 let data = spk_data
                     .nth_data::<LagrangeSetType9>(idx_in_spk)
@@ -85,6 +289,15 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
+            DafDataType::Type12HermiteEqualStep => {
+                let data = spk_data
+                    .nth_data::<HermiteSetType12>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type13HermiteUnequalStep => {
                 let data = spk_data
                     .nth_data::<HermiteSetType13>(idx_in_spk)
@@ -94,18 +307,213 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
-            dtype => {
-                return Err(EphemerisError::SPK {
-                    action: "translation to parent",
-                    source: DAFError::UnsupportedDatatype {
-                        dtype,
-                        kind: "SPK computations",
-                    },
+            kind => {
+                return Err(EphemerisError::UnsupportedDataType {
+                    id: source.ephemeris_id,
+                    kind,
+                })
+            }
+        };
+
+        match self.segment_frame_to_j2000(source, summary, epoch, false)? {
+            Some((rot_mat, rot_mat_dt, _)) => Ok((
+                rot_mat * pos_km,
+                rot_mat * vel_km_s + rot_mat_dt * pos_km,
+                new_frame,
+            )),
+            None => Ok((pos_km, vel_km_s, new_frame)),
+        }
+    }
+
+    /// Returns the rotation matrix from the frame in which this segment is stored into J2000, where the translations are computed,
+    /// along with its first and second time derivatives, or None if the segment is already stored in J2000.
+    ///
+    /// The inertial frames of [inertial_frame_to_j2000] are built in. Any other frame is rotated with the loaded orientation data,
+    /// and the second derivative is only computed if `with_acceleration` is set.
+    fn segment_frame_to_j2000(
+        &self,
+        source: Frame,
+        summary: &SPKSummaryRecord,
+        epoch: Epoch,
+        with_acceleration: bool,
+    ) -> Result<Option<(Matrix3, Matrix3, Matrix3)>, EphemerisError> {
+        if summary.frame_id == J2000 {
+            return Ok(None);
+        }
+
+        if let Some(rot_mat) = inertial_frame_to_j2000(summary.frame_id) {
+            return Ok(Some((rot_mat, Matrix3::zeros(), Matrix3::zeros())));
+        }
+
+        let from_frame = Frame::new(summary.center_id, summary.frame_id);
+        let to_frame = from_frame.with_orient(J2000);
+
+        let (dcm, rot_mat_ddt) = if with_acceleration {
+            self.rotate_with_derivatives(from_frame, to_frame, epoch)
+        } else {
+            self.rotate(from_frame, to_frame, epoch)
+                .map(|dcm| (dcm, Matrix3::zeros()))
+        }
+        .context(SegmentFrameRotationSnafu {
+            id: source.ephemeris_id,
+            frame_id: summary.frame_id,
+        })?;
+
+        Ok(Some((
+            dcm.rot_mat,
+            dcm.rot_mat_dt.unwrap_or_else(Matrix3::zeros),
+            rot_mat_ddt,
+        )))
+    }
+
+    /// Returns the position, velocity, and acceleration vectors of the `source` with respect to its parent in the ephemeris at the provided epoch,
+    /// where the acceleration is computed by analytically differentiating the interpolation polynomials. Units are those used in the SPK,
+    /// typically kilometers, kilometers per second, and kilometers per second squared.
+    ///
+    /// # Errors
+    /// + The discrete states (Type 5) and the modified difference arrays (Type 1) are not interpolated by polynomials, so they return an unsupported operation error.
+    pub(crate) fn translation_parts_to_parent_with_acceleration(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3, Vector3, Frame), EphemerisError> {
+        let (summary, spk_no, idx_in_spk) =
+            match self.spk_summary_at_epoch(source.ephemeris_id, epoch) {
+                Ok(found) => found,
+                Err(e) => {
+                    return match self.fixed_frames.get(&source.ephemeris_id) {
+                        Some(fixed) => self
+                            .fixed_frame_translation_parts_with_acceleration(source, fixed, epoch),
+                        None => Err(e),
+                    }
+                }
+            };
+
+        self.translation_parts_with_acceleration_from_segment(
+            source, summary, spk_no, idx_in_spk, epoch,
+        )
+    }
+
+    /// Evaluates the position, velocity, and acceleration vectors of the `source` with respect to its parent at the provided epoch,
+    /// using the provided segment.
+    fn translation_parts_with_acceleration_from_segment(
+        &self,
+        source: Frame,
+        summary: &SPKSummaryRecord,
+        spk_no: usize,
+        idx_in_spk: usize,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3, Vector3, Frame), EphemerisError> {
+        // The extrapolation is linear, as in translation_parts_from_segment, so the acceleration across the gap is zero.
+        if let Some((boundary, dt_s)) = gap_extrapolation(source, summary, epoch) {
+            let (pos_km, vel_km_s, _, frame) = self
+                .translation_parts_with_acceleration_from_segment(
+                    source, summary, spk_no, idx_in_spk, boundary,
+                )?;
+
+            return Ok((pos_km + vel_km_s * dt_s, vel_km_s, Vector3::zeros(), frame));
+        }
+
+        let new_frame = source.with_ephem(summary.center_id);
+
+        trace!("translate {source} wrt to {new_frame} with acceleration @ {epoch:E}");
+
+        let spk_data = self.spk_data[spk_no]
+            .as_ref()
+            .ok_or(EphemerisError::Unreachable)?;
+
+        let (pos_km, vel_km_s, acc_km_s2) = match summary.data_type()? {
+            DafDataType::Type1ModifiedDifferenceArray => {
+                let data = spk_data
+                    .nth_data::<Type1ModifiedDifferenceSet>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type2ChebyshevTriplet => {
+                let data =
+                    spk_data
+                        .nth_data::<Type2ChebyshevSet>(idx_in_spk)
+                        .context(SPKSnafu {
+                            action: "fetching data for interpolation",
+                        })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type3ChebyshevSextuplet => {
+                let data =
+                    spk_data
+                        .nth_data::<Type3ChebyshevSet>(idx_in_spk)
+                        .context(SPKSnafu {
+                            action: "fetching data for interpolation",
+                        })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type5DiscreteStates => {
+                let data = spk_data
+                    .nth_data::<DiscreteStatesType5>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type8LagrangeEqualStep => {
+                let data = spk_data
+                    .nth_data::<LagrangeSetType8>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type9LagrangeUnequalStep => {
+                let data = spk_data
+                    .nth_data::<LagrangeSetType9>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type12HermiteEqualStep => {
+                let data = spk_data
+                    .nth_data::<HermiteSetType12>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type13HermiteUnequalStep => {
+                let data = spk_data
+                    .nth_data::<HermiteSetType13>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            kind => {
+                return Err(EphemerisError::UnsupportedDataType {
+                    id: source.ephemeris_id,
+                    kind,
                 })
             }
         };
 
-        Ok((pos_km, vel_km_s, new_frame))
+        match self.segment_frame_to_j2000(source, summary, epoch, true)? {
+            Some((rot_mat, rot_mat_dt, rot_mat_ddt)) => Ok((
+                rot_mat * pos_km,
+                rot_mat * vel_km_s + rot_mat_dt * pos_km,
+                rot_mat * acc_km_s2 + 2.0 * rot_mat_dt * vel_km_s + rot_mat_ddt * pos_km,
+                new_frame,
+            )),
+            None => Ok((pos_km, vel_km_s, acc_km_s2, new_frame)),
+        }
     }
 }
 
@@ -8,19 +8,24 @@
  * Documentation: https://nyxspace.com/
  */
 
+use log::warn;
 use snafu::ResultExt;
 
-use super::EphemerisError;
-use super::EphemerisPhysicsSnafu;
+use super::{BatchTranslationSnafu, ChainToSsbSnafu, EphemerisError, EphemerisPhysicsSnafu};
 use crate::almanac::Almanac;
 use crate::astro::aberration::stellar_aberration;
-use crate::astro::Aberration;
-use crate::constants::frames::SSB_J2000;
-use crate::constants::SPEED_OF_LIGHT_KM_S;
-use crate::hifitime::Epoch;
+use crate::astro::{
+    shapiro_delay_s, Aberration, AberrationConfig, LightTimeConvergence, LightTimeDirection,
+};
+use crate::constants::celestial_objects::SOLAR_SYSTEM_BARYCENTER;
+use crate::constants::frames::{SSB_J2000, SUN_J2000};
+use crate::constants::orientations::J2000;
+use crate::constants::{SPEED_OF_LIGHT_KM_S, SUN_GM_KM3_S2};
+use crate::hifitime::{Duration, Epoch};
 use crate::math::cartesian::CartesianState;
 use crate::math::units::*;
 use crate::math::Vector3;
+use crate::naif::daf::DAFError;
 use crate::prelude::Frame;
 
 /// **Limitation:** no translation or rotation may have more than 8 nodes.
@@ -40,18 +45,46 @@ impl Almanac {
     /// # Warning
     /// This function only performs the translation and no rotation whatsoever. Use the `transform` function instead to include rotations.
     ///
+    /// # Light time corrections
+    /// The velocity is corrected for the rate of change of the light time (cf. [Self::one_way_light_time]), but not for the rate of
+    /// change of the stellar aberration.
+    ///
     /// # Note
     /// This function performs a recursion of no more than twice the [MAX_TREE_DEPTH].
     pub fn translate(
         &self,
         target_frame: Frame,
-        mut observer_frame: Frame,
+        observer_frame: Frame,
         epoch: Epoch,
         ab_corr: Option<Aberration>,
     ) -> Result<CartesianState, EphemerisError> {
+        self.translate_cfg(
+            target_frame,
+            observer_frame,
+            epoch,
+            ab_corr,
+            AberrationConfig::default(),
+        )
+        .map(|(state, _)| state)
+    }
+
+    /// Returns the Cartesian state of the target frame as seen from the observer frame at the provided epoch exactly as [Self::translate],
+    /// but iterating the light time of the aberration correction with the provided configuration, along with the outcome of these iterations
+    /// (None without aberration correction).
+    ///
+    /// If the light time of a converged correction does not converge within the maximum number of iterations, a warning is logged and
+    /// the outcome reports it: the state is then that of the last iteration.
+    pub fn translate_cfg(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+        cfg: AberrationConfig,
+    ) -> Result<(CartesianState, Option<LightTimeConvergence>), EphemerisError> {
         if observer_frame == target_frame {
             // Both frames match, return this frame's hash (i.e. no need to go higher up).
-            return Ok(CartesianState::zero(observer_frame));
+            return Ok((CartesianState::zero(observer_frame), None));
         }
 
         // If there is no frame info, the user hasn't loaded this frame, but might still want to compute a translation.
@@ -61,115 +94,469 @@ impl Almanac {
         }
 
         match ab_corr {
-            None => {
-                let (node_count, _path, common_node) =
-                    self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
-
-                // The fwrd variables are the states from the `from frame` to the common node
-                let (mut pos_fwrd, mut vel_fwrd, mut frame_fwrd) =
-                    if observer_frame.ephem_origin_id_match(common_node) {
-                        (Vector3::zeros(), Vector3::zeros(), observer_frame)
-                    } else {
-                        self.translation_parts_to_parent(observer_frame, epoch)?
-                    };
-
-                // The bwrd variables are the states from the `to frame` back to the common node
-                let (mut pos_bwrd, mut vel_bwrd, mut frame_bwrd) =
-                    if target_frame.ephem_origin_id_match(common_node) {
-                        (Vector3::zeros(), Vector3::zeros(), target_frame)
-                    } else {
-                        self.translation_parts_to_parent(target_frame, epoch)?
-                    };
-
-                for _ in 0..node_count {
-                    if !frame_fwrd.ephem_origin_id_match(common_node) {
-                        let (cur_pos_fwrd, cur_vel_fwrd, cur_frame_fwrd) =
-                            self.translation_parts_to_parent(frame_fwrd, epoch)?;
-
-                        pos_fwrd += cur_pos_fwrd;
-                        vel_fwrd += cur_vel_fwrd;
-                        frame_fwrd = cur_frame_fwrd;
-                    }
+            None => self
+                .translate_geometric_parts(target_frame, observer_frame, epoch, |frame| {
+                    self.translation_parts_to_parent(frame, epoch)
+                })
+                .map(|state| (state, None)),
+            Some(ab_corr) => {
+                let (state, _, _, convergence) =
+                    self.translate_light_time(target_frame, observer_frame, epoch, ab_corr, cfg)?;
+                Ok((state, Some(convergence)))
+            }
+        }
+    }
+
+    /// Returns the Cartesian states of the target frame as seen from the observer frame at each of the provided epochs, in the same
+    /// order, and optionally given the aberration correction. Each state is the same as the one returned by [Self::translate].
+    ///
+    /// Without aberration correction, the segment used for each translation is reused for all of the following epochs where the segment
+    /// lookup would return that same segment, so this is faster than calling [Self::translate] for each epoch, especially when the
+    /// epochs are sorted. With aberration corrections, the light time is iterated for each epoch.
+    ///
+    /// # Errors
+    /// If any translation fails, this returns a `BatchTranslation` error with the index and epoch of the first epoch which failed.
+    pub fn translate_many(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epochs: &[Epoch],
+        ab_corr: Option<Aberration>,
+    ) -> Result<Vec<CartesianState>, EphemerisError> {
+        if observer_frame == target_frame {
+            return Ok(vec![CartesianState::zero(observer_frame); epochs.len()]);
+        }
 
-                    if !frame_bwrd.ephem_origin_id_match(common_node) {
-                        let (cur_pos_bwrd, cur_vel_bwrd, cur_frame_bwrd) =
-                            self.translation_parts_to_parent(frame_bwrd, epoch)?;
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
 
-                        pos_bwrd += cur_pos_bwrd;
-                        vel_bwrd += cur_vel_bwrd;
-                        frame_bwrd = cur_frame_bwrd;
-                    }
+        let mut hints = Vec::new();
+
+        epochs
+            .iter()
+            .enumerate()
+            .map(|(index, epoch)| {
+                let epoch = *epoch;
+                match ab_corr {
+                    None => self.translate_geometric_parts(
+                        target_frame,
+                        observer_frame,
+                        epoch,
+                        |frame| {
+                            self.translation_parts_to_parent_with_hints(frame, epoch, &mut hints)
+                        },
+                    ),
+                    Some(_) => self.translate(target_frame, observer_frame, epoch, ab_corr),
                 }
+                .context(BatchTranslationSnafu { index, epoch })
+            })
+            .collect()
+    }
 
-                Ok(CartesianState {
-                    radius_km: pos_bwrd - pos_fwrd,
-                    velocity_km_s: vel_bwrd - vel_fwrd,
-                    epoch,
-                    frame: observer_frame.with_orient(target_frame.orientation_id),
-                })
+    /// Sums the translations of the target and the observer up to their common node, where `parts` returns the translation of a frame
+    /// to its parent at the provided epoch.
+    fn translate_geometric_parts<F>(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        mut parts: F,
+    ) -> Result<CartesianState, EphemerisError>
+    where
+        F: FnMut(Frame) -> Result<(Vector3, Vector3, Frame), EphemerisError>,
+    {
+        let (node_count, _path, common_node) =
+            self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+
+        // The fwrd variables are the states from the `from frame` to the common node
+        let (mut pos_fwrd, mut vel_fwrd, mut frame_fwrd) =
+            if observer_frame.ephem_origin_id_match(common_node) {
+                (Vector3::zeros(), Vector3::zeros(), observer_frame)
+            } else {
+                parts(observer_frame)?
+            };
+
+        // The bwrd variables are the states from the `to frame` back to the common node
+        let (mut pos_bwrd, mut vel_bwrd, mut frame_bwrd) =
+            if target_frame.ephem_origin_id_match(common_node) {
+                (Vector3::zeros(), Vector3::zeros(), target_frame)
+            } else {
+                parts(target_frame)?
+            };
+
+        for _ in 0..node_count {
+            if !frame_fwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_fwrd, cur_vel_fwrd, cur_frame_fwrd) = parts(frame_fwrd)?;
+
+                pos_fwrd += cur_pos_fwrd;
+                vel_fwrd += cur_vel_fwrd;
+                frame_fwrd = cur_frame_fwrd;
             }
+
+            if !frame_bwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_bwrd, cur_vel_bwrd, cur_frame_bwrd) = parts(frame_bwrd)?;
+
+                pos_bwrd += cur_pos_bwrd;
+                vel_bwrd += cur_vel_bwrd;
+                frame_bwrd = cur_frame_bwrd;
+            }
+        }
+
+        Ok(CartesianState {
+            radius_km: pos_bwrd - pos_fwrd,
+            velocity_km_s: vel_bwrd - vel_fwrd,
+            epoch,
+            frame: observer_frame.with_orient(target_frame.orientation_id),
+        })
+    }
+
+    /// Returns the one-way light time between the target and the observer at the provided epoch given the aberration correction, along
+    /// with the rate of change of that light time (unitless, i.e. in seconds per second).
+    ///
+    /// The velocity returned by [Self::translate] with light time corrections includes this rate: the velocity of the target at the
+    /// light time corrected epoch is scaled by `1 - d(lt)/dt` in reception mode, and `1 + d(lt)/dt` in transmission mode, as in SPICE.
+    pub fn one_way_light_time(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Aberration,
+    ) -> Result<(Duration, f64), EphemerisError> {
+        self.one_way_light_time_cfg(
+            target_frame,
+            observer_frame,
+            epoch,
+            ab_corr,
+            AberrationConfig::default(),
+        )
+    }
+
+    /// Returns the one-way light time between the target and the observer and its rate of change exactly as [Self::one_way_light_time],
+    /// but iterating the light time with the provided configuration, e.g. to include the Shapiro delay.
+    pub fn one_way_light_time_cfg(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Aberration,
+        cfg: AberrationConfig,
+    ) -> Result<(Duration, f64), EphemerisError> {
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
+
+        let (_, one_way_lt_s, lt_rate, _) =
+            self.translate_light_time(target_frame, observer_frame, epoch, ab_corr, cfg)?;
+
+        Ok((one_way_lt_s * TimeUnit::Second, lt_rate))
+    }
+
+    /// Returns the epoch of the target as seen from the observer at the provided epoch given the aberration correction, i.e. one light
+    /// time earlier in reception mode and one light time later in transmission mode, or the provided epoch without aberration correction.
+    pub(crate) fn light_time_corrected_epoch(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Epoch, EphemerisError> {
+        match ab_corr {
             Some(ab_corr) => {
-                // This is a rewrite of NAIF SPICE's `spkapo`
-
-                // Find the geometric position of the observer body with respect to the solar system barycenter.
-                let obs_ssb = self.translate(observer_frame, SSB_J2000, epoch, None)?;
-                let obs_ssb_pos_km = obs_ssb.radius_km;
-                let obs_ssb_vel_km_s = obs_ssb.velocity_km_s;
-
-                // Find the geometric position of the target body with respect to the solar system barycenter.
-                let tgt_ssb = self.translate(target_frame, SSB_J2000, epoch, None)?;
-                let tgt_ssb_pos_km = tgt_ssb.radius_km;
-                let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
-
-                // Subtract the position of the observer to get the relative position.
-                let mut rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
-                // NOTE: We never correct the velocity, so the geometric velocity is what we're seeking.
-                let mut rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
-
-                // Use this to compute the one-way light time in seconds.
-                let mut one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
-
-                // To correct for light time, find the position of the target body at the current epoch
-                // minus the one-way light time. Note that the observer remains where he is.
-
-                let num_it = if ab_corr.converged { 3 } else { 1 };
-                let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
-
-                for _ in 0..num_it {
-                    let epoch_lt = epoch + lt_sign * one_way_lt_s * TimeUnit::Second;
-                    let tgt_ssb = self
-                        .translate(target_frame, SSB_J2000, epoch_lt, None)
-                        .map_err(|e| EphemerisError::LightTimeCorrection {
-                            epoch,
-                            epoch_lt,
-                            ab_corr,
-                            source: Box::new(e),
-                        })?;
-                    let tgt_ssb_pos_km = tgt_ssb.radius_km;
-                    let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
-
-                    rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
-                    rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
-                    one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
+                let (lt, _) =
+                    self.one_way_light_time(target_frame, observer_frame, epoch, ab_corr)?;
+                if ab_corr.transmit_mode {
+                    Ok(epoch + lt)
+                } else {
+                    Ok(epoch - lt)
                 }
+            }
+            None => Ok(epoch),
+        }
+    }
+
+    /// Returns the light time and aberration corrected state of the target as seen from the observer, the one-way light time in seconds,
+    /// its rate of change, and the outcome of the light time iterations.
+    fn translate_light_time(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Aberration,
+        cfg: AberrationConfig,
+    ) -> Result<(CartesianState, f64, f64, LightTimeConvergence), EphemerisError> {
+        // This is a rewrite of NAIF SPICE's `spkapo`
+
+        // Find the geometric position of the observer body with respect to the solar system barycenter.
+        let obs_ssb = self.translate(observer_frame, SSB_J2000, epoch, None)?;
+        let obs_ssb_pos_km = obs_ssb.radius_km;
+        let obs_ssb_vel_km_s = obs_ssb.velocity_km_s;
+
+        // Find the geometric state of the target body with respect to the solar system barycenter at the light time corrected epoch.
+        let (tgt_ssb, one_way_lt_s, convergence) =
+            self.converge_light_time(target_frame, obs_ssb_pos_km, epoch, ab_corr, cfg)?;
+        let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
+        let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
+
+        // Subtract the position of the observer to get the relative position.
+        let mut rel_pos_km = tgt_ssb.radius_km - obs_ssb_pos_km;
+        // NOTE: This velocity is corrected for the rate of change of the light time below.
+        let rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
 
-                // If stellar aberration correction is requested, perform it now.
-                if ab_corr.stellar {
-                    // Modifications based on transmission versus reception case is done in the function directly.
-                    rel_pos_km = stellar_aberration(rel_pos_km, obs_ssb_vel_km_s, ab_corr)
-                        .context(EphemerisPhysicsSnafu {
-                            action: "computing stellar aberration",
-                        })?;
+        let geometric_rel_pos_km = rel_pos_km;
+
+        // If stellar aberration correction is requested, perform it now.
+        if ab_corr.stellar {
+            // Modifications based on transmission versus reception case is done in the function directly.
+            rel_pos_km = stellar_aberration(rel_pos_km, obs_ssb_vel_km_s, ab_corr).context(
+                EphemerisPhysicsSnafu {
+                    action: "computing stellar aberration",
+                },
+            )?;
+        }
+
+        // The light time changes as the target and the observer move, so the position of the target at the light time corrected
+        // epoch changes faster or slower than its velocity. Differentiating lt = |r| / c, where r = target(t + s lt) - observer(t)
+        // and s is the light time sign, leads to d(lt)/dt = r_hat . (v_tgt - v_obs) / (c - s r_hat . v_tgt).
+        let rel_pos_hat = geometric_rel_pos_km / geometric_rel_pos_km.norm();
+        let lt_rate = rel_pos_hat.dot(&rel_vel_km_s)
+            / (SPEED_OF_LIGHT_KM_S - lt_sign * rel_pos_hat.dot(&tgt_ssb_vel_km_s));
+        let rel_vel_km_s = (1.0 + lt_sign * lt_rate) * tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
+
+        Ok((
+            CartesianState {
+                radius_km: rel_pos_km,
+                velocity_km_s: rel_vel_km_s,
+                epoch,
+                frame: observer_frame.with_orient(target_frame.orientation_id),
+            },
+            one_way_lt_s,
+            lt_rate,
+            convergence,
+        ))
+    }
+
+    /// Returns the geometric state of the target with respect to the solar system barycenter at the light time corrected epoch, the
+    /// one-way light time in seconds between that state and the observer position at the provided epoch, and the outcome of the iterations.
+    fn converge_light_time(
+        &self,
+        target_frame: Frame,
+        obs_ssb_pos_km: Vector3,
+        epoch: Epoch,
+        ab_corr: Aberration,
+        cfg: AberrationConfig,
+    ) -> Result<(CartesianState, f64, LightTimeConvergence), EphemerisError> {
+        // The Shapiro delay uses the position of the Sun at the epoch: it moves by less than a kilometer during the light time.
+        let shapiro = if cfg.shapiro {
+            let sun_ssb_pos_km = self.translate(SUN_J2000, SSB_J2000, epoch, None)?.radius_km;
+            let sun_mu_km3_s2 = self
+                .frame_from_uid(SUN_J2000)
+                .ok()
+                .and_then(|frame| frame.mu_km3_s2().ok())
+                .unwrap_or(SUN_GM_KM3_S2);
+            Some((sun_ssb_pos_km, sun_mu_km3_s2))
+        } else {
+            None
+        };
+
+        let light_time_s = |tgt_ssb_pos_km: Vector3| {
+            let geometric_lt_s = (tgt_ssb_pos_km - obs_ssb_pos_km).norm() / SPEED_OF_LIGHT_KM_S;
+            match shapiro {
+                Some((sun_ssb_pos_km, sun_mu_km3_s2)) => {
+                    geometric_lt_s
+                        + shapiro_delay_s(
+                            obs_ssb_pos_km - sun_ssb_pos_km,
+                            tgt_ssb_pos_km - sun_ssb_pos_km,
+                            sun_mu_km3_s2,
+                        )
                 }
+                None => geometric_lt_s,
+            }
+        };
+
+        // Find the geometric position of the target body with respect to the solar system barycenter.
+        let mut tgt_ssb = self.translate(target_frame, SSB_J2000, epoch, None)?;
+
+        // Use this to compute the one-way light time in seconds.
+        let mut one_way_lt_s = light_time_s(tgt_ssb.radius_km);
+
+        // To correct for light time, find the position of the target body at the current epoch
+        // minus the one-way light time. Note that the observer remains where he is.
+
+        // The converged corrections iterate until the light time distance changes by less than the tolerance.
+        let num_it = if ab_corr.converged { cfg.max_iter } else { 1 };
+        let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
 
-                Ok(CartesianState {
-                    radius_km: rel_pos_km,
-                    velocity_km_s: rel_vel_km_s,
+        let mut convergence = LightTimeConvergence {
+            iterations: 0,
+            converged: false,
+            residual_km: f64::INFINITY,
+        };
+
+        for _ in 0..num_it {
+            let epoch_lt = epoch + lt_sign * one_way_lt_s * TimeUnit::Second;
+            tgt_ssb = self
+                .translate(target_frame, SSB_J2000, epoch_lt, None)
+                .map_err(|e| EphemerisError::LightTimeCorrection {
                     epoch,
-                    frame: observer_frame.with_orient(target_frame.orientation_id),
-                })
+                    epoch_lt,
+                    ab_corr,
+                    source: Box::new(e),
+                })?;
+
+            let prev_lt_s = one_way_lt_s;
+            one_way_lt_s = light_time_s(tgt_ssb.radius_km);
+
+            convergence.iterations += 1;
+            convergence.residual_km = (one_way_lt_s - prev_lt_s).abs() * SPEED_OF_LIGHT_KM_S;
+            convergence.converged = convergence.residual_km <= cfg.tol_km
+                || (one_way_lt_s - prev_lt_s).abs()
+                    <= Aberration::CONVERGED_LT_REL_TOL * one_way_lt_s;
+
+            if convergence.converged {
+                break;
+            }
+        }
+
+        if ab_corr.converged && !convergence.converged {
+            warn!("{ab_corr:?} light time of {target_frame} at {epoch} {convergence}");
+        }
+
+        Ok((tgt_ssb, one_way_lt_s, convergence))
+    }
+
+    /// Returns the converged one-way light time between the target and the observer at the provided epoch, and the geometric state of
+    /// the target at the light time corrected epoch with respect to the observer at the provided epoch.
+    ///
+    /// In [LightTimeDirection::Reception], the target emits the signal at the epoch of the returned state, one light time before the
+    /// observer receives it. In [LightTimeDirection::Transmission], the observer emits the signal at the provided epoch, and the target
+    /// receives it at the epoch of the returned state, one light time later (the forward problem of `XCN`).
+    ///
+    /// Unlike [Self::translate] with light time corrections, neither the stellar aberration nor the rate of change of the light time is
+    /// applied: the state is that of the target at the light time corrected epoch, minus that of the observer at the provided epoch.
+    ///
+    /// # Convergence
+    /// The light time is iterated at most [Aberration::MAX_CONVERGED_ITERATIONS] times, stopping as soon as it no longer changes. Each
+    /// iteration reduces the error by a factor of the range rate over the speed of light, so the light time converges to the nanosecond
+    /// resolution of the epochs well within this cap anywhere in the solar system.
+    pub fn light_time(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epoch: Epoch,
+        direction: LightTimeDirection,
+    ) -> Result<(Duration, CartesianState), EphemerisError> {
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
+
+        let obs_ssb = self.translate(observer_frame, SSB_J2000, epoch, None)?;
+
+        let (tgt_ssb, one_way_lt_s, _) = self.converge_light_time(
+            target_frame,
+            obs_ssb.radius_km,
+            epoch,
+            direction.converged_aberration(),
+            AberrationConfig::default(),
+        )?;
+
+        Ok((
+            one_way_lt_s * TimeUnit::Second,
+            CartesianState {
+                radius_km: tgt_ssb.radius_km - obs_ssb.radius_km,
+                velocity_km_s: tgt_ssb.velocity_km_s - obs_ssb.velocity_km_s,
+                epoch: tgt_ssb.epoch,
+                frame: observer_frame.with_orient(target_frame.orientation_id),
+            },
+        ))
+    }
+
+    /// Returns the geometric Cartesian state of the target frame as seen from the observer frame at the provided epoch, along with the
+    /// acceleration of the target with respect to the observer in km/s^2.
+    ///
+    /// The acceleration is computed by analytically differentiating the interpolation polynomials of each segment along the path (Chebyshev
+    /// Types 2 and 3, Lagrange Types 8 and 9, and Hermite Types 12 and 13), not by finite differencing.
+    ///
+    /// # Errors
+    /// + The discrete states (Type 5) and modified difference arrays (Type 1) are not polynomials: if any segment along the path is one of these,
+    ///   this returns an unsupported operation error instead of falling back to a two-body model.
+    ///
+    /// # Warning
+    /// This function only performs the translation and no rotation whatsoever, and does not correct for any aberration.
+    pub fn translate_with_derivatives(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(CartesianState, Vector3), EphemerisError> {
+        if observer_frame == target_frame {
+            return Ok((CartesianState::zero(observer_frame), Vector3::zeros()));
+        }
+
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
+
+        let (node_count, _path, common_node) =
+            self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+
+        // The fwrd variables are the states from the `from frame` to the common node
+        let (mut pos_fwrd, mut vel_fwrd, mut acc_fwrd, mut frame_fwrd) =
+            if observer_frame.ephem_origin_id_match(common_node) {
+                (
+                    Vector3::zeros(),
+                    Vector3::zeros(),
+                    Vector3::zeros(),
+                    observer_frame,
+                )
+            } else {
+                self.translation_parts_to_parent_with_acceleration(observer_frame, epoch)?
+            };
+
+        // The bwrd variables are the states from the `to frame` back to the common node
+        let (mut pos_bwrd, mut vel_bwrd, mut acc_bwrd, mut frame_bwrd) =
+            if target_frame.ephem_origin_id_match(common_node) {
+                (
+                    Vector3::zeros(),
+                    Vector3::zeros(),
+                    Vector3::zeros(),
+                    target_frame,
+                )
+            } else {
+                self.translation_parts_to_parent_with_acceleration(target_frame, epoch)?
+            };
+
+        for _ in 0..node_count {
+            if !frame_fwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_fwrd, cur_vel_fwrd, cur_acc_fwrd, cur_frame_fwrd) =
+                    self.translation_parts_to_parent_with_acceleration(frame_fwrd, epoch)?;
+
+                pos_fwrd += cur_pos_fwrd;
+                vel_fwrd += cur_vel_fwrd;
+                acc_fwrd += cur_acc_fwrd;
+                frame_fwrd = cur_frame_fwrd;
+            }
+
+            if !frame_bwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_bwrd, cur_vel_bwrd, cur_acc_bwrd, cur_frame_bwrd) =
+                    self.translation_parts_to_parent_with_acceleration(frame_bwrd, epoch)?;
+
+                pos_bwrd += cur_pos_bwrd;
+                vel_bwrd += cur_vel_bwrd;
+                acc_bwrd += cur_acc_bwrd;
+                frame_bwrd = cur_frame_bwrd;
             }
         }
+
+        Ok((
+            CartesianState {
+                radius_km: pos_bwrd - pos_fwrd,
+                velocity_km_s: vel_bwrd - vel_fwrd,
+                epoch,
+                frame: observer_frame.with_orient(target_frame.orientation_id),
+            },
+            acc_bwrd - acc_fwrd,
+        ))
     }
 
     /// Returns the geometric position vector, velocity vector, and acceleration vector needed to translate the `from_frame` to the `to_frame`, where the distance is in km, the velocity in km/s, and the acceleration in km/s^2.
@@ -182,6 +569,62 @@ impl Almanac {
         self.translate(target_frame, observer_frame, epoch, Aberration::NONE)
     }
 
+    /// Returns the state of the target frame with respect to the solar system barycenter (SSB), in the J2000 frame, at the provided
+    /// epoch, and optionally given the aberration correction of an observer at the SSB.
+    ///
+    /// The geometric state is the sum of the states of each body of the ephemeris chain of the target with respect to its parent
+    /// (target → center → ... → SSB). This is the same state as [Self::translate] with the SSB as observer, apart from the rounding
+    /// errors of its intermediate subtractions.
+    ///
+    /// # Errors
+    /// + If the ephemeris chain of the target does not reach the SSB at this epoch, this returns a `ChainToSsb` error with the last body
+    ///   reached, whose parent could not be found. Its source is the error of that lookup.
+    pub fn state_wrt_ssb(
+        &self,
+        target_frame: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> Result<CartesianState, EphemerisError> {
+        let ssb_frame = self.frame_from_uid(SSB_J2000).unwrap_or(SSB_J2000);
+
+        let mut radius_km = Vector3::zeros();
+        let mut velocity_km_s = Vector3::zeros();
+        let mut frame = target_frame;
+
+        for _ in 0..=MAX_TREE_DEPTH {
+            if frame.ephemeris_id == SOLAR_SYSTEM_BARYCENTER {
+                return match ab_corr {
+                    None => Ok(CartesianState {
+                        radius_km,
+                        velocity_km_s,
+                        epoch,
+                        frame: ssb_frame,
+                    }),
+                    // The chain reaches the SSB, so the light time iterations can look up the target.
+                    Some(_) => {
+                        self.translate(target_frame.with_orient(J2000), ssb_frame, epoch, ab_corr)
+                    }
+                };
+            }
+
+            let (cur_radius_km, cur_velocity_km_s, parent) = self
+                .translation_parts_to_parent(frame, epoch)
+                .context(ChainToSsbSnafu {
+                    target: target_frame.ephemeris_id,
+                    root: frame.ephemeris_id,
+                })?;
+
+            radius_km += cur_radius_km;
+            velocity_km_s += cur_velocity_km_s;
+            frame = parent;
+        }
+
+        Err(EphemerisError::SPK {
+            action: "walking the ephemeris chain to the solar system barycenter",
+            source: DAFError::MaxRecursionDepth,
+        })
+    }
+
     /// Translates the provided Cartesian state into the requested observer frame
     ///
     /// **WARNING:** This function only performs the translation and no rotation _whatsoever_. Use the `transform_to` function instead to include rotations.
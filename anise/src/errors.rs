@@ -8,24 +8,35 @@
  * Documentation: https://nyxspace.com/
  */
 
-use hifitime::Epoch;
 use snafu::prelude::*;
 
+#[cfg(not(feature = "no-std-eval"))]
+use crate::almanac::names::FrameNameError;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::ephemerides::EphemerisError;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::orientations::OrientationError;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::prelude::FrameUid;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::structure::dataset::DataSetError;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::structure::semver::Semver;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::NaifId;
-use core::convert::From;
+#[cfg(not(feature = "no-std-eval"))]
 use der::Error as DerError;
+#[cfg(not(feature = "no-std-eval"))]
+use hifitime::Epoch;
+#[cfg(not(feature = "no-std-eval"))]
 use std::io::ErrorKind as IOErrorKind;
 
-#[cfg(feature = "metaload")]
+#[cfg(all(feature = "metaload", not(feature = "no-std-eval")))]
 use crate::almanac::metaload::MetaAlmanacError;
-#[cfg(feature = "metaload")]
+#[cfg(all(feature = "metaload", not(feature = "no-std-eval")))]
 use crate::almanac::metaload::MetaFile;
 
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Debug, PartialEq, Snafu)]
 #[snafu(visibility(pub))]
 pub enum AlmanacError {
@@ -46,6 +57,12 @@ pub enum AlmanacError {
         path: String,
         source: InputOutputError,
     },
+    #[snafu(display("{source} encountered when loading data from {path}"))]
+    LoadingData {
+        path: String,
+        #[snafu(source(from(AlmanacError, Box::new)))]
+        source: Box<AlmanacError>,
+    },
     #[snafu(display("{source} encountered when {action}"))]
     TLDataSet {
         action: &'static str,
@@ -53,6 +70,14 @@ pub enum AlmanacError {
     },
     #[snafu(display("{err}"))]
     GenericError { err: String },
+    #[snafu(display("cannot remap {from} to {to}: {reason}"))]
+    RemapCollision {
+        from: NaifId,
+        to: NaifId,
+        reason: &'static str,
+    },
+    #[snafu(display("{source}"))]
+    FrameName { source: FrameNameError },
     #[cfg(feature = "metaload")]
     #[snafu(display("processing file #{fno} ({file:?}) caused an error: {source}"))]
     Meta {
@@ -62,8 +87,10 @@ pub enum AlmanacError {
     },
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 pub type AlmanacResult<T> = Result<T, AlmanacError>;
 
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Debug, PartialEq, Snafu)]
 #[snafu(visibility(pub(crate)))]
 #[non_exhaustive]
@@ -74,6 +101,7 @@ pub enum InputOutputError {
     IOUnknownError,
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Copy, Clone, Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 #[non_exhaustive]
@@ -107,6 +135,7 @@ pub enum DecodingError {
     Obscure { kind: &'static str },
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Copy, Clone, PartialEq, Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
 #[non_exhaustive]
@@ -152,6 +181,7 @@ pub enum MathError {
     MaxIterationsReached { iter: usize, action: &'static str },
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Copy, Clone, Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 #[non_exhaustive]
@@ -202,6 +232,8 @@ pub enum PhysicsError {
     HyperbolicTrueAnomaly { ta_deg: f64 },
     #[snafu(display("calculation requires hyperbolic orbit, but its eccentricity is {ecc}"))]
     NotHyperbolic { ecc: f64 },
+    #[snafu(display("calculation requires elliptical orbit, but its eccentricity is {ecc}"))]
+    NotElliptical { ecc: f64 },
     #[snafu(display("infinite value encountered when {action}"))]
     InfiniteValue { action: &'static str },
     #[snafu(display("{source}"))]
@@ -214,6 +246,7 @@ pub enum PhysicsError {
     AberrationError { action: &'static str },
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl From<IOErrorKind> for InputOutputError {
     fn from(kind: IOErrorKind) -> Self {
         Self::IOError { kind }
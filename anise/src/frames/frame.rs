@@ -8,29 +8,39 @@
  * Documentation: https://nyxspace.com/
  */
 
+#[cfg(not(feature = "no-std-eval"))]
 use core::fmt;
 use core::fmt::Debug;
+#[cfg(not(feature = "no-std-eval"))]
 use serde_derive::{Deserialize, Serialize};
+#[cfg(not(feature = "no-std-eval"))]
 use snafu::ResultExt;
 
-#[cfg(feature = "metaload")]
+#[cfg(all(feature = "metaload", not(feature = "no-std-eval")))]
 use serde_dhall::StaticType;
 
+#[cfg(not(feature = "no-std-eval"))]
 use crate::astro::PhysicsResult;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::constants::celestial_objects::{
     celestial_name_from_id, id_from_celestial_name, SOLAR_SYSTEM_BARYCENTER,
 };
+#[cfg(not(feature = "no-std-eval"))]
 use crate::constants::orientations::{id_from_orientation_name, orientation_name_from_id, J2000};
+#[cfg(not(feature = "no-std-eval"))]
 use crate::errors::{AlmanacError, EphemerisSnafu, OrientationSnafu, PhysicsError};
+#[cfg(not(feature = "no-std-eval"))]
+use crate::math::Vector3;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::prelude::FrameUid;
 use crate::structure::planetocentric::ellipsoid::Ellipsoid;
 use crate::NaifId;
 
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::exceptions::PyTypeError;
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::prelude::*;
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::pyclass::CompareOp;
 
 /// A Frame uniquely defined by its ephemeris center and orientation. Refer to FrameDetail for frames combined with parameters.
@@ -40,10 +50,17 @@ use pyo3::pyclass::CompareOp;
 /// :type mu_km3_s2: float, optional
 /// :type shape: Ellipsoid, optional
 /// :rtype: Frame
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
-#[cfg_attr(feature = "metaload", derive(StaticType))]
-#[cfg_attr(feature = "python", pyclass)]
-#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(not(feature = "no-std-eval"), derive(Serialize, Deserialize))]
+#[cfg_attr(
+    all(feature = "metaload", not(feature = "no-std-eval")),
+    derive(StaticType)
+)]
+#[cfg_attr(all(feature = "python", not(feature = "no-std-eval")), pyclass)]
+#[cfg_attr(
+    all(feature = "python", not(feature = "no-std-eval")),
+    pyo3(module = "anise.astro")
+)]
 pub struct Frame {
     pub ephemeris_id: NaifId,
     pub orientation_id: NaifId,
@@ -63,7 +80,10 @@ impl Frame {
             shape: None,
         }
     }
+}
 
+#[cfg(not(feature = "no-std-eval"))]
+impl Frame {
     pub const fn from_ephem_j2000(ephemeris_id: NaifId) -> Self {
         Self::new(ephemeris_id, J2000)
     }
@@ -100,9 +120,40 @@ impl Frame {
         self.strip();
         self
     }
+
+    /// Returns the position in km in this body fixed frame of the provided geodetic latitude (φ) and longitude (λ) in degrees and height in km,
+    /// with respect to the ellipsoid of this frame. Refer to [Ellipsoid::geodetic_to_cartesian] for details.
+    pub fn geodetic_to_cartesian(
+        &self,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        height_km: f64,
+    ) -> PhysicsResult<Vector3> {
+        Ok(self
+            .shape
+            .ok_or(PhysicsError::MissingFrameData {
+                action: "converting geodetic coordinates to Cartesian",
+                data: "shape",
+                frame: self.into(),
+            })?
+            .geodetic_to_cartesian(latitude_deg, longitude_deg, height_km))
+    }
+
+    /// Returns the geodetic latitude (φ) and longitude (λ) in degrees and height in km, with respect to the ellipsoid of this frame,
+    /// of the provided position in km in this body fixed frame. Refer to [Ellipsoid::cartesian_to_geodetic] for details.
+    pub fn cartesian_to_geodetic(&self, radius_km: Vector3) -> PhysicsResult<(f64, f64, f64)> {
+        Ok(self
+            .shape
+            .ok_or(PhysicsError::MissingFrameData {
+                action: "converting Cartesian coordinates to geodetic",
+                data: "shape",
+                frame: self.into(),
+            })?
+            .cartesian_to_geodetic(radius_km))
+    }
 }
 
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 #[cfg_attr(feature = "python", pymethods)]
 impl Frame {
     /// Initializes a new [Frame] provided its ephemeris and orientation identifiers, and optionally its gravitational parameter (in km^3/s^2) and optionally its shape (cf. [Ellipsoid]).
@@ -198,6 +249,7 @@ impl Frame {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[cfg_attr(feature = "python", pymethods)]
 impl Frame {
     /// Returns a copy of this Frame whose ephemeris ID is set to the provided ID
@@ -350,6 +402,7 @@ impl Frame {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::Display for Frame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         let body_name = match celestial_name_from_id(self.ephemeris_id) {
@@ -377,6 +430,7 @@ impl fmt::Display for Frame {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::LowerExp for Frame {
     /// Only prints the ephemeris name
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -387,6 +441,7 @@ impl fmt::LowerExp for Frame {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::Octal for Frame {
     /// Only prints the orientation name
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -397,6 +452,7 @@ impl fmt::Octal for Frame {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::LowerHex for Frame {
     /// Only prints the UID
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -405,7 +461,7 @@ impl fmt::LowerHex for Frame {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "no-std-eval")))]
 mod frame_ut {
     use super::Frame;
     use crate::constants::frames::{EARTH_J2000, EME2000};
@@ -436,4 +492,82 @@ mod frame_ut {
     fn ccsds_name_to_frame() {
         assert_eq!(Frame::from_name("Earth", "ICRF").unwrap(), EARTH_J2000);
     }
+
+    #[test]
+    fn geodetic_round_trip() {
+        use crate::math::{angles::between_pm_180, Vector3};
+        use crate::structure::planetocentric::ellipsoid::Ellipsoid;
+
+        let earth = EARTH_J2000.with_ellipsoid(Ellipsoid::from_spheroid(6378.1366, 6356.7519));
+        // Phobos is genuinely tri-axial
+        let phobos = Frame::new(401, 1).with_ellipsoid(Ellipsoid {
+            semi_major_equatorial_radius_km: 13.0,
+            semi_minor_equatorial_radius_km: 11.4,
+            polar_radius_km: 9.1,
+        });
+
+        let mut lats_deg: Vec<f64> = (-6..=6).map(|i| 15.0 * i as f64).collect();
+        lats_deg.extend([-89.9999, -1e-6, 1e-6, 89.9999]);
+
+        for (frame, heights_km) in [
+            (earth, vec![-100.0, -1.0, 0.0, 1e-3, 0.5, 400.0, 36_000.0]),
+            (phobos, vec![-5.0, -0.5, 0.0, 2.0, 50.0]),
+        ] {
+            for lat_deg in &lats_deg {
+                for lon_deg in (-6..6).map(|i| 30.0 * i as f64 + 1.5) {
+                    for height_km in &heights_km {
+                        let radius_km = frame
+                            .geodetic_to_cartesian(*lat_deg, lon_deg, *height_km)
+                            .unwrap();
+                        let (lat2_deg, lon2_deg, height2_km) =
+                            frame.cartesian_to_geodetic(radius_km).unwrap();
+
+                        let loc = format!("{lat_deg} {lon_deg} {height_km} on {frame:e}");
+                        // Millimeter level
+                        assert!((height2_km - height_km).abs() < 1e-6, "{loc}: {height2_km}");
+                        assert!(
+                            (frame
+                                .geodetic_to_cartesian(lat2_deg, lon2_deg, height2_km)
+                                .unwrap()
+                                - radius_km)
+                                .norm()
+                                < 1e-6,
+                            "{loc}"
+                        );
+                        assert!((lat2_deg - lat_deg).abs() < 1e-9, "{loc}: {lat2_deg}");
+                        if lat_deg.abs() < 90.0 {
+                            assert!(between_pm_180(lon2_deg - lon_deg).abs() < 1e-9, "{loc}");
+                        }
+                    }
+                }
+            }
+
+            // The poles and the equator are exactly known.
+            let shape = frame.shape.unwrap();
+            let pole_km = frame.geodetic_to_cartesian(90.0, 0.0, 1.0).unwrap();
+            assert!((pole_km - Vector3::new(0.0, 0.0, shape.polar_radius_km + 1.0)).norm() < 1e-12);
+            let pole = frame.cartesian_to_geodetic(pole_km).unwrap();
+            assert!((pole.0 - 90.0).abs() < 1e-12 && (pole.2 - 1.0).abs() < 1e-9);
+
+            let equator_km = frame.geodetic_to_cartesian(0.0, 90.0, -1.0).unwrap();
+            assert!(
+                (equator_km - Vector3::new(0.0, shape.semi_minor_equatorial_radius_km - 1.0, 0.0))
+                    .norm()
+                    < 1e-12
+            );
+
+            // Deep inside on the equatorial plane, the nearest surface point is off that plane but still well defined.
+            let (lat_deg, _, height_km) = frame
+                .cartesian_to_geodetic(Vector3::new(0.1, 0.0, 0.0))
+                .unwrap();
+            assert!(lat_deg > 0.0 && lat_deg <= 90.0);
+            assert!(height_km < 0.0 && height_km > -shape.semi_major_equatorial_radius_km);
+        }
+
+        // Frames without a shape cannot be converted.
+        assert!(EARTH_J2000.geodetic_to_cartesian(0.0, 0.0, 0.0).is_err());
+        assert!(EARTH_J2000
+            .cartesian_to_geodetic(Vector3::new(7000.0, 0.0, 0.0))
+            .is_err());
+    }
 }
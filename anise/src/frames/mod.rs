@@ -9,7 +9,9 @@
  */
 
 mod frame;
+#[cfg(not(feature = "no-std-eval"))]
 mod frameuid;
 
 pub use frame::Frame;
+#[cfg(not(feature = "no-std-eval"))]
 pub use frameuid::FrameUid;
@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(feature = "no-std-eval", no_std)]
 /*
  * ANISE Toolkit
  * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
@@ -14,18 +15,25 @@ extern crate const_format;
 extern crate hifitime;
 extern crate log;
 
+#[cfg(not(feature = "no-std-eval"))]
 pub mod almanac;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod astro;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod constants;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod ephemerides;
 pub mod errors;
 pub mod frames;
 pub mod math;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod naif;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod orientations;
 pub mod structure;
 
 /// Re-export of hifitime
+#[cfg(not(feature = "no-std-eval"))]
 pub mod time {
     pub use core::str::FromStr;
     pub use hifitime::*;
@@ -42,6 +50,7 @@ pub mod time {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 pub mod prelude {
     #[cfg(feature = "metaload")]
     pub use crate::almanac::metaload::MetaAlmanac;
@@ -57,10 +66,11 @@ pub mod prelude {
     pub use std::fs::File;
 }
 
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 mod py_errors;
 
 /// Defines the number of bytes in a double (prevents magic numbers)
+#[cfg(not(feature = "no-std-eval"))]
 pub(crate) const DBL_SIZE: usize = 8;
 
 /// Defines the hash used to identify parents.
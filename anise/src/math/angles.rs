@@ -8,6 +8,10 @@
  * Documentation: https://nyxspace.com/
  */
 
+use core::fmt;
+
+use super::Vector3;
+
 /// Returns the provided angle bounded between 0.0 and 360.0
 pub fn between_0_360(angle: f64) -> f64 {
     let mut bounded = angle;
@@ -36,3 +40,36 @@ pub fn between_pm_x(angle: f64, x: f64) -> f64 {
     }
     bounded
 }
+
+/// An angle stored in radians, e.g. the angular separation between two objects.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Angle {
+    pub radians: f64,
+}
+
+impl Angle {
+    /// Builds an angle from its value in degrees
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self {
+            radians: degrees.to_radians(),
+        }
+    }
+
+    /// Returns the angle between the two provided vectors, between 0 and π, which is accurate even for nearly parallel vectors.
+    pub fn between(a: &Vector3, b: &Vector3) -> Self {
+        Self {
+            radians: a.cross(b).norm().atan2(a.dot(b)),
+        }
+    }
+
+    /// Returns this angle in degrees
+    pub fn degrees(&self) -> f64 {
+        self.radians.to_degrees()
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} deg", self.degrees())
+    }
+}
@@ -8,22 +8,37 @@
  * Documentation: https://nyxspace.com/
  */
 
-use super::{perp_vector, root_mean_squared, root_sum_squared, Vector3};
+use super::Vector3;
+#[cfg(not(feature = "no-std-eval"))]
+use super::{perp_vector, root_mean_squared, root_sum_squared};
+use crate::frames::Frame;
+#[cfg(not(feature = "no-std-eval"))]
 use crate::{
     astro::PhysicsResult,
     constants::SPEED_OF_LIGHT_KM_S,
     errors::{EpochMismatchSnafu, FrameMismatchSnafu, MathError, PhysicsError},
-    prelude::Frame,
 };
 
+#[cfg(not(feature = "no-std-eval"))]
 use core::fmt;
+#[cfg(not(feature = "no-std-eval"))]
 use core::ops::{Add, Neg, Sub};
-use hifitime::{Duration, Epoch, TimeUnits};
+#[cfg(not(feature = "no-std-eval"))]
+use core::str::FromStr;
+use hifitime::Epoch;
+#[cfg(not(feature = "no-std-eval"))]
+use hifitime::{Duration, TimeScale, TimeUnits};
 use nalgebra::Vector6;
+#[cfg(not(feature = "no-std-eval"))]
+use serde::de::{self, Deserialize, Deserializer};
+#[cfg(not(feature = "no-std-eval"))]
+use serde::ser::{Serialize, Serializer};
+#[cfg(not(feature = "no-std-eval"))]
 use serde_derive::{Deserialize, Serialize};
-use snafu::ensure;
+#[cfg(not(feature = "no-std-eval"))]
+use snafu::{ensure, Snafu};
 
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::prelude::*;
 
 /// Defines a Cartesian state in a given frame at a given epoch in a given time scale. Radius data is expressed in kilometers. Velocity data is expressed in kilometers per second.
@@ -40,9 +55,23 @@ use pyo3::prelude::*;
 /// :type epoch: Epoch
 /// :type frame: Frame
 /// :rtype: Orbit
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
-#[cfg_attr(feature = "python", pyclass(name = "Orbit"))]
-#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+///
+/// # Serialization
+/// States are serialized with the epoch as a Gregorian TDB string, the frame as `<ephemeris_id>/<orientation_id>`, and each
+/// component of the position and velocity with its unit in its name, e.g. `x_km` and `vx_km_s`. The gravitational parameter and the
+/// shape of the frame are not serialized: fetch them from the almanac with `frame_from_uid` after deserializing.
+///
+/// This is a breaking change of the serialized representation. States serialized by previous versions, with the `radius_km` and
+/// `velocity_km_s` vectors and the whole frame, are still deserialized, including the gravitational parameter and shape of their frame.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    all(feature = "python", not(feature = "no-std-eval")),
+    pyclass(name = "Orbit")
+)]
+#[cfg_attr(
+    all(feature = "python", not(feature = "no-std-eval")),
+    pyo3(module = "anise.astro")
+)]
 pub struct CartesianState {
     /// Position radius in kilometers
     pub radius_km: Vector3,
@@ -175,11 +204,13 @@ impl CartesianState {
     }
 
     /// Returns the unit vector in the direction of the state radius
+    #[cfg(not(feature = "no-std-eval"))]
     pub fn r_hat(&self) -> Vector3 {
         self.radius_km / self.rmag_km()
     }
 
     /// Returns the unit vector in the direction of the state velocity
+    #[cfg(not(feature = "no-std-eval"))]
     pub fn v_hat(&self) -> Vector3 {
         perp_vector(&self.velocity_km_s, &self.r_hat()) / self.rmag_km()
     }
@@ -225,6 +256,7 @@ impl CartesianState {
 }
 
 // Methods shared with Python
+#[cfg(not(feature = "no-std-eval"))]
 #[cfg_attr(feature = "python", pymethods)]
 impl CartesianState {
     /// Returns the magnitude of the radius vector in km
@@ -446,6 +478,7 @@ impl CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl Add for CartesianState {
     type Output = Result<CartesianState, PhysicsError>;
 
@@ -473,6 +506,7 @@ impl Add for CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl PartialEq for CartesianState {
     /// Two states are equal if their position are equal within one centimeter and their velocities within one centimeter per second.
     fn eq(&self, other: &Self) -> bool {
@@ -482,6 +516,7 @@ impl PartialEq for CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl Sub for CartesianState {
     type Output = Result<CartesianState, PhysicsError>;
 
@@ -509,6 +544,7 @@ impl Sub for CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl Neg for CartesianState {
     type Output = Self;
 
@@ -520,6 +556,7 @@ impl Neg for CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[allow(clippy::format_in_format_args)]
 impl fmt::Display for CartesianState {
     // Prints as Cartesian in floating point with units
@@ -540,6 +577,7 @@ impl fmt::Display for CartesianState {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[allow(clippy::format_in_format_args)]
 impl fmt::LowerExp for CartesianState {
     // Prints as Cartesian in scientific notation with units
@@ -560,7 +598,204 @@ impl fmt::LowerExp for CartesianState {
     }
 }
 
-#[cfg(test)]
+/// Header of the CSV rows of [CartesianState::to_csv_row].
+#[cfg(not(feature = "no-std-eval"))]
+pub const CARTESIAN_STATE_CSV_HEADER: &str =
+    "epoch_tdb,frame,x_km,y_km,z_km,vx_km_s,vy_km_s,vz_km_s";
+
+#[cfg(not(feature = "no-std-eval"))]
+#[derive(Clone, Debug, PartialEq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[non_exhaustive]
+pub enum StateParsingError {
+    #[snafu(display("invalid frame `{frame}`, expected `<ephemeris_id>/<orientation_id>`"))]
+    InvalidFrame { frame: String },
+    #[snafu(display("invalid epoch `{epoch}`: {err}"))]
+    InvalidEpoch { epoch: String, err: String },
+    #[snafu(display("invalid {column} `{value}`"))]
+    InvalidComponent { column: &'static str, value: String },
+    #[snafu(display("expected {expected} columns but found {found}"))]
+    ColumnCount { expected: usize, found: usize },
+}
+
+/// Serialized representation of a [CartesianState].
+#[cfg(not(feature = "no-std-eval"))]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CartesianState", deny_unknown_fields)]
+struct SerializedState {
+    epoch: String,
+    frame: String,
+    x_km: f64,
+    y_km: f64,
+    z_km: f64,
+    vx_km_s: f64,
+    vy_km_s: f64,
+    vz_km_s: f64,
+}
+
+/// Representation of a [CartesianState] serialized by the versions prior to [SerializedState], i.e. its derived representation.
+#[cfg(not(feature = "no-std-eval"))]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LegacySerializedState {
+    radius_km: Vector3,
+    velocity_km_s: Vector3,
+    epoch: Epoch,
+    frame: Frame,
+}
+
+/// Any representation of a [CartesianState] which can be deserialized.
+#[cfg(not(feature = "no-std-eval"))]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnySerializedState {
+    Current(SerializedState),
+    Legacy(LegacySerializedState),
+}
+
+#[cfg(not(feature = "no-std-eval"))]
+impl CartesianState {
+    /// Returns the frame formatted as `<ephemeris_id>/<orientation_id>`.
+    fn frame_ids(&self) -> String {
+        format!("{}/{}", self.frame.ephemeris_id, self.frame.orientation_id)
+    }
+
+    /// Parses a frame formatted as `<ephemeris_id>/<orientation_id>`.
+    fn parse_frame_ids(frame: &str) -> Result<Frame, StateParsingError> {
+        frame
+            .split_once('/')
+            .and_then(|(ephemeris_id, orientation_id)| {
+                Some(Frame::new(
+                    ephemeris_id.trim().parse().ok()?,
+                    orientation_id.trim().parse().ok()?,
+                ))
+            })
+            .ok_or_else(|| StateParsingError::InvalidFrame {
+                frame: frame.to_string(),
+            })
+    }
+
+    /// Parses an epoch from its Gregorian representation, in any time scale.
+    fn parse_epoch(epoch: &str) -> Result<Epoch, StateParsingError> {
+        Epoch::from_str(epoch.trim()).map_err(|e| StateParsingError::InvalidEpoch {
+            epoch: epoch.to_string(),
+            err: e.to_string(),
+        })
+    }
+
+    /// Returns this state as a CSV row whose columns are described by [CARTESIAN_STATE_CSV_HEADER]: the epoch as a Gregorian TDB string,
+    /// the frame as `<ephemeris_id>/<orientation_id>`, the position in kilometers, and the velocity in kilometers per second.
+    ///
+    /// The components are written with the shortest representation that parses back to the same value.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.epoch.to_gregorian_str(TimeScale::TDB),
+            self.frame_ids(),
+            self.radius_km.x,
+            self.radius_km.y,
+            self.radius_km.z,
+            self.velocity_km_s.x,
+            self.velocity_km_s.y,
+            self.velocity_km_s.z
+        )
+    }
+
+    /// Parses a CSV row written by [Self::to_csv_row]. The frame only has its ephemeris and orientation IDs.
+    ///
+    /// # Errors
+    /// + The row must have the eight columns of [CARTESIAN_STATE_CSV_HEADER], with a valid epoch, frame, and components.
+    pub fn from_csv_row(row: &str) -> Result<Self, StateParsingError> {
+        let columns: Vec<&str> = row.trim_end_matches(['\r', '\n']).split(',').collect();
+        let [epoch, frame, components @ ..] = columns.as_slice() else {
+            return Err(StateParsingError::ColumnCount {
+                expected: 8,
+                found: columns.len(),
+            });
+        };
+        if components.len() != 6 {
+            return Err(StateParsingError::ColumnCount {
+                expected: 8,
+                found: columns.len(),
+            });
+        }
+
+        let mut values = [0.0; 6];
+        for ((value, column), name) in values
+            .iter_mut()
+            .zip(components)
+            .zip(CARTESIAN_STATE_CSV_HEADER.split(',').skip(2))
+        {
+            *value = column
+                .trim()
+                .parse()
+                .map_err(|_| StateParsingError::InvalidComponent {
+                    column: name,
+                    value: column.to_string(),
+                })?;
+        }
+
+        Ok(Self::new(
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            values[4],
+            values[5],
+            Self::parse_epoch(epoch)?,
+            Self::parse_frame_ids(frame)?,
+        ))
+    }
+}
+
+#[cfg(not(feature = "no-std-eval"))]
+impl Serialize for CartesianState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedState {
+            epoch: self.epoch.to_gregorian_str(TimeScale::TDB),
+            frame: self.frame_ids(),
+            x_km: self.radius_km.x,
+            y_km: self.radius_km.y,
+            z_km: self.radius_km.z,
+            vx_km_s: self.velocity_km_s.x,
+            vy_km_s: self.velocity_km_s.y,
+            vz_km_s: self.velocity_km_s.z,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(not(feature = "no-std-eval"))]
+impl<'de> Deserialize<'de> for CartesianState {
+    /// Deserializes a state, rejecting any epoch or frame which cannot be parsed. States serialized by the previous versions are also
+    /// accepted.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = match AnySerializedState::deserialize(deserializer)? {
+            AnySerializedState::Current(state) => state,
+            AnySerializedState::Legacy(state) => {
+                return Ok(Self {
+                    radius_km: state.radius_km,
+                    velocity_km_s: state.velocity_km_s,
+                    epoch: state.epoch,
+                    frame: state.frame,
+                })
+            }
+        };
+
+        Ok(Self::new(
+            state.x_km,
+            state.y_km,
+            state.z_km,
+            state.vx_km_s,
+            state.vy_km_s,
+            state.vz_km_s,
+            Self::parse_epoch(&state.epoch).map_err(de::Error::custom)?,
+            Self::parse_frame_ids(&state.frame).map_err(de::Error::custom)?,
+        ))
+    }
+}
+
+#[cfg(all(test, not(feature = "no-std-eval")))]
 mod cartesian_state_ut {
 
     use hifitime::{Duration, Epoch, TimeUnits};
@@ -569,7 +804,10 @@ mod cartesian_state_ut {
     use crate::errors::PhysicsError;
     use crate::math::Vector6;
 
-    use super::CartesianState;
+    use super::{CartesianState, StateParsingError, CARTESIAN_STATE_CSV_HEADER};
+    use crate::constants::orientations::IAU_MOON;
+    use crate::prelude::Frame;
+    use hifitime::TimeScale;
 
     #[test]
     fn add_wrong_epoch() {
@@ -665,4 +903,147 @@ mod cartesian_state_ut {
 
         assert_eq!(rtn, state);
     }
+
+    #[test]
+    fn test_json_round_trip() {
+        let epoch = Epoch::from_gregorian(2024, 3, 1, 12, 34, 56, 123_456_789, TimeScale::TDB);
+        let frame = Frame::new(-85, IAU_MOON);
+        let state = CartesianState::new(
+            1_234.567_890_123,
+            -2_345.678_901_234,
+            0.1,
+            -1.234_567_890_123e-3,
+            1.6,
+            -0.015,
+            epoch,
+            frame,
+        );
+
+        let serialized = serde_json::to_string(&state).unwrap();
+        assert!(serialized.contains("\"epoch\":\"2024-03-01T12:34:56.123456789 TDB\""));
+        assert!(serialized.contains("\"frame\":\"-85/301\""));
+        assert!(serialized.contains("\"vx_km_s\":"));
+
+        let rtn: CartesianState = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(rtn.epoch, epoch);
+        assert_eq!(rtn.epoch.to_tdb_duration(), epoch.to_tdb_duration());
+        assert_eq!(rtn.frame, frame);
+        assert_eq!(rtn.radius_km, state.radius_km);
+        assert_eq!(rtn.velocity_km_s, state.velocity_km_s);
+
+        // Epochs in other time scales are serialized in TDB but represent the same instant.
+        let utc_state = CartesianState {
+            epoch: Epoch::from_gregorian_utc(2024, 3, 1, 12, 34, 56, 987_654_321),
+            ..state
+        };
+        let rtn: CartesianState =
+            serde_json::from_str(&serde_json::to_string(&utc_state).unwrap()).unwrap();
+        assert_eq!(rtn.epoch, utc_state.epoch);
+
+        // Frames and epochs which cannot be parsed are rejected.
+        for frame in ["399", "399/", "earth/1", "399/1/2", ""] {
+            let invalid = serialized.replace("-85/301", frame);
+            assert!(
+                serde_json::from_str::<CartesianState>(&invalid).is_err(),
+                "{frame} accepted"
+            );
+        }
+        let invalid = serialized.replace("2024-03-01T12:34:56.123456789 TDB", "yesterday");
+        assert!(serde_json::from_str::<CartesianState>(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_json_legacy() {
+        // Representation of the states serialized by the previous versions, with the whole frame.
+        let legacy = r#"{
+            "radius_km": [1234.567890123, -2345.678901234, 0.1],
+            "velocity_km_s": [-0.001234567890123, 1.6, -0.015],
+            "epoch": "2024-03-01T12:34:56.123456789 TDB",
+            "frame": {
+                "ephemeris_id": 399,
+                "orientation_id": 1,
+                "mu_km3_s2": 398600.435436096,
+                "shape": {
+                    "semi_major_equatorial_radius_km": 6378.1366,
+                    "semi_minor_equatorial_radius_km": 6378.1366,
+                    "polar_radius_km": 6356.7519
+                }
+            }
+        }"#;
+
+        let state: CartesianState = serde_json::from_str(legacy).unwrap();
+        assert_eq!(
+            state.epoch,
+            Epoch::from_gregorian(2024, 3, 1, 12, 34, 56, 123_456_789, TimeScale::TDB)
+        );
+        assert_eq!(state.radius_km.x, 1_234.567_890_123);
+        assert_eq!(state.velocity_km_s.z, -0.015);
+        // The legacy representation keeps the frame data.
+        assert_eq!(state.frame.ephemeris_id, 399);
+        assert_eq!(state.frame.mu_km3_s2, Some(398_600.435_436_096));
+        assert_eq!(state.frame.shape.unwrap().polar_radius_km, 6_356.751_9);
+
+        // It is serialized again in the current representation, without the frame data.
+        let serialized = serde_json::to_string(&state).unwrap();
+        assert!(serialized.contains("\"frame\":\"399/1\""));
+        let rtn: CartesianState = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(rtn.frame, Frame::new(399, 1));
+        assert_eq!(rtn.radius_km, state.radius_km);
+
+        // A mix of both representations is rejected.
+        let mixed = legacy.replace("\"radius_km\"", "\"x_km\"");
+        assert!(serde_json::from_str::<CartesianState>(&mixed).is_err());
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let epoch = Epoch::from_gregorian(2024, 3, 1, 12, 34, 56, 123_456_789, TimeScale::TDB);
+        let state = CartesianState::new(
+            1_234.567_890_123,
+            -2_345.678_901_234,
+            0.1,
+            -1.234_567_890_123e-3,
+            1.6,
+            -0.015,
+            epoch,
+            EARTH_J2000,
+        );
+
+        let row = state.to_csv_row();
+        assert_eq!(
+            row,
+            "2024-03-01T12:34:56.123456789 TDB,399/1,1234.567890123,-2345.678901234,0.1,-0.001234567890123,1.6,-0.015"
+        );
+        assert_eq!(
+            CARTESIAN_STATE_CSV_HEADER.split(',').count(),
+            row.split(',').count()
+        );
+
+        let rtn = CartesianState::from_csv_row(&format!("{row}\n")).unwrap();
+        assert_eq!(rtn.epoch, epoch);
+        assert_eq!(rtn.frame, EARTH_J2000);
+        assert_eq!(rtn.radius_km, state.radius_km);
+        assert_eq!(rtn.velocity_km_s, state.velocity_km_s);
+
+        assert_eq!(
+            CartesianState::from_csv_row(&row.replace(",399/1,", ",399,")),
+            Err(StateParsingError::InvalidFrame {
+                frame: "399".to_string()
+            })
+        );
+        assert_eq!(
+            CartesianState::from_csv_row(&row.replace(",0.1,", ",zero,")),
+            Err(StateParsingError::InvalidComponent {
+                column: "z_km",
+                value: "zero".to_string()
+            })
+        );
+        assert_eq!(
+            CartesianState::from_csv_row("2024-03-01T12:34:56 TDB,399/1,1.0"),
+            Err(StateParsingError::ColumnCount {
+                expected: 8,
+                found: 3
+            })
+        );
+    }
 }
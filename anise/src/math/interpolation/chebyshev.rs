@@ -58,6 +58,58 @@ pub fn chebyshev_eval(
     Ok((val, deriv))
 }
 
+/// Attempts to evaluate a Chebyshev polynomial given the coefficients, returning the value and its first and second derivatives
+///
+/// # Notes
+/// 1. At this point, the splines are expected to be in Chebyshev format and no verification is done.
+pub fn chebyshev_eval_derivatives(
+    normalized_time: f64,
+    spline_coeffs: &[f64],
+    spline_radius_s: f64,
+    eval_epoch: Epoch,
+    degree: usize,
+) -> Result<(f64, f64, f64), InterpolationError> {
+    if spline_radius_s.abs() < f64::EPSILON {
+        return Err(InterpolationError::InterpMath {
+            source: MathError::DivisionByZero {
+                action: "spline radius in Chebyshev eval is zero",
+            },
+        });
+    }
+    // Workspace arrays
+    let mut w = [0.0_f64; 3];
+    let mut dw = [0.0_f64; 3];
+    let mut ddw = [0.0_f64; 3];
+
+    for j in (2..=degree + 1).rev() {
+        w[2] = w[1];
+        w[1] = w[0];
+        w[0] = (spline_coeffs
+            .get(j - 1)
+            .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })?)
+            + (2.0 * normalized_time * w[1] - w[2]);
+
+        ddw[2] = ddw[1];
+        ddw[1] = ddw[0];
+        ddw[0] = dw[0] * 4. + ddw[1] * 2.0 * normalized_time - ddw[2];
+
+        dw[2] = dw[1];
+        dw[1] = dw[0];
+        dw[0] = w[1] * 2. + dw[1] * 2.0 * normalized_time - dw[2];
+    }
+
+    let val = (spline_coeffs
+        .first()
+        .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })?)
+        + (normalized_time * w[0] - w[1]);
+
+    let deriv = (w[0] + normalized_time * dw[0] - dw[1]) / spline_radius_s;
+    // Not powi, which requires the standard library.
+    let second_deriv =
+        (2.0 * dw[0] + normalized_time * ddw[0] - ddw[1]) / (spline_radius_s * spline_radius_s);
+    Ok((val, deriv, second_deriv))
+}
+
 /// Attempts to evaluate a Chebyshev polynomial given the coefficients, returning only the value
 ///
 /// # Notes
@@ -91,3 +143,26 @@ pub fn chebyshev_eval_poly(
 
     Ok(val)
 }
+
+#[test]
+fn chebyshev_derivatives() {
+    // T0 + 2 T1 - T2 + 0.5 T3 = 1 + 2 t - (2 t^2 - 1) + 0.5 (4 t^3 - 3 t)
+    let coeffs = [1.0, 2.0, -1.0, 0.5];
+    let radius_s = 10.0;
+    let epoch = Epoch::from_et_seconds(0.0);
+    for t in [-1.0, -0.3, 0.0, 0.25, 1.0] {
+        let (val, deriv, second_deriv) =
+            chebyshev_eval_derivatives(t, &coeffs, radius_s, epoch, 3).unwrap();
+        let expected = 2.0 + 0.5 * t - 2.0 * t.powi(2) + 2.0 * t.powi(3);
+        let expected_deriv = (0.5 - 4.0 * t + 6.0 * t.powi(2)) / radius_s;
+        let expected_second_deriv = (-4.0 + 12.0 * t) / radius_s.powi(2);
+        assert!((val - expected).abs() < 1e-14, "{t}: {val} != {expected}");
+        assert!((deriv - expected_deriv).abs() < 1e-14);
+        assert!((second_deriv - expected_second_deriv).abs() < 1e-14);
+
+        // Matches the value and first derivative evaluation
+        let (val_ref, deriv_ref) = chebyshev_eval(t, &coeffs, radius_s, epoch, 3).unwrap();
+        assert_eq!(val, val_ref);
+        assert_eq!(deriv, deriv_ref);
+    }
+}
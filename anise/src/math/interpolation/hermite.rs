@@ -212,6 +212,80 @@ pub fn hermite_eval(
     Ok((f, df))
 }
 
+/// From the abscissas (xs), the ordinates (ys), and the first derivatives (ydots), build the Hermite interpolation of the function and evaluate it,
+/// its first derivative, and its second derivative at the requested abscissa (x).
+///
+/// Unlike [hermite_eval], which is transliterated from SPICE, this builds the Newton form of the Hermite polynomial from the divided differences of
+/// the abscissas where each one is repeated twice, and differentiates that form analytically.
+///
+/// # Runtime verifications
+/// 1. Ensure that all provided arrays are of the same size.
+/// 2. Ensure that there are no more than 32 items to interpolate.
+/// 3. Ensure no division by zero errors (zero is set to f64::EPSILON, which is about 2e-16).
+pub fn hermite_eval_derivatives(
+    xs: &[f64],
+    ys: &[f64],
+    ydots: &[f64],
+    x_eval: f64,
+) -> Result<(f64, f64, f64), InterpolationError> {
+    if xs.len() != ys.len() || xs.len() != ydots.len() {
+        return Err(InterpolationError::CorruptedData {
+            what: "lengths of abscissas (xs), ordinates (ys), and first derivatives (ydots) differ",
+        });
+    } else if xs.is_empty() {
+        return Err(InterpolationError::CorruptedData {
+            what: "list of abscissas (xs) is empty",
+        });
+    } else if xs.len() > MAX_SAMPLES {
+        return Err(InterpolationError::CorruptedData {
+            what: "list of abscissas (xs) contains more items than MAX_SAMPLES (32)",
+        });
+    }
+
+    let n = 2 * xs.len();
+    // Each abscissa is repeated twice, and the coefficients are computed in place, column by column of the divided differences table.
+    let mut zs = [0.0; 2 * MAX_SAMPLES];
+    let mut coeffs = [0.0; 2 * MAX_SAMPLES];
+    for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+        zs[2 * i] = *x;
+        zs[2 * i + 1] = *x;
+        coeffs[2 * i] = *y;
+        coeffs[2 * i + 1] = *y;
+    }
+
+    for j in 1..n {
+        for i in (j..n).rev() {
+            if j == 1 && i % 2 == 1 {
+                // Repeated abscissa: the first divided difference is the derivative.
+                coeffs[i] = ydots[i / 2];
+            } else {
+                let denom = zs[i] - zs[i - j];
+                if denom.abs() < f64::EPSILON {
+                    return Err(InterpolationError::InterpMath {
+                        source: MathError::DivisionByZero {
+                            action: "hermite data contains duplicate states",
+                        },
+                    });
+                }
+                coeffs[i] = (coeffs[i] - coeffs[i - 1]) / denom;
+            }
+        }
+    }
+
+    // Evaluate the Newton form and its first two derivatives with Horner's scheme.
+    let mut f = coeffs[n - 1];
+    let mut df = 0.0;
+    let mut ddf = 0.0;
+    for k in (0..n - 1).rev() {
+        let dx = x_eval - zs[k];
+        ddf = ddf * dx + 2.0 * df;
+        df = df * dx + f;
+        f = f * dx + coeffs[k];
+    }
+
+    Ok((f, df, ddf))
+}
+
 #[test]
 fn hermite_spice_docs_example() {
     let ts = [-1.0, 0.0, 3.0, 5.0];
@@ -234,3 +308,35 @@ fn hermite_spice_docs_example() {
     assert!((x - 141.0).abs() < f64::EPSILON, "X error");
     assert!((vx - 456.0).abs() < f64::EPSILON, "VX error");
 }
+
+#[test]
+fn hermite_derivatives_docs_example() {
+    // The SPICE documentation example interpolates f(x) = x^7 + 2 x^2 + 5
+    let ts = [-1.0, 0.0, 3.0, 5.0];
+    let yvals = [6.0, 5.0, 2210.0, 78180.0];
+    let ydotvals = [3.0, 0.0, 5115.0, 109395.0];
+
+    for t in [-1.0, -0.5, 0.0, 1.0, 2.0, 3.0, 4.5, 5.0] {
+        let (f, df, ddf) = hermite_eval_derivatives(&ts, &yvals, &ydotvals, t).unwrap();
+        let f_expected = t.powi(7) + 2.0 * t.powi(2) + 5.0;
+        let df_expected = 7.0 * t.powi(6) + 4.0 * t;
+        let ddf_expected = 42.0 * t.powi(5) + 4.0;
+        assert!(
+            (f - f_expected).abs() < 1e-9,
+            "f({t}) = {f} != {f_expected}"
+        );
+        assert!(
+            (df - df_expected).abs() < 1e-9,
+            "f'({t}) = {df} != {df_expected}"
+        );
+        assert!(
+            (ddf - ddf_expected).abs() < 1e-9,
+            "f''({t}) = {ddf} != {ddf_expected}"
+        );
+
+        // And matches the SPICE algorithm
+        let (f_spice, df_spice) = hermite_eval(&ts, &yvals, &ydotvals, t).unwrap();
+        assert!((f - f_spice).abs() < 1e-9);
+        assert!((df - df_spice).abs() < 1e-9);
+    }
+}
@@ -12,13 +12,15 @@ mod chebyshev;
 mod hermite;
 mod lagrange;
 
-pub use chebyshev::{chebyshev_eval, chebyshev_eval_poly};
-pub use hermite::hermite_eval;
+pub use chebyshev::{chebyshev_eval, chebyshev_eval_derivatives, chebyshev_eval_poly};
+pub use hermite::{hermite_eval, hermite_eval_derivatives};
 use hifitime::Epoch;
 pub use lagrange::lagrange_eval;
 use snafu::Snafu;
 
-use crate::errors::{DecodingError, MathError};
+#[cfg(not(feature = "no-std-eval"))]
+use crate::errors::DecodingError;
+use crate::errors::MathError;
 
 /// Defines the maximum degree for an interpolation.
 /// Until https://github.com/rust-lang/rust/issues/60551 , we cannot do operations on const generic, so we need some hack around it.
@@ -28,6 +30,7 @@ pub(crate) const MAX_SAMPLES: usize = 32;
 #[snafu(visibility(pub(crate)))]
 #[non_exhaustive]
 pub enum InterpolationError {
+    #[cfg(not(feature = "no-std-eval"))]
     #[snafu(display("decoding error during interpolation: {source}"))]
     InterpDecoding {
         #[snafu(backtrace)]
@@ -58,3 +61,154 @@ pub enum InterpolationError {
     ))]
     UnimplementedType { issue: u32, dataset: &'static str },
 }
+
+/// The evaluators are also built without the standard library with the `no-std-eval` feature, so this test runs in both builds
+/// (cf. the CI) and checks the exact bits of the evaluation of a precomputed coefficient block, and of the Cartesian state built from it:
+/// both builds must evaluate identically.
+#[cfg(test)]
+mod ut_no_std_eval {
+    use super::{chebyshev_eval, chebyshev_eval_derivatives, hermite_eval, lagrange_eval};
+    use crate::frames::Frame;
+    use crate::math::{cartesian::CartesianState, Vector3};
+    use hifitime::Epoch;
+
+    /// X, Y, and Z coefficients of a Chebyshev record of degree 10 with a radius of four days, in km.
+    const COEFFS: [[f64; 11]; 3] = [
+        [
+            -1.2577e5, 2.9341e5, 1.0862e4, -2.4187e3, 1.4350e2, 3.0516e1, -4.2210e0, 2.1455e-1,
+            1.3021e-2, -1.5987e-3, 6.4093e-5,
+        ],
+        [
+            3.4410e5, 9.0432e4, -1.8731e4, -8.6551e2, 2.4560e2, -6.3127e0, -2.3440e0, 2.0228e-1,
+            -6.7745e-3, -5.0891e-4, 7.2713e-5,
+        ],
+        [
+            1.8807e5, 4.1687e4, -1.0065e4, -3.6856e2, 1.2641e2, -4.3293e0, -1.1693e0, 1.0879e-1,
+            -4.1057e-3, -2.4796e-4, 3.8890e-5,
+        ],
+    ];
+
+    /// Normalized times of the evaluations, and the bits of the position, velocity, and acceleration of each axis at these times.
+    const EXPECTED: [(f64, [[u64; 3]; 3]); 5] = [
+        (
+            -1.0,
+            [
+                [0xc118c47af28d0e13, 0x3fe500861ab12947, 0x3ead7c2568604efd],
+                [0x410cd09ef639a34b, 0x3fdc70879a381b0d, 0xbe9352deb247b7de],
+                [0x4100b380239df8a4, 0x3fcc59c61003f496, 0xbe879e1cd733774a],
+            ],
+        ),
+        (
+            -0.375,
+            [
+                [0xc10e029a5f7698bc, 0x3fea01ca2969fa8f, 0x3ea27dbe01b30f41],
+                [0x4113b4e18524eca0, 0x3fd65c5922878c73, 0xbea311350acc74ae],
+                [0x4105e471e3429022, 0x3fc56aedb46fa1e2, 0xbe94fcdbf9ee0c5f],
+            ],
+        ),
+        (
+            0.0,
+            [
+                [0xc100a92220db337e, 0x3febda7b1d7e8437, 0x3e97093a78664c50],
+                [0x4116291bbfa4faee, 0x3fd138ad800044b0, 0xbea62d17c2ca12d3],
+                [0x410833b499eb1461, 0x3fbfae82aa34daf7, 0xbe97c7d67aa011c2],
+            ],
+        ),
+        (
+            0.625,
+            [
+                [0x40ebf6a0c2c03e64, 0x3fed427d8e3246ba, 0x3e779ea1bb92267e],
+                [0x4118bc648ef1f262, 0x3fbedc006750674a, 0xbea731c678eb45fd],
+                [0x410a6efa69b26bc9, 0x3fa7333803f927a3, 0xbe982f30b6b52e21],
+            ],
+        ),
+        (
+            1.0,
+            [
+                [0x410583ea917b8746, 0x3fed71f6d9ba4381, 0x3e49074fdbb28104],
+                [0x4119571283733e19, 0x3fa0e9571cd7dbdd, 0xbea5f43637175c09],
+                [0x410ac9a3a5a1e99f, 0xbf0359c3b718f07f, 0xbe96a05fc484f0c1],
+            ],
+        ),
+    ];
+
+    #[test]
+    fn chebyshev_block_bits() {
+        let radius_s = 4.0 * 86_400.0;
+        let epoch = Epoch::from_et_seconds(0.0);
+
+        for (normalized_time, expected) in EXPECTED {
+            for (coeffs, [pos_bits, vel_bits, acc_bits]) in COEFFS.iter().zip(expected) {
+                let (pos, vel) =
+                    chebyshev_eval(normalized_time, coeffs, radius_s, epoch, 10).unwrap();
+                let (pos_dd, vel_dd, acc) =
+                    chebyshev_eval_derivatives(normalized_time, coeffs, radius_s, epoch, 10)
+                        .unwrap();
+
+                assert_eq!(pos.to_bits(), pos_bits, "{normalized_time}: {pos}");
+                assert_eq!(vel.to_bits(), vel_bits, "{normalized_time}: {vel}");
+                assert_eq!(acc.to_bits(), acc_bits, "{normalized_time}: {acc}");
+                assert_eq!(pos_dd.to_bits(), pos_bits);
+                assert_eq!(vel_dd.to_bits(), vel_bits);
+            }
+        }
+    }
+
+    #[test]
+    fn hermite_lagrange_bits() {
+        let ts = [-1.0, 0.0, 3.0, 5.0];
+        let ys = [6.0, 5.0, 2210.0, 78180.0];
+        let ydots = [3.0, 0.0, 5115.0, 109395.0];
+
+        let (f, df) = hermite_eval(&ts, &ys, &ydots, 1.25).unwrap();
+        assert_eq!(f.to_bits(), 0x4029c96800000000);
+        assert_eq!(df.to_bits(), 0x403fb3f000000000);
+
+        let (f, df) = lagrange_eval(&ts, &ys, 1.25).unwrap();
+        assert_eq!(f.to_bits(), 0xc0b53f2400000000);
+        assert_eq!(df.to_bits(), 0xc0aa50e000000000);
+    }
+
+    #[test]
+    fn cartesian_state_from_block() {
+        let radius_s = 4.0 * 86_400.0;
+        let epoch = Epoch::from_et_seconds(0.0);
+        // Moon with respect to the Earth in J2000, the NAIF IDs are spelled out because the constants require std.
+        let frame = Frame::new(399, 1);
+
+        for (normalized_time, expected) in EXPECTED {
+            let mut pos_km = Vector3::zeros();
+            let mut vel_km_s = Vector3::zeros();
+            for (axis, coeffs) in COEFFS.iter().enumerate() {
+                let (pos, vel) =
+                    chebyshev_eval(normalized_time, coeffs, radius_s, epoch, 10).unwrap();
+                pos_km[axis] = pos;
+                vel_km_s[axis] = vel;
+            }
+
+            let state = CartesianState::new(
+                pos_km[0],
+                pos_km[1],
+                pos_km[2],
+                vel_km_s[0],
+                vel_km_s[1],
+                vel_km_s[2],
+                epoch,
+                frame,
+            );
+            let pos_vel = state.to_cartesian_pos_vel();
+
+            for (axis, [pos_bits, vel_bits, _]) in expected.iter().enumerate() {
+                assert_eq!(state.radius_km[axis].to_bits(), *pos_bits);
+                assert_eq!(state.velocity_km_s[axis].to_bits(), *vel_bits);
+                assert_eq!(pos_vel[axis].to_bits(), *pos_bits);
+                assert_eq!(pos_vel[axis + 3].to_bits(), *vel_bits);
+            }
+            assert_eq!(state.frame, frame);
+            assert_eq!(
+                CartesianState::from_cartesian_pos_vel(pos_vel, epoch, frame).radius_km,
+                state.radius_km
+            );
+        }
+    }
+}
@@ -15,17 +15,23 @@ pub type Vector6 = nalgebra::Vector6<f64>;
 pub type Matrix3 = nalgebra::Matrix3<f64>;
 pub type Matrix6 = nalgebra::Matrix6<f64>;
 
+#[cfg(not(feature = "no-std-eval"))]
 pub mod angles;
 pub mod cartesian;
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 mod cartesian_py;
 pub mod interpolation;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod rotation;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod units;
 
+#[cfg(not(feature = "no-std-eval"))]
 use nalgebra::allocator::Allocator;
+#[cfg(not(feature = "no-std-eval"))]
 use nalgebra::{DefaultAllocator, DimName, OVector};
 
+#[cfg(not(feature = "no-std-eval"))]
 /// Returns the root sum squared (RSS) between two vectors of any dimension N.
 pub fn root_sum_squared<N: DimName>(vec_a: &OVector<f64, N>, vec_b: &OVector<f64, N>) -> f64
 where
@@ -39,6 +45,7 @@ where
         .sqrt()
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 /// Returns the root mean squared (RSS) between two vectors of any dimension N.
 pub fn root_mean_squared<N: DimName>(vec_a: &OVector<f64, N>, vec_b: &OVector<f64, N>) -> f64
 where
@@ -54,12 +61,14 @@ where
     mean_of_squares.sqrt()
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 /// Returns the projection of a onto b
 /// Converted from NAIF SPICE's `projv`
 pub fn project_vector(a: &Vector3, b: &Vector3) -> Vector3 {
     b * a.dot(b) / b.dot(b)
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 /// Returns the components of vector a orthogonal to b
 /// Converted from NAIF SPICE's `prepv`
 pub fn perp_vector(a: &Vector3, b: &Vector3) -> Vector3 {
@@ -77,6 +86,7 @@ pub fn perp_vector(a: &Vector3, b: &Vector3) -> Vector3 {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 /// Rotate the vector a around the provided axis by angle theta.
 pub fn rotate_vector(a: &Vector3, axis: &Vector3, theta_rad: f64) -> Vector3 {
     let k_hat = axis.normalize();
@@ -85,7 +95,7 @@ pub fn rotate_vector(a: &Vector3, axis: &Vector3, theta_rad: f64) -> Vector3 {
         + k_hat.scale(k_hat.dot(a) * (1.0 - theta_rad.cos()))
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "no-std-eval")))]
 mod math_ut {
     use super::{rotate_vector, Vector3};
     #[test]
@@ -17,7 +17,7 @@ use crate::{
 use nalgebra::Vector4;
 use snafu::ensure;
 
-use super::{r1, r2, r3, Quaternion, Rotation};
+use super::{r1, r2, r3, EulerSequence, Quaternion, Rotation};
 use core::fmt;
 use core::ops::Mul;
 
@@ -128,6 +128,78 @@ impl DCM {
         }
     }
 
+    /// Builds the rotation from the provided Euler angles in radians, in the order in which the rotations of the sequence are applied.
+    ///
+    /// For example, the 3-2-1 sequence (`EulerSequence::ZYX`) returns `R1(angles[2]) R2(angles[1]) R3(angles[0])`.
+    pub fn from_euler_angles(
+        seq: EulerSequence,
+        angles_rad: [f64; 3],
+        from: NaifId,
+        to: NaifId,
+    ) -> Self {
+        let [i, j, k] = seq.axes();
+        Self {
+            rot_mat: axis_rotation(k, angles_rad[2])
+                * axis_rotation(j, angles_rad[1])
+                * axis_rotation(i, angles_rad[0]),
+            rot_mat_dt: None,
+            from,
+            to,
+        }
+    }
+
+    /// Returns the Euler parameter (quaternion) representation of this rotation.
+    pub fn quaternion(&self) -> Quaternion {
+        Quaternion::from(*self)
+    }
+
+    /// Returns the Euler parameter (quaternion) representation of this rotation, and its time derivative if the time derivative of this DCM is set.
+    pub fn quaternion_with_rate(&self) -> (Quaternion, Option<Quaternion>) {
+        let q = self.quaternion();
+        let q_dot = self.angular_velocity().map(|omega| {
+            // dQ/dt = 1/2 [B(Q)] w
+            let q_dot = 0.5 * q.b_matrix() * omega;
+            Quaternion {
+                w: q_dot[0],
+                x: q_dot[1],
+                y: q_dot[2],
+                z: q_dot[3],
+                from: self.from,
+                to: self.to,
+            }
+        });
+
+        (q, q_dot)
+    }
+
+    /// Returns the angular velocity in rad/s of the `to` frame with respect to the `from` frame, expressed in the `to` frame, if the time
+    /// derivative of this DCM is set. It is such that the time derivative of the DCM is `-[w×] C`.
+    pub fn angular_velocity(&self) -> Option<Vector3> {
+        self.rot_mat_dt.map(|rot_mat_dt| {
+            let w_skew = -rot_mat_dt * self.rot_mat.transpose();
+            0.5 * Vector3::new(
+                w_skew[(2, 1)] - w_skew[(1, 2)],
+                w_skew[(0, 2)] - w_skew[(2, 0)],
+                w_skew[(1, 0)] - w_skew[(0, 1)],
+            )
+        })
+    }
+
+    /// Returns the angular acceleration in rad/s^2, i.e. the time derivative of [Self::angular_velocity], given the second time derivative
+    /// of this DCM, if its first time derivative is set.
+    pub fn angular_acceleration(&self, rot_mat_ddt: &Matrix3) -> Option<Vector3> {
+        self.rot_mat_dt.map(|rot_mat_dt| {
+            // Differentiating `-[w×] = C' C^T` gives `-[w'×] = C'' C^T + C' C'^T`, whose last term is symmetric.
+            let w_dot_skew =
+                -(rot_mat_ddt * self.rot_mat.transpose() + rot_mat_dt * rot_mat_dt.transpose());
+            0.5 * Vector3::new(
+                w_dot_skew[(2, 1)] - w_dot_skew[(1, 2)],
+                w_dot_skew[(0, 2)] - w_dot_skew[(2, 0)],
+                w_dot_skew[(1, 0)] - w_dot_skew[(0, 1)],
+            )
+        })
+    }
+
     /// Multiplies this DCM with another one WITHOUT checking if the frames match.
     pub(crate) fn mul_unchecked(&self, other: Self) -> Self {
         let mut rslt = *self;
@@ -170,6 +242,94 @@ impl DCM {
         self.to == self.from || (self.rot_mat - Matrix3::identity()).norm() < 1e-8
     }
 
+    /// Returns the Euler angles in radians of this rotation for the provided sequence, in the order in which the rotations are applied,
+    /// such that `DCM::from_euler_angles` returns this rotation.
+    ///
+    /// The first and third angles are in [-pi, pi]. The second angle is in [0, pi] for symmetric sequences (e.g. 3-1-3), and in [-pi/2, pi/2]
+    /// for the other sequences (e.g. 3-2-1).
+    ///
+    /// # Gimbal lock
+    /// When the second angle is zero or pi for a symmetric sequence, or plus or minus pi/2 for the other sequences, the rotations about the
+    /// first and third axes cannot be distinguished. As in the SPICE `m2eul` routine, the third angle is then set to zero and the first
+    /// angle holds the whole rotation about that axis.
+    ///
+    /// :type seq: EulerSequence
+    /// :rtype: typing.List
+    pub fn euler_angles(&self, seq: EulerSequence) -> [f64; 3] {
+        self.euler_angles_with_rates(seq).0
+    }
+
+    /// Returns the Euler angles in radians of this rotation for the provided sequence, as in `euler_angles`, and their time derivatives in
+    /// rad/s if the time derivative of this DCM is set.
+    ///
+    /// At gimbal lock, the rate of the third angle is set to zero like the angle itself, and the rates of the first and second angles are
+    /// those of the rotations about their axes.
+    ///
+    /// :type seq: EulerSequence
+    /// :rtype: typing.Tuple
+    pub fn euler_angles_with_rates(&self, seq: EulerSequence) -> ([f64; 3], Option<[f64; 3]>) {
+        let [i, j, k] = seq.axes();
+        let m = &self.rot_mat;
+
+        let (angle1, angle2, angle3, locked) = if seq.is_symmetric() {
+            // The remaining axis, and the orientation of the (i, j, remaining) axes.
+            let r = 3 - i - j;
+            let p = axes_parity(i, j, r);
+            let angle2 = m[(i, i)].clamp(-1.0, 1.0).acos();
+            if angle2.sin() < GIMBAL_LOCK_EPSILON {
+                (0.0, angle2, 0.0, true)
+            } else {
+                (
+                    m[(i, j)].atan2(-p * m[(i, r)]),
+                    angle2,
+                    m[(j, i)].atan2(p * m[(r, i)]),
+                    false,
+                )
+            }
+        } else {
+            let p = axes_parity(i, j, k);
+            let angle2 = (p * m[(k, i)]).clamp(-1.0, 1.0).asin();
+            if angle2.cos() < GIMBAL_LOCK_EPSILON {
+                (0.0, angle2, 0.0, true)
+            } else {
+                (
+                    (-p * m[(k, j)]).atan2(m[(k, k)]),
+                    angle2,
+                    (-p * m[(j, i)]).atan2(m[(i, i)]),
+                    false,
+                )
+            }
+        };
+
+        let angle1 = if locked {
+            // With the third angle set to zero, the rotation matrix is R_j(angle 2) R_i(angle 1).
+            let ri = axis_rotation(j, angle2).transpose() * m;
+            let (u, v) = ((i + 1) % 3, (i + 2) % 3);
+            ri[(u, v)].atan2(ri[(u, u)])
+        } else {
+            angle1
+        };
+
+        let rates = self.angular_velocity().map(|omega| {
+            // The angular velocity is the sum of the rate of each angle about its axis, expressed in the `to` frame.
+            let axis3 = unit_axis(k);
+            let axis2 = axis_rotation(k, angle3) * unit_axis(j);
+            let axis1 = axis_rotation(k, angle3) * axis_rotation(j, angle2) * unit_axis(i);
+
+            if locked {
+                // The first and third axes are aligned, and both are orthogonal to the second axis.
+                [axis1.dot(&omega), axis2.dot(&omega), 0.0]
+            } else {
+                let b = Matrix3::from_columns(&[axis1, axis2, axis3]);
+                // This matrix is invertible away from gimbal lock.
+                let rates = b.try_inverse().unwrap_or_else(Matrix3::zeros) * omega;
+                [rates[0], rates[1], rates[2]]
+            }
+        });
+
+        ([angle1, angle2, angle3], rates)
+    }
+
     /// Returns whether the `rot_mat` of this DCM is a valid rotation matrix.
     /// The criteria for validity are:
     /// -- The columns of the matrix are unit vectors, within a specified tolerance (unit_tol).
@@ -189,6 +349,34 @@ impl DCM {
     }
 }
 
+/// Below this sine (symmetric sequences) or cosine (other sequences) of the second Euler angle, the first and third angles cannot be distinguished.
+const GIMBAL_LOCK_EPSILON: f64 = 1e-12;
+
+/// Returns the rotation matrix about the axis of the provided index (0 for X, 1 for Y, 2 for Z).
+fn axis_rotation(axis: usize, angle_rad: f64) -> Matrix3 {
+    match axis {
+        0 => r1(angle_rad),
+        1 => r2(angle_rad),
+        _ => r3(angle_rad),
+    }
+}
+
+/// Returns the unit vector of the axis of the provided index.
+fn unit_axis(axis: usize) -> Vector3 {
+    let mut unit = Vector3::zeros();
+    unit[axis] = 1.0;
+    unit
+}
+
+/// Returns 1.0 if these three different axes are in cyclic order (X-Y-Z, Y-Z-X, or Z-X-Y), and -1.0 otherwise.
+fn axes_parity(a1: usize, a2: usize, a3: usize) -> f64 {
+    if (a1 + 1) % 3 == a2 && (a2 + 1) % 3 == a3 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
 impl Mul for DCM {
     type Output = Result<Self, PhysicsError>;
 
@@ -434,8 +622,178 @@ impl fmt::Display for DCM {
 mod ut_dcm {
     use crate::math::Matrix3;
 
-    use super::{Vector3, DCM};
-    use core::f64::consts::FRAC_PI_2;
+    use super::{axis_rotation, unit_axis, EulerSequence, Quaternion, Vector3, DCM};
+    use core::f64::consts::{FRAC_PI_2, PI};
+
+    const SEQUENCES: [EulerSequence; 12] = [
+        EulerSequence::XYX,
+        EulerSequence::XYZ,
+        EulerSequence::XZX,
+        EulerSequence::XZY,
+        EulerSequence::YXY,
+        EulerSequence::YXZ,
+        EulerSequence::YZX,
+        EulerSequence::YZY,
+        EulerSequence::ZXY,
+        EulerSequence::ZXZ,
+        EulerSequence::ZYX,
+        EulerSequence::ZYZ,
+    ];
+
+    /// A spread of rotations, built from the 3-1-3 Euler angles on a grid, each with a time derivative.
+    fn rotations() -> Vec<DCM> {
+        let mut dcms = Vec::new();
+        for angle1 in [-3.0, -1.2, 0.0, 0.4, 2.5] {
+            for angle2 in [0.0, 0.3, 1.0, FRAC_PI_2, 2.2, PI] {
+                for angle3 in [-2.0, 0.0, 0.7, 3.1] {
+                    let mut dcm =
+                        DCM::from_euler_angles(EulerSequence::ZXZ, [angle1, angle2, angle3], 1, 2);
+                    dcm.rot_mat_dt = Some(rot_mat_dt(
+                        EulerSequence::ZXZ,
+                        [angle1, angle2, angle3],
+                        [1e-3, -2e-3, 5e-4],
+                    ));
+                    dcms.push(dcm);
+                }
+            }
+        }
+        dcms
+    }
+
+    /// Returns the time derivative of the rotation matrix with these Euler angles and rates, by central finite differencing.
+    fn rot_mat_dt(seq: EulerSequence, angles: [f64; 3], rates: [f64; 3]) -> Matrix3 {
+        let step_s = 1e-3;
+        let at = |dt_s: f64| {
+            DCM::from_euler_angles(
+                seq,
+                [
+                    angles[0] + rates[0] * dt_s,
+                    angles[1] + rates[1] * dt_s,
+                    angles[2] + rates[2] * dt_s,
+                ],
+                1,
+                2,
+            )
+            .rot_mat
+        };
+        (at(step_s) - at(-step_s)) / (2.0 * step_s)
+    }
+
+    #[test]
+    fn dcm_quaternion_round_trip() {
+        for dcm in rotations() {
+            let (q, q_dot) = dcm.quaternion_with_rate();
+            assert!((q.scalar_norm() - 1.0).abs() < 1e-12);
+            assert_eq!(q.from, 1);
+            assert_eq!(q.to, 2);
+
+            let back = DCM::from(q);
+            assert!((back.rot_mat - dcm.rot_mat).norm() < 1e-12, "{dcm}");
+
+            // The quaternion rate matches the finite difference of the quaternions, accounting for its sign.
+            let step_s = 1e-3;
+            let mut after = dcm;
+            after.rot_mat += dcm.rot_mat_dt.unwrap() * step_s;
+            let mut before = dcm;
+            before.rot_mat -= dcm.rot_mat_dt.unwrap() * step_s;
+            let sign = |other: Quaternion| {
+                if other.as_vector().dot(&q.as_vector()) < 0.0 {
+                    -other.as_vector()
+                } else {
+                    other.as_vector()
+                }
+            };
+            let q_dot_fd = (sign(after.quaternion()) - sign(before.quaternion())) / (2.0 * step_s);
+            assert!(
+                (q_dot.unwrap().as_vector() - q_dot_fd).norm() < 1e-8,
+                "{dcm}\n{:?} vs {q_dot_fd}",
+                q_dot.unwrap()
+            );
+        }
+
+        assert!(DCM::identity(1, 2).quaternion_with_rate().1.is_none());
+    }
+
+    #[test]
+    fn dcm_euler_round_trip() {
+        for dcm in rotations() {
+            for seq in SEQUENCES {
+                let (angles, rates) = dcm.euler_angles_with_rates(seq);
+                assert_eq!(angles, dcm.euler_angles(seq));
+
+                let back = DCM::from_euler_angles(seq, angles, dcm.from, dcm.to);
+                assert!(
+                    (back.rot_mat - dcm.rot_mat).norm() < 1e-12,
+                    "{seq}: {angles:?}\n{dcm}{back}"
+                );
+
+                // The rates rebuild the same time derivative, except near gimbal lock where the rates are ill-conditioned.
+                let locked = if seq.is_symmetric() {
+                    angles[1].sin() < 1e-6
+                } else {
+                    angles[1].cos() < 1e-6
+                };
+                if !locked {
+                    let rebuilt = rot_mat_dt(seq, angles, rates.unwrap());
+                    assert!(
+                        (rebuilt - dcm.rot_mat_dt.unwrap()).norm() < 1e-9,
+                        "{seq}: {angles:?} {rates:?}"
+                    );
+                }
+            }
+
+            assert!(DCM {
+                rot_mat_dt: None,
+                ..dcm
+            }
+            .euler_angles_with_rates(EulerSequence::ZYX)
+            .1
+            .is_none());
+        }
+    }
+
+    #[test]
+    fn dcm_euler_ranges_and_gimbal_lock() {
+        // The 3-2-1 yaw, pitch, and roll angles
+        let dcm = DCM::from_euler_angles(EulerSequence::ZYX, [0.3, -0.2, 0.1], 1, 2);
+        let angles = dcm.euler_angles(EulerSequence::ZYX);
+        for (angle, expected) in angles.iter().zip([0.3, -0.2, 0.1]) {
+            assert!((angle - expected).abs() < 1e-14);
+        }
+        assert_eq!(
+            dcm.rot_mat,
+            axis_rotation(0, 0.1) * axis_rotation(1, -0.2) * axis_rotation(2, 0.3)
+        );
+
+        // At gimbal lock, the third angle is set to zero as in SPICE.
+        for (seq, angle2) in [
+            (EulerSequence::ZYX, FRAC_PI_2),
+            (EulerSequence::ZYX, -FRAC_PI_2),
+            (EulerSequence::XZY, FRAC_PI_2),
+            (EulerSequence::ZXZ, 0.0),
+            (EulerSequence::ZXZ, PI),
+            (EulerSequence::YXY, PI),
+        ] {
+            let [i, j, k] = seq.axes();
+            let mut dcm = DCM::from_euler_angles(seq, [0.3, angle2, 0.7], 1, 2);
+            // Rotating about the locked axis
+            let omega = (axis_rotation(k, 0.7) * axis_rotation(j, angle2) * unit_axis(i)) * 0.01;
+            dcm.rot_mat_dt = Some(-omega.cross_matrix() * dcm.rot_mat);
+
+            let (angles, rates) = dcm.euler_angles_with_rates(seq);
+            assert_eq!(angles[2], 0.0, "{seq}");
+            assert!((angles[1] - angle2).abs() < 1e-12, "{seq}");
+            let back = DCM::from_euler_angles(seq, angles, 1, 2);
+            assert!((back.rot_mat - dcm.rot_mat).norm() < 1e-12, "{seq}");
+
+            let rates = rates.unwrap();
+            assert!((rates[0] - 0.01).abs() < 1e-12, "{seq}: {rates:?}");
+            assert!(rates[1].abs() < 1e-12, "{seq}: {rates:?}");
+            assert_eq!(rates[2], 0.0);
+        }
+
+        assert_eq!(format!("{}", EulerSequence::ZYX), "3-2-1");
+    }
 
     #[test]
     fn test_r1() {
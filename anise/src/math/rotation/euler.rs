@@ -0,0 +1,69 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Sequence of the three axes of an Euler angle rotation, in the order in which the rotations are applied.
+///
+/// For example, the 3-2-1 sequence `ZYX` (yaw, pitch, and roll) rotates first about the Z axis by the first angle, then about the new Y axis by
+/// the second angle, and finally about the new X axis by the third angle, so its rotation matrix is `R1(angle 3) R2(angle 2) R3(angle 1)`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+#[cfg_attr(feature = "python", pyo3(module = "anise.rotation"))]
+pub enum EulerSequence {
+    XYX,
+    XYZ,
+    XZX,
+    XZY,
+    YXY,
+    YXZ,
+    YZX,
+    YZY,
+    ZXY,
+    ZXZ,
+    ZYX,
+    ZYZ,
+}
+
+impl EulerSequence {
+    /// Returns the indexes (0 for X, 1 for Y, 2 for Z) of the three axes of this sequence, in the order in which the rotations are applied.
+    pub const fn axes(&self) -> [usize; 3] {
+        match self {
+            Self::XYX => [0, 1, 0],
+            Self::XYZ => [0, 1, 2],
+            Self::XZX => [0, 2, 0],
+            Self::XZY => [0, 2, 1],
+            Self::YXY => [1, 0, 1],
+            Self::YXZ => [1, 0, 2],
+            Self::YZX => [1, 2, 0],
+            Self::YZY => [1, 2, 1],
+            Self::ZXY => [2, 0, 1],
+            Self::ZXZ => [2, 0, 2],
+            Self::ZYX => [2, 1, 0],
+            Self::ZYZ => [2, 1, 2],
+        }
+    }
+
+    /// Returns whether the first and last axes of this sequence are the same (e.g. 3-1-3), as opposed to all three axes being different (e.g. 3-2-1).
+    pub const fn is_symmetric(&self) -> bool {
+        let axes = self.axes();
+        axes[0] == axes[2]
+    }
+}
+
+impl fmt::Display for EulerSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a1, a2, a3] = self.axes();
+        write!(f, "{}-{}-{}", a1 + 1, a2 + 1, a3 + 1)
+    }
+}
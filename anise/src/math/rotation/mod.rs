@@ -14,9 +14,11 @@ pub(crate) const EPSILON_RAD: f64 = 4.8e-6;
 pub(crate) const EPSILON: f64 = 1e-12;
 
 mod dcm;
+mod euler;
 mod mrp;
 mod quaternion;
 pub use dcm::DCM;
+pub use euler::EulerSequence;
 pub use mrp::MRP;
 pub use quaternion::Quaternion;
 
@@ -37,6 +39,12 @@ pub fn r1_dot(angle_rad: f64) -> Matrix3 {
     Matrix3::new(0.0, 0.0, 0.0, 0.0, -s, c, 0.0, -c, -s)
 }
 
+/// Build the second derivative of the 3x3 rotation matrix around the X axis
+pub fn r1_ddot(angle_rad: f64) -> Matrix3 {
+    let (s, c) = angle_rad.sin_cos();
+    Matrix3::new(0.0, 0.0, 0.0, 0.0, -c, -s, 0.0, s, -c)
+}
+
 /// Build a 3x3 rotation matrix around the Y axis
 pub fn r2(angle_rad: f64) -> Matrix3 {
     let (s, c) = angle_rad.sin_cos();
@@ -49,6 +57,12 @@ pub fn r2_dot(angle_rad: f64) -> Matrix3 {
     Matrix3::new(-s, 0.0, -c, 0.0, 0.0, 0.0, c, 0.0, -s)
 }
 
+/// Build the second derivative of the 3x3 rotation matrix around the Y axis
+pub fn r2_ddot(angle_rad: f64) -> Matrix3 {
+    let (s, c) = angle_rad.sin_cos();
+    Matrix3::new(-c, 0.0, s, 0.0, 0.0, 0.0, -s, 0.0, -c)
+}
+
 /// Build a 3x3 rotation matrix around the Z axis
 pub fn r3(angle_rad: f64) -> Matrix3 {
     let (s, c) = angle_rad.sin_cos();
@@ -61,6 +75,12 @@ pub fn r3_dot(angle_rad: f64) -> Matrix3 {
     Matrix3::new(-s, c, 0.0, -c, -s, 0.0, 0.0, 0.0, 0.0)
 }
 
+/// Build the second derivative of the 3x3 rotation matrix around the Z axis
+pub fn r3_ddot(angle_rad: f64) -> Matrix3 {
+    let (s, c) = angle_rad.sin_cos();
+    Matrix3::new(-c, -s, 0.0, s, -c, 0.0, 0.0, 0.0, 0.0)
+}
+
 /// Generates the angles for the test
 #[cfg(test)]
 pub(crate) fn generate_angles() -> Vec<f64> {
@@ -11,7 +11,7 @@
 use super::file_record::FileRecordError;
 use super::{
     DAFError, DecodingNameSnafu, DecodingSummarySnafu, FileRecordSnafu, IOSnafu, NAIFDataSet,
-    NAIFRecord, NAIFSummaryRecord,
+    NAIFRecord, NAIFSummaryRecord, COMMENT_EOL, COMMENT_EOT, COMMENT_RCRD_LEN,
 };
 pub use super::{FileRecord, NameRecord, SummaryRecord};
 use crate::errors::DecodingError;
@@ -26,6 +26,7 @@ use core::ops::Deref;
 use hifitime::{Epoch, Unit};
 use log::{debug, error, trace};
 use snafu::ResultExt;
+use std::sync::OnceLock;
 
 use zerocopy::IntoBytes;
 use zerocopy::{FromBytes, Ref};
@@ -42,10 +43,11 @@ macro_rules! io_imports {
 io_imports!();
 
 pub(crate) const RCRD_LEN: usize = 1024;
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug)]
 pub struct GenericDAF<R: NAIFSummaryRecord, W: MutKind> {
     pub bytes: W,
-    pub crc32_checksum: u32,
+    /// Checksum of the bytes when they were loaded, which is only computed on the first integrity check of memory mapped files.
+    crc32_checksum: OnceLock<u32>,
     pub _daf_type: PhantomData<R>,
 }
 
@@ -62,16 +64,24 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         crc32fast::hash(&self.bytes)
     }
 
+    /// Returns the CRC32 of the bytes when this DAF was loaded, against which [Self::scrub] checks the bytes.
+    ///
+    /// The checksum of a DAF loaded with [DAF::from_mmap] is not computed at load time, since that would read the whole file: it is
+    /// computed on the first call to this function or to [Self::scrub].
+    pub fn crc32_checksum(&self) -> u32 {
+        *self.crc32_checksum.get_or_init(|| self.crc32())
+    }
+
     /// Scrubs the data by computing the CRC32 of the bytes and making sure that it still matches the previously known hash
+    ///
+    /// For memory mapped DAFs, the first scrub computes the known hash, so it only detects the changes made to the file afterward.
     pub fn scrub(&self) -> Result<(), IntegrityError> {
-        if self.crc32() == self.crc32_checksum {
+        let computed = self.crc32();
+        let expected = *self.crc32_checksum.get_or_init(|| computed);
+        if computed == expected {
             Ok(())
         } else {
-            // Compiler will optimize the double computation away
-            Err(IntegrityError::ChecksumInvalid {
-                expected: self.crc32_checksum,
-                computed: self.crc32(),
-            })
+            Err(IntegrityError::ChecksumInvalid { expected, computed })
         }
     }
 
@@ -259,13 +269,22 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
 
     /// Provided a name that is in the summary, return its full data, if name is available.
     pub fn nth_data<'a, S: NAIFDataSet<'a>>(&'a self, idx: usize) -> Result<S, DAFError> {
+        let data = self.nth_data_slice(idx, S::DATASET_NAME)?;
+
+        // Convert it
+        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+    }
+
+    /// Returns the raw data of the n-th summary, as stored in this DAF, e.g. to copy it into another DAF.
+    pub fn nth_data_f64(&self, idx: usize) -> Result<&[f64], DAFError> {
+        self.nth_data_slice(idx, R::NAME)
+    }
+
+    fn nth_data_slice(&self, idx: usize, kind: &'static str) -> Result<&[f64], DAFError> {
         let this_summary = self
             .data_summaries()?
             .get(idx)
-            .ok_or(DAFError::InvalidIndex {
-                idx,
-                kind: S::DATASET_NAME,
-            })?;
+            .ok_or(DAFError::InvalidIndex { idx, kind })?;
         // Grab the data in native endianness (TODO: How to support both big and little endian?)
         trace!("{idx} -> {this_summary:?}");
         if self.file_record()?.is_empty() {
@@ -300,50 +319,61 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
             .unwrap(),
         );
 
-        // Convert it
-        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+        Ok(data)
     }
 
+    /// Returns the text of the comment area of this DAF, or None if it is empty.
+    ///
+    /// The comment area is made of the records between the file record and the first summary record. SPICE stores 1000 characters
+    /// per record, terminates each line with a null character, and terminates the text with an EOT character: these are decoded
+    /// so that the comments set with the `with_comments` of the SPK and BPC writers are returned unchanged.
     pub fn comments(&self) -> Result<Option<String>, DAFError> {
-        // TODO: This can be cleaned up to avoid allocating a string. In my initial tests there were a bunch of additional spaces, so I canceled those changes.
-        let mut rslt = String::new();
-        // FWRD has the initial record of the summary. So we assume that all records between the second record and that one are comments
-        for rid in 1..self.file_record()?.fwrd_idx() {
-            match core::str::from_utf8(
-                match self
-                    .bytes
-                    .get(rid * RCRD_LEN..(rid + 1) * RCRD_LEN)
-                    .ok_or_else(|| DecodingError::InaccessibleBytes {
-                        start: rid * RCRD_LEN,
-                        end: (rid + 1) * RCRD_LEN,
+        let mut text = Vec::new();
+        let mut terminated = false;
+        'records: for rid in 1..self.file_record()?.fwrd_idx() {
+            let start = rid * RCRD_LEN;
+            let end = start + COMMENT_RCRD_LEN;
+            let record = self
+                .bytes
+                .get(start..end)
+                .ok_or_else(|| DAFError::DecodingComments {
+                    kind: R::NAME,
+                    source: DecodingError::InaccessibleBytes {
+                        start,
+                        end,
                         size: self.bytes.len(),
-                    }) {
-                    Ok(it) => it,
-                    Err(source) => {
-                        return Err(DAFError::DecodingComments {
-                            kind: R::NAME,
-                            source,
-                        })
-                    }
-                },
-            ) {
-                Ok(s) => rslt += s.replace('\u{0}', "\n").trim(),
-                Err(e) => {
-                    // At this point, we know that the bytes are accessible because the embedded `match`
-                    // did not fail, so we can perform a direct access.
-                    let valid_s = core::str::from_utf8(
-                        &self.bytes[rid * RCRD_LEN..(rid * RCRD_LEN + e.valid_up_to())],
-                    )
-                    .unwrap();
-                    rslt += valid_s.replace('\u{0}', "\n").trim()
+                    },
+                })?;
+
+            for c in record {
+                if *c == COMMENT_EOT {
+                    terminated = true;
+                    break 'records;
                 }
+                text.push(*c);
             }
         }
 
-        if rslt.is_empty() {
+        if terminated {
+            // The last line is terminated like all others.
+            if text.last() == Some(&COMMENT_EOL) {
+                text.pop();
+            }
+        } else {
+            // Without an EOT character, the end of the comment area is only padded with null characters.
+            while text.last() == Some(&COMMENT_EOL) {
+                text.pop();
+            }
+        }
+
+        if text.is_empty() {
             Ok(None)
         } else {
-            Ok(Some(rslt))
+            let text: Vec<u8> = text
+                .into_iter()
+                .map(|c| if c == COMMENT_EOL { b'\n' } else { c })
+                .collect();
+            Ok(Some(String::from_utf8_lossy(&text).into_owned()))
         }
     }
 
@@ -376,6 +406,13 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
     }
 }
 
+impl<R: NAIFSummaryRecord, W: MutKind> PartialEq for GenericDAF<R, W> {
+    /// Only the bytes are compared, from which the checksum is derived.
+    fn eq(&self, other: &Self) -> bool {
+        *self.bytes == *other.bytes
+    }
+}
+
 impl<R: NAIFSummaryRecord, W: MutKind> Hash for GenericDAF<R, W> {
     /// Hash will only hash the bytes, nothing else (since these are derived from the bytes anyway).
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
@@ -389,7 +426,7 @@ impl<R: NAIFSummaryRecord> DAF<R> {
         let crc32_checksum = crc32fast::hash(&bytes);
         let me = Self {
             bytes: Bytes::copy_from_slice(&bytes),
-            crc32_checksum,
+            crc32_checksum: OnceLock::from(crc32_checksum),
             _daf_type: PhantomData,
         };
         // Check that these calls will succeed.
@@ -413,6 +450,23 @@ impl<R: NAIFSummaryRecord> DAF<R> {
         Self::parse(bytes)
     }
 
+    /// Parse the provided memory map of a SPICE Double Array File without copying it onto the heap.
+    ///
+    /// Only the file record is read, to check that the endianness of the file is that of this platform, followed by the name record.
+    /// The checksum is not computed until the first integrity check (cf. [Self::crc32_checksum]), so only the summary records and the data
+    /// of the segments which are evaluated are read from the file.
+    pub fn from_mmap(mmap: memmap2::Mmap) -> Result<Self, DAFError> {
+        let me = Self {
+            bytes: Bytes::from_owner(mmap),
+            crc32_checksum: OnceLock::new(),
+            _daf_type: PhantomData,
+        };
+        // Check that these calls will succeed.
+        me.file_record()?;
+        me.name_record()?;
+        Ok(me)
+    }
+
     pub fn load(path: &str) -> Result<Self, DAFError> {
         let bytes = file2heap!(path).context(IOSnafu {
             action: format!("loading {path:?}"),
@@ -430,7 +484,7 @@ impl<R: NAIFSummaryRecord> DAF<R> {
     pub fn to_mutable(&self) -> MutDAF<R> {
         MutDAF {
             bytes: BytesMut::from_iter(&self.bytes),
-            crc32_checksum: self.crc32_checksum,
+            crc32_checksum: self.crc32_checksum.clone(),
             _daf_type: PhantomData,
         }
     }
@@ -449,6 +503,7 @@ mod daf_ut {
         },
         prelude::SPK,
     };
+    use std::sync::OnceLock;
 
     #[test]
     fn crc32_errors() {
@@ -469,7 +524,7 @@ mod daf_ut {
         );
 
         // Change the checksum of the traj and check that scrub fails
-        traj.crc32_checksum += 1;
+        traj.crc32_checksum = OnceLock::from(nominal_crc + 1);
         assert_eq!(
             traj.scrub(),
             Err(IntegrityError::ChecksumInvalid {
@@ -531,4 +586,70 @@ mod daf_ut {
             );
         }
     }
+
+    #[test]
+    fn from_mmap() {
+        use crate::errors::InputOutputError;
+        use crate::file_mmap;
+        use std::fs::File;
+
+        let traj = SPK::load("../data/gmat-hermite.bsp").unwrap();
+        let mapped = SPK::from_mmap(file_mmap!("../data/gmat-hermite.bsp").unwrap()).unwrap();
+        assert_eq!(mapped, traj);
+        assert_eq!(mapped.scrub(), Ok(()));
+
+        assert_eq!(
+            SPK::from_mmap(file_mmap!("../data/gmat-hermite-big-endian.bsp").unwrap()),
+            Err(DAFError::FileRecord {
+                kind: "SPKSummaryRecord",
+                source: FileRecordError::WrongEndian
+            })
+        );
+    }
+
+    /// Loading a memory mapped file must not read its data: once the file is truncated after its name record, reading the data of the
+    /// mapping would raise a bus error.
+    #[cfg(unix)]
+    #[test]
+    fn from_mmap_does_not_read_data() {
+        use crate::constants::frames::EARTH_J2000;
+        use crate::math::{cartesian::CartesianState, Vector3};
+        use crate::naif::daf::RCRD_LEN;
+        use crate::naif::spk::writer::SPKWriter;
+        use hifitime::TimeUnits;
+        use std::{env, fs::File};
+
+        let start = Epoch::from_et_seconds(0.0);
+        let states = (0..=10 * 24 * 60)
+            .map(|i| {
+                let dt_s = i as f64 * 60.0;
+                CartesianState {
+                    radius_km: Vector3::new(7000.0 + dt_s, 100.0, -50.0),
+                    velocity_km_s: Vector3::new(1.0, 0.0, 0.0),
+                    epoch: start + dt_s.seconds(),
+                    frame: EARTH_J2000,
+                }
+            })
+            .collect::<Vec<CartesianState>>();
+
+        let mut writer = SPKWriter::new("ANISE lazy mmap test");
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 7, 1.hours(), "TEST SC")
+            .unwrap();
+        let path = env::temp_dir().join("anise-ut-lazy-mmap.bsp");
+        writer.write(&path).unwrap();
+        let expected = writer.build().unwrap().data_summaries().unwrap().to_vec();
+
+        let file = File::options().read(true).write(true).open(&path).unwrap();
+        let mmap = unsafe { memmap2::Mmap::map(&file).unwrap() };
+        assert!(mmap.len() > 64 * RCRD_LEN);
+        // Only keep the file, summary, and name records on disk.
+        file.set_len(3 * RCRD_LEN as u64).unwrap();
+
+        let mapped = SPK::from_mmap(mmap).unwrap();
+        assert_eq!(mapped.data_summaries().unwrap(), expected.as_slice());
+
+        drop(mapped);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -15,7 +15,9 @@ use snafu::{ensure, ResultExt};
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        interpolation::{chebyshev_eval, InterpDecodingSnafu, InterpolationError},
+        interpolation::{
+            chebyshev_eval, chebyshev_eval_derivatives, InterpDecodingSnafu, InterpolationError,
+        },
         Vector3,
     },
     naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
@@ -177,6 +179,41 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         Ok((state, rate))
     }
 
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        let spline_idx = self.spline_idx(epoch, summary)?;
+
+        let radius_s = self.interval_length.to_seconds() / 2.0;
+
+        let record = self
+            .nth_record(spline_idx - 1)
+            .context(InterpDecodingSnafu)?;
+
+        let normalized_time = (epoch.to_et_seconds() - record.midpoint_et_s) / radius_s;
+
+        let mut state = Vector3::zeros();
+        let mut rate = Vector3::zeros();
+        let mut accel = Vector3::zeros();
+
+        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
+            .iter()
+            .enumerate()
+        {
+            (state[cno], rate[cno], accel[cno]) = chebyshev_eval_derivatives(
+                normalized_time,
+                coeffs,
+                radius_s,
+                epoch,
+                self.degree(),
+            )?;
+        }
+
+        Ok((state, rate, accel))
+    }
+
     fn check_integrity(&self) -> Result<(), IntegrityError> {
         // Verify that none of the data is invalid once when we load it.
         for val in self.record_data {
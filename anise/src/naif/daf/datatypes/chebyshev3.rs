@@ -15,7 +15,9 @@ use snafu::{ensure, ResultExt};
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        interpolation::{chebyshev_eval_poly, InterpDecodingSnafu, InterpolationError},
+        interpolation::{
+            chebyshev_eval, chebyshev_eval_poly, InterpDecodingSnafu, InterpolationError,
+        },
         Vector3,
     },
     naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
@@ -182,6 +184,44 @@ impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
         Ok((state, rate))
     }
 
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        let spline_idx = self.spline_idx(epoch, summary)?;
+
+        let radius_s = self.interval_length.to_seconds() / 2.0;
+
+        let record = self
+            .nth_record(spline_idx - 1)
+            .context(InterpDecodingSnafu)?;
+
+        let normalized_time = (epoch.to_et_seconds() - record.midpoint_et_s) / radius_s;
+
+        let mut state = Vector3::zeros();
+        let mut rate = Vector3::zeros();
+        let mut accel = Vector3::zeros();
+
+        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
+            .iter()
+            .enumerate()
+        {
+            state[cno] = chebyshev_eval_poly(normalized_time, coeffs, epoch, self.degree())?;
+        }
+
+        // The velocity has its own polynomials, so the acceleration is their derivative.
+        for (cno, coeffs) in [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs]
+            .iter()
+            .enumerate()
+        {
+            (rate[cno], accel[cno]) =
+                chebyshev_eval(normalized_time, coeffs, radius_s, epoch, self.degree())?;
+        }
+
+        Ok((state, rate, accel))
+    }
+
     fn check_integrity(&self) -> Result<(), IntegrityError> {
         // Verify that none of the data is invalid once when we load it.
         for val in self.record_data {
@@ -0,0 +1,233 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+/// Number of epochs between two consecutive entries of an epoch directory.
+pub(crate) const DIRECTORY_STEP: usize = 100;
+
+/// Returns the index of the first epoch greater than or equal to `et`, or the number of epochs if there is none.
+///
+/// The epoch directory stores every 100th epoch, so it is searched first to limit the search to a single block of epochs.
+pub(crate) fn first_index_at_or_after(
+    epoch_data: &[f64],
+    epoch_registry: &[f64],
+    et: f64,
+) -> usize {
+    let dir_idx = epoch_registry.partition_point(|&dir_epoch| dir_epoch < et);
+    // All of the epochs before this block are strictly before the requested epoch.
+    let start_idx = (dir_idx * DIRECTORY_STEP).min(epoch_data.len());
+    let end_idx = (start_idx + DIRECTORY_STEP).min(epoch_data.len());
+
+    start_idx + epoch_data[start_idx..end_idx].partition_point(|&epoch_et| epoch_et < et)
+}
+
+/// Returns the index of the first sample of the interpolation window of `window_size` samples, following the SPICE convention.
+///
+/// If the window size is even, the requested epoch is centered in the window, i.e. there are as many samples before as after it.
+/// If the window size is odd, the window is centered on the sample closest to the requested epoch (the earlier one in case of a tie).
+/// Near the edges of the data, the window is shifted such that it always contains `window_size` samples (or all of them if there are fewer).
+pub(crate) fn window_first_index(
+    epoch_data: &[f64],
+    insertion_idx: usize,
+    et: f64,
+    window_size: usize,
+) -> usize {
+    let num_records = epoch_data.len();
+    let window_size = window_size.min(num_records);
+
+    let first_idx = if window_size % 2 == 0 {
+        insertion_idx.saturating_sub(window_size / 2)
+    } else {
+        let nearest_idx = if insertion_idx == 0 {
+            0
+        } else if insertion_idx == num_records
+            || et - epoch_data[insertion_idx - 1] <= epoch_data[insertion_idx] - et
+        {
+            insertion_idx - 1
+        } else {
+            insertion_idx
+        };
+        nearest_idx.saturating_sub((window_size - 1) / 2)
+    };
+
+    first_idx.min(num_records - window_size)
+}
+
+/// Returns the index of the first sample of the interpolation window of `window_size` samples for equally spaced samples.
+///
+/// The index is computed directly from the epoch of the first sample and the step size, following the same SPICE convention as
+/// [window_first_index], with the exception that an odd window is centered on the later sample in case of a tie (as SPICE rounds half away from zero).
+pub(crate) fn equal_step_window_first_index(
+    num_records: usize,
+    first_et: f64,
+    step_s: f64,
+    et: f64,
+    window_size: usize,
+) -> usize {
+    let window_size = window_size.min(num_records);
+    let steps_since_first = (et - first_et) / step_s;
+
+    let nearest_idx = if window_size % 2 == 0 {
+        steps_since_first.floor()
+    } else {
+        steps_since_first.round()
+    };
+
+    // The cast saturates negative and non-finite values to zero.
+    let first_idx = (nearest_idx as usize).saturating_sub((window_size.max(1) - 1) / 2);
+
+    first_idx.min(num_records - window_size)
+}
+
+#[cfg(test)]
+mod directory_ut {
+    use super::{
+        equal_step_window_first_index, first_index_at_or_after, window_first_index, DIRECTORY_STEP,
+    };
+
+    /// Builds unequally spaced epochs and their directory.
+    fn epochs_and_directory(num: usize) -> (Vec<f64>, Vec<f64>) {
+        let epochs: Vec<f64> = (0..num).map(|i| 10.0 * i as f64 + (i % 3) as f64).collect();
+        let directory = epochs
+            .iter()
+            .skip(DIRECTORY_STEP - 1)
+            .step_by(DIRECTORY_STEP)
+            .copied()
+            .collect();
+        (epochs, directory)
+    }
+
+    #[test]
+    fn directory_matches_linear_search() {
+        for num in [1, 99, 100, 101, 250, 300] {
+            let (epochs, directory) = epochs_and_directory(num);
+            let last = *epochs.last().unwrap();
+            let mut et = -5.0;
+            while et <= last + 5.0 {
+                let expected = epochs.partition_point(|&e| e < et);
+                assert_eq!(
+                    first_index_at_or_after(&epochs, &directory, et),
+                    expected,
+                    "{num} epochs @ {et}"
+                );
+                et += 0.5;
+            }
+            // Exactly on each epoch, including the directory epochs
+            for (idx, et) in epochs.iter().enumerate() {
+                assert_eq!(first_index_at_or_after(&epochs, &directory, *et), idx);
+            }
+        }
+    }
+
+    #[test]
+    fn directory_matches_linear_scan_large_segment() {
+        let (epochs, directory) = epochs_and_directory(50_000);
+        assert_eq!(directory.len(), 500);
+
+        // The sweep is chronological, so the linear scan resumes from the previous result.
+        let mut linear_idx = 0;
+        let mut linear_scan = |et: f64| {
+            while linear_idx < epochs.len() && epochs[linear_idx] < et {
+                linear_idx += 1;
+            }
+            linear_idx
+        };
+
+        let mut queries = vec![epochs[0] - 1.0];
+        for pair in epochs.windows(2) {
+            // Exactly on the interval boundary, just around it, and in the middle of the interval
+            queries.extend([
+                pair[0] - 1e-6,
+                pair[0],
+                pair[0] + 1e-6,
+                0.5 * (pair[0] + pair[1]),
+            ]);
+        }
+        let last = *epochs.last().unwrap();
+        queries.extend([last - 1e-6, last, last + 1e-6, last + 1.0]);
+
+        for et in queries {
+            assert_eq!(
+                first_index_at_or_after(&epochs, &directory, et),
+                linear_scan(et),
+                "@ {et}"
+            );
+        }
+    }
+
+    #[test]
+    fn even_window() {
+        let epochs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        // Between 3 and 4, so the window is [2, 3, 4, 5]
+        assert_eq!(window_first_index(&epochs, 4, 3.5, 4), 2);
+        // Just after a sample
+        assert_eq!(window_first_index(&epochs, 4, 3.01, 4), 2);
+        // Near the start and end, the window is shifted
+        assert_eq!(window_first_index(&epochs, 1, 0.5, 4), 0);
+        assert_eq!(window_first_index(&epochs, 7, 6.5, 4), 4);
+        assert_eq!(window_first_index(&epochs, 8, 7.0, 4), 4);
+        // Window larger than the data
+        assert_eq!(window_first_index(&epochs, 4, 3.5, 10), 0);
+    }
+
+    #[test]
+    fn odd_window() {
+        let epochs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        // Closest to 3, so the window is [2, 3, 4]
+        assert_eq!(window_first_index(&epochs, 4, 3.4, 3), 2);
+        // Closest to 4, so the window is [3, 4, 5]
+        assert_eq!(window_first_index(&epochs, 4, 3.6, 3), 3);
+        // Ties go to the earlier sample
+        assert_eq!(window_first_index(&epochs, 4, 3.5, 3), 2);
+        // Near the start and end, the window is shifted
+        assert_eq!(window_first_index(&epochs, 0, 0.0, 5), 0);
+        assert_eq!(window_first_index(&epochs, 1, 0.9, 5), 0);
+        assert_eq!(window_first_index(&epochs, 7, 6.9, 5), 3);
+    }
+
+    #[test]
+    fn equal_step_window() {
+        // Eight samples from 100 s to 170 s
+        let first_idx =
+            |et, window_size| equal_step_window_first_index(8, 100.0, 10.0, et, window_size);
+
+        // Even window: between 130 and 140, so the window is [2, 3, 4, 5]
+        assert_eq!(first_idx(135.0, 4), 2);
+        assert_eq!(first_idx(130.1, 4), 2);
+        // Odd window: centered on the nearest sample, ties go to the later one
+        assert_eq!(first_idx(134.0, 3), 2);
+        assert_eq!(first_idx(136.0, 3), 3);
+        assert_eq!(first_idx(135.0, 3), 3);
+        // Near the start and end, the window is shifted
+        assert_eq!(first_idx(100.0, 4), 0);
+        assert_eq!(first_idx(105.0, 5), 0);
+        assert_eq!(first_idx(165.0, 4), 4);
+        assert_eq!(first_idx(170.0, 4), 4);
+        assert_eq!(first_idx(170.0, 5), 3);
+        // Window larger than the data
+        assert_eq!(first_idx(135.0, 10), 0);
+
+        // Matches the unequal step computation away from ties and samples (where no interpolation is needed)
+        let epochs: Vec<f64> = (0..8).map(|i| 100.0 + 10.0 * i as f64).collect();
+        for window_size in [2, 3, 4, 5, 8] {
+            let mut et = 100.0;
+            while et <= 170.0 {
+                if (et - 100.0) % 5.0 != 0.0 {
+                    let idx = epochs.partition_point(|&e| e < et);
+                    assert_eq!(
+                        first_idx(et, window_size),
+                        window_first_index(&epochs, idx, et, window_size),
+                        "window of {window_size} @ {et}"
+                    );
+                }
+                et += 0.5;
+            }
+        }
+    }
+}
@@ -0,0 +1,368 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::PI;
+use core::fmt;
+use hifitime::Epoch;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    astro::utils::propagate_universal_variable,
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpMathSnafu, InterpolationError},
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord, NAIFSummaryRecord},
+    DBL_SIZE,
+};
+
+use super::{directory::first_index_at_or_after, posvel::PositionVelocityRecord};
+
+/// Discrete states, propagated with two-body dynamics (SPK Type 5).
+///
+/// The states bracketing the requested epoch are both propagated to that epoch and blended
+/// with the same cosine weighting as SPICE's SPKE05. Outside of the stored states, the nearest
+/// state is propagated.
+#[derive(PartialEq)]
+pub struct DiscreteStatesType5<'a> {
+    pub gm_km3_s2: f64,
+    pub num_records: usize,
+    pub state_data: &'a [f64],
+    pub epoch_data: &'a [f64],
+    pub epoch_registry: &'a [f64],
+}
+
+impl DiscreteStatesType5<'_> {
+    /// Propagates the n-th state to the provided epoch.
+    fn propagate_nth(
+        &self,
+        n: usize,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        let (pos_km, vel_km_s) = self
+            .nth_record(n)
+            .context(InterpDecodingSnafu)?
+            .to_pos_vel();
+
+        propagate_universal_variable(
+            pos_km,
+            vel_km_s,
+            self.gm_km3_s2,
+            epoch.to_et_seconds() - self.epoch_data[n],
+        )
+        .context(InterpMathSnafu)
+    }
+}
+
+impl fmt::Display for DiscreteStatesType5<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Discrete States Type 5 from {:E} to {:E} with GM = {} km^3/s^2 ({} items, {} epoch directories)",
+            Epoch::from_et_seconds(*self.epoch_data.first().unwrap_or(&0.0)),
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            self.gm_km3_s2,
+            self.epoch_data.len(),
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for DiscreteStatesType5<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = PositionVelocityRecord;
+    const DATASET_NAME: &'static str = "Discrete States Type 5";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 9,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 9_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() || num_records_f64 < 1.0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be a finite value greater than zero",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+
+        let gm_km3_s2 = slice[slice.len() - 2];
+        if !gm_km3_s2.is_finite() || gm_km3_s2 <= 0.0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "gravitational parameter",
+                    value: gm_km3_s2,
+                    reason: "must be a finite value greater than zero",
+                },
+            });
+        }
+
+        // NOTE: The ::SIZE returns the C representation memory size of this, but we only want the number of doubles.
+        let state_data_end_idx = PositionVelocityRecord::SIZE / DBL_SIZE * num_records;
+        let state_data =
+            slice
+                .get(0..state_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: state_data_end_idx,
+                    size: slice.len(),
+                })?;
+        let epoch_data_end_idx = state_data_end_idx + num_records;
+        let epoch_data = slice.get(state_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: state_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
+        // And the epoch directory is whatever remains minus the metadata
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 2).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 2,
+                size: slice.len(),
+            },
+        )?;
+
+        Ok(Self {
+            gm_km3_s2,
+            num_records,
+            state_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let rcrd_len = self.state_data.len() / self.num_records;
+        Ok(Self::RecordKind::from_slice_f64(
+            self.state_data
+                .get(n * rcrd_len..(n + 1) * rcrd_len)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: n * rcrd_len,
+                    end: (n + 1) * rcrd_len,
+                    size: self.state_data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let et = epoch.to_et_seconds();
+        if et < summary.start_epoch_et_s() - 1e-7 || et > summary.end_epoch_et_s() + 1e-7 {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+            });
+        }
+
+        // Use the epoch directory to limit the search space.
+        let idx = first_index_at_or_after(self.epoch_data, self.epoch_registry, et);
+
+        // If the requested epoch matches a stored state, no propagation is needed.
+        for state_idx in [idx.saturating_sub(1), idx] {
+            if state_idx < self.num_records && (self.epoch_data[state_idx] - et).abs() < 1e-7 {
+                return Ok(self
+                    .nth_record(state_idx)
+                    .context(InterpDecodingSnafu)?
+                    .to_pos_vel());
+            }
+        }
+
+        if idx == 0 {
+            // Before the first state, so propagate it backward.
+            self.propagate_nth(0, epoch)
+        } else if idx == self.num_records {
+            // After the last state, so propagate it forward.
+            self.propagate_nth(self.num_records - 1, epoch)
+        } else {
+            // Propagate both bracketing states and blend them.
+            let t1 = self.epoch_data[idx - 1];
+            let t2 = self.epoch_data[idx];
+
+            let (pos1_km, vel1_km_s) = self.propagate_nth(idx - 1, epoch)?;
+            let (pos2_km, vel2_km_s) = self.propagate_nth(idx, epoch)?;
+
+            let darg_dt = PI / (t2 - t1);
+            let arg = (et - t1) * darg_dt;
+            let w = 0.5 + 0.5 * arg.cos();
+            let dw_dt = -0.5 * darg_dt * arg.sin();
+
+            let pos_km = w * pos1_km + (1.0 - w) * pos2_km;
+            let vel_km_s = w * vel1_km_s + (1.0 - w) * vel2_km_s + dw_dt * (pos1_km - pos2_km);
+
+            Ok((pos_km, vel_km_s))
+        }
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        // Verify that none of the data is invalid once when we load it.
+        for val in self.epoch_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch data",
+                });
+            }
+        }
+
+        for val in self.epoch_registry {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch registry data",
+                });
+            }
+        }
+
+        for val in self.state_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the state data",
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod discrete_states_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        astro::utils::propagate_universal_variable,
+        errors::{DecodingError, IntegrityError},
+        math::Vector3,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::DiscreteStatesType5;
+
+    const GM: f64 = 398600.4418;
+
+    #[test]
+    fn too_small() {
+        if DiscreteStatesType5::from_f64_slice(&[0.1, 0.2])
+            != Err(DecodingError::TooFewDoubles {
+                dataset: "Discrete States Type 5",
+                got: 2,
+                need: 9,
+            })
+        {
+            panic!("test failure");
+        }
+    }
+
+    #[test]
+    fn invalid_data() {
+        // One state, one epoch, and the metadata
+        let mut data = [0.0_f64; 6 + 1 + 2];
+        data[8] = 1.0;
+        match DiscreteStatesType5::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid GM"),
+            Err(e) => {
+                assert_eq!(
+                    e,
+                    DecodingError::Integrity {
+                        source: IntegrityError::InvalidValue {
+                            dataset: "Discrete States Type 5",
+                            variable: "gravitational parameter",
+                            value: 0.0,
+                            reason: "must be a finite value greater than zero",
+                        },
+                    }
+                );
+            }
+        }
+
+        data[7] = GM;
+        data[0] = f64::NAN;
+        let dataset = DiscreteStatesType5::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(),
+            Err(IntegrityError::SubNormal {
+                dataset: "Discrete States Type 5",
+                variable: "one of the state data",
+            })
+        );
+    }
+
+    #[test]
+    fn two_body_states() {
+        // Build three states on the same Keplerian orbit.
+        let r0 = Vector3::new(7000.0, 0.0, 0.0);
+        let v0 = Vector3::new(0.0, 7.0, 1.0);
+        let epochs = [0.0, 600.0, 1500.0];
+
+        let mut data = Vec::new();
+        let mut states = Vec::new();
+        for et in epochs {
+            let (r, v) = propagate_universal_variable(r0, v0, GM, et).unwrap();
+            data.extend([r.x, r.y, r.z, v.x, v.y, v.z]);
+            states.push((r, v));
+        }
+        data.extend(epochs);
+        data.push(GM);
+        data.push(epochs.len() as f64);
+
+        let dataset = DiscreteStatesType5::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: -300.0,
+            end_epoch_et_s: 1800.0,
+            ..Default::default()
+        };
+
+        // At the state epochs, the answer must match exactly.
+        for (et, (r, v)) in epochs.iter().zip(&states) {
+            let (pos_km, vel_km_s) = dataset
+                .evaluate(Epoch::from_et_seconds(*et), &summary)
+                .unwrap();
+            assert_eq!(pos_km, *r);
+            assert_eq!(vel_km_s, *v);
+        }
+
+        // Since all states are on the same orbit, the blend and the extrapolation must match the two-body solution.
+        for et in [-300.0, 100.0, 1000.0, 1800.0] {
+            let (pos_km, vel_km_s) = dataset
+                .evaluate(Epoch::from_et_seconds(et), &summary)
+                .unwrap();
+            let (r, v) = propagate_universal_variable(r0, v0, GM, et).unwrap();
+            assert!((pos_km - r).norm() < 1e-6, "{et}: {pos_km} != {r}");
+            assert!((vel_km_s - v).norm() < 1e-9, "{et}: {vel_km_s} != {v}");
+        }
+
+        // Outside of the segment
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(1900.0), &summary)
+            .is_err());
+    }
+}
@@ -9,26 +9,29 @@
  */
 
 use core::fmt;
+use core::ops::Range;
 use hifitime::{Duration, Epoch, TimeUnits};
 use snafu::{ensure, ResultExt};
 
 use crate::errors::{DecodingError, IntegrityError, TooFewDoublesSnafu};
-use crate::math::interpolation::{
-    hermite_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES,
-};
+use crate::math::interpolation::{InterpDecodingSnafu, InterpolationError, MAX_SAMPLES};
 use crate::naif::daf::NAIFSummaryRecord;
 use crate::{
-    math::{cartesian::CartesianState, Vector3},
+    math::Vector3,
     naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord},
     DBL_SIZE,
 };
 
-use super::posvel::PositionVelocityRecord;
+use super::{
+    directory::{equal_step_window_first_index, first_index_at_or_after, window_first_index},
+    posvel::{PositionVelocityRecord, PositionVelocityWindow},
+};
 
 #[derive(PartialEq)]
 pub struct HermiteSetType12<'a> {
     pub first_state_epoch: Epoch,
     pub step_size: Duration,
+    /// Number of samples to use to build the interpolation
     pub window_size: usize,
     pub num_records: usize,
     pub record_data: &'a [f64],
@@ -48,8 +51,67 @@ impl fmt::Display for HermiteSetType12<'_> {
     }
 }
 
+impl HermiteSetType12<'_> {
+    /// Returns the index of the record at exactly the provided epoch, if any, and the indexes of the records of the interpolation
+    /// window around that epoch.
+    fn interpolation_window(
+        &self,
+        epoch: Epoch,
+    ) -> Result<(Option<usize>, Range<usize>), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(InterpolationError::MissingInterpolationData { epoch });
+        }
+
+        let first_et = self.first_state_epoch.to_et_seconds();
+        let step_s = self.step_size.to_seconds();
+        if step_s <= 0.0 {
+            return Err(InterpolationError::CorruptedData {
+                what: "Hermite Type 12 step size is not strictly positive",
+            });
+        }
+
+        // Check that we even have interpolation data for that time
+        let last_et = first_et + step_s * (self.num_records - 1) as f64;
+        if epoch.to_et_seconds() < first_et - 1e-7 || epoch.to_et_seconds() > last_et + 1e-7 {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: Epoch::from_et_seconds(last_et),
+            });
+        }
+
+        if self.window_size > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "Hermite Type 12 window size exceeds the maximum number of samples",
+            });
+        }
+
+        // The states are equally spaced, so the nearest state is found without any search.
+        let nearest_idx = ((epoch.to_et_seconds() - first_et) / step_s).round() as usize;
+        let exact_idx = (nearest_idx < self.num_records
+            && first_et + step_s * nearest_idx as f64 == epoch.to_et_seconds())
+        .then_some(nearest_idx);
+
+        let first_idx = equal_step_window_first_index(
+            self.num_records,
+            first_et,
+            step_s,
+            epoch.to_et_seconds(),
+            self.window_size,
+        );
+        let last_idx = self.num_records.min(first_idx + self.window_size);
+
+        Ok((exact_idx, first_idx..last_idx))
+    }
+
+    /// Returns the epoch of the n-th record, in ET seconds.
+    fn nth_epoch_et_s(&self, n: usize) -> f64 {
+        self.first_state_epoch.to_et_seconds() + self.step_size.to_seconds() * n as f64
+    }
+}
+
 impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
-    type StateKind = CartesianState;
+    type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
     const DATASET_NAME: &'static str = "Hermite Type 12";
 
@@ -85,7 +147,19 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
         }
 
         let step_size = step_size_s.seconds();
-        let window_size = slice[slice.len() - 2] as usize;
+        // NOTE: The Type 12 and 13 specify that the windows size minus one is stored!
+        let window_size_f64 = slice[slice.len() - 2];
+        if !window_size_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of interpolation samples",
+                    value: window_size_f64,
+                    reason: "must be a finite value",
+                },
+            });
+        }
+        let window_size = window_size_f64 as usize + 1;
         let num_records = slice[slice.len() - 1] as usize;
 
         Ok(Self {
@@ -112,13 +186,31 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
+        epoch: Epoch,
         _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 14,
-        })
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let (exact_idx, window) = self.interpolation_window(epoch)?;
+        if let Some(idx) = exact_idx {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(idx)
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
+        }
+
+        PositionVelocityWindow::from_records(self, window, |idx| self.nth_epoch_et_s(idx))?
+            .hermite(epoch.to_et_seconds())
+    }
+
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        // The records do not store the acceleration, so the window is interpolated even at the nodes.
+        let (_, window) = self.interpolation_window(epoch)?;
+        PositionVelocityWindow::from_records(self, window, |idx| self.nth_epoch_et_s(idx))?
+            .hermite_with_acceleration(epoch.to_et_seconds())
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
@@ -150,6 +242,45 @@ pub struct HermiteSetType13<'a> {
 }
 
 impl HermiteSetType13<'_> {
+    /// Returns the index of the record at exactly the provided epoch, if any, and the indexes of the records of the interpolation
+    /// window around that epoch.
+    fn interpolation_window(
+        &self,
+        epoch: Epoch,
+    ) -> Result<(Option<usize>, Range<usize>), InterpolationError> {
+        if self.epoch_data.is_empty() {
+            return Err(InterpolationError::MissingInterpolationData { epoch });
+        }
+        // Check that we even have interpolation data for that time
+        if epoch.to_et_seconds() < self.epoch_data[0] - 1e-7
+            || epoch.to_et_seconds() > *self.epoch_data.last().unwrap() + 1e-7
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(self.epoch_data[0]),
+                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
+            });
+        }
+
+        if self.samples > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "Hermite Type 13 window size exceeds the maximum number of samples",
+            });
+        }
+
+        // Use the epoch registry to limit the search space in the total number of epochs.
+        let idx =
+            first_index_at_or_after(self.epoch_data, self.epoch_registry, epoch.to_et_seconds());
+        let exact_idx = (idx < self.num_records && self.epoch_data[idx] == epoch.to_et_seconds())
+            .then_some(idx);
+
+        let first_idx =
+            window_first_index(self.epoch_data, idx, epoch.to_et_seconds(), self.samples);
+        let last_idx = self.num_records.min(first_idx + self.samples);
+
+        Ok((exact_idx, first_idx..last_idx))
+    }
+
     pub fn degree(&self) -> usize {
         2 * self.samples - 1
     }
@@ -266,137 +397,28 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
         epoch: Epoch,
         _: &S,
     ) -> Result<Self::StateKind, InterpolationError> {
-        // Start by doing a binary search on the epoch registry to limit the search space in the total number of epochs.
-        if self.epoch_data.is_empty() {
-            return Err(InterpolationError::NoInterpolationData {
-                req: epoch,
-                start: Epoch::from_et_seconds(self.epoch_data[0]),
-                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
-            });
+        let (exact_idx, window) = self.interpolation_window(epoch)?;
+        if let Some(idx) = exact_idx {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(idx)
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
         }
-        // Check that we even have interpolation data for that time
-        if epoch.to_et_seconds() < self.epoch_data[0] - 1e-7
-            || epoch.to_et_seconds() > *self.epoch_data.last().unwrap() + 1e-7
-        {
-            return Err(InterpolationError::NoInterpolationData {
-                req: epoch,
-                start: Epoch::from_et_seconds(self.epoch_data[0]),
-                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
-            });
-        }
-
-        // Search through a reduced data slice if available
-        let (search_data_slice, slice_offset) = if self.epoch_registry.is_empty() {
-            // No registry, search the entire epoch_data
-            (self.epoch_data, 0)
-        } else {
-            // Use epoch_registry to narrow down the search space.
-            // dir_idx is the index of the first registry epoch such that epoch_registry[dir_idx] >= et_target.
-            let dir_idx = self
-                .epoch_registry
-                .partition_point(|&reg_epoch| reg_epoch < epoch.to_et_seconds());
-
-            let sub_array_start_idx = if dir_idx == 0 {
-                // et_target <= self.epoch_registry[0] (i.e., et_target is before or at the first directory epoch, E_100).
-                // Search in the first block of epoch_data (indices 0-99, or up to num_records-1).
-                0
-            } else {
-                // self.epoch_registry[dir_idx - 1] < et_target.
-                // The block of 100 epochs in epoch_data starts with the epoch corresponding to
-                // the (dir_idx-1)-th entry in epoch_registry. This is E_(dir_idx * 100).
-                // Its 0-based index in epoch_data is (dir_idx * 100) - 1.
-                (dir_idx * 100) - 1
-            };
-
-            // The block is at most 100 records long, or fewer if at the end of epoch_data.
-            // Ensure end index does not exceed total number of records.
-            let sub_array_end_idx = (sub_array_start_idx + 99).min(self.num_records - 1);
-
-            // It's possible num_records is small enough that sub_array_start_idx is already past sub_array_end_idx if not careful,
-            // however, epoch_registry is non-empty only if num_records >= 100 (approx), so sub_array_start_idx should be valid.
-            // The slice must be valid, e.g. start <= end.
-            (
-                &self.epoch_data[sub_array_start_idx..=sub_array_end_idx.max(sub_array_start_idx)],
-                sub_array_start_idx,
-            )
-        };
-
-        match search_data_slice.binary_search_by(|epoch_et| {
-            epoch_et
-                .partial_cmp(&epoch.to_et_seconds())
-                .expect("epochs in Hermite data is now NaN or infinite but was not before")
-        }) {
-            Ok(idx) => {
-                // Oh wow, this state actually exists, no interpolation needed!
-                Ok(self
-                    .nth_record(idx + slice_offset)
-                    .context(InterpDecodingSnafu)?
-                    .to_pos_vel())
-            }
-            Err(idx) => {
-                // We didn't find et_target exactly. `idx` is the insertion point in `search_data_slice`.
-                // Convert `idx` (local insertion point) to an absolute index in `self.epoch_data`.
-                let absolute_insertion_idx = idx + slice_offset;
-                let num_left = self.samples / 2;
-
-                // Ensure that we aren't fetching out of the window
-                let mut first_idx = absolute_insertion_idx.saturating_sub(num_left);
-                let last_idx = self.num_records.min(first_idx + self.samples);
-
-                // Check that we have enough samples
-                if last_idx == self.num_records {
-                    first_idx = last_idx - 2 * num_left;
-                }
 
-                // Statically allocated arrays of the maximum number of samples
-                let mut epochs = [0.0; MAX_SAMPLES];
-                let mut xs = [0.0; MAX_SAMPLES];
-                let mut ys = [0.0; MAX_SAMPLES];
-                let mut zs = [0.0; MAX_SAMPLES];
-                let mut vxs = [0.0; MAX_SAMPLES];
-                let mut vys = [0.0; MAX_SAMPLES];
-                let mut vzs = [0.0; MAX_SAMPLES];
-                for (cno, idx) in (first_idx..last_idx).enumerate() {
-                    let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
-                    xs[cno] = record.x_km;
-                    ys[cno] = record.y_km;
-                    zs[cno] = record.z_km;
-                    vxs[cno] = record.vx_km_s;
-                    vys[cno] = record.vy_km_s;
-                    vzs[cno] = record.vz_km_s;
-                    epochs[cno] = self.epoch_data[idx];
-                }
+        PositionVelocityWindow::from_records(self, window, |idx| self.epoch_data[idx])?
+            .hermite(epoch.to_et_seconds())
+    }
 
-                // Build the interpolation polynomials making sure to limit the slices to exactly the number of items we actually used
-                // The other ones are zeros, which would cause the interpolation function to fail.
-                let (x_km, vx_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &xs[..self.samples],
-                    &vxs[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (y_km, vy_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &ys[..self.samples],
-                    &vys[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (z_km, vz_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &zs[..self.samples],
-                    &vzs[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
-
-                // And build the result
-                let pos_km = Vector3::new(x_km, y_km, z_km);
-                let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
-
-                Ok((pos_km, vel_km_s))
-            }
-        }
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        // The records do not store the acceleration, so the window is interpolated even at the nodes.
+        let (_, window) = self.interpolation_window(epoch)?;
+        PositionVelocityWindow::from_records(self, window, |idx| self.epoch_data[idx])?
+            .hermite_with_acceleration(epoch.to_et_seconds())
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
@@ -434,12 +456,133 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
 
 #[cfg(test)]
 mod hermite_ut {
+    use hifitime::Epoch;
+
     use crate::{
         errors::{DecodingError, IntegrityError},
-        naif::daf::NAIFDataSet,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
     };
 
-    use super::HermiteSetType13;
+    use super::{HermiteSetType12, HermiteSetType13};
+
+    /// Quintic polynomial, which Hermite interpolations of three samples and above reproduce exactly.
+    fn poly(t: f64) -> (f64, f64) {
+        (
+            1e-14 * t.powi(5) - 1e-5 * t.powi(3) + 1e-2 * t.powi(2) + t,
+            5e-14 * t.powi(4) - 3e-5 * t.powi(2) + 2e-2 * t + 1.0,
+        )
+    }
+
+    #[test]
+    fn window_boundaries() {
+        let summary = SPKSummaryRecord::default();
+        let num_records = 250;
+        let epochs: Vec<f64> = (0..num_records)
+            .map(|i| 10.0 * i as f64 + (i % 3) as f64)
+            .collect();
+
+        for samples in [3, 4] {
+            let mut data = Vec::new();
+            for et in &epochs {
+                let (x, vx) = poly(*et);
+                data.extend([x, -x, 2.0 * x, vx, -vx, 2.0 * vx]);
+            }
+            data.extend(&epochs);
+            data.extend(epochs.iter().skip(99).step_by(100));
+            // Type 13 stores the window size minus one
+            data.push((samples - 1) as f64);
+            data.push(num_records as f64);
+
+            let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.samples, samples);
+            assert_eq!(dataset.epoch_registry.len(), 2);
+            dataset.check_integrity().unwrap();
+
+            // Around the start, the end, and the directory epochs
+            for idx in [0, 1, 2, 98, 99, 100, 101, 198, 199, 200, 247, 248, 249] {
+                for offset in [-0.5, 0.0, 0.5] {
+                    let et = epochs[idx] + offset;
+                    if et < epochs[0] || et > epochs[num_records - 1] {
+                        continue;
+                    }
+                    let (pos_km, vel_km_s) = dataset
+                        .evaluate(Epoch::from_et_seconds(et), &summary)
+                        .unwrap();
+                    let (x, vx) = poly(Epoch::from_et_seconds(et).to_et_seconds());
+                    assert!(
+                        (pos_km.x - x).abs() < 1e-6 && (pos_km.z - 2.0 * x).abs() < 1e-6,
+                        "{samples} samples @ {et}: {} != {x}",
+                        pos_km.x
+                    );
+                    assert!(
+                        (vel_km_s.y + vx).abs() < 1e-9,
+                        "{samples} samples @ {et}: {} != {vx}",
+                        vel_km_s.y
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn type12_equal_step() {
+        let summary = SPKSummaryRecord::default();
+        let num_records = 50;
+        let (first_et, step_s) = (100.0, 10.0);
+
+        for samples in [3, 4] {
+            let mut data = Vec::new();
+            for i in 0..num_records {
+                let (x, vx) = poly(first_et + step_s * i as f64);
+                data.extend([x, -x, 2.0 * x, vx, -vx, 2.0 * vx]);
+            }
+            // Type 12 stores the window size minus one
+            data.extend([first_et, step_s, (samples - 1) as f64, num_records as f64]);
+
+            let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.window_size, samples);
+            assert_eq!(dataset.num_records, num_records);
+            dataset.check_integrity().unwrap();
+
+            // Exactly on the states, the stored state is returned
+            for idx in [0, 1, 25, 48, 49] {
+                let et = first_et + step_s * idx as f64;
+                let (pos_km, vel_km_s) = dataset
+                    .evaluate(Epoch::from_et_seconds(et), &summary)
+                    .unwrap();
+                assert_eq!(pos_km.x, data[6 * idx]);
+                assert_eq!(vel_km_s.z, data[6 * idx + 5]);
+            }
+
+            // Between the states, including in the windows clamped at the start and the end of the segment
+            for idx in [0, 1, 2, 24, 25, 46, 47, 48] {
+                for offset in [0.5, 2.5, 5.0, 7.5, 9.5] {
+                    let et = first_et + step_s * idx as f64 + offset;
+                    let (pos_km, vel_km_s) = dataset
+                        .evaluate(Epoch::from_et_seconds(et), &summary)
+                        .unwrap();
+                    let (x, vx) = poly(Epoch::from_et_seconds(et).to_et_seconds());
+                    assert!(
+                        (pos_km.x - x).abs() < 1e-6 && (pos_km.z - 2.0 * x).abs() < 1e-6,
+                        "{samples} samples @ {et}: {} != {x}",
+                        pos_km.x
+                    );
+                    assert!(
+                        (vel_km_s.y + vx).abs() < 1e-9,
+                        "{samples} samples @ {et}: {} != {vx}",
+                        vel_km_s.y
+                    );
+                }
+            }
+
+            let last_et = first_et + step_s * (num_records - 1) as f64;
+            for et in [first_et - 1.0, last_et + 1.0] {
+                assert!(dataset
+                    .evaluate(Epoch::from_et_seconds(et), &summary)
+                    .is_err());
+            }
+        }
+    }
 
     #[test]
     fn too_small() {
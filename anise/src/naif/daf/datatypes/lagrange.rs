@@ -9,21 +9,24 @@
  */
 
 use core::fmt;
+use core::ops::Range;
 use hifitime::{Duration, Epoch, TimeUnits};
 use snafu::{ensure, ResultExt};
 
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        cartesian::CartesianState,
-        interpolation::{lagrange_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES},
+        interpolation::{InterpDecodingSnafu, InterpolationError, MAX_SAMPLES},
         Vector3,
     },
     naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord, NAIFSummaryRecord},
     DBL_SIZE,
 };
 
-use super::posvel::PositionVelocityRecord;
+use super::{
+    directory::{equal_step_window_first_index, first_index_at_or_after, window_first_index},
+    posvel::{PositionVelocityRecord, PositionVelocityWindow},
+};
 
 #[derive(PartialEq)]
 pub struct LagrangeSetType8<'a> {
@@ -48,8 +51,68 @@ impl fmt::Display for LagrangeSetType8<'_> {
     }
 }
 
+impl LagrangeSetType8<'_> {
+    /// Returns the index of the record at exactly the provided epoch, if any, and the indexes of the records of the interpolation
+    /// window around that epoch.
+    fn interpolation_window(
+        &self,
+        epoch: Epoch,
+    ) -> Result<(Option<usize>, Range<usize>), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(InterpolationError::MissingInterpolationData { epoch });
+        }
+
+        let first_et = self.first_state_epoch.to_et_seconds();
+        let step_s = self.step_size.to_seconds();
+        if step_s <= 0.0 {
+            return Err(InterpolationError::CorruptedData {
+                what: "Lagrange Type 8 step size is not strictly positive",
+            });
+        }
+
+        // Check that we even have interpolation data for that time
+        let last_et = first_et + step_s * (self.num_records - 1) as f64;
+        if epoch.to_et_seconds() < first_et - 1e-7 || epoch.to_et_seconds() > last_et + 1e-7 {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: Epoch::from_et_seconds(last_et),
+            });
+        }
+
+        let group_size = self.degree + 1;
+        if group_size > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "Lagrange Type 8 window size exceeds the maximum number of samples",
+            });
+        }
+
+        // The states are equally spaced, so the nearest state is found without any search.
+        let nearest_idx = ((epoch.to_et_seconds() - first_et) / step_s).round() as usize;
+        let exact_idx = (nearest_idx < self.num_records
+            && first_et + step_s * nearest_idx as f64 == epoch.to_et_seconds())
+        .then_some(nearest_idx);
+
+        let first_idx = equal_step_window_first_index(
+            self.num_records,
+            first_et,
+            step_s,
+            epoch.to_et_seconds(),
+            group_size,
+        );
+        let last_idx = self.num_records.min(first_idx + group_size);
+
+        Ok((exact_idx, first_idx..last_idx))
+    }
+
+    /// Returns the epoch of the n-th record, in ET seconds.
+    fn nth_epoch_et_s(&self, n: usize) -> f64 {
+        self.first_state_epoch.to_et_seconds() + self.step_size.to_seconds() * n as f64
+    }
+}
+
 impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
-    type StateKind = CartesianState;
+    type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
     const DATASET_NAME: &'static str = "Lagrange Type 8";
 
@@ -79,7 +142,7 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
         if !step_size_s.is_finite() {
             return Err(DecodingError::Integrity {
                 source: IntegrityError::SubNormal {
-                    dataset: "Hermite Type 12",
+                    dataset: Self::DATASET_NAME,
                     variable: "step size in seconds",
                 },
             });
@@ -113,13 +176,34 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
+        epoch: Epoch,
         _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 12,
-        })
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let (exact_idx, window) = self.interpolation_window(epoch)?;
+        if let Some(idx) = exact_idx {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(idx)
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
+        }
+
+        let (pos_km, vel_km_s, _) =
+            PositionVelocityWindow::from_records(self, window, |idx| self.nth_epoch_et_s(idx))?
+                .lagrange(epoch.to_et_seconds())?;
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        // The records do not store the acceleration, so the window is interpolated even at the nodes.
+        let (_, window) = self.interpolation_window(epoch)?;
+        PositionVelocityWindow::from_records(self, window, |idx| self.nth_epoch_et_s(idx))?
+            .lagrange(epoch.to_et_seconds())
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
@@ -159,6 +243,45 @@ impl fmt::Display for LagrangeSetType9<'_> {
     }
 }
 
+impl LagrangeSetType9<'_> {
+    /// Returns the index of the record at exactly the provided epoch, if any, and the indexes of the records of the interpolation
+    /// window around that epoch.
+    fn interpolation_window(
+        &self,
+        epoch: Epoch,
+    ) -> Result<(Option<usize>, Range<usize>), InterpolationError> {
+        // Check that we even have interpolation data for that time
+        if self.epoch_data.is_empty()
+            || epoch.to_et_seconds() < self.epoch_data[0] - 1e-7
+            || epoch.to_et_seconds() > *self.epoch_data.last().unwrap() + 1e-7
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(*self.epoch_data.first().unwrap_or(&0.0)),
+                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            });
+        }
+
+        let group_size = self.degree + 1;
+        if group_size > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "Lagrange Type 9 window size exceeds the maximum number of samples",
+            });
+        }
+
+        // Use the epoch registry to limit the search space in the total number of epochs.
+        let idx =
+            first_index_at_or_after(self.epoch_data, self.epoch_registry, epoch.to_et_seconds());
+        let exact_idx = (idx < self.num_records && self.epoch_data[idx] == epoch.to_et_seconds())
+            .then_some(idx);
+
+        let first_idx = window_first_index(self.epoch_data, idx, epoch.to_et_seconds(), group_size);
+        let last_idx = self.num_records.min(first_idx + group_size);
+
+        Ok((exact_idx, first_idx..last_idx))
+    }
+}
+
 impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
     type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
@@ -212,112 +335,31 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
         epoch: Epoch,
         _: &S,
     ) -> Result<Self::StateKind, InterpolationError> {
-        // Start by doing a binary search on the epoch registry to limit the search space in the total number of epochs.
-        // TODO: use the epoch registry to reduce the search space
-        // Check that we even have interpolation data for that time
-        if epoch.to_et_seconds() < self.epoch_data[0] - 1e-7
-            || epoch.to_et_seconds() > *self.epoch_data.last().unwrap() + 1e-7
-        {
-            return Err(InterpolationError::NoInterpolationData {
-                req: epoch,
-                start: Epoch::from_et_seconds(self.epoch_data[0]),
-                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
-            });
+        let (exact_idx, window) = self.interpolation_window(epoch)?;
+        if let Some(idx) = exact_idx {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(idx)
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
         }
-        // Now, perform a binary search on the epochs themselves.
-        match self.epoch_data.binary_search_by(|epoch_et| {
-            epoch_et
-                .partial_cmp(&epoch.to_et_seconds())
-                .expect("epochs in Hermite data is now NaN or infinite but was not before")
-        }) {
-            Ok(idx) => {
-                // Oh wow, this state actually exists, no interpolation needed!
-                Ok(self
-                    .nth_record(idx)
-                    .context(InterpDecodingSnafu)?
-                    .to_pos_vel())
-            }
-            Err(idx) => {
-                // We didn't find it, so let's build an interpolation here.
-                let group_size = self.degree + 1;
-                let num_left = group_size / 2;
-
-                // Ensure that we aren't fetching out of the window
-                let mut first_idx = idx.saturating_sub(num_left);
-                let last_idx = self.num_records.min(first_idx + group_size);
-
-                // Check that we have enough samples
-                if last_idx == self.num_records {
-                    first_idx = last_idx - 2 * num_left;
-                }
 
-                // Statically allocated arrays of the maximum number of samples
-                let mut epochs = [0.0; MAX_SAMPLES];
-                let mut xs = [0.0; MAX_SAMPLES];
-                let mut ys = [0.0; MAX_SAMPLES];
-                let mut zs = [0.0; MAX_SAMPLES];
-                let mut vxs = [0.0; MAX_SAMPLES];
-                let mut vys = [0.0; MAX_SAMPLES];
-                let mut vzs = [0.0; MAX_SAMPLES];
-
-                for (cno, idx) in (first_idx..last_idx).enumerate() {
-                    let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
-                    xs[cno] = record.x_km;
-                    ys[cno] = record.y_km;
-                    zs[cno] = record.z_km;
-                    vxs[cno] = record.vx_km_s;
-                    vys[cno] = record.vy_km_s;
-                    vzs[cno] = record.vz_km_s;
-                    epochs[cno] = self.epoch_data[idx];
-                }
+        let (pos_km, vel_km_s, _) =
+            PositionVelocityWindow::from_records(self, window, |idx| self.epoch_data[idx])?
+                .lagrange(epoch.to_et_seconds())?;
 
-                // TODO: Build a container that uses the underlying data and provides an index into it.
-
-                // Build the interpolation polynomials making sure to limit the slices to exactly the number of items we actually used
-                // The other ones are zeros, which would cause the interpolation function to fail.
-                let (x_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &xs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (y_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &ys[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (z_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &zs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vx_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vxs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vy_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vys[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vz_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vzs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                // And build the result
-                let pos_km = Vector3::new(x_km, y_km, z_km);
-                let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
-
-                Ok((pos_km, vel_km_s))
-            }
-        }
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        // The records do not store the acceleration, so the window is interpolated even at the nodes.
+        let (_, window) = self.interpolation_window(epoch)?;
+        PositionVelocityWindow::from_records(self, window, |idx| self.epoch_data[idx])?
+            .lagrange(epoch.to_et_seconds())
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
@@ -352,3 +394,138 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod lagrange_ut {
+    use hifitime::Epoch;
+
+    use crate::naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord};
+
+    use super::{LagrangeSetType8, LagrangeSetType9};
+
+    /// Cubic polynomial, which Lagrange interpolations of degree 3 and above reproduce exactly.
+    fn poly(t: f64) -> (f64, f64) {
+        (
+            -1e-5 * t.powi(3) + 1e-2 * t.powi(2) + t,
+            -3e-5 * t.powi(2) + 2e-2 * t + 1.0,
+        )
+    }
+
+    /// Builds a Type 9 segment of unequally spaced states, with its epoch directory.
+    fn build_segment(num_records: usize, degree: usize) -> Vec<f64> {
+        let epochs: Vec<f64> = (0..num_records)
+            .map(|i| 10.0 * i as f64 + (i % 3) as f64)
+            .collect();
+        let mut data = Vec::new();
+        for et in &epochs {
+            let (x, vx) = poly(*et);
+            data.extend([x, -x, 2.0 * x, vx, -vx, 2.0 * vx]);
+        }
+        data.extend(&epochs);
+        data.extend(epochs.iter().skip(99).step_by(100));
+        data.push(degree as f64);
+        data.push(num_records as f64);
+        data
+    }
+
+    #[test]
+    fn window_boundaries() {
+        let summary = SPKSummaryRecord::default();
+        for degree in [3, 4] {
+            let data = build_segment(250, degree);
+            let dataset = LagrangeSetType9::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.epoch_registry.len(), 2);
+            dataset.check_integrity().unwrap();
+
+            // Around the start, the end, and the directory epochs
+            for idx in [0, 1, 2, 98, 99, 100, 101, 198, 199, 200, 247, 248, 249] {
+                for offset in [-0.5, 0.0, 0.5] {
+                    let et = dataset.epoch_data[idx] + offset;
+                    if et < dataset.epoch_data[0] || et > dataset.epoch_data[249] {
+                        continue;
+                    }
+                    let (pos_km, vel_km_s) = dataset
+                        .evaluate(Epoch::from_et_seconds(et), &summary)
+                        .unwrap();
+                    let (x, vx) = poly(Epoch::from_et_seconds(et).to_et_seconds());
+                    assert!(
+                        (pos_km.x - x).abs() < 1e-6 && (pos_km.z - 2.0 * x).abs() < 1e-6,
+                        "degree {degree} @ {et}: {} != {x}",
+                        pos_km.x
+                    );
+                    assert!(
+                        (vel_km_s.y + vx).abs() < 1e-9,
+                        "degree {degree} @ {et}: {} != {vx}",
+                        vel_km_s.y
+                    );
+                }
+            }
+
+            assert!(dataset
+                .evaluate(
+                    Epoch::from_et_seconds(dataset.epoch_data[249] + 1.0),
+                    &summary
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn type8_equal_step() {
+        let summary = SPKSummaryRecord::default();
+        let num_records = 50;
+        let (first_et, step_s) = (100.0, 10.0);
+
+        for degree in [3, 4] {
+            let mut data = Vec::new();
+            for i in 0..num_records {
+                let (x, vx) = poly(first_et + step_s * i as f64);
+                data.extend([x, -x, 2.0 * x, vx, -vx, 2.0 * vx]);
+            }
+            data.extend([first_et, step_s, degree as f64, num_records as f64]);
+
+            let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.degree, degree);
+            assert_eq!(dataset.num_records, num_records);
+            dataset.check_integrity().unwrap();
+
+            // Exactly on the states, the stored state is returned
+            for idx in [0, 1, 25, 48, 49] {
+                let et = first_et + step_s * idx as f64;
+                let (pos_km, vel_km_s) = dataset
+                    .evaluate(Epoch::from_et_seconds(et), &summary)
+                    .unwrap();
+                assert_eq!(pos_km.x, data[6 * idx]);
+                assert_eq!(vel_km_s.z, data[6 * idx + 5]);
+            }
+
+            // Between the states, including in the windows clamped at the start and the end of the segment
+            for idx in [0, 1, 2, 24, 25, 46, 47, 48] {
+                for offset in [0.5, 2.5, 5.0, 7.5, 9.5] {
+                    let et = first_et + step_s * idx as f64 + offset;
+                    let (pos_km, vel_km_s) = dataset
+                        .evaluate(Epoch::from_et_seconds(et), &summary)
+                        .unwrap();
+                    let (x, vx) = poly(Epoch::from_et_seconds(et).to_et_seconds());
+                    assert!(
+                        (pos_km.x - x).abs() < 1e-6 && (pos_km.z - 2.0 * x).abs() < 1e-6,
+                        "degree {degree} @ {et}: {} != {x}",
+                        pos_km.x
+                    );
+                    assert!(
+                        (vel_km_s.y + vx).abs() < 1e-9,
+                        "degree {degree} @ {et}: {} != {vx}",
+                        vel_km_s.y
+                    );
+                }
+            }
+
+            let last_et = first_et + step_s * (num_records - 1) as f64;
+            for et in [first_et - 1.0, last_et + 1.0] {
+                assert!(dataset
+                    .evaluate(Epoch::from_et_seconds(et), &summary)
+                    .is_err());
+            }
+        }
+    }
+}
@@ -10,11 +10,16 @@
 
 pub mod chebyshev;
 pub mod chebyshev3;
+pub(crate) mod directory;
+pub mod discrete_states;
 pub mod hermite;
 pub mod lagrange;
+pub mod modified_difference;
 pub mod posvel;
 
 pub use chebyshev::*;
 pub use chebyshev3::*;
+pub use discrete_states::*;
 pub use hermite::*;
 pub use lagrange::*;
+pub use modified_difference::*;
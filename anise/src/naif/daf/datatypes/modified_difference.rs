@@ -0,0 +1,493 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::Epoch;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, MathError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpolationError},
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+use super::directory::first_index_at_or_after;
+
+/// Maximum number of difference lines in a Type 1 record (MAXDIM in SPICE).
+const MAX_DIM: usize = 15;
+
+/// Number of doubles in a single Type 1 record.
+const RECORD_LEN: usize = 71;
+
+/// A Modified Difference Array record, as stored in the SPK Type 1 segments.
+/// Each record stores the reference epoch and state, the integrator step size
+/// history, and the difference table for each of the three position components.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ModifiedDifferenceArray {
+    /// Reference epoch of this difference line, in seconds past J2000 TDB
+    pub reference_epoch_et_s: f64,
+    /// Step size function vector (G in SPICE)
+    pub step_sizes: [f64; MAX_DIM],
+    pub ref_pos_km: Vector3,
+    pub ref_vel_km_s: Vector3,
+    /// Modified divided difference arrays, one per position component (DT in SPICE)
+    pub diff_table: [[f64; MAX_DIM]; 3],
+    /// Maximum integration order plus one (KQMAX1 in SPICE)
+    pub kqmax1: usize,
+    /// Integration order of each component (KQ in SPICE)
+    pub kq: [usize; 3],
+}
+
+impl ModifiedDifferenceArray {
+    /// Evaluates the position and velocity of this record at the provided epoch, following the SPKE01 algorithm.
+    pub fn evaluate(&self, epoch: Epoch) -> Result<(Vector3, Vector3), InterpolationError> {
+        if self.kqmax1 < 2 || self.kqmax1 > MAX_DIM + 1 {
+            return Err(InterpolationError::CorruptedData {
+                what: "Type 1 maximum integration order plus one (KQMAX1) must be within [2; 16]",
+            });
+        }
+
+        if self.kq.iter().any(|kq| *kq > MAX_DIM) {
+            return Err(InterpolationError::CorruptedData {
+                what: "Type 1 integration order must be at most 15",
+            });
+        }
+
+        let delta = epoch.to_et_seconds() - self.reference_epoch_et_s;
+
+        // Compute the step size coefficients. SPICE uses one-indexed arrays, so these are offset by one.
+        let mq2 = self.kqmax1 - 2;
+        let mut fc = [0.0_f64; MAX_DIM + 1];
+        let mut wc = [0.0_f64; MAX_DIM];
+        fc[0] = 1.0;
+        let mut tp = delta;
+        for (j, step_size) in self.step_sizes.iter().enumerate().take(mq2) {
+            if step_size.abs() < f64::EPSILON {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action: "step size of Type 1 record is zero",
+                    },
+                });
+            }
+            fc[j + 1] = tp / step_size;
+            wc[j] = delta / step_size;
+            tp = delta + step_size;
+        }
+
+        // Integration coefficients
+        let mut w = [0.0_f64; MAX_DIM + 3];
+        for (j, wj) in w.iter_mut().enumerate().take(self.kqmax1) {
+            *wj = 1.0 / (j + 1) as f64;
+        }
+
+        // Compute the W(K) terms needed for the position interpolation.
+        let mut jx = 0;
+        let mut ks = self.kqmax1 - 1;
+        let mut ks1 = ks - 1;
+
+        while ks >= 2 {
+            jx += 1;
+            for (j, wcj) in wc.iter().enumerate().take(jx) {
+                w[j + ks] = fc[j + 1] * w[j + ks1] - wcj * w[j + ks];
+            }
+            ks = ks1;
+            ks1 -= 1;
+        }
+
+        let mut pos_km = Vector3::zeros();
+        for (i, (diffs, kq)) in self.diff_table.iter().zip(self.kq).enumerate() {
+            let sum: f64 = (0..kq).rev().map(|j| diffs[j] * w[j + ks]).sum();
+            pos_km[i] = self.ref_pos_km[i] + delta * (self.ref_vel_km_s[i] + delta * sum);
+        }
+
+        // Update the W(K) terms for the velocity interpolation (at this point, KS is one and KS1 is zero).
+        for (j, wcj) in wc.iter().enumerate().take(jx) {
+            w[j + ks] = fc[j + 1] * w[j + ks1] - wcj * w[j + ks];
+        }
+        ks -= 1;
+
+        let mut vel_km_s = Vector3::zeros();
+        for (i, (diffs, kq)) in self.diff_table.iter().zip(self.kq).enumerate() {
+            let sum: f64 = (0..kq).rev().map(|j| diffs[j] * w[j + ks]).sum();
+            vel_km_s[i] = self.ref_vel_km_s[i] + delta * sum;
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+}
+
+impl fmt::Display for ModifiedDifferenceArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MDA reference epoch: {:E}\torder: {}",
+            Epoch::from_et_seconds(self.reference_epoch_et_s),
+            self.kqmax1 - 1
+        )
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for ModifiedDifferenceArray {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let mut step_sizes = [0.0; MAX_DIM];
+        step_sizes.copy_from_slice(&slice[1..1 + MAX_DIM]);
+
+        // The reference position and velocity are interleaved.
+        let ref_pos_km = Vector3::new(slice[16], slice[18], slice[20]);
+        let ref_vel_km_s = Vector3::new(slice[17], slice[19], slice[21]);
+
+        // The difference table is stored in column major order.
+        let mut diff_table = [[0.0; MAX_DIM]; 3];
+        for (i, component) in diff_table.iter_mut().enumerate() {
+            let start = 22 + i * MAX_DIM;
+            component.copy_from_slice(&slice[start..start + MAX_DIM]);
+        }
+
+        Self {
+            reference_epoch_et_s: slice[0],
+            step_sizes,
+            ref_pos_km,
+            ref_vel_km_s,
+            diff_table,
+            kqmax1: slice[67] as usize,
+            kq: [slice[68] as usize, slice[69] as usize, slice[70] as usize],
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct Type1ModifiedDifferenceSet<'a> {
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+    pub epoch_data: &'a [f64],
+    pub epoch_registry: &'a [f64],
+}
+
+impl Type1ModifiedDifferenceSet<'_> {
+    /// Returns the index of the record covering the requested epoch, i.e. the first record whose final epoch is after the requested epoch.
+    fn record_idx(&self, epoch: Epoch) -> Result<usize, InterpolationError> {
+        let et = epoch.to_et_seconds();
+        let last_epoch_et_s = *self
+            .epoch_data
+            .last()
+            .ok_or(InterpolationError::MissingInterpolationData { epoch })?;
+
+        if et > last_epoch_et_s + 1e-7 {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(self.epoch_data[0]),
+                end: Epoch::from_et_seconds(last_epoch_et_s),
+            });
+        }
+
+        // Use the epoch directory to limit the search space.
+        let idx = first_index_at_or_after(self.epoch_data, self.epoch_registry, et);
+
+        // Allow for the rounding tolerance at the very end of the segment.
+        Ok(idx.min(self.num_records - 1))
+    }
+}
+
+impl fmt::Display for Type1ModifiedDifferenceSet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Modified Difference Array Type 1 until {:E} ({} items, {} epoch directories)",
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for Type1ModifiedDifferenceSet<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = ModifiedDifferenceArray;
+    const DATASET_NAME: &'static str = "Modified Difference Array Type 1";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= RECORD_LEN + 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: RECORD_LEN + 2,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the number of records is stored at the very end of the dataset
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() || num_records_f64 < 1.0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be a finite value greater than zero",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+
+        let record_data_end_idx = RECORD_LEN * num_records;
+        let record_data =
+            slice
+                .get(0..record_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: record_data_end_idx,
+                    size: slice.len(),
+                })?;
+
+        let epoch_data_end_idx = record_data_end_idx + num_records;
+        let epoch_data = slice.get(record_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: record_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
+
+        // And the epoch directory is whatever remains minus the number of records
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 1).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 1,
+                size: slice.len(),
+            },
+        )?;
+
+        Ok(Self {
+            num_records,
+            record_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(
+            self.record_data
+                .get(n * RECORD_LEN..(n + 1) * RECORD_LEN)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: n * RECORD_LEN,
+                    end: (n + 1) * RECORD_LEN,
+                    size: self.record_data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if epoch.to_et_seconds() < summary.start_epoch_et_s() - 1e-7 {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+            });
+        }
+
+        let idx = self.record_idx(epoch)?;
+
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch)
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for val in self.record_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the record data",
+                });
+            }
+        }
+
+        for val in self.epoch_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch data",
+                });
+            }
+        }
+
+        for val in self.epoch_registry {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch registry data",
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod modified_difference_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::Vector3,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::{Type1ModifiedDifferenceSet, RECORD_LEN};
+
+    /// Builds a record of constant acceleration: with an integration order of one, the MDA reduces to a second order polynomial.
+    fn constant_accel_record(ref_et_s: f64, accel: [f64; 3]) -> [f64; RECORD_LEN] {
+        let mut rcrd = [0.0; RECORD_LEN];
+        rcrd[0] = ref_et_s;
+        rcrd[1] = 60.0;
+        // Interleaved reference position and velocity
+        rcrd[16] = 7000.0;
+        rcrd[17] = 1.0;
+        rcrd[18] = -100.0;
+        rcrd[19] = 7.5;
+        rcrd[20] = 25.0;
+        rcrd[21] = -0.5;
+        rcrd[22] = accel[0];
+        rcrd[22 + 15] = accel[1];
+        rcrd[22 + 30] = accel[2];
+        rcrd[67] = 2.0;
+        rcrd[68] = 1.0;
+        rcrd[69] = 1.0;
+        rcrd[70] = 1.0;
+        rcrd
+    }
+
+    #[test]
+    fn too_small() {
+        if Type1ModifiedDifferenceSet::from_f64_slice(&[0.1, 0.2])
+            != Err(DecodingError::TooFewDoubles {
+                dataset: "Modified Difference Array Type 1",
+                got: 2,
+                need: RECORD_LEN + 2,
+            })
+        {
+            panic!("test failure");
+        }
+    }
+
+    #[test]
+    fn invalid_data() {
+        let mut data = vec![0.0; RECORD_LEN + 2];
+        data[RECORD_LEN + 1] = f64::INFINITY;
+        match Type1ModifiedDifferenceSet::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid num records"),
+            Err(e) => {
+                assert_eq!(
+                    e,
+                    DecodingError::Integrity {
+                        source: IntegrityError::InvalidValue {
+                            dataset: "Modified Difference Array Type 1",
+                            variable: "number of records",
+                            value: f64::INFINITY,
+                            reason: "must be a finite value greater than zero",
+                        },
+                    }
+                );
+            }
+        }
+
+        data[RECORD_LEN + 1] = 1.0;
+        data[RECORD_LEN] = f64::NAN;
+        let dataset = Type1ModifiedDifferenceSet::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(),
+            Err(IntegrityError::SubNormal {
+                dataset: "Modified Difference Array Type 1",
+                variable: "one of the epoch data",
+            })
+        );
+    }
+
+    #[test]
+    fn constant_acceleration() {
+        let accel = [1e-3, -2e-3, 5e-4];
+        let mut data = constant_accel_record(0.0, accel).to_vec();
+        // Final epoch of the record
+        data.push(3600.0);
+        // Number of records
+        data.push(1.0);
+
+        let dataset = Type1ModifiedDifferenceSet::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: -3600.0,
+            end_epoch_et_s: 3600.0,
+            ..Default::default()
+        };
+
+        let r0 = Vector3::new(7000.0, -100.0, 25.0);
+        let v0 = Vector3::new(1.0, 7.5, -0.5);
+        let a = Vector3::new(accel[0], accel[1], accel[2]);
+
+        for delta in [-1800.0, 0.0, 120.0, 3600.0] {
+            let (pos_km, vel_km_s) = dataset
+                .evaluate(Epoch::from_et_seconds(delta), &summary)
+                .unwrap();
+
+            let exp_pos_km = r0 + delta * v0 + 0.5 * delta * delta * a;
+            let exp_vel_km_s = v0 + delta * a;
+
+            assert!((pos_km - exp_pos_km).norm() < 1e-9, "{delta}: {pos_km}");
+            assert!(
+                (vel_km_s - exp_vel_km_s).norm() < 1e-12,
+                "{delta}: {vel_km_s}"
+            );
+        }
+
+        // Past the end of the data
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(3601.0), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn record_selection() {
+        // Two records, each with a different reference epoch and acceleration.
+        let mut data = constant_accel_record(0.0, [1e-3, 0.0, 0.0]).to_vec();
+        data.extend(constant_accel_record(100.0, [-1e-3, 0.0, 0.0]));
+        data.push(100.0);
+        data.push(200.0);
+        data.push(2.0);
+
+        let dataset = Type1ModifiedDifferenceSet::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.num_records, 2);
+        assert!(dataset.epoch_registry.is_empty());
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 200.0,
+            ..Default::default()
+        };
+
+        // At exactly the final epoch of the first record, the first record must be used.
+        assert_eq!(dataset.record_idx(Epoch::from_et_seconds(100.0)), Ok(0));
+        assert_eq!(dataset.record_idx(Epoch::from_et_seconds(100.5)), Ok(1));
+
+        let (_, vel_km_s) = dataset
+            .evaluate(Epoch::from_et_seconds(150.0), &summary)
+            .unwrap();
+        assert!((vel_km_s.x - (1.0 - 50.0 * 1e-3)).abs() < 1e-12);
+    }
+}
@@ -8,12 +8,19 @@
  * Documentation: https://nyxspace.com/
  */
 
-use core::fmt;
+use core::{fmt, ops::Range};
+use snafu::ResultExt;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use crate::{
-    math::Vector3,
-    naif::daf::{NAIFDataRecord, NAIFRecord},
+    math::{
+        interpolation::{
+            hermite_eval, hermite_eval_derivatives, lagrange_eval, InterpDecodingSnafu,
+            InterpolationError, MAX_SAMPLES,
+        },
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord},
 };
 
 #[derive(Copy, Clone, Default, IntoBytes, FromBytes, KnownLayout, Immutable, Debug)]
@@ -56,3 +63,108 @@ impl<'a> NAIFDataRecord<'a> for PositionVelocityRecord {
         }
     }
 }
+
+/// The position and velocity records of an interpolation window, split by component.
+pub(crate) struct PositionVelocityWindow {
+    epochs: [f64; MAX_SAMPLES],
+    pos_km: [[f64; MAX_SAMPLES]; 3],
+    vel_km_s: [[f64; MAX_SAMPLES]; 3],
+    len: usize,
+}
+
+impl PositionVelocityWindow {
+    /// Copies the records within the provided indexes (at most MAX_SAMPLES of them), whose epochs are returned by `epoch_et_s`.
+    pub(crate) fn from_records<'a, D>(
+        data: &D,
+        indexes: Range<usize>,
+        epoch_et_s: impl Fn(usize) -> f64,
+    ) -> Result<Self, InterpolationError>
+    where
+        D: NAIFDataSet<'a, RecordKind = PositionVelocityRecord>,
+    {
+        // Statically allocated arrays of the maximum number of samples
+        let mut window = Self {
+            epochs: [0.0; MAX_SAMPLES],
+            pos_km: [[0.0; MAX_SAMPLES]; 3],
+            vel_km_s: [[0.0; MAX_SAMPLES]; 3],
+            len: indexes.len(),
+        };
+
+        if window.len > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "window size exceeds the maximum number of samples",
+            });
+        }
+
+        for (cno, idx) in indexes.enumerate() {
+            let record = data.nth_record(idx).context(InterpDecodingSnafu)?;
+            window.pos_km[0][cno] = record.x_km;
+            window.pos_km[1][cno] = record.y_km;
+            window.pos_km[2][cno] = record.z_km;
+            window.vel_km_s[0][cno] = record.vx_km_s;
+            window.vel_km_s[1][cno] = record.vy_km_s;
+            window.vel_km_s[2][cno] = record.vz_km_s;
+            window.epochs[cno] = epoch_et_s(idx);
+        }
+
+        Ok(window)
+    }
+
+    /// Interpolates the positions and the velocities separately with Lagrange polynomials, and returns the position, the velocity,
+    /// and the acceleration as the derivative of the velocity interpolation.
+    ///
+    /// The slices are limited to exactly the number of samples in this window: the other ones are zeros, which would cause the interpolation to fail.
+    pub(crate) fn lagrange(
+        &self,
+        et: f64,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        let epochs = &self.epochs[..self.len];
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+        let mut acc_km_s2 = Vector3::zeros();
+        for i in 0..3 {
+            (pos_km[i], _) = lagrange_eval(epochs, &self.pos_km[i][..self.len], et)?;
+            (vel_km_s[i], acc_km_s2[i]) = lagrange_eval(epochs, &self.vel_km_s[i][..self.len], et)?;
+        }
+
+        Ok((pos_km, vel_km_s, acc_km_s2))
+    }
+
+    /// Interpolates the positions and their derivatives with Hermite polynomials, and returns the position and the velocity.
+    pub(crate) fn hermite(&self, et: f64) -> Result<(Vector3, Vector3), InterpolationError> {
+        let epochs = &self.epochs[..self.len];
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+        for i in 0..3 {
+            (pos_km[i], vel_km_s[i]) = hermite_eval(
+                epochs,
+                &self.pos_km[i][..self.len],
+                &self.vel_km_s[i][..self.len],
+                et,
+            )?;
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    /// Interpolates the positions and their derivatives with Hermite polynomials, and returns the position, the velocity, and the acceleration.
+    pub(crate) fn hermite_with_acceleration(
+        &self,
+        et: f64,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        let epochs = &self.epochs[..self.len];
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+        let mut acc_km_s2 = Vector3::zeros();
+        for i in 0..3 {
+            (pos_km[i], vel_km_s[i], acc_km_s2[i]) = hermite_eval_derivatives(
+                epochs,
+                &self.pos_km[i][..self.len],
+                &self.vel_km_s[i][..self.len],
+                et,
+            )?;
+        }
+
+        Ok((pos_km, vel_km_s, acc_km_s2))
+    }
+}
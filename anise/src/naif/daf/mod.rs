@@ -9,7 +9,9 @@
  */
 
 use crate::{
-    errors::IntegrityError, math::interpolation::InterpolationError, prelude::InputOutputError,
+    errors::IntegrityError,
+    math::{interpolation::InterpolationError, Vector3},
+    prelude::InputOutputError,
     NaifId,
 };
 use core::fmt::Display;
@@ -18,6 +20,12 @@ use snafu::prelude::*;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 pub(crate) const RCRD_LEN: usize = 1024;
+/// Number of characters of comments stored in each record of the comment area, as in SPICE.
+pub(crate) const COMMENT_RCRD_LEN: usize = 1000;
+/// Character terminating each line of the comment area.
+pub(crate) const COMMENT_EOL: u8 = 0x0;
+/// Character terminating the text of the comment area.
+pub(crate) const COMMENT_EOT: u8 = 0x4;
 #[allow(clippy::module_inception)]
 pub mod daf;
 mod data_types;
@@ -39,6 +47,29 @@ pub use summary_record::SummaryRecord;
 
 use self::file_record::FileRecordError;
 
+/// Encodes the provided text into the records of a DAF comment area: each line is terminated by a null character, the text by an EOT
+/// character, and only the first 1000 bytes of each record are used. Returns no bytes if the text is empty.
+pub(crate) fn comment_records(comments: &str) -> Vec<u8> {
+    if comments.is_empty() {
+        return Vec::new();
+    }
+
+    let mut text: Vec<u8> = comments
+        .bytes()
+        .map(|c| if c == b'\n' { COMMENT_EOL } else { c })
+        .collect();
+    text.push(COMMENT_EOL);
+    text.push(COMMENT_EOT);
+
+    let mut bytes = Vec::with_capacity(text.len().div_ceil(COMMENT_RCRD_LEN) * RCRD_LEN);
+    for chunk in text.chunks(COMMENT_RCRD_LEN) {
+        bytes.extend(chunk);
+        bytes.resize(bytes.len().div_ceil(RCRD_LEN) * RCRD_LEN, 0x0);
+    }
+
+    bytes
+}
+
 pub trait NAIFRecord:
     IntoBytes + FromBytes + Sized + Default + Debug + Immutable + KnownLayout
 {
@@ -101,6 +132,22 @@ pub trait NAIFDataSet<'a>: Sized + Display + PartialEq {
         summary: &S,
     ) -> Result<Self::StateKind, InterpolationError>;
 
+    /// Evaluates the position, the velocity, and the acceleration at the provided epoch, by analytically differentiating the interpolation.
+    ///
+    /// # Error
+    /// By default, this returns an unsupported operation error, which is the case of the data sets that are not interpolated by polynomials
+    /// (e.g. the discrete states of Type 5 are propagated with a two-body model).
+    fn evaluate_with_acceleration<S: NAIFSummaryRecord>(
+        &self,
+        _epoch: Epoch,
+        _summary: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "evaluating the acceleration",
+        })
+    }
+
     /// Checks the integrity of this data set, returns an error if the data has issues.
     fn check_integrity(&self) -> Result<(), IntegrityError>;
 
@@ -11,8 +11,8 @@
 use core::{marker::PhantomData, ops::Deref};
 
 use super::{
-    daf::MutDAF, DAFError, DecodingNameSnafu, IOSnafu, NAIFDataSet, NAIFSummaryRecord, NameRecord,
-    RCRD_LEN,
+    daf::MutDAF, DAFError, DecodingNameSnafu, DecodingSummarySnafu, IOSnafu, NAIFDataSet,
+    NAIFSummaryRecord, NameRecord, RCRD_LEN,
 };
 use crate::{
     errors::DecodingError,
@@ -23,6 +23,7 @@ use crate::{
 use bytes::BytesMut;
 use hifitime::Epoch;
 use snafu::ResultExt;
+use std::sync::OnceLock;
 use zerocopy::IntoBytes;
 
 impl<R: NAIFSummaryRecord> MutDAF<R> {
@@ -33,7 +34,7 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
         buf.extend(bytes.iter());
         let me = Self {
             bytes: buf,
-            crc32_checksum,
+            crc32_checksum: OnceLock::from(crc32_checksum),
             _daf_type: PhantomData,
         };
         // Check that these calls will succeed.
@@ -67,6 +68,28 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
         Ok(())
     }
 
+    /// Replaces the n-th summary of this DAF file, leaving its data unchanged.
+    pub fn set_nth_summary(&mut self, idx: usize, new_summary: R) -> Result<(), DAFError> {
+        if idx >= self.data_summaries()?.len() {
+            return Err(DAFError::InvalidIndex { idx, kind: R::NAME });
+        }
+
+        let rcrd_idx = (self.file_record()?.fwrd_idx() - 1) * RCRD_LEN;
+        let start = rcrd_idx + SummaryRecord::SIZE + idx * R::SIZE;
+        let size = self.bytes.len();
+        let summary_bytes = self
+            .bytes
+            .get_mut(start..start + R::SIZE)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start,
+                end: start + R::SIZE,
+                size,
+            })
+            .context(DecodingSummarySnafu { kind: R::NAME })?;
+        summary_bytes.copy_from_slice(new_summary.as_bytes());
+        Ok(())
+    }
+
     /// Sets the data for the n-th segment of this DAF file.
     pub fn set_nth_data<'a, S: NAIFDataSet<'a>>(
         &mut self,
@@ -94,6 +94,28 @@ pub fn parse_bytes<R: BufRead, I: KPLItem>(
     reader: &mut R,
     show_comments: bool,
 ) -> Result<HashMap<i32, I>, DataSetError> {
+    let assignments = parse_assignments(reader, show_comments);
+    // Now let's parse all of the assignments and put it into a pretty hash map.
+    let mut map = HashMap::new();
+    for item in assignments {
+        let key = I::extract_key(&item);
+        if key == -1 {
+            // This is metadata
+            continue;
+        }
+        map.entry(key).or_insert_with(|| I::default());
+        let body_map = map.get_mut(&key).unwrap();
+        body_map.parse(item);
+    }
+    Ok(map)
+}
+
+/// Returns all of the assignments of the data blocks of a KPL file, in the order in which they are defined, where the values spanning
+/// several lines are joined. The keyword of an incremental assignment (`+=`) keeps its trailing `+`.
+pub(crate) fn parse_assignments<R: BufRead>(
+    reader: &mut R,
+    show_comments: bool,
+) -> Vec<Assignment> {
     let mut block_type = BlockType::Comment;
     let mut assignments = vec![];
 
@@ -132,19 +154,15 @@ pub fn parse_bytes<R: BufRead, I: KPLItem>(
             }
         }
     }
-    // Now let's parse all of the assignments and put it into a pretty hash map.
-    let mut map = HashMap::new();
-    for item in assignments {
-        let key = I::extract_key(&item);
-        if key == -1 {
-            // This is metadata
-            continue;
-        }
-        map.entry(key).or_insert_with(|| I::default());
-        let body_map = map.get_mut(&key).unwrap();
-        body_map.parse(item);
+    assignments
+}
+
+/// Pads the phase angle polynomial to its three terms (offset, rate, and acceleration), such that the nutation and precession
+/// coefficients appended afterward are not mistaken for a polynomial term.
+fn pad_phase_polynomial(data: &mut Vec<f64>) {
+    if data.len() < 3 {
+        data.resize(3, 0.0);
     }
-    Ok(map)
 }
 
 /// Converts two KPL/TPC files, one defining the planetary constants as text, and the other defining the gravity parameters, into the PlanetaryDataSet equivalent ANISE file.
@@ -216,6 +234,7 @@ pub fn convert_tpc_items(
                                 match data {
                                     KPLValue::Matrix(pole_ra_data) => {
                                         let mut pole_ra_data = pole_ra_data.clone();
+                                        pad_phase_polynomial(&mut pole_ra_data);
                                         if let Some(coeffs) =
                                             planetary_data.data.get(&Parameter::NutPrecRa)
                                         {
@@ -238,6 +257,7 @@ pub fn convert_tpc_items(
                                                 "PoleDec must be a matrix but was {pole_dec_data:?}"
                                             ),
                                             })?;
+                                        pad_phase_polynomial(&mut pola_dec_data);
                                         if let Some(coeffs) =
                                             planetary_data.data.get(&Parameter::NutPrecDec)
                                         {
@@ -257,6 +277,7 @@ pub fn convert_tpc_items(
                                         let mut prime_mer_data: Vec<f64> = prime_mer_data
                                         .to_vec_f64()
                                         .map_err(|_| DataSetError::Conversion { action: format!("PrimeMeridian must be a matrix but was {prime_mer_data:?}") })?;
+                                        pad_phase_polynomial(&mut prime_mer_data);
                                         if let Some(coeffs) =
                                             planetary_data.data.get(&Parameter::NutPrecPm)
                                         {
@@ -381,6 +402,8 @@ pub fn convert_tpc_items(
                                 coeffs[i] = PhaseAngle::<0> {
                                     offset_deg: nut_prec[0],
                                     rate_deg: nut_prec[1],
+                                    // Only set when the max phase degree is 2, e.g. for the Mars system in pck00011.
+                                    accel_deg: *nut_prec.get(2).unwrap_or(&0.0),
                                     ..Default::default()
                                 };
                                 num += 1;
@@ -14,6 +14,9 @@ pub mod kpl;
 pub mod pck;
 pub mod spk;
 
+/// Binary PCK (DAF/PCK) kernels, e.g. the BPC writer is also available at `naif::bpc::writer`.
+pub use self::pck as bpc;
+
 pub mod pretty_print;
 
 use self::{
@@ -8,6 +8,9 @@
  * Documentation: https://nyxspace.com/
  */
 
+// Defines how to write a BPC
+pub mod writer;
+
 use crate::{
     naif::daf::{NAIFRecord, NAIFSummaryRecord},
     orientations::OrientationError,
@@ -0,0 +1,425 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::TAU;
+use std::path::Path;
+
+use hifitime::{Duration, Epoch};
+use snafu::prelude::*;
+
+use crate::{
+    errors::InputOutputError,
+    math::{rotation::DCM, Vector3},
+    naif::{
+        daf::{DAFError, RCRD_LEN},
+        spk::writer::{
+            daf_bytes, fit_type2_records, ChebyshevFitResidual, FitSample, SPKWriterError,
+        },
+        BPC,
+    },
+    NaifId, DBL_SIZE,
+};
+
+use super::BPCSummaryRecord;
+
+/// Number of doubles in a BPC summary (ND + (NI + 1) / 2)
+const BPC_SUMMARY_SIZE: usize = 5;
+/// Maximum number of segments that fit in a single summary record
+pub const MAX_SEGMENTS: usize = (RCRD_LEN - 3 * DBL_SIZE) / (BPC_SUMMARY_SIZE * DBL_SIZE);
+/// Below this sine of the second Euler angle, the first and third angles cannot be distinguished.
+const GIMBAL_LOCK_SIN: f64 = 1e-12;
+
+#[derive(Debug, Snafu, PartialEq)]
+#[snafu(visibility(pub(crate)))]
+#[non_exhaustive]
+pub enum BPCWriterError {
+    #[snafu(display("cannot build segment: {reason}"))]
+    InvalidSegment { reason: &'static str },
+    #[snafu(display("rotation at {epoch} is invalid: {reason}"))]
+    InvalidRotation { epoch: Epoch, reason: &'static str },
+    #[snafu(display("could not fit the Euler angles: {source}"))]
+    EulerAnglesFit { source: SPKWriterError },
+    #[snafu(display("a BPC summary record holds at most {max} segments"))]
+    TooManySegments { max: usize },
+    #[snafu(display("while {action} encountered input/output error {source}"))]
+    WriterIO {
+        action: String,
+        source: InputOutputError,
+    },
+    #[snafu(display("the built BPC is invalid: {source}"))]
+    BuiltDAF { source: DAFError },
+}
+
+/// Builds BPC files from computed rotations, by fitting Chebyshev polynomials (PCK Type 2) of the 3-1-3 Euler angles over fixed length intervals.
+///
+/// The rotations are the DCMs from the inertial frame to the body-fixed frame, as returned by [crate::almanac::Almanac::rotation_to_parent].
+/// Quaternions can be converted into DCMs with `DCM::from`.
+///
+/// # Example
+/// ```ignore
+/// let mut writer = BPCWriter::new("my attitude");
+/// let residuals = writer.add_type2_segment(&rotations, 3000, 1, 11, Unit::Hour * 12, "ITRF93")?;
+/// writer.write("attitude.bpc")?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BPCWriter {
+    pub internal_filename: String,
+    pub comments: String,
+    summaries: Vec<BPCSummaryRecord>,
+    names: Vec<String>,
+    segments: Vec<Vec<f64>>,
+}
+
+impl BPCWriter {
+    /// Initializes a new BPC writer with the provided internal file name (truncated to 60 characters).
+    pub fn new(internal_filename: &str) -> Self {
+        Self {
+            internal_filename: internal_filename.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the text of the comment area of the BPC, typically used to document how it was produced.
+    pub fn with_comments(mut self, comments: &str) -> Self {
+        self.comments = comments.to_string();
+        self
+    }
+
+    /// Returns the number of segments added to this writer.
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Fits the provided rotations with Chebyshev polynomials of the provided degree over intervals of the provided length, and adds that Type 2 segment.
+    ///
+    /// The rotations must be sorted in increasing epoch order, and rotate from `inertial_frame_id` to `frame_id`. They are fit as the 3-1-3 Euler angles
+    /// stored in BPCs, and their rates are included in the fit if the time derivative of the DCM is set. The first and third angles are unwrapped
+    /// between consecutive rotations, so these must be sampled finely enough that neither angle changes by more than half a turn between two samples.
+    ///
+    /// Returns the maximum residual of the fit in each interval, in radians and radians per second.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_type2_segment(
+        &mut self,
+        rotations: &[(Epoch, DCM)],
+        frame_id: NaifId,
+        inertial_frame_id: NaifId,
+        degree: usize,
+        interval_length: Duration,
+        name: &str,
+    ) -> Result<Vec<ChebyshevFitResidual>, BPCWriterError> {
+        ensure!(
+            self.segments.len() < MAX_SEGMENTS,
+            TooManySegmentsSnafu { max: MAX_SEGMENTS }
+        );
+        ensure!(
+            rotations.len() >= 2,
+            InvalidSegmentSnafu {
+                reason: "at least two rotations are needed"
+            }
+        );
+        ensure!(
+            interval_length > Duration::ZERO,
+            InvalidSegmentSnafu {
+                reason: "interval length must be strictly positive"
+            }
+        );
+        ensure!(
+            rotations.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            InvalidSegmentSnafu {
+                reason: "rotations must be in strictly increasing epoch order"
+            }
+        );
+
+        let mut samples: Vec<FitSample> = Vec::with_capacity(rotations.len());
+        for (epoch, dcm) in rotations {
+            ensure!(
+                dcm.from == inertial_frame_id && dcm.to == frame_id,
+                InvalidRotationSnafu {
+                    epoch: *epoch,
+                    reason:
+                        "DCM does not rotate from the inertial frame to the frame of the segment"
+                }
+            );
+
+            let (mut angles, rates) = euler_313(dcm).ok_or(BPCWriterError::InvalidRotation {
+                epoch: *epoch,
+                reason: "second Euler angle is zero or pi, so the first and third are undefined",
+            })?;
+
+            if let Some(prev) = samples.last() {
+                for i in [0, 2] {
+                    angles[i] += TAU * ((prev.values[i] - angles[i]) / TAU).round();
+                }
+            }
+
+            samples.push(FitSample {
+                et_s: epoch.to_et_seconds(),
+                values: angles,
+                rates,
+            });
+        }
+
+        let (record_data, residuals) =
+            fit_type2_records(&samples, degree, interval_length).context(EulerAnglesFitSnafu)?;
+
+        self.summaries.push(BPCSummaryRecord {
+            start_epoch_et_s: samples[0].et_s,
+            end_epoch_et_s: samples[samples.len() - 1].et_s,
+            frame_id,
+            inertial_frame_id,
+            data_type_i: 2,
+            // Indexes are set when building the file
+            start_idx: 0,
+            end_idx: 0,
+            unused: 0,
+        });
+        self.names.push(name.to_string());
+        self.segments.push(record_data);
+
+        Ok(residuals)
+    }
+
+    /// Builds the bytes of the DAF/PCK file, in the native endianness of this platform.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BPCWriterError> {
+        ensure!(
+            !self.segments.is_empty(),
+            InvalidSegmentSnafu {
+                reason: "no segment to write"
+            }
+        );
+
+        Ok(daf_bytes(
+            *b"DAF/PCK ",
+            5,
+            BPC_SUMMARY_SIZE,
+            &self.internal_filename,
+            &self.comments,
+            &self.summaries,
+            &self.names,
+            &self.segments,
+        ))
+    }
+
+    /// Builds the BPC from the segments added to this writer.
+    pub fn build(&self) -> Result<BPC, BPCWriterError> {
+        BPC::parse(self.to_bytes()?).context(BuiltDAFSnafu)
+    }
+
+    /// Writes the BPC to the provided path.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), BPCWriterError> {
+        let bytes = self.to_bytes()?;
+        std::fs::write(&path, bytes).map_err(|e| BPCWriterError::WriterIO {
+            action: format!("writing BPC to {:?}", path.as_ref()),
+            source: InputOutputError::IOError { kind: e.kind() },
+        })
+    }
+}
+
+/// Returns the 3-1-3 Euler angles (and their rates if the DCM has a time derivative) such that the rotation matrix is `R3(angle 3) R1(angle 2) R3(angle 1)`,
+/// or None if the second angle is too close to zero or pi for the first and the third to be distinguished.
+fn euler_313(dcm: &DCM) -> Option<(Vector3, Option<Vector3>)> {
+    let r = &dcm.rot_mat;
+    let angle2 = r[(2, 2)].clamp(-1.0, 1.0).acos();
+    let sin2 = angle2.sin();
+    if sin2 < GIMBAL_LOCK_SIN {
+        return None;
+    }
+
+    let angle1 = r[(2, 0)].atan2(-r[(2, 1)]);
+    let angle3 = r[(0, 2)].atan2(r[(1, 2)]);
+
+    let rates = dcm.rot_mat_dt.map(|r_dt| {
+        let (sin1, cos1) = angle1.sin_cos();
+        let (sin3, cos3) = angle3.sin_cos();
+        Vector3::new(
+            (cos1 * r_dt[(2, 0)] + sin1 * r_dt[(2, 1)]) / sin2,
+            -r_dt[(2, 2)] / sin2,
+            (cos3 * r_dt[(0, 2)] - sin3 * r_dt[(1, 2)]) / sin2,
+        )
+    });
+
+    Some((Vector3::new(angle1, angle2, angle3), rates))
+}
+
+#[cfg(test)]
+mod writer_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        almanac::Almanac,
+        math::{
+            rotation::{r1, r1_dot, r3, r3_dot, DCM},
+            Matrix3,
+        },
+        naif::daf::NAIFSummaryRecord,
+        prelude::Frame,
+    };
+
+    use super::{euler_313, BPCWriter, BPCWriterError};
+
+    const FRAME_ID: i32 = 3000;
+
+    /// Returns the Euler angles and their rates of a body rotating about a slowly nutating pole.
+    fn angles(et_s: f64) -> ([f64; 3], [f64; 3]) {
+        let nutation_rad_s = core::f64::consts::TAU / 86_400.0 / 10.0;
+        (
+            [
+                0.3 + 1e-7 * et_s,
+                0.4 + 0.01 * (nutation_rad_s * et_s).sin(),
+                1.0 + 7.292115e-5 * et_s,
+            ],
+            [
+                1e-7,
+                0.01 * nutation_rad_s * (nutation_rad_s * et_s).cos(),
+                7.292115e-5,
+            ],
+        )
+    }
+
+    fn rot_mat(et_s: f64) -> (Matrix3, Matrix3) {
+        let ([a1, a2, a3], [d1, d2, d3]) = angles(et_s);
+        (
+            r3(a3) * r1(a2) * r3(a1),
+            d3 * r3_dot(a3) * r1(a2) * r3(a1)
+                + d2 * r3(a3) * r1_dot(a2) * r3(a1)
+                + d1 * r3(a3) * r1(a2) * r3_dot(a1),
+        )
+    }
+
+    fn rotations(start: Epoch, num: usize, step_s: f64) -> Vec<(Epoch, DCM)> {
+        (0..num)
+            .map(|i| {
+                let dt_s = i as f64 * step_s;
+                let (rot_mat, rot_mat_dt) = rot_mat(start.to_et_seconds() + dt_s);
+                (
+                    start + dt_s.seconds(),
+                    DCM {
+                        rot_mat,
+                        rot_mat_dt: Some(rot_mat_dt),
+                        from: 1,
+                        to: FRAME_ID,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn euler_angles() {
+        for et_s in [0.0, 1e4, 5e4, 1e5] {
+            let (rot_mat, rot_mat_dt) = rot_mat(et_s);
+            let dcm = DCM {
+                rot_mat,
+                rot_mat_dt: Some(rot_mat_dt),
+                from: 1,
+                to: FRAME_ID,
+            };
+            let (expected, expected_rates) = angles(et_s);
+            let (angles, rates) = euler_313(&dcm).unwrap();
+            let rates = rates.unwrap();
+            for i in 0..3 {
+                let wrapped = (angles[i] - expected[i]).rem_euclid(core::f64::consts::TAU);
+                assert!(wrapped.min(core::f64::consts::TAU - wrapped) < 1e-12);
+                assert!((rates[i] - expected_rates[i]).abs() < 1e-15);
+            }
+        }
+
+        // Rotations about the Z axis only cannot be decomposed.
+        assert!(euler_313(&DCM::r3(0.5, 1, FRAME_ID)).is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let start = Epoch::from_et_seconds(0.0);
+        // Two days of rotations, every ten minutes
+        let rotations = rotations(start, 2 * 144 + 1, 600.0);
+
+        let mut writer = BPCWriter::new("ANISE BPC writer test");
+        let residuals = writer
+            .add_type2_segment(&rotations, FRAME_ID, 1, 11, 12.hours(), "TEST FRAME")
+            .unwrap();
+        assert_eq!(residuals.len(), 4);
+        for residual in &residuals {
+            assert!(residual.max_residual_km < 1e-11, "{residual:?}");
+            assert!(residual.max_residual_km_s < 1e-13, "{residual:?}");
+        }
+
+        let bpc = writer.build().unwrap();
+        let file_record = bpc.file_record().unwrap();
+        assert_eq!(file_record.identification().unwrap(), "PCK");
+        assert_eq!(file_record.nd, 2);
+        assert_eq!(file_record.ni, 5);
+        assert_eq!(
+            file_record.internal_filename().unwrap(),
+            "ANISE BPC writer test"
+        );
+
+        let summaries = bpc.data_summaries().unwrap();
+        assert_eq!(summaries[0].frame_id, FRAME_ID);
+        assert_eq!(summaries[0].inertial_frame_id, 1);
+        assert_eq!(summaries[0].data_type_i, 2);
+        assert_eq!(
+            bpc.name_record().unwrap().nth_name(0, 5),
+            "TEST FRAME",
+            "wrong segment name"
+        );
+        assert!((summaries[0].start_epoch_et_s() - start.to_et_seconds()).abs() < 1e-6);
+
+        let almanac = Almanac::default().with_bpc(bpc).unwrap();
+        let body_fixed = Frame::new(399, FRAME_ID);
+
+        // Check between the samples as well, including at the interval boundaries.
+        for step in 0..(2 * 144 * 4) {
+            let dt_s = step as f64 * 150.0;
+            let dcm = almanac
+                .rotation_to_parent(body_fixed, start + dt_s.seconds())
+                .unwrap();
+            assert_eq!(dcm.from, 1);
+            assert_eq!(dcm.to, FRAME_ID);
+
+            let (rot_mat, rot_mat_dt) = rot_mat(dt_s);
+            assert!(
+                (dcm.rot_mat - rot_mat).norm() < 1e-10,
+                "{dt_s}: {}",
+                (dcm.rot_mat - rot_mat).norm()
+            );
+            assert!(
+                (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm() < 1e-12,
+                "{dt_s}: {}",
+                (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_rotations() {
+        let start = Epoch::from_et_seconds(0.0);
+        let mut rotations = rotations(start, 10, 600.0);
+
+        // Wrong frames
+        rotations[3].1.to = FRAME_ID + 1;
+        let mut writer = BPCWriter::new("");
+        assert!(matches!(
+            writer.add_type2_segment(&rotations, FRAME_ID, 1, 5, 1.hours(), "TEST FRAME"),
+            Err(BPCWriterError::InvalidRotation { .. })
+        ));
+
+        assert!(matches!(
+            writer.build(),
+            Err(BPCWriterError::InvalidSegment { .. })
+        ));
+
+        rotations.reverse();
+        assert!(matches!(
+            writer.add_type2_segment(&rotations, FRAME_ID, 1, 5, 1.hours(), "TEST FRAME"),
+            Err(BPCWriterError::InvalidSegment { .. })
+        ));
+    }
+}
@@ -10,3 +10,9 @@
 
 // Defines how to read an SPK
 pub mod summary;
+
+// Defines how to iterate over the data of an SPK segment
+pub mod segment;
+
+// Defines how to write an SPK
+pub mod writer;
@@ -0,0 +1,323 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+use super::summary::SPKSummaryRecord;
+use crate::{
+    ephemerides::{EphemInterpolationSnafu, EphemerisError, SPKSnafu},
+    math::{cartesian::CartesianState, interpolation::InterpDecodingSnafu, Vector3},
+    naif::{
+        daf::{
+            datatypes::{
+                DiscreteStatesType5, HermiteSetType12, HermiteSetType13, LagrangeSetType8,
+                LagrangeSetType9, Type1ModifiedDifferenceSet, Type2ChebyshevSet, Type3ChebyshevSet,
+            },
+            DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord,
+        },
+        SPK,
+    },
+};
+
+/// A single segment of an SPK: its summary and the data it points to.
+#[derive(Copy, Clone)]
+pub struct SPKSegment<'a> {
+    pub summary: &'a SPKSummaryRecord,
+    spk: &'a SPK,
+    idx: usize,
+}
+
+impl SPK {
+    /// Returns the segment stored at the provided index of this SPK, or an error if there is no such segment or if it is empty.
+    pub fn nth_segment(&self, idx: usize) -> Result<SPKSegment<'_>, DAFError> {
+        let summary = self
+            .data_summaries()?
+            .get(idx)
+            .filter(|summary| !summary.is_empty())
+            .ok_or(DAFError::InvalidIndex {
+                kind: SPKSummaryRecord::NAME,
+                idx,
+            })?;
+
+        Ok(SPKSegment {
+            summary,
+            spk: self,
+            idx,
+        })
+    }
+
+    /// Returns an iterator over all of the non-empty segments of this SPK.
+    pub fn segments(&self) -> Result<impl Iterator<Item = SPKSegment<'_>>, DAFError> {
+        Ok(self
+            .data_summaries()?
+            .iter()
+            .enumerate()
+            .filter(|(_, summary)| !summary.is_empty())
+            .map(|(idx, summary)| SPKSegment {
+                summary,
+                spk: self,
+                idx,
+            }))
+    }
+}
+
+impl<'a> SPKSegment<'a> {
+    /// Returns the index of this segment in its SPK.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Returns the interpolation nodes of this segment, in chronological order, as the state of the target with respect to the center
+    /// of this segment and in its frame.
+    ///
+    /// + For Chebyshev types (2 and 3), the nodes are the midpoints of each interval, where the polynomials are evaluated;
+    /// + For the Modified Difference Arrays (type 1), the nodes are the reference epoch and state of each record;
+    /// + For the Lagrange, Hermite, and discrete states types (5, 8, 9, 12, and 13), the nodes are the stored states.
+    ///
+    /// # Errors
+    /// The whole segment is decoded before the first state is returned, so any decoding or evaluation error is returned here.
+    pub fn states_iter(
+        &self,
+    ) -> Result<impl Iterator<Item = (Epoch, CartesianState)> + 'a, EphemerisError> {
+        let frame = self.summary.center_frame();
+        let nodes = self.nodes()?;
+
+        Ok(nodes
+            .into_iter()
+            .map(move |(epoch, radius_km, velocity_km_s)| {
+                (
+                    epoch,
+                    CartesianState {
+                        radius_km,
+                        velocity_km_s,
+                        epoch,
+                        frame,
+                    },
+                )
+            }))
+    }
+
+    /// Decodes the epochs and states of the interpolation nodes of this segment.
+    fn nodes(&self) -> Result<Vec<(Epoch, Vector3, Vector3)>, EphemerisError> {
+        const ACTION: &str = "fetching data to iterate over its states";
+
+        let mut nodes = Vec::new();
+
+        match self.summary.data_type()? {
+            DafDataType::Type1ModifiedDifferenceArray => {
+                let data = self
+                    .spk
+                    .nth_data::<Type1ModifiedDifferenceSet>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for n in 0..data.num_records {
+                    let record = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?;
+                    nodes.push((
+                        Epoch::from_et_seconds(record.reference_epoch_et_s),
+                        record.ref_pos_km,
+                        record.ref_vel_km_s,
+                    ));
+                }
+            }
+            DafDataType::Type2ChebyshevTriplet => {
+                let data = self
+                    .spk
+                    .nth_data::<Type2ChebyshevSet>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for n in 0..data.num_records {
+                    let epoch = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .midpoint_epoch();
+                    let (pos_km, vel_km_s) = data
+                        .evaluate(epoch, self.summary)
+                        .context(EphemInterpolationSnafu)?;
+                    nodes.push((epoch, pos_km, vel_km_s));
+                }
+            }
+            DafDataType::Type3ChebyshevSextuplet => {
+                let data = self
+                    .spk
+                    .nth_data::<Type3ChebyshevSet>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for n in 0..data.num_records {
+                    let epoch = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .midpoint_epoch();
+                    let (pos_km, vel_km_s) = data
+                        .evaluate(epoch, self.summary)
+                        .context(EphemInterpolationSnafu)?;
+                    nodes.push((epoch, pos_km, vel_km_s));
+                }
+            }
+            DafDataType::Type5DiscreteStates => {
+                let data = self
+                    .spk
+                    .nth_data::<DiscreteStatesType5>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for (n, epoch_et_s) in data.epoch_data.iter().enumerate() {
+                    let (pos_km, vel_km_s) = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .to_pos_vel();
+                    nodes.push((Epoch::from_et_seconds(*epoch_et_s), pos_km, vel_km_s));
+                }
+            }
+            DafDataType::Type8LagrangeEqualStep => {
+                let data = self
+                    .spk
+                    .nth_data::<LagrangeSetType8>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for n in 0..data.num_records {
+                    let (pos_km, vel_km_s) = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .to_pos_vel();
+                    nodes.push((
+                        data.first_state_epoch + data.step_size * (n as i64),
+                        pos_km,
+                        vel_km_s,
+                    ));
+                }
+            }
+            DafDataType::Type9LagrangeUnequalStep => {
+                let data = self
+                    .spk
+                    .nth_data::<LagrangeSetType9>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for (n, epoch_et_s) in data.epoch_data.iter().enumerate() {
+                    let (pos_km, vel_km_s) = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .to_pos_vel();
+                    nodes.push((Epoch::from_et_seconds(*epoch_et_s), pos_km, vel_km_s));
+                }
+            }
+            DafDataType::Type12HermiteEqualStep => {
+                let data = self
+                    .spk
+                    .nth_data::<HermiteSetType12>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for n in 0..data.num_records {
+                    let (pos_km, vel_km_s) = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .to_pos_vel();
+                    nodes.push((
+                        data.first_state_epoch + data.step_size * (n as i64),
+                        pos_km,
+                        vel_km_s,
+                    ));
+                }
+            }
+            DafDataType::Type13HermiteUnequalStep => {
+                let data = self
+                    .spk
+                    .nth_data::<HermiteSetType13>(self.idx)
+                    .context(SPKSnafu { action: ACTION })?;
+                for (n, epoch_et_s) in data.epoch_data.iter().enumerate() {
+                    let (pos_km, vel_km_s) = data
+                        .nth_record(n)
+                        .context(InterpDecodingSnafu)
+                        .context(EphemInterpolationSnafu)?
+                        .to_pos_vel();
+                    nodes.push((Epoch::from_et_seconds(*epoch_et_s), pos_km, vel_km_s));
+                }
+            }
+            kind => {
+                return Err(EphemerisError::UnsupportedDataType {
+                    id: self.summary.target_id,
+                    kind,
+                })
+            }
+        }
+
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod segment_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        astro::utils::propagate_universal_variable,
+        constants::frames::EARTH_J2000,
+        math::{cartesian::CartesianState, Vector3},
+        naif::spk::writer::SPKWriter,
+    };
+
+    fn two_body(et_s: f64) -> (Vector3, Vector3) {
+        propagate_universal_variable(
+            Vector3::new(7000.0, 100.0, -50.0),
+            Vector3::new(0.1, 7.2, 1.5),
+            398600.4418,
+            et_s,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn chebyshev_midpoints() {
+        let states: Vec<CartesianState> = (0..=12 * 60)
+            .map(|i| {
+                let (r, v) = two_body(60.0 * i as f64);
+                CartesianState {
+                    radius_km: r,
+                    velocity_km_s: v,
+                    epoch: Epoch::from_et_seconds(60.0 * i as f64),
+                    frame: EARTH_J2000,
+                }
+            })
+            .collect();
+
+        let mut writer = SPKWriter::new("ANISE segment test");
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+        let spk = writer.build().unwrap();
+
+        let segments: Vec<_> = spk.segments().unwrap().collect();
+        assert_eq!(segments.len(), 1);
+        let segment = spk.nth_segment(0).unwrap();
+        assert_eq!(segment.index(), 0);
+        assert_eq!(segment.summary, segments[0].summary);
+
+        let nodes: Vec<_> = segment.states_iter().unwrap().collect();
+        assert_eq!(nodes.len(), 12);
+        for (hour, (epoch, state)) in nodes.iter().enumerate() {
+            assert_eq!(
+                *epoch,
+                Epoch::from_et_seconds(0.0) + 30.minutes() + (hour as i64).hours()
+            );
+            assert_eq!(state.epoch, *epoch);
+            // Centered on the Earth, in the J2000 frame
+            assert_eq!(state.frame, segment.summary.center_frame());
+            assert_eq!(state.frame.ephemeris_id, 399);
+            assert_eq!(state.frame.orientation_id, 1);
+
+            let (r, v) = two_body(epoch.to_et_seconds());
+            assert!((state.radius_km - r).norm() < 1e-6);
+            assert!((state.velocity_km_s - v).norm() < 1e-9);
+        }
+
+        assert!(spk.nth_segment(1).is_err());
+    }
+}
@@ -0,0 +1,644 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::path::Path;
+
+use hifitime::{Duration, Epoch};
+use nalgebra::DMatrix;
+use snafu::prelude::*;
+use zerocopy::IntoBytes;
+
+use crate::{
+    errors::InputOutputError,
+    math::{cartesian::CartesianState, Vector3},
+    naif::{
+        daf::{comment_records, DAFError, FileRecord, NAIFSummaryRecord, NameRecord, RCRD_LEN},
+        Endian, SPK,
+    },
+    NaifId, DBL_SIZE,
+};
+
+use super::summary::SPKSummaryRecord;
+
+/// Number of doubles in an SPK summary (ND + (NI + 1) / 2)
+const SPK_SUMMARY_SIZE: usize = 5;
+/// Maximum number of segments that fit in a single summary record
+pub const MAX_SEGMENTS: usize = (RCRD_LEN - 3 * DBL_SIZE) / (SPK_SUMMARY_SIZE * DBL_SIZE);
+/// FTP validation string expected by SPICE in the file record
+const FTP_STR: &[u8; 28] = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP";
+
+#[derive(Debug, Snafu, PartialEq)]
+#[snafu(visibility(pub(crate)))]
+#[non_exhaustive]
+pub enum SPKWriterError {
+    #[snafu(display("cannot build segment: {reason}"))]
+    InvalidSegment { reason: &'static str },
+    #[snafu(display(
+        "interval starting {start} has {got} samples but a degree {degree} fit requires at least {need}"
+    ))]
+    TooFewSamples {
+        start: Epoch,
+        got: usize,
+        need: usize,
+        degree: usize,
+    },
+    #[snafu(display("Chebyshev fit of interval starting {start} failed: {reason}"))]
+    FitFailed { start: Epoch, reason: &'static str },
+    #[snafu(display("an SPK summary record holds at most {max} segments"))]
+    TooManySegments { max: usize },
+    #[snafu(display("while {action} encountered input/output error {source}"))]
+    WriterIO {
+        action: String,
+        source: InputOutputError,
+    },
+    #[snafu(display("the built SPK is invalid: {source}"))]
+    BuiltDAF { source: DAFError },
+}
+
+/// Quality of the Chebyshev fit of a single interval of a Type 2 segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChebyshevFitResidual {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    /// Number of states used in the fit of this interval
+    pub num_samples: usize,
+    /// Maximum position error between the fit and the input states
+    pub max_residual_km: f64,
+    /// Maximum velocity error between the fit and the input states
+    pub max_residual_km_s: f64,
+}
+
+/// Builds SPK files from computed states, by fitting Chebyshev polynomials (SPK Type 2) over fixed length intervals.
+///
+/// # Example
+/// ```ignore
+/// let mut writer = SPKWriter::new("my trajectory");
+/// let residuals = writer.add_type2_segment(&states, -10000001, 399, 1, 11, Unit::Hour * 2, "SC")?;
+/// writer.write("trajectory.bsp")?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SPKWriter {
+    pub internal_filename: String,
+    pub comments: String,
+    summaries: Vec<SPKSummaryRecord>,
+    names: Vec<String>,
+    segments: Vec<Vec<f64>>,
+}
+
+impl SPKWriter {
+    /// Initializes a new SPK writer with the provided internal file name (truncated to 60 characters).
+    pub fn new(internal_filename: &str) -> Self {
+        Self {
+            internal_filename: internal_filename.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the text of the comment area of the SPK, typically used to document how it was produced.
+    pub fn with_comments(mut self, comments: &str) -> Self {
+        self.comments = comments.to_string();
+        self
+    }
+
+    /// Returns the number of segments added to this writer.
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Fits the provided states with Chebyshev polynomials of the provided degree over intervals of the provided length, and adds that Type 2 segment.
+    ///
+    /// The states must be sorted in increasing epoch order and are expressed in the frame given by `frame_id` and centered on `center_id`.
+    /// Each interval is fit by least squares on both the positions and velocities of the states within that interval (including its bounds),
+    /// so each interval requires at least `(degree + 1) / 2` states.
+    ///
+    /// Returns the maximum residual of the fit in each interval.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_type2_segment(
+        &mut self,
+        states: &[CartesianState],
+        target_id: NaifId,
+        center_id: NaifId,
+        frame_id: NaifId,
+        degree: usize,
+        interval_length: Duration,
+        name: &str,
+    ) -> Result<Vec<ChebyshevFitResidual>, SPKWriterError> {
+        ensure!(
+            self.segments.len() < MAX_SEGMENTS,
+            TooManySegmentsSnafu { max: MAX_SEGMENTS }
+        );
+        ensure!(
+            states.len() >= 2,
+            InvalidSegmentSnafu {
+                reason: "at least two states are needed"
+            }
+        );
+        ensure!(
+            interval_length > Duration::ZERO,
+            InvalidSegmentSnafu {
+                reason: "interval length must be strictly positive"
+            }
+        );
+        ensure!(
+            states.windows(2).all(|pair| pair[0].epoch < pair[1].epoch),
+            InvalidSegmentSnafu {
+                reason: "states must be in strictly increasing epoch order"
+            }
+        );
+
+        let samples: Vec<FitSample> = states
+            .iter()
+            .map(|state| FitSample {
+                et_s: state.epoch.to_et_seconds(),
+                values: state.radius_km,
+                rates: Some(state.velocity_km_s),
+            })
+            .collect();
+
+        let (record_data, residuals) = fit_type2_records(&samples, degree, interval_length)?;
+        let init_et_s = samples[0].et_s;
+        let final_et_s = samples[samples.len() - 1].et_s;
+
+        self.summaries.push(SPKSummaryRecord {
+            start_epoch_et_s: init_et_s,
+            end_epoch_et_s: final_et_s,
+            target_id,
+            center_id,
+            frame_id,
+            data_type_i: 2,
+            // Indexes are set when building the file
+            start_idx: 0,
+            end_idx: 0,
+        });
+        self.names.push(name.to_string());
+        self.segments.push(record_data);
+
+        Ok(residuals)
+    }
+
+    /// Adds a segment whose data is already laid out as expected by its data type, e.g. copied from another SPK.
+    ///
+    /// The start and end indexes of the summary are set when building the file.
+    pub fn add_segment_data(
+        &mut self,
+        summary: SPKSummaryRecord,
+        name: &str,
+        data: Vec<f64>,
+    ) -> Result<(), SPKWriterError> {
+        ensure!(
+            self.segments.len() < MAX_SEGMENTS,
+            TooManySegmentsSnafu { max: MAX_SEGMENTS }
+        );
+        ensure!(
+            !data.is_empty(),
+            InvalidSegmentSnafu {
+                reason: "segment data is empty"
+            }
+        );
+
+        self.summaries.push(summary);
+        self.names.push(name.to_string());
+        self.segments.push(data);
+
+        Ok(())
+    }
+
+    /// Builds the bytes of the DAF/SPK file, in the native endianness of this platform.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SPKWriterError> {
+        ensure!(
+            !self.segments.is_empty(),
+            InvalidSegmentSnafu {
+                reason: "no segment to write"
+            }
+        );
+
+        Ok(daf_bytes(
+            *b"DAF/SPK ",
+            6,
+            SPK_SUMMARY_SIZE,
+            &self.internal_filename,
+            &self.comments,
+            &self.summaries,
+            &self.names,
+            &self.segments,
+        ))
+    }
+
+    /// Builds the SPK from the segments added to this writer.
+    pub fn build(&self) -> Result<SPK, SPKWriterError> {
+        SPK::parse(self.to_bytes()?).context(BuiltDAFSnafu)
+    }
+
+    /// Writes the SPK to the provided path.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), SPKWriterError> {
+        let bytes = self.to_bytes()?;
+        std::fs::write(&path, bytes).map_err(|e| SPKWriterError::WriterIO {
+            action: format!("writing SPK to {:?}", path.as_ref()),
+            source: InputOutputError::IOError { kind: e.kind() },
+        })
+    }
+}
+
+/// A sample of the three components to fit with Chebyshev polynomials, and optionally their time derivatives.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FitSample {
+    pub et_s: f64,
+    pub values: Vector3,
+    pub rates: Option<Vector3>,
+}
+
+/// Fits the provided samples with Chebyshev polynomials of the provided degree over intervals of the provided length, and returns the
+/// data of that Type 2 segment (including its trailing metadata) and the maximum residual of the fit in each interval.
+///
+/// The samples must be sorted in increasing epoch order. Each sample provides one equation on the values, and another on the rates if they are set.
+pub(crate) fn fit_type2_records(
+    samples: &[FitSample],
+    degree: usize,
+    interval_length: Duration,
+) -> Result<(Vec<f64>, Vec<ChebyshevFitResidual>), SPKWriterError> {
+    let init_et_s = samples[0].et_s;
+    let final_et_s = samples[samples.len() - 1].et_s;
+    let interval_s = interval_length.to_seconds();
+    let radius_s = interval_s / 2.0;
+    let num_records = ((final_et_s - init_et_s) / interval_s).ceil().max(1.0) as usize;
+    let num_coeffs = degree + 1;
+    let rsize = 2 + 3 * num_coeffs;
+
+    let mut record_data = Vec::with_capacity(num_records * rsize + 4);
+    let mut residuals = Vec::with_capacity(num_records);
+
+    for rno in 0..num_records {
+        let start_et_s = init_et_s + (rno as f64) * interval_s;
+        let end_et_s = start_et_s + interval_s;
+        let mid_et_s = start_et_s + radius_s;
+
+        let interval_samples: Vec<&FitSample> = samples
+            .iter()
+            .filter(|sample| sample.et_s >= start_et_s && sample.et_s <= end_et_s)
+            .collect();
+
+        let num_equations: usize = interval_samples
+            .iter()
+            .map(|sample| if sample.rates.is_some() { 2 } else { 1 })
+            .sum();
+
+        ensure!(
+            num_equations >= num_coeffs,
+            TooFewSamplesSnafu {
+                start: Epoch::from_et_seconds(start_et_s),
+                got: interval_samples.len(),
+                need: if num_equations == 2 * interval_samples.len() {
+                    num_coeffs.div_ceil(2)
+                } else {
+                    num_coeffs
+                },
+                degree,
+            }
+        );
+
+        // Build the least squares problem: each sample provides one equation on the values and one on the rates.
+        let mut design = DMatrix::<f64>::zeros(num_equations, num_coeffs);
+        let mut observations = DMatrix::<f64>::zeros(num_equations, 3);
+        let mut row = 0;
+        for sample in &interval_samples {
+            let tau = (sample.et_s - mid_et_s) / radius_s;
+            let (basis, dbasis) = chebyshev_basis(tau, num_coeffs);
+            for (k, t_k) in basis.iter().enumerate() {
+                design[(row, k)] = *t_k;
+            }
+            for i in 0..3 {
+                observations[(row, i)] = sample.values[i];
+            }
+            row += 1;
+
+            if let Some(rates) = sample.rates {
+                for (k, dt_k) in dbasis.iter().enumerate() {
+                    design[(row, k)] = *dt_k;
+                }
+                for i in 0..3 {
+                    // Scale the rate by the radius to match the derivative with respect to the normalized time.
+                    observations[(row, i)] = rates[i] * radius_s;
+                }
+                row += 1;
+            }
+        }
+
+        let coeffs = design
+            .svd(true, true)
+            .solve(&observations, 1e-14)
+            .map_err(|reason| SPKWriterError::FitFailed {
+                start: Epoch::from_et_seconds(start_et_s),
+                reason,
+            })?;
+
+        // Compute the residuals of this fit
+        let mut max_residual_km = 0.0_f64;
+        let mut max_residual_km_s = 0.0_f64;
+        for sample in &interval_samples {
+            let tau = (sample.et_s - mid_et_s) / radius_s;
+            let (basis, dbasis) = chebyshev_basis(tau, num_coeffs);
+            let mut value_err2 = 0.0;
+            let mut rate_err2 = 0.0;
+            for i in 0..3 {
+                let value: f64 = (0..num_coeffs).map(|k| coeffs[(k, i)] * basis[k]).sum();
+                value_err2 += (value - sample.values[i]).powi(2);
+                if let Some(rates) = sample.rates {
+                    let rate: f64 = (0..num_coeffs)
+                        .map(|k| coeffs[(k, i)] * dbasis[k])
+                        .sum::<f64>()
+                        / radius_s;
+                    rate_err2 += (rate - rates[i]).powi(2);
+                }
+            }
+            max_residual_km = max_residual_km.max(value_err2.sqrt());
+            max_residual_km_s = max_residual_km_s.max(rate_err2.sqrt());
+        }
+
+        residuals.push(ChebyshevFitResidual {
+            start_epoch: Epoch::from_et_seconds(start_et_s),
+            end_epoch: Epoch::from_et_seconds(end_et_s),
+            num_samples: interval_samples.len(),
+            max_residual_km,
+            max_residual_km_s,
+        });
+
+        record_data.push(mid_et_s);
+        record_data.push(radius_s);
+        for i in 0..3 {
+            record_data.extend(coeffs.column(i).iter());
+        }
+    }
+
+    // Type 2 segments end with the initial epoch, the interval length, the record size, and the number of records.
+    record_data.push(init_et_s);
+    record_data.push(interval_s);
+    record_data.push(rsize as f64);
+    record_data.push(num_records as f64);
+
+    Ok((record_data, residuals))
+}
+
+/// Builds the bytes of a single summary record DAF file with the provided summaries and segment data, in the native endianness of this platform.
+///
+/// All DAF files written by ANISE have two doubles in their summaries (the start and end epochs), and `ni` integers.
+pub(crate) fn daf_bytes<R: NAIFSummaryRecord>(
+    id_str: [u8; 8],
+    ni: u32,
+    summary_size: usize,
+    internal_filename: &str,
+    comments: &str,
+    summaries: &[R],
+    names: &[String],
+    segments: &[Vec<f64>],
+) -> Vec<u8> {
+    // Record 1 is the file record, followed by the comment records, the summary record, the name record, and the data.
+    // DAF addresses are one-indexed double precision words.
+    let comment_rcrds = comment_records(comments);
+    let summary_rcrd_idx = 2 + comment_rcrds.len() / RCRD_LEN;
+    let mut next_addr = (summary_rcrd_idx + 1) * RCRD_LEN / DBL_SIZE + 1;
+    let mut summaries = summaries.to_vec();
+    for (summary, data) in summaries.iter_mut().zip(segments) {
+        summary.update_indexes(next_addr, next_addr + data.len() - 1);
+        next_addr += data.len();
+    }
+
+    let mut file_record = FileRecord {
+        id_str,
+        nd: 2,
+        ni,
+        forward: summary_rcrd_idx as u32,
+        backward: summary_rcrd_idx as u32,
+        free_addr: next_addr as u32,
+        ..Default::default()
+    };
+    file_record.internal_filename = [b' '; 60];
+    let name_len = internal_filename.len().min(60);
+    file_record.internal_filename[..name_len]
+        .copy_from_slice(&internal_filename.as_bytes()[..name_len]);
+    file_record.endian_str = match Endian::f64_native() {
+        Endian::Little => *b"LTL-IEEE",
+        Endian::Big => *b"BIG-IEEE",
+    };
+    file_record.ftp_str = *FTP_STR;
+
+    let mut bytes = Vec::with_capacity((next_addr - 1) * DBL_SIZE + RCRD_LEN);
+    bytes.extend(file_record.as_bytes());
+    bytes.extend(comment_rcrds);
+
+    // Summary record: next record, previous record, number of summaries, and the summaries themselves.
+    let mut summary_rcrd = Vec::with_capacity(RCRD_LEN);
+    summary_rcrd.extend([0.0, 0.0, summaries.len() as f64].as_bytes());
+    summary_rcrd.extend(summaries.as_bytes());
+    summary_rcrd.resize(RCRD_LEN, 0x0);
+    bytes.extend(summary_rcrd);
+
+    let mut name_rcrd = NameRecord::default();
+    for (nno, name) in names.iter().enumerate() {
+        name_rcrd.set_nth_name(nno, summary_size, name);
+    }
+    bytes.extend(name_rcrd.as_bytes());
+
+    for data in segments {
+        bytes.extend(data.as_bytes());
+    }
+
+    // Pad the final record
+    bytes.resize(bytes.len().div_ceil(RCRD_LEN) * RCRD_LEN, 0x0);
+
+    bytes
+}
+
+/// Returns the Chebyshev polynomials of the first kind and their derivatives evaluated at the normalized time `tau`.
+fn chebyshev_basis(tau: f64, num_coeffs: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut basis = vec![0.0; num_coeffs];
+    let mut dbasis = vec![0.0; num_coeffs];
+    basis[0] = 1.0;
+    if num_coeffs > 1 {
+        basis[1] = tau;
+        dbasis[1] = 1.0;
+    }
+    for k in 2..num_coeffs {
+        basis[k] = 2.0 * tau * basis[k - 1] - basis[k - 2];
+        dbasis[k] = 2.0 * basis[k - 1] + 2.0 * tau * dbasis[k - 1] - dbasis[k - 2];
+    }
+    (basis, dbasis)
+}
+
+#[cfg(test)]
+mod writer_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        almanac::Almanac,
+        astro::utils::propagate_universal_variable,
+        constants::frames::EARTH_J2000,
+        math::{cartesian::CartesianState, Vector3},
+        naif::daf::NAIFSummaryRecord,
+        prelude::Frame,
+    };
+
+    use super::{SPKWriter, SPKWriterError};
+
+    const GM: f64 = 398600.4418;
+
+    fn two_body(et_s: f64) -> (Vector3, Vector3) {
+        propagate_universal_variable(
+            Vector3::new(7000.0, 100.0, -50.0),
+            Vector3::new(0.1, 7.2, 1.5),
+            GM,
+            et_s,
+        )
+        .unwrap()
+    }
+
+    fn states(start: Epoch, num: usize, step_s: f64) -> Vec<CartesianState> {
+        (0..num)
+            .map(|i| {
+                let dt_s = i as f64 * step_s;
+                let (r, v) = two_body(dt_s);
+                CartesianState {
+                    radius_km: r,
+                    velocity_km_s: v,
+                    epoch: start + dt_s.seconds(),
+                    frame: EARTH_J2000,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let start = Epoch::from_et_seconds(0.0);
+        let states = states(start, 12 * 60 + 1, 60.0);
+
+        let mut writer = SPKWriter::new("ANISE SPK writer test");
+        let residuals = writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+        assert_eq!(residuals.len(), 12);
+        for residual in &residuals {
+            assert!(residual.max_residual_km < 1e-6, "{residual:?}");
+            assert!(residual.max_residual_km_s < 1e-9, "{residual:?}");
+        }
+
+        let spk = writer.build().unwrap();
+        let file_record = spk.file_record().unwrap();
+        assert_eq!(file_record.identification().unwrap(), "SPK");
+        assert_eq!(
+            file_record.internal_filename().unwrap(),
+            "ANISE SPK writer test"
+        );
+
+        let summaries = spk.data_summaries().unwrap();
+        assert_eq!(summaries[0].target_id, -10000001);
+        assert_eq!(summaries[0].center_id, 399);
+        assert_eq!(summaries[0].data_type_i, 2);
+        assert_eq!(
+            spk.name_record().unwrap().nth_name(0, 5),
+            "TEST SC",
+            "wrong segment name"
+        );
+        assert!((summaries[0].start_epoch_et_s() - start.to_et_seconds()).abs() < 1e-6);
+
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+        let sc = Frame::new(-10000001, 1);
+
+        // Check between the samples as well, including at the interval boundaries.
+        for step in 1..(12 * 60 * 2) {
+            let dt_s = step as f64 * 30.0;
+            let state = almanac
+                .translate_to_parent(sc, start + dt_s.seconds())
+                .unwrap();
+            let (r, v) = two_body(dt_s);
+            assert!(
+                (state.radius_km - r).norm() < 1e-5,
+                "{dt_s}: {}",
+                (state.radius_km - r).norm()
+            );
+            assert!(
+                (state.velocity_km_s - v).norm() < 1e-8,
+                "{dt_s}: {}",
+                (state.velocity_km_s - v).norm()
+            );
+        }
+    }
+
+    #[test]
+    fn comments_round_trip() {
+        let start = Epoch::from_et_seconds(0.0);
+        let states = states(start, 2 * 60 + 1, 60.0);
+
+        // Long enough to span several comment records, with empty lines and a trailing new line.
+        let mut comments = "Produced by the ANISE SPK writer test\n\n".to_string();
+        for i in 0..200 {
+            comments += &format!("  line {i}: r = {:?}\n", states[i % states.len()].radius_km);
+        }
+
+        let mut writer = SPKWriter::new("ANISE SPK writer test").with_comments(&comments);
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+
+        let spk = writer.build().unwrap();
+        assert!(spk.file_record().unwrap().fwrd_idx() > 3);
+        assert_eq!(spk.comments().unwrap(), Some(comments));
+        assert_eq!(spk.data_summaries().unwrap()[0].target_id, -10000001);
+
+        // The data is unaffected by the comments.
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+        let state = almanac
+            .translate_to_parent(Frame::new(-10000001, 1), start + 1.hours())
+            .unwrap();
+        assert!((state.radius_km - two_body(3600.0).0).norm() < 1e-5);
+
+        // Without comments, the comment area is empty.
+        let mut writer = SPKWriter::new("ANISE SPK writer test");
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+        let spk = writer.build().unwrap();
+        assert_eq!(spk.file_record().unwrap().fwrd_idx(), 2);
+        assert_eq!(spk.comments().unwrap(), None);
+
+        // Without a trailing new line.
+        let mut writer = SPKWriter::new("").with_comments("single line");
+        writer
+            .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+            .unwrap();
+        assert_eq!(
+            writer.build().unwrap().comments().unwrap().as_deref(),
+            Some("single line")
+        );
+    }
+
+    #[test]
+    fn too_few_samples() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+        let states = states(start, 5, 600.0);
+
+        let mut writer = SPKWriter::new("");
+        assert!(matches!(
+            writer.add_type2_segment(&states, -10000001, 399, 1, 11, 1.hours(), "TEST SC"),
+            Err(SPKWriterError::TooFewSamples { need: 6, .. })
+        ));
+
+        assert!(matches!(
+            writer.build(),
+            Err(SPKWriterError::InvalidSegment { .. })
+        ));
+
+        let mut reversed = states.clone();
+        reversed.reverse();
+        assert!(matches!(
+            writer.add_type2_segment(&reversed, -10000001, 399, 1, 3, 1.hours(), "TEST SC"),
+            Err(SPKWriterError::InvalidSegment { .. })
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use log::trace;
+use snafu::ResultExt;
+
+use super::{OrientationError, OrientationPhysicsSnafu};
+use crate::almanac::Almanac;
+use crate::constants::orientations::{ECLIPJ2000, J2000};
+use crate::hifitime::Epoch;
+use crate::math::cartesian::CartesianState;
+use crate::math::rotation::{r1, r1_ddot, r1_dot, r3, r3_ddot, r3_dot, DCM};
+use crate::math::{Matrix3, Vector3};
+use crate::naif::daf::datatypes::Type2ChebyshevSet;
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::orientations::{BPCSnafu, OrientationInterpolationSnafu};
+use crate::prelude::Frame;
+
+/// A DCM along with the second time derivative of its rotation matrix.
+type DCMWithAcceleration = (DCM, Matrix3);
+
+/// Multiplies two DCMs and the second time derivatives of their rotation matrices, i.e. `(AB)'' = A''B + 2A'B' + AB''`.
+fn mul(
+    lhs: DCMWithAcceleration,
+    rhs: DCMWithAcceleration,
+) -> Result<DCMWithAcceleration, OrientationError> {
+    let dcm = (lhs.0 * rhs.0).context(OrientationPhysicsSnafu)?;
+    let lhs_dt = lhs.0.rot_mat_dt.unwrap_or_else(Matrix3::zeros);
+    let rhs_dt = rhs.0.rot_mat_dt.unwrap_or_else(Matrix3::zeros);
+    Ok((
+        dcm,
+        lhs.1 * rhs.0.rot_mat + 2.0 * lhs_dt * rhs_dt + lhs.0.rot_mat * rhs.1,
+    ))
+}
+
+fn transpose(dcm: DCMWithAcceleration) -> DCMWithAcceleration {
+    (dcm.0.transpose(), dcm.1.transpose())
+}
+
+fn identity(from: i32, to: i32) -> DCMWithAcceleration {
+    (DCM::identity(from, to), Matrix3::zeros())
+}
+
+impl Almanac {
+    /// Returns the DCM to rotate from the `source` to its parent in the orientation hierarchy at the provided epoch, exactly as
+    /// [Self::rotation_to_parent], along with the second time derivative of its rotation matrix.
+    ///
+    /// The second derivative is computed by analytically differentiating the Chebyshev polynomials of the BPC angles, by finite
+    /// differencing the planetary constants (as for their first derivative), and is zero for the fixed frames and the Euler parameters.
+    ///
+    /// # Errors
+    /// + The BPC records of degree below two do not define the second derivative of their angles, so they return an acceleration unavailable error.
+    /// + The BPC data types other than Type 2 are not supported.
+    pub fn rotation_to_parent_with_acceleration(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+    ) -> Result<(DCM, Matrix3), OrientationError> {
+        if source.orient_origin_id_match(J2000) || source.orient_origin_id_match(ECLIPJ2000) {
+            return Ok((self.rotation_to_parent(source, epoch)?, Matrix3::zeros()));
+        }
+
+        let Ok((summary, bpc_no, idx_in_bpc)) =
+            self.bpc_summary_at_epoch(source.orientation_id, epoch)
+        else {
+            // Also checks that this is not a high precision frame missing its BPC data.
+            let dcm = self.rotation_to_parent(source, epoch)?;
+            if let Ok(planetary_data) = self.planetary_data.get_by_id(source.orientation_id) {
+                trace!("query {source} wrt to its parent with acceleration @ {epoch:E} using planetary data");
+                let system_data = self
+                    .planetary_data
+                    .get_by_id(planetary_data.parent_id)
+                    .unwrap_or(planetary_data);
+
+                return planetary_data
+                    .rotation_to_parent_with_acceleration(epoch, &system_data)
+                    .context(OrientationPhysicsSnafu);
+            }
+            // Fixed frames and Euler parameters are constant.
+            return Ok((dcm, Matrix3::zeros()));
+        };
+
+        trace!("rotate {source} wrt to its parent with acceleration @ {epoch:E}");
+
+        let bpc_data = self.bpc_data[bpc_no]
+            .as_ref()
+            .ok_or(OrientationError::Unreachable)?;
+
+        let (ra_dec_w, d_ra_dec_w, dd_ra_dec_w) = match summary.data_type()? {
+            DafDataType::Type2ChebyshevTriplet => {
+                let data =
+                    bpc_data
+                        .nth_data::<Type2ChebyshevSet>(idx_in_bpc)
+                        .context(BPCSnafu {
+                            action: "fetching data for interpolation",
+                        })?;
+                if data.degree() < 2 {
+                    return Err(OrientationError::AccelerationUnavailable {
+                        id: source.orientation_id,
+                        reason: "its BPC records are polynomials of degree below two",
+                    });
+                }
+                data.evaluate_with_acceleration(epoch, summary)
+                    .context(OrientationInterpolationSnafu)?
+            }
+            kind => {
+                return Err(OrientationError::UnsupportedDataType {
+                    id: source.orientation_id,
+                    kind,
+                })
+            }
+        };
+
+        let dcm = self.rotation_to_parent(source, epoch)?;
+
+        // Each of the three rotations, and their first and second time derivatives
+        let [ra_rad, dec_rad, twist_rad] = [ra_dec_w[0], ra_dec_w[1], ra_dec_w[2]];
+        let [ra_dot, dec_dot, twist_dot] = [d_ra_dec_w[0], d_ra_dec_w[1], d_ra_dec_w[2]];
+        let [ra_ddot, dec_ddot, twist_ddot] = [dd_ra_dec_w[0], dd_ra_dec_w[1], dd_ra_dec_w[2]];
+
+        let (a, a_dt, a_ddt) = (
+            r3(twist_rad),
+            twist_dot * r3_dot(twist_rad),
+            twist_ddot * r3_dot(twist_rad) + twist_dot.powi(2) * r3_ddot(twist_rad),
+        );
+        let (b, b_dt, b_ddt) = (
+            r1(dec_rad),
+            dec_dot * r1_dot(dec_rad),
+            dec_ddot * r1_dot(dec_rad) + dec_dot.powi(2) * r1_ddot(dec_rad),
+        );
+        let (c, c_dt, c_ddt) = (
+            r3(ra_rad),
+            ra_dot * r3_dot(ra_rad),
+            ra_ddot * r3_dot(ra_rad) + ra_dot.powi(2) * r3_ddot(ra_rad),
+        );
+
+        let rot_mat_ddt = a_ddt * b * c
+            + a * b_ddt * c
+            + a * b * c_ddt
+            + 2.0 * (a_dt * b_dt * c + a_dt * b * c_dt + a * b_dt * c_dt);
+
+        Ok((dcm, rot_mat_ddt))
+    }
+
+    /// Returns the DCM to rotate from the `from_frame` to the `to_frame`, exactly as [Self::rotate], along with the second time
+    /// derivative of its rotation matrix, computed along the orientation path with [Self::rotation_to_parent_with_acceleration].
+    ///
+    /// # Errors
+    /// + Any rotation along the path which does not provide its second time derivative causes an error, cf. [Self::rotation_to_parent_with_acceleration].
+    pub fn rotate_with_derivatives(
+        &self,
+        from_frame: Frame,
+        mut to_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(DCM, Matrix3), OrientationError> {
+        if let Ok(to_frame_info) = self.frame_from_uid(to_frame) {
+            to_frame = to_frame_info;
+        }
+
+        if from_frame.orient_origin_match(to_frame) {
+            return Ok(identity(from_frame.orientation_id, to_frame.orientation_id));
+        }
+
+        let (node_count, path, common_node) =
+            self.common_orientation_path(from_frame, to_frame, epoch)?;
+
+        // The same traversal as the rotations, keeping track of the second derivatives.
+        let mut dcm_fwrd = if from_frame.orient_origin_id_match(common_node) {
+            identity(common_node, common_node)
+        } else {
+            self.rotation_to_parent_with_acceleration(from_frame, epoch)?
+        };
+
+        let mut dcm_bwrd = if to_frame.orient_origin_id_match(common_node) {
+            identity(common_node, common_node)
+        } else {
+            transpose(self.rotation_to_parent_with_acceleration(to_frame, epoch)?)
+        };
+
+        for cur_node_id in path.iter().take(node_count) {
+            let next_parent = cur_node_id.unwrap();
+            if next_parent == J2000 {
+                continue;
+            }
+
+            let cur_dcm = self
+                .rotation_to_parent_with_acceleration(Frame::from_orient_ssb(next_parent), epoch)?;
+
+            if dcm_fwrd.0.from == cur_dcm.0.from {
+                dcm_fwrd = mul(cur_dcm, transpose(dcm_fwrd))?;
+            } else if dcm_fwrd.0.from == cur_dcm.0.to {
+                dcm_fwrd = transpose(mul(dcm_fwrd, cur_dcm)?);
+            } else if dcm_bwrd.0.to == cur_dcm.0.from {
+                dcm_bwrd = mul(cur_dcm, dcm_bwrd)?;
+            } else if dcm_bwrd.0.to == cur_dcm.0.to {
+                dcm_bwrd = mul(transpose(dcm_bwrd), cur_dcm)?;
+            } else {
+                return Err(OrientationError::Unreachable);
+            }
+
+            if next_parent == common_node {
+                break;
+            }
+        }
+
+        if dcm_fwrd.0.from == dcm_bwrd.0.from {
+            mul(dcm_bwrd, transpose(dcm_fwrd))
+        } else if dcm_fwrd.0.from == dcm_bwrd.0.to {
+            Ok(transpose(mul(dcm_fwrd, dcm_bwrd)?))
+        } else if dcm_fwrd.0.to == dcm_bwrd.0.to {
+            Ok(transpose(mul(transpose(dcm_fwrd), dcm_bwrd)?))
+        } else {
+            mul(dcm_bwrd, dcm_fwrd)
+        }
+    }
+
+    /// Rotates the provided Cartesian state and its acceleration into the requested frame, where the acceleration includes the Coriolis
+    /// term `2 C' v`, and the centripetal and Euler terms `C'' r` of the rotation between both frames.
+    pub fn rotate_to_with_acceleration(
+        &self,
+        state: CartesianState,
+        acceleration_km_s2: Vector3,
+        to_frame: Frame,
+    ) -> Result<(CartesianState, Vector3), OrientationError> {
+        let (dcm, rot_mat_ddt) =
+            self.rotate_with_derivatives(state.frame, to_frame, state.epoch)?;
+        let rot_mat_dt = dcm.rot_mat_dt.unwrap_or_else(Matrix3::zeros);
+
+        let new_acceleration_km_s2 = dcm.rot_mat * acceleration_km_s2
+            + 2.0 * rot_mat_dt * state.velocity_km_s
+            + rot_mat_ddt * state.radius_km;
+
+        Ok((
+            (dcm * state).context(OrientationPhysicsSnafu)?,
+            new_acceleration_km_s2,
+        ))
+    }
+}
@@ -12,10 +12,16 @@ use hifitime::Epoch;
 use snafu::prelude::*;
 
 use crate::{
-    errors::PhysicsError, math::interpolation::InterpolationError, naif::daf::DAFError,
-    prelude::FrameUid, structure::dataset::DataSetError,
+    constants::orientations::ITRF93,
+    errors::PhysicsError,
+    math::interpolation::InterpolationError,
+    naif::daf::{DAFError, DafDataType},
+    prelude::FrameUid,
+    structure::dataset::DataSetError,
+    NaifId,
 };
 
+mod acceleration;
 mod paths;
 mod rotate_to_parent;
 mod rotations;
@@ -42,6 +48,31 @@ pub enum OrientationError {
     },
     #[snafu(display("no orientation data loaded (must call load_bpc or DataSet::from_bytes)"))]
     NoOrientationsLoaded,
+    #[snafu(display(
+        "no path from {from} to {to}: orientation {id} is neither in the loaded BPCs, planetary data, Euler parameters, nor a fixed frame (is a kernel missing?)"
+    ))]
+    NoConnectingPath {
+        from: FrameUid,
+        to: FrameUid,
+        id: NaifId,
+    },
+    #[snafu(display(
+        "orientation {id} is only covered from {} to {} by the loaded BPCs but was requested at {requested}",
+        available.0,
+        available.1
+    ))]
+    EpochOutOfCoverage {
+        id: NaifId,
+        requested: Epoch,
+        /// Earliest start and latest end of the segments of this ID, which may have gaps in between
+        available: (Epoch, Epoch),
+    },
+    #[snafu(display(
+        "orientation {id} is stored as {kind:?} data, which is not supported for this computation"
+    ))]
+    UnsupportedDataType { id: NaifId, kind: DafDataType },
+    #[snafu(display("orientation {id} does not provide its angular acceleration: {reason}"))]
+    AccelerationUnavailable { id: NaifId, reason: &'static str },
     #[snafu(display("when {action} caused {source}"))]
     BPC {
         action: &'static str,
@@ -65,4 +96,32 @@ pub enum OrientationError {
     },
     #[snafu(display("unknown orientation ID associated with `{name}`"))]
     OrientationNameToId { name: String },
+    #[snafu(display("orientation {id} at {epoch} requires high precision BPC data (e.g. earth_latest_high_prec.bpc) and no lower precision model is used instead: {source}"))]
+    HighPrecisionUnavailable {
+        id: NaifId,
+        epoch: Epoch,
+        #[snafu(source(from(OrientationError, Box::new)))]
+        source: Box<OrientationError>,
+    },
+}
+
+/// Orientations which are only defined by high precision BPC data: they are never computed from the planetary data of the same body
+/// (e.g. ITRF93 is never replaced by IAU_EARTH), even if that data is loaded.
+pub const HIGH_PRECISION_ORIENTATIONS: [NaifId; 1] = [ITRF93];
+
+/// Returns an error if this orientation is only defined by high precision BPC data, which could not be found because of the provided error.
+pub(crate) fn ensure_not_high_precision(
+    id: NaifId,
+    epoch: Epoch,
+    bpc_error: OrientationError,
+) -> Result<(), OrientationError> {
+    if HIGH_PRECISION_ORIENTATIONS.contains(&id) {
+        Err(OrientationError::HighPrecisionUnavailable {
+            id,
+            epoch,
+            source: Box::new(bpc_error),
+        })
+    } else {
+        Ok(())
+    }
 }
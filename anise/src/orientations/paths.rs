@@ -11,7 +11,7 @@
 use hifitime::Epoch;
 use snafu::{ensure, ResultExt};
 
-use super::{BPCSnafu, NoOrientationsLoadedSnafu, OrientationDataSetSnafu, OrientationError};
+use super::{ensure_not_high_precision, BPCSnafu, NoOrientationsLoadedSnafu, OrientationError};
 use crate::almanac::Almanac;
 use crate::constants::orientations::{ECLIPJ2000, J2000};
 use crate::frames::Frame;
@@ -94,23 +94,8 @@ impl Almanac {
         }
 
         // Grab the summary data, which we use to find the paths
-        // Let's see if this orientation is defined in the loaded BPC files
-        let mut inertial_frame_id = match self.bpc_summary_at_epoch(source.orientation_id, epoch) {
-            Ok((summary, _, _)) => summary.inertial_frame_id,
-            Err(_) => {
-                // Not available as a BPC, so let's see if there's planetary data for it.
-                match self.planetary_data.get_by_id(source.orientation_id) {
-                    Ok(planetary_data) => planetary_data.parent_id,
-                    Err(_) => {
-                        // Finally, let's see if it's in the loaded Euler Parameters.
-                        self.euler_param_data
-                            .get_by_id(source.orientation_id)
-                            .context(OrientationDataSetSnafu)?
-                            .to
-                    }
-                }
-            }
-        };
+        let mut inertial_frame_id =
+            self.orientation_parent_id(source.orientation_id, source, common_center, epoch)?;
 
         of_path[of_path_len] = Some(inertial_frame_id);
         of_path_len += 1;
@@ -128,22 +113,8 @@ impl Almanac {
         }
 
         for _ in 0..MAX_TREE_DEPTH - 1 {
-            inertial_frame_id = match self.bpc_summary_at_epoch(inertial_frame_id, epoch) {
-                Ok((summary, _, _)) => summary.inertial_frame_id,
-                Err(_) => {
-                    // Not available as a BPC, so let's see if there's planetary data for it.
-                    match self.planetary_data.get_by_id(inertial_frame_id) {
-                        Ok(planetary_data) => planetary_data.parent_id,
-                        Err(_) => {
-                            // Finally, let's see if it's in the loaded Euler Parameters.
-                            self.euler_param_data
-                                .get_by_id(inertial_frame_id)
-                                .context(OrientationDataSetSnafu)?
-                                .to
-                        }
-                    }
-                }
-            };
+            inertial_frame_id =
+                self.orientation_parent_id(inertial_frame_id, source, common_center, epoch)?;
 
             of_path[of_path_len] = Some(inertial_frame_id);
             of_path_len += 1;
@@ -159,6 +130,53 @@ impl Almanac {
         })
     }
 
+    /// Returns the parent of this orientation ID at the provided epoch, looking it up in the loaded BPCs, planetary data, fixed frames, and
+    /// Euler parameters, in that order.
+    ///
+    /// # Errors
+    /// + [OrientationError::EpochOutOfCoverage] if it is only available in the loaded BPCs at other epochs;
+    /// + [OrientationError::NoConnectingPath] from the source to the root, if it is not loaded at all.
+    fn orientation_parent_id(
+        &self,
+        id: NaifId,
+        source: Frame,
+        root: NaifId,
+        epoch: Epoch,
+    ) -> Result<NaifId, OrientationError> {
+        let bpc_error = match self.bpc_summary_at_epoch(id, epoch) {
+            Ok((summary, _, _)) => return Ok(summary.inertial_frame_id),
+            Err(bpc_error) => bpc_error,
+        };
+
+        let coverage = match bpc_error {
+            OrientationError::EpochOutOfCoverage { available, .. } => Some(available),
+            _ => None,
+        };
+        ensure_not_high_precision(id, epoch, bpc_error)?;
+
+        // Not available as a BPC, so let's see if there's planetary data for it.
+        if let Ok(planetary_data) = self.planetary_data.get_by_id(id) {
+            Ok(planetary_data.parent_id)
+        } else if let Some(fixed) = self.fixed_frames.get(&id) {
+            Ok(fixed.parent.orientation_id)
+        } else if let Ok(euler_param) = self.euler_param_data.get_by_id(id) {
+            // Finally, let's see if it's in the loaded Euler Parameters.
+            Ok(euler_param.to)
+        } else if let Some(available) = coverage {
+            Err(OrientationError::EpochOutOfCoverage {
+                id,
+                requested: epoch,
+                available,
+            })
+        } else {
+            Err(OrientationError::NoConnectingPath {
+                from: source.into(),
+                to: Frame::new(source.ephemeris_id, root).into(),
+                id,
+            })
+        }
+    }
+
     /// Returns the orientation path between two frames and the common node. This may return a `DisjointRoots` error if the frames do not share a common root, which is considered a file integrity error.
     pub fn common_orientation_path(
         &self,
@@ -171,9 +189,40 @@ impl Almanac {
             return Ok((0, [None; MAX_TREE_DEPTH], from_frame.orientation_id));
         }
 
-        // Grab the paths
-        let (from_len, from_path) = self.orientation_path_to_root(from_frame, epoch)?;
-        let (to_len, to_path) = self.orientation_path_to_root(to_frame, epoch)?;
+        let ids = (from_frame.orientation_id, to_frame.orientation_id);
+        if let Some(path) = self.path_cache.orientation_path(ids.0, ids.1, epoch) {
+            return Ok(path);
+        }
+
+        let path = self.compute_common_orientation_path(from_frame, to_frame, epoch)?;
+        let validity = self.orientation_path_validity([ids.0, ids.1], epoch);
+        self.path_cache
+            .insert_orientation_path(ids.0, ids.1, path, validity);
+
+        Ok(path)
+    }
+
+    fn compute_common_orientation_path(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(usize, [Option<NaifId>; MAX_TREE_DEPTH], NaifId), OrientationError> {
+        // Grab the paths, and report any missing node as missing between both frames.
+        let between_frames = |e: OrientationError| match e {
+            OrientationError::NoConnectingPath { id, .. } => OrientationError::NoConnectingPath {
+                from: from_frame.into(),
+                to: to_frame.into(),
+                id,
+            },
+            e => e,
+        };
+        let (from_len, from_path) = self
+            .orientation_path_to_root(from_frame, epoch)
+            .map_err(between_frames)?;
+        let (to_len, to_path) = self
+            .orientation_path_to_root(to_frame, epoch)
+            .map_err(between_frames)?;
 
         // Now that we have the paths, we can find the matching origin.
 
@@ -205,4 +254,59 @@ impl Almanac {
             Ok((items, common_path, common_node))
         }
     }
+
+    /// Returns the span (in ET seconds) around the epoch over which the orientation paths of these frames up to the root do not change.
+    ///
+    /// This is the intersection of the coverage of the BPC segments used at this epoch, excluding the coverage of any other BPC segment
+    /// of the same frame with a different inertial frame, since that segment would change the path.
+    fn orientation_path_validity(&self, ids: [NaifId; 2], epoch: Epoch) -> (f64, f64) {
+        let et_s = epoch.to_et_seconds();
+        let mut start_et_s = f64::NEG_INFINITY;
+        let mut end_et_s = f64::INFINITY;
+
+        for mut id in ids {
+            for _ in 0..MAX_TREE_DEPTH {
+                let parent_id = if let Ok((summary, _, _)) = self.bpc_summary_at_epoch(id, epoch) {
+                    start_et_s = start_et_s.max(summary.start_epoch_et_s);
+                    end_et_s = end_et_s.min(summary.end_epoch_et_s);
+                    summary.inertial_frame_id
+                } else if let Ok(planetary_data) = self.planetary_data.get_by_id(id) {
+                    planetary_data.parent_id
+                } else if let Some(fixed) = self.fixed_frames.get(&id) {
+                    fixed.parent.orientation_id
+                } else if let Ok(euler_param) = self.euler_param_data.get_by_id(id) {
+                    euler_param.to
+                } else {
+                    // We've reached the root
+                    break;
+                };
+
+                for bpc in self.bpc_data.iter().take(self.num_loaded_bpc()).flatten() {
+                    for summary in bpc
+                        .data_summaries()
+                        .unwrap_or(&[])
+                        .iter()
+                        .filter(|summary| {
+                            !summary.is_empty()
+                                && summary.frame_id == id
+                                && summary.inertial_frame_id != parent_id
+                        })
+                    {
+                        if summary.end_epoch_et_s < et_s {
+                            start_et_s = start_et_s.max(summary.end_epoch_et_s);
+                        } else if summary.start_epoch_et_s > et_s {
+                            end_et_s = end_et_s.min(summary.start_epoch_et_s);
+                        }
+                    }
+                }
+
+                if parent_id == id {
+                    break;
+                }
+                id = parent_id;
+            }
+        }
+
+        (start_et_s, end_et_s)
+    }
 }
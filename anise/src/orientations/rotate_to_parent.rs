@@ -15,15 +15,22 @@ use super::{OrientationError, OrientationPhysicsSnafu};
 use crate::almanac::Almanac;
 use crate::constants::orientations::{ECLIPJ2000, J2000, J2000_TO_ECLIPJ2000_ANGLE_RAD};
 use crate::hifitime::Epoch;
+use crate::math::interpolation::{InterpDecodingSnafu, InterpolationError};
 use crate::math::rotation::{r1, r1_dot, r3, r3_dot, DCM};
+use crate::math::Vector3;
 use crate::naif::daf::datatypes::Type2ChebyshevSet;
-use crate::naif::daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord};
-use crate::orientations::{BPCSnafu, OrientationDataSetSnafu, OrientationInterpolationSnafu};
+use crate::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::orientations::{
+    ensure_not_high_precision, BPCSnafu, OrientationDataSetSnafu, OrientationInterpolationSnafu,
+};
 use crate::prelude::Frame;
 
 impl Almanac {
     /// Returns the direct cosine matrix (DCM) to rotate from the `source` to its parent in the orientation hierarchy at the provided epoch,
     ///
+    /// BPC records of degree zero only store the angles, so the time derivative of the DCM is then estimated from the angles of the
+    /// neighboring records: use [Self::rotation_to_parent_with_rate_flag] to know whether that is the case.
+    ///
     /// # Example
     /// If the ephemeris stores position interpolation coefficients in kilometer but this function is called with millimeters as a distance unit,
     /// the output vectors will be in mm, mm/s, mm/s^2 respectively.
@@ -33,17 +40,32 @@ impl Almanac {
     ///
     /// **WARNING:** This function only performs the rotation and no translation whatsoever. Use the `transform_to_parent_from` function instead to include rotations.
     pub fn rotation_to_parent(&self, source: Frame, epoch: Epoch) -> Result<DCM, OrientationError> {
+        self.rotation_to_parent_with_rate_flag(source, epoch)
+            .map(|(dcm, _)| dcm)
+    }
+
+    /// Returns the DCM to rotate from the `source` to its parent at the provided epoch, like [Self::rotation_to_parent], and whether its
+    /// time derivative was estimated by finite differencing because the underlying data does not define it (e.g. BPC records which
+    /// only store the angles). The estimated rate is less accurate than one computed from the data itself.
+    pub fn rotation_to_parent_with_rate_flag(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+    ) -> Result<(DCM, bool), OrientationError> {
         if source.orient_origin_id_match(J2000) {
             // The parent of Earth ecliptic J2000 is the J2000 inertial frame.
-            return Ok(DCM::identity(J2000, J2000));
+            return Ok((DCM::identity(J2000, J2000), false));
         } else if source.orient_origin_id_match(ECLIPJ2000) {
             // The parent of Earth ecliptic J2000 is the J2000 inertial frame.
-            return Ok(DCM {
-                rot_mat: r1(J2000_TO_ECLIPJ2000_ANGLE_RAD),
-                rot_mat_dt: None,
-                from: J2000,
-                to: ECLIPJ2000,
-            });
+            return Ok((
+                DCM {
+                    rot_mat: r1(J2000_TO_ECLIPJ2000_ANGLE_RAD),
+                    rot_mat_dt: None,
+                    from: J2000,
+                    to: ECLIPJ2000,
+                },
+                false,
+            ));
         }
         // Let's see if this orientation is defined in the loaded BPC files
         match self.bpc_summary_at_epoch(source.orientation_id, epoch) {
@@ -58,6 +80,7 @@ impl Almanac {
                     .ok_or(OrientationError::Unreachable)?;
 
                 // Compute the angles and their rates
+                let mut rate_estimated = false;
                 let (ra_dec_w, d_ra_dec_w) = match summary.data_type()? {
                     DafDataType::Type2ChebyshevTriplet => {
                         let data = bpc_data.nth_data::<Type2ChebyshevSet>(idx_in_bpc).context(
@@ -65,16 +88,25 @@ impl Almanac {
                                 action: "fetching data for interpolation",
                             },
                         )?;
-                        data.evaluate(epoch, summary)
-                            .context(OrientationInterpolationSnafu)?
+                        let (angles, rates) = data
+                            .evaluate(epoch, summary)
+                            .context(OrientationInterpolationSnafu)?;
+                        if data.degree() == 0 {
+                            // Constant records only store the angles, so their rates are always zero.
+                            rate_estimated = true;
+                            (
+                                angles,
+                                estimated_angle_rates(&data, summary, epoch)
+                                    .context(OrientationInterpolationSnafu)?,
+                            )
+                        } else {
+                            (angles, rates)
+                        }
                     }
-                    dtype => {
-                        return Err(OrientationError::BPC {
-                            action: "rotation to parent",
-                            source: DAFError::UnsupportedDatatype {
-                                dtype,
-                                kind: "BPC computations",
-                            },
+                    kind => {
+                        return Err(OrientationError::UnsupportedDataType {
+                            id: source.orientation_id,
+                            kind,
                         })
                     }
                 };
@@ -95,14 +127,18 @@ impl Almanac {
                         + ra_dot_rad * r3(twist_rad) * r1(dec_rad) * r3_dot(ra_rad),
                 );
 
-                Ok(DCM {
-                    rot_mat,
-                    rot_mat_dt,
-                    from: summary.inertial_frame_id,
-                    to: source.orientation_id,
-                })
+                Ok((
+                    DCM {
+                        rot_mat,
+                        rot_mat_dt,
+                        from: summary.inertial_frame_id,
+                        to: source.orientation_id,
+                    },
+                    rate_estimated,
+                ))
             }
-            Err(_) => {
+            Err(bpc_error) => {
+                ensure_not_high_precision(source.orientation_id, epoch, bpc_error)?;
                 // Not available as a BPC, so let's see if there's planetary data for it.
                 match self.planetary_data.get_by_id(source.orientation_id) {
                     Ok(planetary_data) => {
@@ -116,20 +152,65 @@ impl Almanac {
 
                         planetary_data
                             .rotation_to_parent(epoch, &system_data)
+                            .map(|dcm| (dcm, false))
                             .context(OrientationPhysicsSnafu)
                     }
                     Err(_) => {
+                        if let Some(fixed) = self.fixed_frames.get(&source.orientation_id) {
+                            trace!("query {source} wrt to its parent @ {epoch:E} using fixed frame data");
+                            return Ok((
+                                Self::fixed_frame_rotation_to_parent(source.orientation_id, fixed),
+                                false,
+                            ));
+                        }
                         trace!("query {source} wrt to its parent @ {epoch:E} using Euler parameter data");
                         // Finally, let's see if it's in the loaded Euler Parameters.
                         // We can call `into` because EPs can be converted directly into DCMs.
-                        Ok(self
-                            .euler_param_data
-                            .get_by_id(source.orientation_id)
-                            .context(OrientationDataSetSnafu)?
-                            .into())
+                        Ok((
+                            self.euler_param_data
+                                .get_by_id(source.orientation_id)
+                                .context(OrientationDataSetSnafu)?
+                                .into(),
+                            false,
+                        ))
                     }
                 }
             }
         }
     }
 }
+
+/// Returns the rates of the angles stored in these Chebyshev records by central differences between the angles of the records before and
+/// after the one covering the provided epoch, or by one sided differences at the edges of the segment.
+fn estimated_angle_rates<S: NAIFSummaryRecord>(
+    data: &Type2ChebyshevSet,
+    summary: &S,
+    epoch: Epoch,
+) -> Result<Vector3, InterpolationError> {
+    let last_record = data.num_records.saturating_sub(1);
+    // The records are indexed from the initial epoch of the data, which may precede the start of the segment.
+    let record_no = (((epoch - data.init_epoch).to_seconds() / data.interval_length.to_seconds())
+        .max(0.0) as usize)
+        .min(last_record);
+
+    let before = record_no.saturating_sub(1);
+    let after = (record_no + 1).min(last_record);
+    if before == after {
+        // A single record does not tell how the angles change.
+        return Ok(Vector3::zeros());
+    }
+
+    let mut mid_et_s = [0.0; 2];
+    let mut angles = [Vector3::zeros(); 2];
+    for (i, record_no) in [before, after].into_iter().enumerate() {
+        mid_et_s[i] = data
+            .nth_record(record_no)
+            .context(InterpDecodingSnafu)?
+            .midpoint_et_s;
+        angles[i] = data
+            .evaluate(Epoch::from_et_seconds(mid_et_s[i]), summary)?
+            .0;
+    }
+
+    Ok((angles[1] - angles[0]) / (mid_et_s[1] - mid_et_s[0]))
+}
@@ -35,6 +35,18 @@ impl Almanac {
         to_frame: Frame,
         epoch: Epoch,
     ) -> Result<DCM, OrientationError> {
+        self.rotate_with_rate_flag(from_frame, to_frame, epoch)
+            .map(|(dcm, _)| dcm)
+    }
+
+    /// Returns the DCM needed to rotate the `from_frame` to the `to_frame`, like [Self::rotate], and whether the time derivative of any
+    /// of the rotations composed along the way was estimated by finite differencing (cf. [Self::rotation_to_parent_with_rate_flag]).
+    pub fn rotate_with_rate_flag(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(DCM, bool), OrientationError> {
         let mut to_frame: Frame = to_frame;
 
         // If there is no frame info, the user hasn't loaded this frame, but might still want to compute a translation.
@@ -45,9 +57,9 @@ impl Almanac {
 
         if from_frame.orient_origin_match(to_frame) {
             // Both frames match, return this frame's hash (i.e. no need to go higher up).
-            return Ok(DCM::identity(
-                from_frame.orientation_id,
-                to_frame.orientation_id,
+            return Ok((
+                DCM::identity(from_frame.orientation_id, to_frame.orientation_id),
+                false,
             ));
         }
 
@@ -55,17 +67,22 @@ impl Almanac {
             self.common_orientation_path(from_frame, to_frame, epoch)?;
 
         // The fwrd variables are the states from the `from frame` to the common node
+        let mut rate_estimated = false;
         let mut dcm_fwrd = if from_frame.orient_origin_id_match(common_node) {
             DCM::identity(common_node, common_node)
         } else {
-            self.rotation_to_parent(from_frame, epoch)?
+            let (dcm, estimated) = self.rotation_to_parent_with_rate_flag(from_frame, epoch)?;
+            rate_estimated |= estimated;
+            dcm
         };
 
         // The bwrd variables are the states from the `to frame` back to the common node
         let mut dcm_bwrd = if to_frame.orient_origin_id_match(common_node) {
             DCM::identity(common_node, common_node)
         } else {
-            self.rotation_to_parent(to_frame, epoch)?.transpose()
+            let (dcm, estimated) = self.rotation_to_parent_with_rate_flag(to_frame, epoch)?;
+            rate_estimated |= estimated;
+            dcm.transpose()
         };
 
         for cur_node_id in path.iter().take(node_count) {
@@ -75,7 +92,9 @@ impl Almanac {
                 continue;
             }
 
-            let cur_dcm = self.rotation_to_parent(Frame::from_orient_ssb(next_parent), epoch)?;
+            let (cur_dcm, estimated) =
+                self.rotation_to_parent_with_rate_flag(Frame::from_orient_ssb(next_parent), epoch)?;
+            rate_estimated |= estimated;
 
             if dcm_fwrd.from == cur_dcm.from {
                 dcm_fwrd = (cur_dcm * dcm_fwrd.transpose()).context(OrientationPhysicsSnafu)?;
@@ -96,19 +115,21 @@ impl Almanac {
             }
         }
 
-        if dcm_fwrd.from == dcm_bwrd.from {
-            (dcm_bwrd * dcm_fwrd.transpose()).context(OrientationPhysicsSnafu)
+        let dcm = if dcm_fwrd.from == dcm_bwrd.from {
+            (dcm_bwrd * dcm_fwrd.transpose()).context(OrientationPhysicsSnafu)?
         } else if dcm_fwrd.from == dcm_bwrd.to {
-            Ok((dcm_fwrd * dcm_bwrd)
+            (dcm_fwrd * dcm_bwrd)
                 .context(OrientationPhysicsSnafu)?
-                .transpose())
+                .transpose()
         } else if dcm_fwrd.to == dcm_bwrd.to {
-            Ok((dcm_fwrd.transpose() * dcm_bwrd)
+            (dcm_fwrd.transpose() * dcm_bwrd)
                 .context(OrientationPhysicsSnafu)?
-                .transpose())
+                .transpose()
         } else {
-            (dcm_bwrd * dcm_fwrd).context(OrientationPhysicsSnafu)
-        }
+            (dcm_bwrd * dcm_fwrd).context(OrientationPhysicsSnafu)?
+        };
+
+        Ok((dcm, rate_estimated))
     }
 
     /// Rotates the provided Cartesian state into the requested observer frame
@@ -9,6 +9,7 @@
  */
 
 use crate::almanac::metaload::MetaAlmanacError;
+use crate::almanac::names::FrameNameError;
 use crate::almanac::planetary::PlanetaryDataError;
 use crate::ephemerides::EphemerisError;
 use crate::errors::{AlmanacError, DecodingError, InputOutputError, IntegrityError, PhysicsError};
@@ -61,6 +62,12 @@ impl From<PlanetaryDataError> for PyErr {
     }
 }
 
+impl From<FrameNameError> for PyErr {
+    fn from(err: FrameNameError) -> PyErr {
+        PyException::new_err(err.to_string())
+    }
+}
+
 impl From<MetaAlmanacError> for PyErr {
     fn from(err: MetaAlmanacError) -> PyErr {
         PyException::new_err(err.to_string())
@@ -12,22 +12,30 @@
  * This module only contains the serialization and deserialization components of ANISE.
  * All other computations are at a higher level module.
  */
+#[cfg(not(feature = "no-std-eval"))]
 pub mod dataset;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod lookuptable;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod metadata;
 pub mod planetocentric;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod semver;
+#[cfg(not(feature = "no-std-eval"))]
 pub mod spacecraft;
 
+#[cfg(not(feature = "no-std-eval"))]
 use self::{
     dataset::DataSet, planetocentric::PlanetaryData, semver::Semver, spacecraft::SpacecraftData,
 };
+#[cfg(not(feature = "no-std-eval"))]
 use crate::{
     almanac::{MAX_PLANETARY_DATA, MAX_SPACECRAFT_DATA},
     math::rotation::Quaternion,
 };
 
 /// The current version of ANISE
+#[cfg(not(feature = "no-std-eval"))]
 pub const ANISE_VERSION: Semver = Semver {
     major: 0,
     minor: 4,
@@ -35,8 +43,11 @@ pub const ANISE_VERSION: Semver = Semver {
 };
 
 /// Spacecraft Data Set allow mapping an ID and/or name to spacecraft data, optionally including mass, drag, SRP, an inertia information
+#[cfg(not(feature = "no-std-eval"))]
 pub type SpacecraftDataSet = DataSet<SpacecraftData, MAX_SPACECRAFT_DATA>;
 /// Planetary Data Set allow mapping an ID and/or name to planetary data, optionally including shape information and rotation information
+#[cfg(not(feature = "no-std-eval"))]
 pub type PlanetaryDataSet = DataSet<PlanetaryData, MAX_PLANETARY_DATA>;
 /// Euler Parameter Data Set allow mapping an ID and/or name to a time invariant Quaternion
+#[cfg(not(feature = "no-std-eval"))]
 pub type EulerParameterDataSet = DataSet<Quaternion, MAX_PLANETARY_DATA>;
@@ -8,20 +8,30 @@
  * Documentation: https://nyxspace.com/
  */
 
+#[cfg(not(feature = "no-std-eval"))]
 use core::fmt;
+#[cfg(not(feature = "no-std-eval"))]
 use der::{Decode, Encode, Reader, Writer};
+#[cfg(not(feature = "no-std-eval"))]
 use serde_derive::{Deserialize, Serialize};
 
-#[cfg(feature = "metaload")]
+#[cfg(not(feature = "no-std-eval"))]
+use crate::math::Vector3;
+
+#[cfg(all(feature = "metaload", not(feature = "no-std-eval")))]
 use serde_dhall::StaticType;
 
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::exceptions::PyTypeError;
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::prelude::*;
-#[cfg(feature = "python")]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 use pyo3::pyclass::CompareOp;
 
+/// Maximum number of bisections to convert a Cartesian position into geodetic coordinates, more than enough to reach machine precision.
+#[cfg(not(feature = "no-std-eval"))]
+const MAX_GEODETIC_ITERATIONS: usize = 2048;
+
 /// Only the tri-axial Ellipsoid shape model is currently supported by ANISE.
 /// This is directly inspired from SPICE PCK.
 /// > For each body, three radii are listed: The first number is
@@ -37,10 +47,17 @@ use pyo3::pyclass::CompareOp;
 /// :type polar_radius_km: float, optional
 /// :type semi_minor_equatorial_radius_km: float, optional
 /// :rtype: Ellipsoid
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
-#[cfg_attr(feature = "metaload", derive(StaticType))]
-#[cfg_attr(feature = "python", pyclass)]
-#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(not(feature = "no-std-eval"), derive(Serialize, Deserialize))]
+#[cfg_attr(
+    all(feature = "metaload", not(feature = "no-std-eval")),
+    derive(StaticType)
+)]
+#[cfg_attr(all(feature = "python", not(feature = "no-std-eval")), pyclass)]
+#[cfg_attr(
+    all(feature = "python", not(feature = "no-std-eval")),
+    pyo3(module = "anise.astro")
+)]
 pub struct Ellipsoid {
     pub semi_major_equatorial_radius_km: f64,
     pub semi_minor_equatorial_radius_km: f64,
@@ -67,8 +84,193 @@ impl Ellipsoid {
     }
 }
 
-#[cfg_attr(feature = "python", pymethods)]
-#[cfg(feature = "python")]
+#[cfg(not(feature = "no-std-eval"))]
+impl Ellipsoid {
+    /// Returns the semi axes of this ellipsoid as a vector, in the order of the body fixed X, Y, and Z axes.
+    fn semi_axes_km(&self) -> Vector3 {
+        Vector3::new(
+            self.semi_major_equatorial_radius_km,
+            self.semi_minor_equatorial_radius_km,
+            self.polar_radius_km,
+        )
+    }
+
+    /// Returns the body fixed Cartesian position in km of the provided geodetic latitude (φ) and longitude (λ) in degrees, and height in km.
+    ///
+    /// The geodetic latitude and longitude are those of the normal to the surface of this ellipsoid, so this is exact for spheroids
+    /// and tri-axial ellipsoids alike. For spheroids, this is the same computation as SPICE `georec`.
+    pub fn geodetic_to_cartesian(
+        &self,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        height_km: f64,
+    ) -> Vector3 {
+        let (sin_lat, cos_lat) = latitude_deg.to_radians().sin_cos();
+        let (sin_long, cos_long) = longitude_deg.to_radians().sin_cos();
+        let normal = Vector3::new(cos_lat * cos_long, cos_lat * sin_long, sin_lat);
+
+        // The surface point whose normal is this unit vector.
+        let axes_sq = self.semi_axes_km().component_mul(&self.semi_axes_km());
+        let surface_km =
+            axes_sq.component_mul(&normal) / axes_sq.dot(&normal.component_mul(&normal)).sqrt();
+
+        surface_km + height_km * normal
+    }
+
+    /// Returns the geodetic latitude (φ) in degrees, the geodetic longitude (λ) in degrees between -180 and +180, and the height in km
+    /// above this ellipsoid, of the provided body fixed Cartesian position in km.
+    ///
+    /// # Algorithm
+    /// For spheroids, this uses the closed form solution of Vermeille (2004), which is exact everywhere outside of the evolute of the
+    /// meridian ellipse (the few tens of kilometers around the center of the Earth). In that region, and for tri-axial ellipsoids, the
+    /// nearest point on the surface is found by a bisection on the Lagrange multiplier of that optimization problem, which converges
+    /// in all cases, including at the poles and below the surface.
+    pub fn cartesian_to_geodetic(&self, radius_km: Vector3) -> (f64, f64, f64) {
+        if self.is_spheroid() {
+            if let Some(geodetic) = self.spheroid_to_geodetic(radius_km) {
+                return geodetic;
+            }
+        }
+
+        self.ellipsoid_to_geodetic(radius_km)
+    }
+
+    /// Returns the first intersection, in km in the body fixed frame, of the surface of this ellipsoid with the ray starting at the provided
+    /// origin in km along the provided direction, or None if the ray misses the ellipsoid. If the origin is inside the ellipsoid, this
+    /// is where the ray exits the ellipsoid.
+    pub fn intersect_ray(&self, origin_km: Vector3, direction: Vector3) -> Option<Vector3> {
+        // Scaling each coordinate by its semi axis turns the ellipsoid into the unit sphere.
+        let axes_km = self.semi_axes_km();
+        let origin = origin_km.component_div(&axes_km);
+        let dir = direction.component_div(&axes_km);
+
+        // Solve a t^2 + 2 b t + c = 0 without cancellation.
+        let a = dir.norm_squared();
+        let b = origin.dot(&dir);
+        let c = origin.norm_squared() - 1.0;
+        let discriminant = b.powi(2) - a * c;
+        if a == 0.0 || discriminant < 0.0 {
+            return None;
+        }
+
+        let q = -(b + b.signum() * discriminant.sqrt());
+        let (near, far) = if q == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let (t1, t2) = (q / a, c / q);
+            (t1.min(t2), t1.max(t2))
+        };
+
+        let t = if near >= 0.0 {
+            near
+        } else if far >= 0.0 {
+            far
+        } else {
+            // The ellipsoid is behind the origin.
+            return None;
+        };
+
+        Some(origin_km + t * direction)
+    }
+
+    /// Vermeille's closed form solution, or None if the position is inside the evolute of the meridian ellipse.
+    fn spheroid_to_geodetic(&self, radius_km: Vector3) -> Option<(f64, f64, f64)> {
+        let a_km = self.semi_major_equatorial_radius_km;
+        let e2 = 1.0 - (self.polar_radius_km / a_km).powi(2);
+        let e4 = e2.powi(2);
+
+        let rho_km = radius_km.x.hypot(radius_km.y);
+        let z_km = radius_km.z;
+
+        let p = (rho_km / a_km).powi(2);
+        let q = (1.0 - e2) / a_km.powi(2) * z_km.powi(2);
+        let r = (p + q - e4) / 6.0;
+        if r <= 0.0 {
+            return None;
+        }
+
+        let s = e4 * p * q / (4.0 * r.powi(3));
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r * (1.0 + t + 1.0 / t);
+        let v = (u.powi(2) + e4 * q).sqrt();
+        let w = e2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w.powi(2)).sqrt() - w;
+        let d_km = k * rho_km / (k + e2);
+
+        let latitude_deg = (2.0 * z_km.atan2(d_km + d_km.hypot(z_km))).to_degrees();
+        let height_km = (k + e2 - 1.0) / k * d_km.hypot(z_km);
+
+        (latitude_deg.is_finite() && height_km.is_finite()).then_some((
+            latitude_deg,
+            radius_km.y.atan2(radius_km.x).to_degrees(),
+            height_km,
+        ))
+    }
+
+    /// Finds the nearest point on the surface, `p_i = a_i^2 x_i / (a_i^2 - c^2 + s)` with `c` the smallest semi axis, by bisecting on `s`
+    /// until that point is on the ellipsoid. This function is strictly decreasing for positive `s`, which brackets the solution.
+    fn ellipsoid_to_geodetic(&self, radius_km: Vector3) -> (f64, f64, f64) {
+        let axes_km = self.semi_axes_km();
+        let min_idx = axes_km.imin();
+        let c_sq = axes_km[min_idx].powi(2);
+        let deltas = axes_km.map(|a_km| a_km.powi(2) - c_sq);
+
+        // Components that are zero do not contribute, which also avoids 0/0 on the smallest axis.
+        let surface_eq = |s: f64| {
+            (0..3)
+                .filter(|&i| radius_km[i] != 0.0)
+                .map(|i| (axes_km[i] * radius_km[i] / (deltas[i] + s)).powi(2))
+                .sum::<f64>()
+                - 1.0
+        };
+
+        let (surface_km, s) = if radius_km[min_idx] == 0.0 && surface_eq(0.0) <= 0.0 {
+            // Deep inside the body and on the plane of the two largest axes: the nearest point is not on that plane,
+            // so pick the one in the positive direction of the smallest axis.
+            let mut surface_km = Vector3::zeros();
+            for i in (0..3).filter(|&i| i != min_idx && deltas[i] > 0.0) {
+                surface_km[i] = axes_km[i].powi(2) * radius_km[i] / deltas[i];
+            }
+            let on_plane = (0..3)
+                .filter(|&i| i != min_idx)
+                .map(|i| (surface_km[i] / axes_km[i]).powi(2))
+                .sum::<f64>();
+            surface_km[min_idx] = axes_km[min_idx] * (1.0 - on_plane).max(0.0).sqrt();
+            (surface_km, 0.0)
+        } else {
+            let mut lower = 0.0;
+            let mut upper = c_sq + axes_km.max() * radius_km.norm();
+            for _ in 0..MAX_GEODETIC_ITERATIONS {
+                let mid = 0.5 * (lower + upper);
+                if mid <= lower || mid >= upper {
+                    break;
+                }
+                if surface_eq(mid) > 0.0 {
+                    lower = mid;
+                } else {
+                    upper = mid;
+                }
+            }
+            let s = 0.5 * (lower + upper);
+            let surface_km =
+                Vector3::from_fn(|i, _| axes_km[i].powi(2) * radius_km[i] / (deltas[i] + s));
+            (surface_km, s)
+        };
+
+        // The offset from the surface point is along the normal: x - p = (s - c^2) p / a^2.
+        let gradient = surface_km.component_div(&axes_km.component_mul(&axes_km));
+        let normal = gradient.normalize();
+
+        (
+            normal.z.atan2(normal.x.hypot(normal.y)).to_degrees(),
+            normal.y.atan2(normal.x).to_degrees(),
+            (s - c_sq) * gradient.norm(),
+        )
+    }
+}
+
+#[cfg_attr(all(feature = "python", not(feature = "no-std-eval")), pymethods)]
+#[cfg(all(feature = "python", not(feature = "no-std-eval")))]
 impl Ellipsoid {
     /// Initializes a new [Ellipsoid] shape provided at least its semi major equatorial radius, optionally its semi minor equatorial radius, and optionally its polar radius.
     /// All units are in kilometers. If the semi minor equatorial radius is not provided, a bi-axial spheroid will be created using the semi major equatorial radius as
@@ -163,6 +365,7 @@ impl Ellipsoid {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 #[cfg_attr(feature = "python", pymethods)]
 impl Ellipsoid {
     /// Returns the mean equatorial radius in kilometers
@@ -196,6 +399,7 @@ impl Ellipsoid {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::Display for Ellipsoid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         if self.is_sphere() {
@@ -221,6 +425,7 @@ impl fmt::Display for Ellipsoid {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl Encode for Ellipsoid {
     fn encoded_len(&self) -> der::Result<der::Length> {
         self.semi_major_equatorial_radius_km.encoded_len()?
@@ -235,6 +440,7 @@ impl Encode for Ellipsoid {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl<'a> Decode<'a> for Ellipsoid {
     fn decode<R: Reader<'a>>(decoder: &mut R) -> der::Result<Self> {
         Ok(Self {
@@ -8,6 +8,11 @@
  * Documentation: https://nyxspace.com/
  */
 
+pub mod ellipsoid;
+#[cfg(not(feature = "no-std-eval"))]
+pub mod phaseangle;
+
+#[cfg(not(feature = "no-std-eval"))]
 use crate::{
     astro::PhysicsResult,
     constants::orientations::orientation_name_from_id,
@@ -18,17 +23,23 @@ use crate::{
     prelude::{Frame, FrameUid},
     NaifId,
 };
+#[cfg(not(feature = "no-std-eval"))]
 use core::f64::consts::FRAC_PI_2;
+#[cfg(not(feature = "no-std-eval"))]
 use core::fmt;
-pub mod ellipsoid;
-pub mod phaseangle;
+#[cfg(not(feature = "no-std-eval"))]
 use der::{Decode, Encode, Reader, Writer};
+#[cfg(not(feature = "no-std-eval"))]
 use ellipsoid::Ellipsoid;
+#[cfg(not(feature = "no-std-eval"))]
 use hifitime::{Epoch, TimeUnits, Unit};
+#[cfg(not(feature = "no-std-eval"))]
 use phaseangle::PhaseAngle;
 
+#[cfg(not(feature = "no-std-eval"))]
 use super::dataset::DataSetT;
 
+#[cfg(not(feature = "no-std-eval"))]
 pub const MAX_NUT_PREC_ANGLES: usize = 32;
 
 /// ANISE supports two different kinds of orientation data. High precision, with spline based interpolations, and constants right ascension, declination, and prime meridian, typically used for planetary constant data.
@@ -64,6 +75,7 @@ pub const MAX_NUT_PREC_ANGLES: usize = 32;
 /// t = ephemeris time, expressed as seconds past the reference epoch
 /// for this body or planetary system
 ///
+#[cfg(not(feature = "no-std-eval"))]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct PlanetaryData {
     /// The NAIF ID of this object
@@ -84,10 +96,12 @@ pub struct PlanetaryData {
     pub nut_prec_angles: [PhaseAngle<0>; MAX_NUT_PREC_ANGLES],
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl DataSetT for PlanetaryData {
     const NAME: &'static str = "planetary data";
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl PlanetaryData {
     /// Converts this planetary data into a Frame, unsetting any shape data for non-body-fixed frames (ID < 100).
     pub fn to_frame(&self, uid: FrameUid) -> Frame {
@@ -258,8 +272,27 @@ impl PlanetaryData {
             Ok(dcm)
         }
     }
+
+    /// Computes the rotation to the parent frame, including its time derivative as [Self::rotation_to_parent], and its second time
+    /// derivative, also by finite differencing.
+    pub fn rotation_to_parent_with_acceleration(
+        &self,
+        epoch: Epoch,
+        system: &Self,
+    ) -> PhysicsResult<(DCM, Matrix3)> {
+        let dcm = self.rotation_to_parent(epoch, system)?;
+        if dcm.rot_mat_dt.is_none() {
+            return Ok((dcm, Matrix3::zeros()));
+        }
+
+        let pre_rot_dcm = self.dcm_to_parent(epoch - 1.seconds(), system)?;
+        let post_rot_dcm = self.dcm_to_parent(epoch + 1.seconds(), system)?;
+
+        Ok((dcm, post_rot_dcm - 2.0 * dcm.rot_mat + pre_rot_dcm))
+    }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl Encode for PlanetaryData {
     fn encoded_len(&self) -> der::Result<der::Length> {
         let available_flags = self.available_data();
@@ -291,6 +324,7 @@ impl Encode for PlanetaryData {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl<'a> Decode<'a> for PlanetaryData {
     fn decode<R: Reader<'a>>(decoder: &mut R) -> der::Result<Self> {
         let object_id: NaifId = decoder.decode()?;
@@ -344,6 +378,7 @@ impl<'a> Decode<'a> for PlanetaryData {
     }
 }
 
+#[cfg(not(feature = "no-std-eval"))]
 impl fmt::Display for PlanetaryData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Initialize new frame UIDs with arbitrary ephemeris centers, and we don't print those.
@@ -379,7 +414,7 @@ impl fmt::Display for PlanetaryData {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "no-std-eval")))]
 mod planetary_constants_ut {
     use super::{Ellipsoid, PhaseAngle, PlanetaryData};
     use der::{Decode, Encode};
@@ -1,11 +1,14 @@
 // Start by creating the ANISE planetary data
 use anise::{
     constants::frames::{EARTH_ITRF93, EARTH_J2000, SUN_J2000},
-    naif::kpl::parser::convert_tpc,
-    prelude::{Aberration, Almanac, Orbit, BPC, SPK},
+    errors::AlmanacError,
+    naif::{kpl::parser::convert_tpc, spk::writer::SPKWriter},
+    prelude::{Aberration, Almanac, Frame, Orbit, BPC, SPK},
 };
 use core::str::FromStr;
-use hifitime::Epoch;
+use hifitime::{Epoch, Unit};
+use std::env;
+use std::path::PathBuf;
 
 #[test]
 fn test_load_ctx() {
@@ -105,3 +108,1336 @@ fn test_type3_state_transformation() {
 
     println!("{state:x}");
 }
+
+/// Writes an SPK to a temporary file with a single segment of a fixed position for the provided target.
+fn write_fixed_target_spk(file_name: &str, target_id: i32, x_km: f64) -> PathBuf {
+    let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+    let eme2k = EARTH_J2000;
+    let states = (0..=24)
+        .map(|hour| {
+            Orbit::new(
+                x_km,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                start + Unit::Hour * hour,
+                eme2k,
+            )
+        })
+        .collect::<Vec<Orbit>>();
+
+    let mut writer = SPKWriter::new(file_name);
+    writer
+        .add_type2_segment(&states, target_id, 399, 1, 5, 6 * Unit::Hour, file_name)
+        .unwrap();
+
+    let path = env::temp_dir().join(file_name);
+    writer.write(&path).unwrap();
+    path
+}
+
+#[test]
+fn test_load_many() {
+    let target = Frame::from_ephem_j2000(-2001);
+    let epoch = Epoch::from_gregorian_utc_hms(2025, 1, 1, 12, 0, 0);
+
+    let first = write_fixed_target_spk("anise-load-many-first.bsp", -2001, 7000.0);
+    let second = write_fixed_target_spk("anise-load-many-second.bsp", -2001, 8000.0);
+    let paths = [
+        PathBuf::from("../data/de440s.bsp"),
+        PathBuf::from("../data/pck08.pca"),
+        first.clone(),
+        second.clone(),
+    ];
+
+    let x_km = |almanac: &Almanac| {
+        almanac
+            .translate_to_parent(target, epoch)
+            .unwrap()
+            .radius_km
+            .x
+    };
+
+    // Loaded in order, the last segment wins, exactly as with sequential loading.
+    let almanac = Almanac::default().load_many(&paths).unwrap();
+    let sequential = paths.iter().fold(Almanac::default(), |almanac, path| {
+        almanac.load(path.to_str().unwrap()).unwrap()
+    });
+    assert_eq!(almanac.num_loaded_spk(), 3);
+    assert!((x_km(&almanac) - 8000.0).abs() < 1e-9);
+    assert_eq!(x_km(&almanac), x_km(&sequential));
+    // The planetary data is also loaded
+    assert!(almanac.frame_from_uid(EARTH_J2000).is_ok());
+
+    // Repeated loading always gives the same result, regardless of thread scheduling.
+    for _ in 0..10 {
+        let almanac = Almanac::default().load_many(&paths).unwrap();
+        assert_eq!(x_km(&almanac), 8000.0);
+    }
+
+    // Swapping the order of the colliding files swaps which one wins.
+    let almanac = Almanac::default()
+        .load_many(&[second.clone(), first.clone()])
+        .unwrap();
+    assert!((x_km(&almanac) - 7000.0).abs() < 1e-9);
+
+    // Errors are attributed to the file that caused them.
+    let missing = PathBuf::from("../data/does-not-exist.bsp");
+    match Almanac::default().load_many(&[first.clone(), missing]) {
+        Err(AlmanacError::Loading { path, .. }) => assert!(path.ends_with("does-not-exist.bsp")),
+        other => panic!("expected a loading error, got {other:?}"),
+    }
+
+    let malformed = env::temp_dir().join("anise-load-many-malformed.bsp");
+    std::fs::write(&malformed, [0xAB_u8; 2048]).unwrap();
+    match Almanac::default().load_many(&[malformed.clone(), second.clone()]) {
+        Err(AlmanacError::LoadingData { path, .. }) => {
+            assert_eq!(path, malformed.to_string_lossy())
+        }
+        other => panic!("expected a loading error, got {other:?}"),
+    }
+
+    for path in [first, second, malformed] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_unload() {
+    use anise::math::rotation::DCM;
+    use anise::naif::bpc::writer::BPCWriter;
+
+    let target = Frame::from_ephem_j2000(-2002);
+    let epoch = Epoch::from_gregorian_utc_hms(2025, 1, 1, 12, 0, 0);
+
+    let predicted = write_fixed_target_spk("anise-unload-predicted.bsp", -2002, 7000.0);
+    let updated = write_fixed_target_spk("anise-unload-updated.bsp", -2002, 8000.0);
+
+    let x_km = |almanac: &Almanac| {
+        almanac
+            .translate(target, EARTH_J2000, epoch, None)
+            .unwrap()
+            .radius_km
+            .x
+    };
+
+    let almanac = Almanac::default()
+        .load(predicted.to_str().unwrap())
+        .unwrap()
+        .load(updated.to_str().unwrap())
+        .unwrap();
+    assert_eq!(almanac.num_loaded_spk(), 2);
+    assert!((x_km(&almanac) - 8000.0).abs() < 1e-9);
+
+    // Unloading the most recent file makes the segments of the other one active again.
+    let reverted = almanac.unload(updated.to_str().unwrap()).unwrap();
+    assert_eq!(reverted.num_loaded_spk(), 1);
+    assert!((x_km(&reverted) - 7000.0).abs() < 1e-9);
+    // The original almanac is unchanged
+    assert!((x_km(&almanac) - 8000.0).abs() < 1e-9);
+
+    // Unloading the older file keeps the most recent one active, and loading files afterward still works.
+    let rotated = almanac.unload(predicted.to_str().unwrap()).unwrap();
+    assert_eq!(rotated.num_loaded_spk(), 1);
+    assert!((x_km(&rotated) - 8000.0).abs() < 1e-9);
+    let reloaded = rotated.load(predicted.to_str().unwrap()).unwrap();
+    assert!((x_km(&reloaded) - 7000.0).abs() < 1e-9);
+
+    // Without any data left, the translation fails.
+    let empty = reverted.unload(predicted.to_str().unwrap()).unwrap();
+    assert_eq!(empty.num_loaded_spk(), 0);
+    assert!(empty.translate(target, EARTH_J2000, epoch, None).is_err());
+
+    // Nothing matches anymore
+    assert!(empty.unload(predicted.to_str().unwrap()).is_err());
+    assert!(almanac.unload("not-loaded.bsp").is_err());
+
+    // Data loaded without a path is identified by its internal file name.
+    let orientation = |angle_rad: f64, name: &str| {
+        let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+        let rotations = (0..=24)
+            .map(|hour| (start + Unit::Hour * hour, DCM::r1(angle_rad, 1, 3000)))
+            .collect::<Vec<_>>();
+        let mut writer = BPCWriter::new(name);
+        writer
+            .add_type2_segment(&rotations, 3000, 1, 2, 24 * Unit::Hour, name)
+            .unwrap();
+        writer.build().unwrap()
+    };
+    let body_fixed = Frame::new(399, 3000);
+
+    let almanac = almanac
+        .with_bpc(orientation(0.5, "ANISE UNLOAD PREDICTED"))
+        .unwrap()
+        .with_bpc(orientation(0.7, "ANISE UNLOAD UPDATED"))
+        .unwrap();
+    let is_predicted = |almanac: &Almanac| {
+        let dcm = almanac.rotation_to_parent(body_fixed, epoch).unwrap();
+        (dcm.rot_mat - DCM::r1(0.5, 1, 3000).rot_mat).norm() < 1e-12
+    };
+    assert!(!is_predicted(&almanac));
+
+    let reverted = almanac.unload("ANISE UNLOAD UPDATED").unwrap();
+    assert_eq!(reverted.num_loaded_bpc(), 1);
+    assert_eq!(reverted.num_loaded_spk(), 2);
+    assert!(is_predicted(&reverted));
+
+    // The ANISE data sets are identified by their path, and unloading one empties it.
+    let with_pck = reverted.load("../data/pck08.pca").unwrap();
+    assert!(with_pck.frame_from_uid(EARTH_J2000).is_ok());
+    let without_pck = with_pck.unload("../data/pck08.pca").unwrap();
+    assert!(without_pck.frame_from_uid(EARTH_J2000).is_err());
+    assert_eq!(without_pck.num_loaded_spk(), 2);
+    assert_eq!(without_pck.num_loaded_bpc(), 1);
+    assert!(without_pck.unload("../data/pck08.pca").is_err());
+
+    for path in [predicted, updated] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_coverage() {
+    use anise::math::rotation::DCM;
+    use anise::naif::bpc::writer::BPCWriter;
+
+    let target = Frame::from_ephem_j2000(-2003);
+    // Two disjoint windows of a day each, with a day of gap in between.
+    let windows = [
+        Epoch::from_gregorian_utc_at_midnight(2025, 1, 1),
+        Epoch::from_gregorian_utc_at_midnight(2025, 1, 3),
+    ];
+
+    let mut writer = SPKWriter::new("ANISE COVERAGE");
+    for start in windows {
+        let states = (0..=24)
+            .map(|hour| {
+                Orbit::new(
+                    7000.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    start + Unit::Hour * hour,
+                    EARTH_J2000,
+                )
+            })
+            .collect::<Vec<Orbit>>();
+        writer
+            .add_type2_segment(&states, -2003, 399, 1, 5, 6 * Unit::Hour, "ANISE COVERAGE")
+            .unwrap();
+    }
+    let almanac = Almanac::default()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+
+    let expected = windows
+        .iter()
+        .map(|start| (*start, *start + Unit::Day * 1))
+        .collect::<Vec<_>>();
+
+    assert_eq!(almanac.ephemeris_coverage(target, EARTH_J2000), expected);
+    assert_eq!(almanac.ephemeris_coverage(EARTH_J2000, target), expected);
+    // Nothing is known about this object
+    assert!(almanac
+        .ephemeris_coverage(Frame::from_ephem_j2000(-2004), EARTH_J2000)
+        .is_empty());
+
+    // Same for the orientations
+    let mut writer = BPCWriter::new("ANISE COVERAGE");
+    for start in windows {
+        let rotations = (0..=24)
+            .map(|hour| (start + Unit::Hour * hour, DCM::r1(0.5, 1, 3000)))
+            .collect::<Vec<_>>();
+        writer
+            .add_type2_segment(&rotations, 3000, 1, 2, 24 * Unit::Hour, "ANISE COVERAGE")
+            .unwrap();
+    }
+    let almanac = almanac.with_bpc(writer.build().unwrap()).unwrap();
+
+    assert_eq!(
+        almanac.orientation_coverage(Frame::new(399, 3000), EARTH_J2000),
+        expected
+    );
+    // The rotation from J2000 to J2000 is always defined.
+    assert_eq!(
+        almanac.orientation_coverage(EARTH_J2000, EARTH_J2000).len(),
+        1
+    );
+}
+
+#[test]
+fn test_topocentric_frame() {
+    use anise::almanac::fixed_frames::FIXED_FRAME_FIRST_ID;
+    use anise::constants::frames::IAU_EARTH_FRAME;
+    use anise::constants::orientations::J2000;
+    use anise::math::Vector3;
+
+    let mut almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    // Madrid Deep Space Communications Complex
+    let (latitude_deg, longitude_deg, height_km) = (40.427_222, 355.749_444, 0.834_939);
+    let station = almanac
+        .add_topocentric_frame(
+            "MADRID_TOPO",
+            IAU_EARTH_FRAME,
+            latitude_deg,
+            longitude_deg,
+            height_km,
+        )
+        .unwrap();
+    assert_eq!(
+        station,
+        Frame::new(FIXED_FRAME_FIRST_ID, FIXED_FRAME_FIRST_ID)
+    );
+
+    let epoch = Epoch::from_gregorian_utc_hms(2025, 1, 1, 12, 0, 0);
+
+    // Manual computation: geodetic to Cartesian in the body fixed frame, and rotation into J2000.
+    let iau_earth = almanac.frame_from_uid(IAU_EARTH_FRAME).unwrap();
+    let radius_bf_km = iau_earth
+        .geodetic_to_cartesian(latitude_deg, longitude_deg, height_km)
+        .unwrap();
+    let dcm = almanac.rotate(IAU_EARTH_FRAME, EARTH_J2000, epoch).unwrap();
+    let expected_km = dcm.rot_mat * radius_bf_km;
+    let expected_km_s = dcm.rot_mat_dt.unwrap() * radius_bf_km;
+
+    // Translations are computed in J2000, like for any other frame.
+    let state = almanac
+        .transform(station.with_orient(J2000), EARTH_J2000, epoch, None)
+        .unwrap();
+    assert!((state.radius_km - expected_km).norm() < 1e-9);
+    assert!((state.velocity_km_s - expected_km_s).norm() < 1e-12);
+    // The station rotates with the Earth
+    assert!((state.velocity_km_s.norm() - 0.3546).abs() < 1e-3);
+
+    // The zenith of the station frame is the normal to the ellipsoid.
+    let sez_to_bf = almanac.rotate(station, IAU_EARTH_FRAME, epoch).unwrap();
+    let (sin_lat, cos_lat) = latitude_deg.to_radians().sin_cos();
+    let (sin_long, cos_long) = longitude_deg.to_radians().sin_cos();
+    let zenith = Vector3::new(cos_lat * cos_long, cos_lat * sin_long, sin_lat);
+    assert!((sez_to_bf.rot_mat * Vector3::z() - zenith).norm() < 1e-12);
+
+    // The Earth is below the station when seen from the SEZ frame.
+    let earth_sez = almanac
+        .transform(EARTH_J2000, station, epoch, None)
+        .unwrap();
+    assert!(earth_sez.radius_km.z < -6300.0);
+
+    // The frame is kept when loading new data, and new frames get new IDs.
+    let mut reloaded = almanac.load("../data/pck08.pca").unwrap();
+    let reloaded_state = reloaded
+        .transform(station.with_orient(J2000), EARTH_J2000, epoch, None)
+        .unwrap();
+    assert_eq!(reloaded_state.radius_km, state.radius_km);
+
+    let other = reloaded
+        .add_topocentric_frame("EQUATOR_TOPO", IAU_EARTH_FRAME, 0.0, 0.0, 0.0)
+        .unwrap();
+    assert_eq!(other.ephemeris_id, FIXED_FRAME_FIRST_ID + 1);
+
+    // Frames without a shape cannot host a topocentric frame.
+    assert!(reloaded
+        .add_topocentric_frame("NO_SHAPE", Frame::new(-2005, -2005), 0.0, 0.0, 0.0)
+        .is_err());
+}
+
+#[test]
+fn test_export_subset() {
+    use anise::constants::frames::{EARTH_MOON_BARYCENTER_J2000, MOON_J2000};
+
+    let original_path = "../data/de440s.bsp";
+    let almanac = Almanac::new(original_path).unwrap();
+
+    // The window boundaries are not aligned with the Chebyshev records of DE440s.
+    let window = (
+        Epoch::from_gregorian_utc_hms(2021, 1, 3, 5, 17, 0),
+        Epoch::from_gregorian_utc_hms(2021, 2, 11, 21, 42, 0),
+    );
+    let path = env::temp_dir().join("anise-de440s-subset.bsp");
+    almanac
+        .export_subset(&[3, 399, 301], window, &path)
+        .unwrap();
+
+    let original_size = std::fs::metadata(original_path).unwrap().len();
+    let subset_size = std::fs::metadata(&path).unwrap().len();
+    assert!(
+        subset_size * 100 < original_size,
+        "subset of {subset_size} bytes is not substantially smaller than {original_size} bytes"
+    );
+
+    // The subset is a standalone SPK.
+    let subset = Almanac::new(&path.to_string_lossy()).unwrap();
+    let summaries = subset.spk_data[0]
+        .as_ref()
+        .unwrap()
+        .data_summaries()
+        .unwrap();
+    assert!(summaries
+        .iter()
+        .all(|summary| [3, 399, 301].contains(&summary.target_id)));
+
+    let mut epoch = window.0;
+    while epoch <= window.1 {
+        for frame in [EARTH_MOON_BARYCENTER_J2000, EARTH_J2000, MOON_J2000] {
+            let expected = almanac.translate_to_parent(frame, epoch).unwrap();
+            let state = subset.translate_to_parent(frame, epoch).unwrap();
+            let err_km = (state.radius_km - expected.radius_km).norm();
+            let err_km_s = (state.velocity_km_s - expected.velocity_km_s).norm();
+            if epoch > window.0 + Unit::Day * 16 && epoch < window.1 - Unit::Day * 16 {
+                // Interior epochs use the copied records, so they match to machine precision.
+                assert!(
+                    err_km <= f64::EPSILON * expected.rmag_km(),
+                    "{frame} {epoch}: {err_km:e} km"
+                );
+                assert!(
+                    err_km_s <= f64::EPSILON * expected.vmag_km_s(),
+                    "{frame} {epoch}: {err_km_s:e} km/s"
+                );
+            } else {
+                // Boundary epochs may use the refit records.
+                assert!(err_km < 1e-6, "{frame} {epoch}: {err_km:e} km");
+                assert!(err_km_s < 1e-9, "{frame} {epoch}: {err_km_s:e} km/s");
+            }
+        }
+        epoch += Unit::Hour * 5 + Unit::Minute * 13;
+    }
+
+    // Sun is not in the subset, nor are epochs outside of the window.
+    assert!(subset.translate_to_parent(SUN_J2000, window.0).is_err());
+    assert!(subset
+        .translate_to_parent(EARTH_J2000, window.0 - Unit::Day * 1)
+        .is_err());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_transform_aberration_config() {
+    use anise::astro::AberrationConfig;
+    use anise::constants::frames::SATURN_BARYCENTER_J2000;
+
+    let almanac = Almanac::new("../data/de440s.bsp").unwrap();
+
+    let epoch = Epoch::from_str("2021-10-29 12:34:56 TDB").unwrap();
+
+    // The default configuration is that of the transform.
+    let expected = almanac
+        .transform(SATURN_BARYCENTER_J2000, EARTH_J2000, epoch, Aberration::CN)
+        .unwrap();
+    let (state, convergence) = almanac
+        .transform_cfg(
+            SATURN_BARYCENTER_J2000,
+            EARTH_J2000,
+            epoch,
+            Aberration::CN,
+            AberrationConfig::default(),
+        )
+        .unwrap();
+    assert_eq!(state, expected);
+    let convergence = convergence.unwrap();
+    assert!(convergence.converged, "{convergence}");
+    assert!(convergence.iterations <= Aberration::MAX_CONVERGED_ITERATIONS);
+
+    // Without aberration correction, there are no iterations to report.
+    let (_, convergence) = almanac
+        .transform_cfg(
+            SATURN_BARYCENTER_J2000,
+            EARTH_J2000,
+            epoch,
+            None,
+            AberrationConfig::default(),
+        )
+        .unwrap();
+    assert!(convergence.is_none());
+
+    // Tightening the tolerance requires more iterations and brings the state closer to the converged one: the error in the position
+    // is the velocity of the target times the error in the light time, which is at most the tolerance divided by the speed of light.
+    let mut prev_iterations = 0;
+    for tol_km in [1e4, 1e2, 1.0, 1e-2, 1e-4, 0.0] {
+        let (state, convergence) = almanac
+            .transform_cfg(
+                SATURN_BARYCENTER_J2000,
+                EARTH_J2000,
+                epoch,
+                Aberration::CN,
+                AberrationConfig {
+                    max_iter: Aberration::MAX_CONVERGED_ITERATIONS,
+                    tol_km,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let convergence = convergence.unwrap();
+        assert!(convergence.converged, "{tol_km} km: {convergence}");
+        assert!(convergence.residual_km <= tol_km);
+        assert!(convergence.iterations >= prev_iterations);
+        prev_iterations = convergence.iterations;
+
+        let err_km = (state.radius_km - expected.radius_km).norm();
+        assert!(err_km <= tol_km, "{tol_km} km: err = {err_km:e} km");
+        println!("{tol_km:e} km: {convergence}\terr = {err_km:e} km");
+    }
+    assert!(prev_iterations > 1);
+
+    // A single iteration cannot converge to Saturn, which is reported instead of silently returning the unconverged state.
+    let (state, convergence) = almanac
+        .transform_cfg(
+            SATURN_BARYCENTER_J2000,
+            EARTH_J2000,
+            epoch,
+            Aberration::CN,
+            AberrationConfig {
+                max_iter: 1,
+                tol_km: 1e-3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let convergence = convergence.unwrap();
+    assert!(!convergence.converged);
+    assert_eq!(convergence.iterations, 1);
+    assert!(convergence.residual_km > 1e-3);
+    // That state is the unconverged light time correction.
+    let unconverged = almanac
+        .transform(SATURN_BARYCENTER_J2000, EARTH_J2000, epoch, Aberration::LT)
+        .unwrap();
+    assert_eq!(state.radius_km, unconverged.radius_km);
+}
+
+#[test]
+fn test_sub_solar_point() {
+    use anise::almanac::surface::SubPointMethod;
+    use anise::constants::frames::IAU_EARTH_FRAME;
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    let sub_solar = |epoch: Epoch, method: SubPointMethod, ab_corr: Option<Aberration>| {
+        almanac
+            .sub_observer_point(IAU_EARTH_FRAME, SUN_J2000, epoch, method, ab_corr)
+            .unwrap()
+    };
+
+    // Reference: the March equinox of 2024 occurred on 2024-03-20 at 03:06 UTC, and the equation of time was then about -7.6 minutes,
+    // so the Sun crossed the Greenwich meridian at about 12:07:36 UTC that day, i.e. it was 1.9 degrees east of it at noon UTC.
+    // Its declination increases by about 0.394 degrees per day around the equinox.
+    let equinox = Epoch::from_str("2024-03-20T03:06:00 UTC").unwrap();
+    let noon = Epoch::from_str("2024-03-20T12:00:00 UTC").unwrap();
+
+    for method in [SubPointMethod::NearPoint, SubPointMethod::Intercept] {
+        let (lat_deg, long_deg, alt_km) = sub_solar(equinox, method, None);
+        println!("{method} @ {equinox}: {lat_deg:.6} deg\t{long_deg:.6} deg\t{alt_km:.3} km");
+        assert!(lat_deg.abs() < 0.01, "{method}: {lat_deg} deg");
+        // 03:06 UTC is 133.5 degrees before noon at Greenwich.
+        assert!(
+            (long_deg - (133.5 + 1.9)).abs() < 0.15,
+            "{method}: {long_deg} deg"
+        );
+        assert!((1.47e8..1.50e8).contains(&alt_km), "{method}: {alt_km} km");
+
+        let (lat_deg, long_deg, _) = sub_solar(noon, method, None);
+        println!("{method} @ {noon}: {lat_deg:.6} deg\t{long_deg:.6} deg");
+        assert!((lat_deg - 0.146).abs() < 0.02, "{method}: {lat_deg} deg");
+        assert!((long_deg - 1.9).abs() < 0.15, "{method}: {long_deg} deg");
+    }
+
+    // Both methods agree for a distant observer above a spheroid: the Earth flattening only matters away from the equator.
+    let (near_lat_deg, near_long_deg, near_alt_km) =
+        sub_solar(noon, SubPointMethod::NearPoint, None);
+    let (icpt_lat_deg, icpt_long_deg, icpt_alt_km) =
+        sub_solar(noon, SubPointMethod::Intercept, None);
+    assert!((near_lat_deg - icpt_lat_deg).abs() < 1e-3);
+    assert!((near_long_deg - icpt_long_deg).abs() < 1e-9);
+    assert!((near_alt_km - icpt_alt_km).abs() < 1e-3);
+
+    // With light time correction, the orientation of the Earth is that of about 497 seconds earlier, when it had rotated less.
+    let (lt_lat_deg, lt_long_deg, _) = sub_solar(noon, SubPointMethod::NearPoint, Aberration::LT);
+    let lt_shift_deg = 497.0 * 360.985_612_288_808_1 / 86_400.0;
+    assert!((lt_lat_deg - near_lat_deg).abs() < 0.01);
+    assert!(
+        (lt_long_deg - near_long_deg - lt_shift_deg).abs() < 0.02,
+        "shift = {} deg",
+        lt_long_deg - near_long_deg
+    );
+
+    // The center of the Earth is inside its ellipsoid, so it has no sub-observer point by intercept.
+    assert!(almanac
+        .sub_observer_point(
+            IAU_EARTH_FRAME,
+            EARTH_J2000,
+            noon,
+            SubPointMethod::Intercept,
+            None
+        )
+        .is_err());
+}
+
+#[test]
+fn test_surface_intercept() {
+    use anise::almanac::surface::SubPointMethod;
+    use anise::constants::frames::IAU_EARTH_FRAME;
+    use anise::constants::SPEED_OF_LIGHT_KM_S;
+    use anise::math::Vector3;
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/gmat-hermite.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    let sc = Frame::from_ephem_j2000(-10000001);
+    let epoch = Epoch::from_str("2000-01-01T14:00:00 UTC").unwrap();
+
+    // A ray pointing to the center of the Earth hits the sub-satellite point.
+    let nadir_j2000 = -almanac
+        .translate(sc, EARTH_J2000, epoch, None)
+        .unwrap()
+        .r_hat();
+
+    let intercept = almanac
+        .surface_intercept(sc, IAU_EARTH_FRAME, epoch, EARTH_J2000, nadir_j2000, None)
+        .unwrap()
+        .expect("nadir ray missed the Earth");
+    println!("{intercept}");
+    assert_eq!(intercept.epoch, epoch);
+
+    let (lat_deg, long_deg, height_km) = intercept.geodetic().unwrap();
+    assert!(height_km.abs() < 1e-6);
+
+    let (sub_lat_deg, sub_long_deg, alt_km) = almanac
+        .sub_observer_point(IAU_EARTH_FRAME, sc, epoch, SubPointMethod::Intercept, None)
+        .unwrap();
+    assert!((lat_deg - sub_lat_deg).abs() < 1e-9);
+    assert!((long_deg - sub_long_deg).abs() < 1e-9);
+    assert!((intercept.surface_vector_km.norm() - alt_km).abs() < 1e-6);
+
+    // The same ray expressed in the body fixed frame hits the same point, and only its direction matters.
+    let nadir_fixed = almanac
+        .rotate(EARTH_J2000, IAU_EARTH_FRAME, epoch)
+        .unwrap()
+        .rot_mat
+        * nadir_j2000;
+    let fixed = almanac
+        .surface_intercept(
+            sc,
+            IAU_EARTH_FRAME,
+            epoch,
+            IAU_EARTH_FRAME,
+            10.0 * nadir_fixed,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+    assert!((fixed.point_km - intercept.point_km).norm() < 1e-6);
+
+    // With light time correction, the light left the surface a few milliseconds earlier, when the Earth was about a hundred meters
+    // away along its orbit.
+    let lt = almanac
+        .surface_intercept(
+            sc,
+            IAU_EARTH_FRAME,
+            epoch,
+            EARTH_J2000,
+            nadir_j2000,
+            Aberration::CN,
+        )
+        .unwrap()
+        .unwrap();
+    let lt_s = lt.surface_vector_km.norm() / SPEED_OF_LIGHT_KM_S;
+    assert!(((epoch - lt.epoch).to_seconds() - lt_s).abs() < 1e-9);
+    assert!((lt.point_km - intercept.point_km).norm() < 0.5);
+
+    // Pointing away from the Earth, or tangentially to it, misses it.
+    assert!(almanac
+        .surface_intercept(sc, IAU_EARTH_FRAME, epoch, EARTH_J2000, -nadir_j2000, None)
+        .unwrap()
+        .is_none());
+    let tangent = nadir_j2000.cross(&Vector3::z()).normalize();
+    assert!(almanac
+        .surface_intercept(sc, IAU_EARTH_FRAME, epoch, EARTH_J2000, tangent, None)
+        .unwrap()
+        .is_none());
+
+    // The ray is not corrected for stellar aberration.
+    assert!(almanac
+        .surface_intercept(
+            sc,
+            IAU_EARTH_FRAME,
+            epoch,
+            EARTH_J2000,
+            nadir_j2000,
+            Aberration::CN_S
+        )
+        .is_err());
+}
+
+#[test]
+fn test_angular_separation_phase_angle() {
+    use anise::constants::frames::{MARS_BARYCENTER_J2000, MOON_J2000};
+    use core::f64::consts::PI;
+
+    let almanac = Almanac::new("../data/de440s.bsp").unwrap();
+
+    // Reference: the greatest eclipse of the total solar eclipse of 2024-04-08 occurred at 18:17:16 UTC with gamma = 0.3431, so the
+    // shadow axis passed 0.3431 Earth radii from the center of the Earth, and the Moon was about 359,840 km away.
+    let greatest_eclipse = Epoch::from_str("2024-04-08T18:17:16 UTC").unwrap();
+    let expected_deg = (0.3431_f64 * 6378.137 / 359_840.0).to_degrees();
+
+    let separation = |epoch: Epoch| {
+        almanac
+            .angular_separation(SUN_J2000, MOON_J2000, EARTH_J2000, epoch, Aberration::LT_S)
+            .unwrap()
+    };
+
+    let sep = separation(greatest_eclipse);
+    println!("Sun-Moon separation at greatest eclipse: {sep}");
+    assert!((sep.degrees() - expected_deg).abs() < 0.02, "{sep}");
+    assert_eq!(sep.radians.to_degrees(), sep.degrees());
+    // And the Moon kept moving along its orbit, by about half a degree per hour relative to the Sun.
+    for hours in [-1, 1] {
+        assert!(separation(greatest_eclipse + Unit::Hour * hours).degrees() > sep.degrees() + 0.2);
+    }
+
+    // The phase angle of Mars closes the Sun-Earth-Mars triangle with the elongation of Mars and the Earth-Sun-Mars angle.
+    let epoch = Epoch::from_str("2021-02-01T00:00:00 UTC").unwrap();
+    let phase = almanac
+        .phase_angle(MARS_BARYCENTER_J2000, EARTH_J2000, SUN_J2000, epoch, None)
+        .unwrap();
+    let elongation = almanac
+        .angular_separation(SUN_J2000, MARS_BARYCENTER_J2000, EARTH_J2000, epoch, None)
+        .unwrap();
+    let sun_angle = almanac
+        .angular_separation(EARTH_J2000, MARS_BARYCENTER_J2000, SUN_J2000, epoch, None)
+        .unwrap();
+    println!("Mars phase: {phase}\telongation: {elongation}\tSun angle: {sun_angle}");
+    assert!((phase.radians + elongation.radians + sun_angle.radians - PI).abs() < 1e-12);
+
+    // The light time correction changes the phase angle slightly.
+    let lt_phase = almanac
+        .phase_angle(
+            MARS_BARYCENTER_J2000,
+            EARTH_J2000,
+            SUN_J2000,
+            epoch,
+            Aberration::CN,
+        )
+        .unwrap();
+    assert!((lt_phase.degrees() - phase.degrees()).abs() < 0.01);
+    assert_ne!(lt_phase, phase);
+
+    // Reference: Mars is seen almost fully lit at the opposition of 2020-10-13, and its phase angle never exceeds about 47.4 degrees,
+    // the arc sine of the ratio of the largest Earth-Sun distance to the smallest Mars-Sun distance.
+    let opposition = Epoch::from_str("2020-10-13T23:20:00 UTC").unwrap();
+    let mut max_phase_deg: f64 = 0.0;
+    let mut min_phase_deg: f64 = 180.0;
+    for day in 0..780 {
+        let phase_deg = almanac
+            .phase_angle(
+                MARS_BARYCENTER_J2000,
+                EARTH_J2000,
+                SUN_J2000,
+                opposition + Unit::Day * day,
+                Aberration::CN,
+            )
+            .unwrap()
+            .degrees();
+        max_phase_deg = max_phase_deg.max(phase_deg);
+        min_phase_deg = min_phase_deg.min(phase_deg);
+    }
+    println!("Mars phase angle between {min_phase_deg} and {max_phase_deg} deg");
+    assert!(min_phase_deg < 5.0);
+    assert!((35.0..47.5).contains(&max_phase_deg));
+}
+
+#[test]
+fn test_frame_from_name() {
+    use anise::almanac::names::FrameNameError;
+    use anise::constants::frames::{
+        EARTH_MOON_BARYCENTER_J2000, IAU_EARTH_FRAME, IAU_MARS_FRAME, MOON_J2000,
+        MOON_ME_DE421_FRAME, SSB_J2000,
+    };
+    use anise::naif::kpl::parser::convert_fk;
+
+    let almanac = Almanac::default();
+
+    // The built-in names are case insensitive, and spaces and dashes are underscores.
+    assert_eq!(almanac.frame_from_name("earth").unwrap(), EARTH_J2000);
+    assert_eq!(almanac.frame_from_name("iau_mars").unwrap(), IAU_MARS_FRAME);
+    assert_eq!(almanac.frame_from_name("J2000").unwrap(), SSB_J2000);
+    assert_eq!(
+        almanac.frame_from_name("Earth-Moon Barycenter").unwrap(),
+        EARTH_MOON_BARYCENTER_J2000
+    );
+
+    // Typos report the closest known name.
+    let err = almanac.frame_from_name("EARHT").unwrap_err();
+    assert_eq!(
+        err,
+        FrameNameError::UnknownFrameName {
+            name: "EARHT".to_string(),
+            closest: Some("EARTH".to_string())
+        }
+    );
+    println!("{err}");
+    assert!(err.to_string().contains("did you mean `EARTH`?"));
+
+    // The frames of the loaded FK are added to the built-in names, centered on the body which starts their name.
+    assert!(almanac.frame_from_name("MOON_ME_DE421").is_err());
+    let almanac = almanac
+        .with_euler_parameters(convert_fk("../data/moon_080317.txt", false).unwrap())
+        .load("../data/pck08.pca")
+        .unwrap()
+        .load("../data/de440s.bsp")
+        .unwrap();
+    let moon_me = almanac.frame_from_name("moon_me_de421").unwrap();
+    assert_eq!(moon_me.ephemeris_id, MOON_ME_DE421_FRAME.ephemeris_id);
+    assert_eq!(moon_me.orientation_id, MOON_ME_DE421_FRAME.orientation_id);
+    // And the planetary data is filled in when it is loaded.
+    let earth = almanac.frame_from_name("Earth").unwrap();
+    assert_eq!(earth, almanac.frame_from_uid(EARTH_J2000).unwrap());
+    assert!(earth.mu_km3_s2.is_some());
+
+    let epoch = Epoch::from_str("2024-04-08T18:17:16 UTC").unwrap();
+    let by_name = almanac
+        .translate_by_name("MOON", "EARTH", epoch, Aberration::LT)
+        .unwrap();
+    let by_frame = almanac
+        .translate(MOON_J2000, EARTH_J2000, epoch, Aberration::LT)
+        .unwrap();
+    assert_eq!(by_name.radius_km, by_frame.radius_km);
+    assert_eq!(by_name.velocity_km_s, by_frame.velocity_km_s);
+
+    let by_name = almanac
+        .transform_by_name("Moon", "IAU Earth", epoch, None)
+        .unwrap();
+    let by_frame = almanac
+        .transform(MOON_J2000, IAU_EARTH_FRAME, epoch, None)
+        .unwrap();
+    assert_eq!(by_name.radius_km, by_frame.radius_km);
+    assert_eq!(by_name.velocity_km_s, by_frame.velocity_km_s);
+
+    assert!(matches!(
+        almanac.translate_by_name("MOON", "EARHT", epoch, None),
+        Err(AlmanacError::FrameName { .. })
+    ));
+}
+
+#[test]
+fn test_load_body_names() {
+    use anise::almanac::names::FrameNameError;
+    use anise::constants::orientations::J2000;
+
+    let almanac = Almanac::default();
+    assert_eq!(almanac.body_id_from_name("Mars"), Some(499));
+    assert_eq!(almanac.body_name_from_id(399), Some("EARTH"));
+    assert_eq!(almanac.body_id_from_name("MY_SAT"), None);
+    // The standard bodies which are not built in frame names also resolve as frames.
+    assert_eq!(
+        almanac.frame_from_name("Phobos").unwrap(),
+        Frame::new(401, J2000)
+    );
+
+    let path = env::temp_dir().join("anise-body-names.tf");
+    std::fs::write(
+        &path,
+        "KPL/FK
+
+\\begindata
+
+NAIF_BODY_CODE += -999999
+NAIF_BODY_NAME += 'MY_SAT'
+
+\\begintext
+",
+    )
+    .unwrap();
+    let almanac = almanac.load(&path.to_string_lossy()).unwrap();
+    assert_eq!(almanac.body_id_from_name("my sat"), Some(-999999));
+    assert_eq!(almanac.body_name_from_id(-999999), Some("MY_SAT"));
+    assert_eq!(
+        almanac.frame_from_name("MY_SAT").unwrap(),
+        Frame::new(-999999, J2000)
+    );
+
+    // A later kernel overrides the earlier definitions.
+    std::fs::write(
+        &path,
+        "KPL/FK
+\\begindata
+NAIF_BODY_CODE += -999998
+NAIF_BODY_NAME += 'MY_SAT'
+",
+    )
+    .unwrap();
+    let almanac = almanac.load(&path.to_string_lossy()).unwrap();
+    assert_eq!(almanac.body_id_from_name("MY_SAT"), Some(-999998));
+    assert_eq!(almanac.body_name_from_id(-999999), Some("MY_SAT"));
+
+    // Invalid definitions are reported, and text kernels without body definitions still need a conversion.
+    std::fs::write(
+        &path,
+        "KPL/FK
+\\begindata
+NAIF_BODY_CODE += ( -1, -2 )
+NAIF_BODY_NAME += 'ONLY ONE'
+",
+    )
+    .unwrap();
+    assert_eq!(
+        almanac.load(&path.to_string_lossy()).err(),
+        Some(AlmanacError::FrameName {
+            source: FrameNameError::MismatchedBodyDefinitions { codes: 4, names: 3 }
+        })
+    );
+    std::fs::write(&path, "KPL/PCK\n\\begindata\nBODY399_RADII = ( 1 2 3 )\n").unwrap();
+    assert!(almanac.load(&path.to_string_lossy()).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn test_load_from_zip() {
+    use bytes::Bytes;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, path) in [
+        ("kernels/de440s.bsp", "../data/de440s.bsp"),
+        ("kernels/pck00008.tpc", "../data/pck00008.tpc"),
+        ("kernels/gm_de431.tpc", "../data/gm_de431.tpc"),
+    ] {
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&std::fs::read(path).unwrap()).unwrap();
+    }
+    // Unrecognized entries are skipped.
+    writer
+        .add_directory("docs", SimpleFileOptions::default())
+        .unwrap();
+    writer
+        .start_file("docs/README.md", SimpleFileOptions::default())
+        .unwrap();
+    writer.write_all(b"# Mission kernels\n").unwrap();
+    let bytes = Bytes::from(writer.finish().unwrap().into_inner());
+
+    let zipped = Almanac::default().load_from_zip(bytes).unwrap();
+    let individually = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .with_planetary_data(convert_tpc("../data/pck00008.tpc", "../data/gm_de431.tpc").unwrap());
+
+    assert_eq!(zipped.num_loaded_spk(), individually.num_loaded_spk());
+    assert_eq!(zipped.num_loaded_bpc(), 0);
+    assert_eq!(
+        zipped.spk_data[0].as_ref().unwrap().crc32(),
+        individually.spk_data[0].as_ref().unwrap().crc32()
+    );
+
+    assert_eq!(
+        zipped.planetary_data.lut.by_id.len(),
+        individually.planetary_data.lut.by_id.len()
+    );
+    for id in individually.planetary_data.lut.by_id.keys() {
+        assert_eq!(
+            zipped.planetary_data.get_by_id(*id).unwrap(),
+            individually.planetary_data.get_by_id(*id).unwrap(),
+            "planetary data of {id} differs"
+        );
+    }
+
+    let epoch = Epoch::from_str("2024-04-08T18:17:16 UTC").unwrap();
+    let sun = |almanac: &Almanac| {
+        almanac
+            .transform(SUN_J2000, EARTH_J2000, epoch, Aberration::LT)
+            .unwrap()
+    };
+    assert_eq!(sun(&zipped), sun(&individually));
+    assert_eq!(
+        zipped.frame_from_uid(EARTH_J2000).unwrap(),
+        individually.frame_from_uid(EARTH_J2000).unwrap()
+    );
+
+    // Entries are unloaded by their name in the archive.
+    assert!(zipped.unload("kernels/de440s.bsp").is_ok());
+
+    // Something which isn't a zip archive is an error.
+    assert!(Almanac::default()
+        .load_from_zip(Bytes::from_static(b"not a zip archive"))
+        .is_err());
+}
+
+#[test]
+fn test_transform_state() {
+    use anise::constants::frames::{IAU_EARTH_FRAME, IAU_MOON_FRAME};
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    let epoch = Epoch::from_str("2021-10-29 12:34:56 TDB").unwrap();
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let orig_state = Orbit::keplerian(
+        8_191.93, 1e-6, 12.85, 306.614, 314.19, 99.887_7, epoch, eme2k,
+    );
+
+    // Same center, rotating frame: the velocity includes the ω×r term.
+    let fixed = almanac
+        .transform_state(orig_state, IAU_EARTH_FRAME, epoch)
+        .unwrap();
+    assert_eq!(fixed.frame.ephemeris_id, IAU_EARTH_FRAME.ephemeris_id);
+    assert_eq!(fixed.frame.orientation_id, IAU_EARTH_FRAME.orientation_id);
+
+    let dcm = almanac.rotate(EARTH_J2000, IAU_EARTH_FRAME, epoch).unwrap();
+    let rotated_velocity_km_s = dcm.rot_mat * orig_state.velocity_km_s;
+    let transport_km_s = dcm.rot_mat_dt.unwrap() * orig_state.radius_km;
+    assert!((fixed.radius_km - dcm.rot_mat * orig_state.radius_km).norm() < 1e-9);
+    assert!((fixed.velocity_km_s - (rotated_velocity_km_s + transport_km_s)).norm() < 1e-12);
+    // The ω×r term of the Earth rotation is significant at this altitude.
+    assert!(transport_km_s.norm() > 0.5);
+
+    // Each component of the round trip must match the original state.
+    let assert_round_trip = |back: Orbit, pos_tol_km: f64| {
+        for i in 0..3 {
+            let pos_err_km = (back.radius_km[i] - orig_state.radius_km[i]).abs();
+            let vel_err_km_s = (back.velocity_km_s[i] - orig_state.velocity_km_s[i]).abs();
+            assert!(pos_err_km < pos_tol_km, "position #{i}: {pos_err_km:e} km");
+            assert!(vel_err_km_s < 1e-9, "velocity #{i}: {vel_err_km_s:e} km/s");
+        }
+    };
+
+    let back = almanac.transform_state(fixed, EARTH_J2000, epoch).unwrap();
+    assert!((back.radius_km - orig_state.radius_km).norm() < 1e-9);
+    assert!((back.velocity_km_s - orig_state.velocity_km_s).norm() < 1e-12);
+    assert_round_trip(back, 1e-9);
+
+    // Different center and rotating frame, and back.
+    let moon_fixed = almanac
+        .transform_state(orig_state, IAU_MOON_FRAME, epoch)
+        .unwrap();
+    assert_eq!(moon_fixed.frame.ephemeris_id, IAU_MOON_FRAME.ephemeris_id);
+    assert_eq!(
+        moon_fixed.frame.orientation_id,
+        IAU_MOON_FRAME.orientation_id
+    );
+    let earth_from_moon = almanac
+        .transform(EARTH_J2000, IAU_MOON_FRAME, epoch, None)
+        .unwrap();
+    assert!((moon_fixed.rmag_km() - earth_from_moon.rmag_km()).abs() < orig_state.rmag_km() + 1e-6);
+
+    let back = almanac
+        .transform_state(moon_fixed, EARTH_J2000, epoch)
+        .unwrap();
+    assert!((back.radius_km - orig_state.radius_km).norm() < 1e-6);
+    assert!((back.velocity_km_s - orig_state.velocity_km_s).norm() < 1e-9);
+    assert_round_trip(back, 1e-6);
+
+    // The state must be valid at the requested epoch.
+    assert!(almanac
+        .transform_state(orig_state, IAU_EARTH_FRAME, epoch + Unit::Second * 1.0)
+        .is_err());
+}
+
+#[test]
+fn test_summary() {
+    use anise::almanac::summary::KernelKind;
+    use anise::naif::daf::NAIFSummaryRecord;
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/moon_pa_de440_200625.bpc")
+        .unwrap();
+
+    let spk = SPK::load("../data/de440s.bsp").unwrap();
+    let bpc = BPC::load("../data/moon_pa_de440_200625.bpc").unwrap();
+    let num_spk_segments = spk
+        .data_summaries()
+        .unwrap()
+        .iter()
+        .filter(|summary| !summary.is_empty())
+        .count();
+    let num_bpc_segments = bpc
+        .data_summaries()
+        .unwrap()
+        .iter()
+        .filter(|summary| !summary.is_empty())
+        .count();
+
+    let summary = almanac.summary();
+    assert_eq!(summary.len(), num_spk_segments + num_bpc_segments);
+    assert_eq!(
+        summary
+            .iter()
+            .filter(|segment| segment.kind == KernelKind::SPK)
+            .count(),
+        num_spk_segments
+    );
+    // Sorted by kernel, then by segment index.
+    for pair in summary.windows(2) {
+        assert!(
+            (pair[0].kind, pair[0].kernel_no, pair[0].segment_no)
+                < (pair[1].kind, pair[1].kernel_no, pair[1].segment_no)
+        );
+    }
+
+    let moon = summary
+        .iter()
+        .find(|segment| segment.kind == KernelKind::SPK && segment.target_id == 301)
+        .unwrap();
+    assert_eq!(moon.observer_id, 3);
+    assert_eq!(moon.frame_id, 1);
+    assert_eq!(moon.source.as_deref(), Some("../data/de440s.bsp"));
+
+    let report = almanac.summary_report();
+    println!("{report}");
+    assert!(report.contains(&format!(
+        "=== SPK #0 (../data/de440s.bsp) with {num_spk_segments} segments ==="
+    )));
+    assert!(report.contains(&format!(
+        "=== BPC #0 (../data/moon_pa_de440_200625.bpc) with {num_bpc_segments} segments ==="
+    )));
+    assert!(report.contains("\n301 -> 3\n"));
+    assert!(report.contains("\n31008 -> 1\n"));
+    // Deterministic, so it can be used in snapshot tests.
+    assert_eq!(report, almanac.summary_report());
+    assert_eq!(report, almanac.clone().summary_report());
+}
+
+#[test]
+fn test_load_mmap() {
+    use anise::constants::frames::{MARS_BARYCENTER_J2000, MOON_J2000, MOON_PA_DE440_FRAME};
+
+    let buffered = Almanac::default()
+        .load("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/moon_pa_de440_200625.bpc")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    let mapped = Almanac::default()
+        .load_mmap("../data/de440s.bsp")
+        .unwrap()
+        .load_mmap("../data/moon_pa_de440_200625.bpc")
+        .unwrap()
+        .load_mmap("../data/pck08.pca")
+        .unwrap();
+
+    assert_eq!(mapped.num_loaded_spk(), 1);
+    assert_eq!(mapped.num_loaded_bpc(), 1);
+    assert_eq!(mapped.planetary_data, buffered.planetary_data);
+    assert_eq!(mapped.spk_data[0], buffered.spk_data[0]);
+
+    let start = Epoch::from_gregorian_utc_at_midnight(2021, 1, 1);
+    for day in 0..30 {
+        let epoch = start + Unit::Day * (day * 7);
+        for (target, observer) in [
+            (MOON_J2000, EARTH_J2000),
+            (EARTH_J2000, SUN_J2000),
+            (MARS_BARYCENTER_J2000, EARTH_J2000),
+            (MOON_PA_DE440_FRAME, EARTH_J2000),
+        ] {
+            assert_eq!(
+                mapped.transform(target, observer, epoch, None).unwrap(),
+                buffered.transform(target, observer, epoch, None).unwrap(),
+                "{target} -> {observer} @ {epoch}"
+            );
+        }
+    }
+
+    // Files loaded from a memory map can be unloaded by their path.
+    let unloaded = mapped.unload("../data/de440s.bsp").unwrap();
+    assert_eq!(unloaded.num_loaded_spk(), 0);
+
+    assert!(matches!(
+        Almanac::default().load_mmap("../data/does-not-exist.bsp"),
+        Err(AlmanacError::Loading { .. })
+    ));
+}
+
+#[test]
+fn test_transform_covariance() {
+    use anise::constants::frames::IAU_EARTH_FRAME;
+    use anise::math::{Matrix3, Matrix6, Vector6};
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    let epoch = Epoch::from_str("2021-10-29 12:34:56 TDB").unwrap();
+
+    // Diagonal position covariance, in km^2, and no velocity uncertainty.
+    let mut covariance = Matrix6::zeros();
+    covariance[(0, 0)] = 1.0;
+    covariance[(1, 1)] = 4.0;
+    covariance[(2, 2)] = 9.0;
+
+    let fixed = almanac
+        .transform_covariance(covariance, EARTH_J2000, IAU_EARTH_FRAME, epoch)
+        .unwrap();
+
+    let dcm = almanac.rotate(EARTH_J2000, IAU_EARTH_FRAME, epoch).unwrap();
+    let pos_cov: Matrix3 = covariance.fixed_view::<3, 3>(0, 0).into();
+    let rot_mat_dt = dcm.rot_mat_dt.unwrap();
+    assert!(
+        (fixed.fixed_view::<3, 3>(0, 0) - dcm.rot_mat * pos_cov * dcm.rot_mat.transpose()).norm()
+            < 1e-12
+    );
+    // The spin of the frame correlates the velocity with the position.
+    let vel_pos_cov = rot_mat_dt * pos_cov * dcm.rot_mat.transpose();
+    assert!(vel_pos_cov.norm() > 1e-5);
+    assert!((fixed.fixed_view::<3, 3>(3, 0) - vel_pos_cov).norm() < 1e-15);
+    assert!((fixed.fixed_view::<3, 3>(0, 3) - vel_pos_cov.transpose()).norm() < 1e-15);
+    assert!((fixed - fixed.transpose()).norm() < 1e-15);
+
+    // The Jacobian is that of the state transformation.
+    let state = Orbit::keplerian(
+        8_191.93,
+        1e-6,
+        12.85,
+        306.614,
+        314.19,
+        99.887_7,
+        epoch,
+        EARTH_J2000,
+    );
+    let nominal = almanac
+        .transform_state(state, IAU_EARTH_FRAME, epoch)
+        .unwrap();
+    let delta = Vector6::new(0.1, -0.2, 0.3, 1e-4, 2e-4, -3e-4);
+    let mut perturbed = state;
+    perturbed.radius_km += delta.fixed_rows::<3>(0);
+    perturbed.velocity_km_s += delta.fixed_rows::<3>(3);
+    let perturbed = almanac
+        .transform_state(perturbed, IAU_EARTH_FRAME, epoch)
+        .unwrap();
+    let jacobian = dcm.state_dcm();
+    assert!(
+        ((perturbed.to_cartesian_pos_vel() - nominal.to_cartesian_pos_vel()) - jacobian * delta)
+            .norm()
+            < 1e-9
+    );
+
+    // And back to the original covariance.
+    let back = almanac
+        .transform_covariance(fixed, IAU_EARTH_FRAME, EARTH_J2000, epoch)
+        .unwrap();
+    assert!((back - covariance).norm() < 1e-12, "{back}");
+}
+
+#[test]
+fn test_load_with_remap() {
+    use anise::constants::frames::MOON_J2000;
+    use anise::file2heap;
+    use std::collections::HashMap;
+
+    let bytes = file2heap!("../data/de440s.bsp").unwrap();
+    let original = Almanac::default().load("../data/de440s.bsp").unwrap();
+
+    // Relabel the Moon, leaving the other bodies unchanged.
+    let remap = HashMap::from([(301, -301)]);
+    let remapped = Almanac::default()
+        .load_with_remap(bytes.clone(), &remap)
+        .unwrap();
+
+    let relabeled = Frame::from_ephem_j2000(-301);
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2021, 1, 1);
+    for day in 0..10 {
+        let epoch = epoch + Unit::Day * (day * 30);
+        let expected = original
+            .translate(MOON_J2000, EARTH_J2000, epoch, None)
+            .unwrap();
+        let state = remapped
+            .translate(relabeled, EARTH_J2000, epoch, None)
+            .unwrap();
+        assert_eq!(state.radius_km, expected.radius_km);
+        assert_eq!(state.velocity_km_s, expected.velocity_km_s);
+        assert_eq!(state.frame.ephemeris_id, EARTH_J2000.ephemeris_id);
+
+        // The original ID is no longer available, but the other bodies are.
+        assert!(remapped
+            .translate(MOON_J2000, EARTH_J2000, epoch, None)
+            .is_err());
+        assert_eq!(
+            remapped
+                .translate(SUN_J2000, EARTH_J2000, epoch, None)
+                .unwrap(),
+            original
+                .translate(SUN_J2000, EARTH_J2000, epoch, None)
+                .unwrap()
+        );
+    }
+
+    // The same kernel can then be loaded again with its original IDs for a parallel scenario.
+    let both = remapped.load_from_bytes(bytes.clone()).unwrap();
+    assert_eq!(
+        both.translate(relabeled, MOON_J2000, epoch, None)
+            .unwrap()
+            .rmag_km(),
+        0.0
+    );
+
+    // Collisions are reported.
+    assert_eq!(
+        Almanac::default()
+            .load_with_remap(bytes.clone(), &HashMap::from([(301, 399)]))
+            .err(),
+        Some(AlmanacError::RemapCollision {
+            from: 301,
+            to: 399,
+            reason: "that ID is also in this kernel"
+        })
+    );
+    assert_eq!(
+        remapped
+            .load_with_remap(bytes.clone(), &HashMap::from([(399, -301)]))
+            .err(),
+        Some(AlmanacError::RemapCollision {
+            from: 399,
+            to: -301,
+            reason: "that ID is already loaded"
+        })
+    );
+    assert_eq!(
+        Almanac::default()
+            .load_with_remap(bytes.clone(), &HashMap::from([(301, -5), (399, -5)]))
+            .err(),
+        Some(AlmanacError::RemapCollision {
+            from: 399,
+            to: -5,
+            reason: "another ID of this kernel is remapped to it"
+        })
+    );
+    // Swapping two IDs is not a collision.
+    let swapped = Almanac::default()
+        .load_with_remap(bytes, &HashMap::from([(301, 399), (399, 301)]))
+        .unwrap();
+    assert_eq!(
+        swapped
+            .translate(
+                Frame::from_ephem_j2000(399),
+                EARTH_J2000.with_ephem(3),
+                epoch,
+                None
+            )
+            .unwrap()
+            .radius_km,
+        original
+            .translate(MOON_J2000, EARTH_J2000.with_ephem(3), epoch, None)
+            .unwrap()
+            .radius_km
+    );
+}
@@ -1,12 +1,16 @@
 use anise::{
+    almanac::visibility::VisibilityEventKind,
     constants::{
-        frames::{EME2000, IAU_EARTH_FRAME},
+        frames::{EARTH_J2000, EME2000, IAU_EARTH_FRAME, MOON_J2000},
         usual_planetary_constants::MEAN_EARTH_ANGULAR_VELOCITY_DEG_S,
     },
-    prelude::{Almanac, Orbit},
+    naif::spk::writer::SPKWriter,
+    prelude::{Almanac, Frame, Orbit},
 };
 use core::str::FromStr;
-use hifitime::Epoch;
+use hifitime::{Epoch, TimeSeries, TimeUnits, Unit};
+use spice::cstr;
+use std::env;
 
 // Define location of DSN DSS-65 in Madrid, Spain
 const DSS65_LATITUDE_DEG: f64 = 40.427_222;
@@ -212,3 +216,285 @@ fn validate_aer_vs_gmat_cislunar2() {
         );
     }
 }
+
+/// Checks the rises and sets of an ISS-like orbit over DSS-65 against a brute force sampling of the elevation.
+#[test]
+fn elevation_crossings_iss_like() {
+    let almanac = Almanac::default().load("../data/pck08.pca").unwrap();
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let iau_earth = almanac.frame_from_uid(IAU_EARTH_FRAME).unwrap();
+
+    // Write a day of a low Earth orbit, fit over 20 minute records.
+    let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+    let iss = Orbit::try_keplerian(6778.0, 0.0005, 51.6, 30.0, 0.0, 0.0, start, eme2k).unwrap();
+    let states = (0..=1440)
+        .map(|minute| iss.propagate_two_body(Unit::Minute * minute).unwrap())
+        .collect::<Vec<Orbit>>();
+
+    let target_id = -2003;
+    let mut writer = SPKWriter::new("anise-elevation-crossings.bsp");
+    writer
+        .add_type2_segment(
+            &states,
+            target_id,
+            399,
+            1,
+            11,
+            20 * Unit::Minute,
+            "ISS-like",
+        )
+        .unwrap();
+    let path = env::temp_dir().join("anise-elevation-crossings.bsp");
+    writer.write(&path).unwrap();
+
+    let almanac = almanac.load(path.to_str().unwrap()).unwrap();
+    let target = Frame::from_ephem_j2000(target_id);
+
+    let station = Orbit::try_latlongalt(
+        DSS65_LATITUDE_DEG,
+        DSS65_LONGITUDE_DEG,
+        DSS65_HEIGHT_KM,
+        MEAN_EARTH_ANGULAR_VELOCITY_DEG_S,
+        start,
+        iau_earth,
+    )
+    .unwrap();
+
+    let elevation_deg = |epoch: Epoch| {
+        let rx = almanac
+            .transform(target, station.frame, epoch, None)
+            .unwrap();
+        almanac
+            .azimuth_elevation_range_sez(rx, Orbit { epoch, ..station }, None, None)
+            .unwrap()
+            .elevation_deg
+    };
+
+    let min_el_deg = 10.0;
+    let (search_start, search_stop) = (start + Unit::Minute * 10, start + Unit::Hour * 23);
+    let events = almanac
+        .elevation_crossings(
+            target,
+            station,
+            (search_start, search_stop),
+            min_el_deg,
+            Unit::Minute * 1,
+        )
+        .unwrap();
+
+    // Brute force the crossings by sampling every few seconds, including the edges if the target is visible there.
+    let brute_step = Unit::Second * 5;
+    let mut expected = Vec::new();
+    let mut prev_el_deg = elevation_deg(search_start);
+    if prev_el_deg >= min_el_deg {
+        expected.push(search_start);
+    }
+    for epoch in TimeSeries::inclusive(search_start + brute_step, search_stop, brute_step) {
+        let el_deg = elevation_deg(epoch);
+        if (prev_el_deg < min_el_deg) != (el_deg < min_el_deg) {
+            expected.push(epoch);
+        }
+        prev_el_deg = el_deg;
+    }
+    if prev_el_deg >= min_el_deg {
+        expected.push(search_stop);
+    }
+
+    assert!(
+        events.len() >= 4,
+        "expected at least two passes, got {events:?}"
+    );
+    assert_eq!(events.len(), expected.len(), "{events:?}");
+
+    for (pair, expected) in events.chunks(2).zip(expected.chunks(2)) {
+        let (rise, set) = (pair[0], pair[1]);
+        println!("{rise}\n{set}");
+        assert_eq!(rise.kind, VisibilityEventKind::Rise);
+        assert_eq!(set.kind, VisibilityEventKind::Set);
+
+        for (event, expected) in [rise, set].iter().zip(expected) {
+            assert!((event.epoch - *expected).abs() <= brute_step);
+            if event.at_interval_edge {
+                assert_eq!(event.epoch, *expected);
+            } else {
+                assert!((event.elevation_deg - min_el_deg).abs() < 1e-3);
+                assert!((elevation_deg(event.epoch) - min_el_deg).abs() < 1e-3);
+            }
+        }
+
+        // The peak is above any of the samples of the pass.
+        assert_eq!(rise.peak_epoch, set.peak_epoch);
+        assert!(rise.peak_epoch >= rise.epoch && rise.peak_epoch <= set.epoch);
+        let sampled_peak_deg = TimeSeries::inclusive(rise.epoch, set.epoch, Unit::Second * 1)
+            .map(elevation_deg)
+            .fold(f64::MIN, f64::max);
+        assert!(rise.peak_elevation_deg > min_el_deg);
+        assert!(rise.peak_elevation_deg >= sampled_peak_deg - 1e-9);
+        assert!((elevation_deg(rise.peak_epoch) - rise.peak_elevation_deg).abs() < 1e-9);
+    }
+
+    // Start and stop the search in the middle of the first pass, where the target is already or still visible.
+    let (rise, set) = events
+        .chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|(rise, set)| !rise.at_interval_edge && !set.at_interval_edge)
+        .unwrap();
+    let mid_pass = rise.epoch + (set.epoch - rise.epoch) * 0.5;
+
+    let from_mid_pass = almanac
+        .elevation_crossings(
+            target,
+            station,
+            (mid_pass, set.epoch + Unit::Minute * 10),
+            min_el_deg,
+            Unit::Minute * 1,
+        )
+        .unwrap();
+    assert_eq!(from_mid_pass.len(), 2);
+    assert_eq!(from_mid_pass[0].kind, VisibilityEventKind::Rise);
+    assert!(from_mid_pass[0].at_interval_edge);
+    assert_eq!(from_mid_pass[0].epoch, mid_pass);
+    assert!(from_mid_pass[0].elevation_deg > min_el_deg);
+    assert_eq!(from_mid_pass[1].kind, VisibilityEventKind::Set);
+    assert!(!from_mid_pass[1].at_interval_edge);
+    assert!((from_mid_pass[1].epoch - set.epoch).abs() < Unit::Millisecond * 2);
+
+    let until_mid_pass = almanac
+        .elevation_crossings(
+            target,
+            station,
+            (rise.epoch - Unit::Minute * 10, mid_pass),
+            min_el_deg,
+            Unit::Minute * 1,
+        )
+        .unwrap();
+    assert_eq!(until_mid_pass.len(), 2);
+    assert!(!until_mid_pass[0].at_interval_edge);
+    assert!((until_mid_pass[0].epoch - rise.epoch).abs() < Unit::Millisecond * 2);
+    assert_eq!(until_mid_pass[1].kind, VisibilityEventKind::Set);
+    assert!(until_mid_pass[1].at_interval_edge);
+    assert_eq!(until_mid_pass[1].epoch, mid_pass);
+
+    // The peak of a cut pass is within the searched interval.
+    for event in from_mid_pass.iter().chain(until_mid_pass.iter()) {
+        assert!(event.peak_elevation_deg >= event.elevation_deg);
+    }
+
+    assert!(almanac
+        .elevation_crossings(
+            target,
+            station,
+            (search_start, search_stop),
+            min_el_deg,
+            Unit::Second * 0,
+        )
+        .is_err());
+}
+
+/// Validates the frame based AER of the Moon from DSS-65 against SPICE's `azlcpo` with the `ELLIPSOID` method,
+/// with the azimuth measured clockwise from North and the elevation positive towards the zenith.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_aer_from_frames_vs_spice_azlcpo() {
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+    let iau_earth = almanac.frame_from_uid(IAU_EARTH_FRAME).unwrap();
+
+    spice::furnsh("../data/de440s.bsp");
+    spice::furnsh("../data/pck00008.tpc");
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 14);
+
+    // Build the J2000 ephemeris of the station fixed on the Earth.
+    let station_id = 399_065;
+    let station_at = |epoch: Epoch| {
+        Orbit::try_latlongalt(
+            DSS65_LATITUDE_DEG,
+            DSS65_LONGITUDE_DEG,
+            DSS65_HEIGHT_KM,
+            0.0,
+            epoch,
+            iau_earth,
+        )
+        .unwrap()
+    };
+    let states = (-120_i32..=120)
+        .map(|minute| {
+            almanac
+                .transform_to(
+                    station_at(epoch + Unit::Minute * f64::from(minute)),
+                    EARTH_J2000,
+                    None,
+                )
+                .unwrap()
+        })
+        .collect::<Vec<Orbit>>();
+
+    let mut writer = SPKWriter::new("ANISE AER validation");
+    writer
+        .add_type2_segment(&states, station_id, 399, 1, 11, 1.hours(), "DSS65")
+        .unwrap();
+    let almanac = almanac.with_spk(writer.build().unwrap()).unwrap();
+
+    let observer = Frame::new(station_id, iau_earth.orientation_id);
+    // SPICE uses the body fixed position of the station directly.
+    let station = station_at(epoch);
+    let obspos_km = [
+        station.radius_km.x,
+        station.radius_km.y,
+        station.radius_km.z,
+    ];
+
+    let mut max_angle_err_deg = 0.0_f64;
+    for epoch in TimeSeries::inclusive(epoch - 110.minutes(), epoch + 110.minutes(), 10.minutes()) {
+        let anise_aer = almanac
+            .azimuth_elevation_range_sez_from_frames(MOON_J2000, observer, epoch, None)
+            .unwrap();
+
+        // [range, azimuth, elevation, range rate, azimuth rate, elevation rate] in km, rad, and per second.
+        let mut azlsta = [0.0_f64; 6];
+        let mut lt = 0.0;
+        unsafe {
+            spice::c::azlcpo_c(
+                cstr!("ELLIPSOID"),
+                cstr!("MOON"),
+                epoch.to_et_seconds(),
+                cstr!("NONE"),
+                0,
+                1,
+                obspos_km.as_ptr(),
+                cstr!("EARTH"),
+                cstr!("IAU_EARTH"),
+                azlsta.as_mut_ptr(),
+                &mut lt,
+            );
+        }
+
+        // Wrap the azimuth error around 360 degrees.
+        let az_err_deg = {
+            let err = (anise_aer.aer.azimuth_deg - azlsta[1].to_degrees()).rem_euclid(360.0);
+            err.min(360.0 - err)
+        };
+        let el_err_deg = (anise_aer.aer.elevation_deg - azlsta[2].to_degrees()).abs();
+        max_angle_err_deg = max_angle_err_deg.max(az_err_deg).max(el_err_deg);
+
+        println!(
+            "{epoch}\taz err = {az_err_deg:.3e} deg\tel err = {el_err_deg:.3e} deg\trange err = {:.3e} km",
+            anise_aer.aer.range_km - azlsta[0]
+        );
+
+        assert!(az_err_deg < 1e-5, "azimuth error of {az_err_deg:e} deg");
+        assert!(el_err_deg < 1e-5, "elevation error of {el_err_deg:e} deg");
+        assert!((anise_aer.aer.range_km - azlsta[0]).abs() < 1e-3);
+        assert!((anise_aer.aer.range_rate_km_s - azlsta[3]).abs() < 1e-6);
+        assert!((anise_aer.azimuth_rate_deg_s - azlsta[4].to_degrees()).abs() < 1e-7);
+        assert!((anise_aer.elevation_rate_deg_s - azlsta[5].to_degrees()).abs() < 1e-7);
+    }
+
+    println!("max az/el error = {max_angle_err_deg:.3e} deg");
+
+    spice::unload("../data/pck00008.tpc");
+    spice::unload("../data/de440s.bsp");
+}
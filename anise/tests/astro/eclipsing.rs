@@ -10,8 +10,13 @@
 
 use core::f64;
 
-use anise::constants::frames::EARTH_J2000;
+use anise::astro::OccultationState;
+use anise::constants::frames::{
+    EARTH_J2000, IAU_EARTH_FRAME, IAU_MARS_FRAME, MARS_BARYCENTER_J2000, MOON_J2000, SUN_J2000,
+};
+use anise::math::{Matrix3, Vector3};
 use anise::prelude::*;
+use anise::structure::planetocentric::ellipsoid::Ellipsoid;
 
 use rstest::*;
 
@@ -98,3 +103,205 @@ fn verif_beta_angle_eclipse_time(almanac: Almanac) {
         }
     }
 }
+
+/// The total lunar eclipse of 2022 November 08 had its greatest eclipse at 10:59 UTC, and totality lasted from 10:17 UTC until 11:42 UTC.
+/// The Moon entered the penumbra at 08:02 UTC and the umbra at 09:09 UTC. As seen from the center of the Moon, a lunar eclipse is an
+/// occultation of the Sun by the Earth.
+#[rstest]
+fn occultation_state_lunar_eclipse(almanac: Almanac) {
+    for (hour, minute, expected) in [
+        (7, 0, OccultationState::None),
+        (9, 0, OccultationState::Partial),
+        (10, 59, OccultationState::Total),
+        (11, 30, OccultationState::Total),
+        (14, 30, OccultationState::None),
+    ] {
+        let epoch = Epoch::from_gregorian_utc_hms(2022, 11, 8, hour, minute, 0);
+        let state = almanac
+            .occultation_state(
+                SUN_J2000,
+                IAU_EARTH_FRAME,
+                MOON_J2000,
+                epoch,
+                Aberration::LT,
+            )
+            .unwrap();
+        assert_eq!(state, expected, "{epoch}: got {state}");
+    }
+
+    // The Earth is behind the Sun, so it does not hide it.
+    let epoch = Epoch::from_gregorian_utc_hms(2022, 11, 8, 10, 59, 0);
+    assert_eq!(
+        almanac
+            .occultation_state(
+                IAU_EARTH_FRAME,
+                SUN_J2000,
+                MOON_J2000,
+                epoch,
+                Aberration::LT
+            )
+            .unwrap(),
+        OccultationState::None
+    );
+}
+
+/// Checks each occultation state with a synthetic transit of Phobos: Phobos is placed at the radius of its orbit on the line of sight of
+/// the Sun from a Mars surface observer, and then moved away from it. Refer to [occultation_state_phobos_transit_mar097] for the
+/// transit computed with the ephemeris of Phobos.
+#[rstest]
+fn occultation_state_phobos_transit(mut almanac: Almanac) {
+    let epoch = Epoch::from_gregorian_utc_hms(2022, 4, 2, 12, 0, 0);
+    let sun_dir = almanac
+        .translate(SUN_J2000, MARS_BARYCENTER_J2000, epoch, Aberration::LT)
+        .unwrap()
+        .radius_km
+        .normalize();
+    // Any direction orthogonal to the line of sight.
+    let cross_dir = sun_dir.cross(&Vector3::z()).normalize();
+
+    let observer = almanac
+        .add_fixed_frame(
+            "MARS_SURFACE",
+            MARS_BARYCENTER_J2000,
+            3389.5 * sun_dir,
+            Matrix3::identity(),
+        )
+        .unwrap();
+
+    let phobos_shape = Ellipsoid {
+        semi_major_equatorial_radius_km: 13.0,
+        semi_minor_equatorial_radius_km: 11.4,
+        polar_radius_km: 9.1,
+    };
+
+    // The Sun is about 0.18 deg in radius from Mars, and Phobos is at most 0.13 deg in radius, so Phobos fits inside the Sun.
+    for (name, cross_km, expected) in [
+        ("PHOBOS_TRANSIT", 0.0, OccultationState::Annular),
+        ("PHOBOS_LIMB", 15.0, OccultationState::Partial),
+        ("PHOBOS_CLEAR", 40.0, OccultationState::None),
+    ] {
+        let phobos = almanac
+            .add_fixed_frame(
+                name,
+                MARS_BARYCENTER_J2000,
+                9376.0 * sun_dir + cross_km * cross_dir,
+                Matrix3::identity(),
+            )
+            .unwrap()
+            .with_ellipsoid(phobos_shape);
+
+        let state = almanac
+            .occultation_state(SUN_J2000, phobos, observer, epoch, Aberration::LT)
+            .unwrap();
+        assert_eq!(state, expected, "{name}: got {state}");
+    }
+
+    // Frames without a shape cannot be used to compute occultations.
+    assert!(almanac
+        .occultation_state(SUN_J2000, observer, observer, epoch, Aberration::LT)
+        .is_err());
+}
+
+/// Perseverance imaged a transit of Phobos across the Sun from Jezero crater (18.4447 deg N, 77.4508 deg E) on Sol 397, i.e. 2022 April
+/// 02, and the transit lasted about 40 seconds. This searches the transits seen from Jezero around that date with the Phobos ephemeris
+/// of the mar097 kernel, and checks that Phobos was fully in front of the Sun for less than a minute.
+#[rstest]
+fn occultation_state_phobos_transit_mar097(almanac: Almanac) {
+    use std::path::PathBuf;
+
+    let manifest_dir =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or(".".to_string()));
+
+    let mut almanac = almanac
+        .load(
+            &manifest_dir
+                .clone()
+                .join("../data/mar097.bsp")
+                .to_string_lossy(),
+        )
+        .unwrap();
+
+    let jezero = almanac
+        .add_topocentric_frame("JEZERO", IAU_MARS_FRAME, 18.4447, 77.4508, -2.6)
+        .unwrap();
+    // The shape of Phobos is that of the loaded planetary data, oriented by its body fixed frame.
+    let phobos = Frame::new(401, 401);
+
+    let state_at = |epoch: Epoch| {
+        almanac
+            .occultation_state(SUN_J2000, phobos, jezero, epoch, Aberration::LT)
+            .unwrap()
+    };
+    // The axes of the topocentric frame are South, East, and Zenith, so the Sun is up if it is above the SE plane.
+    let sun_up = |epoch: Epoch| {
+        almanac
+            .transform(
+                SUN_J2000.with_orient(jezero.orientation_id),
+                jezero,
+                epoch,
+                Aberration::LT,
+            )
+            .unwrap()
+            .radius_km
+            .z
+            > 0.0
+    };
+
+    // Coarse search of the epochs when Phobos is in front of the Sun, over a Martian sol on each side of the documented date. At night,
+    // Phobos may be on the line of sight of the Sun on the other side of Mars, which hides both.
+    let start = Epoch::from_gregorian_utc_at_noon(2022, 4, 1);
+    let end = Epoch::from_gregorian_utc_at_noon(2022, 4, 3);
+    let coarse: Vec<Epoch> = TimeSeries::inclusive(start, end, 10.seconds())
+        .filter(|epoch| state_at(*epoch) != OccultationState::None && sun_up(*epoch))
+        .collect();
+
+    assert!(!coarse.is_empty(), "no transit of Phobos found from Jezero");
+
+    let mut transits = Vec::new();
+    let mut prev_epoch: Option<Epoch> = None;
+    for epoch in coarse {
+        if prev_epoch.is_some_and(|prev| epoch - prev <= 10.seconds()) {
+            // Same transit as the previous coarse epoch
+            prev_epoch = Some(epoch);
+            continue;
+        }
+        prev_epoch = Some(epoch);
+
+        // Fine search of the states of this transit, from ten seconds before its first coarse epoch until a minute after it.
+        let states: Vec<OccultationState> =
+            TimeSeries::inclusive(epoch - 10.seconds(), epoch + 1.minutes(), 1.seconds())
+                .map(state_at)
+                .collect();
+
+        let annular_s = states
+            .iter()
+            .filter(|state| **state == OccultationState::Annular)
+            .count();
+        let partial_s = states
+            .iter()
+            .filter(|state| **state == OccultationState::Partial)
+            .count();
+
+        println!("transit at {epoch}: annular for {annular_s} s and partial for {partial_s} s");
+
+        // Phobos is smaller than the Sun as seen from Mars, so it is never a total occultation.
+        assert!(!states.contains(&OccultationState::Total));
+        // Phobos crosses the limb of the Sun when it enters and exits its disk.
+        assert!(partial_s > 0, "transit at {epoch}");
+        assert!(annular_s < 60, "transit at {epoch}");
+        // The transit starts and ends outside of the disk of the Sun.
+        assert_eq!(states.first(), Some(&OccultationState::None));
+        assert_eq!(states.last(), Some(&OccultationState::None));
+
+        transits.push((epoch, annular_s));
+    }
+
+    // Perseverance saw Phobos fully inside the disk of the Sun on 2022 April 02.
+    let documented_day = Epoch::from_gregorian_utc_at_midnight(2022, 4, 2);
+    assert!(
+        transits.iter().any(|(epoch, annular_s)| {
+            *epoch >= documented_day && *epoch < documented_day + 1.days() && *annular_s > 0
+        }),
+        "no annular transit on 2022 April 02: {transits:?}"
+    );
+}
@@ -655,6 +655,127 @@ fn verif_geodetic_vallado(almanac: Almanac) {
     f64_eq!(r.latitude_deg().unwrap(), 0.1, "latitude (φ)");
 }
 
+#[rstest]
+fn verif_frame_geodetic_vallado(almanac: Almanac) {
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let epoch = Epoch::from_mjd_tai(51_545.0);
+
+    // Test case from Vallado, 4th Ed., page 173, Example 3-3, with the same values as the Heikkinen procedure.
+    let (lat_deg, long_deg, height_km) = eme2k
+        .cartesian_to_geodetic(Vector3::new(6524.834, 6862.875, 6448.296))
+        .unwrap();
+    f64_eq_tol!(lat_deg, 34.352_519_916_935_62, 1e-10, "latitude (φ)");
+    f64_eq_tol!(long_deg, 46.446_416_856_789_96, 1e-10, "longitude (λ)");
+    f64_eq_tol!(height_km, 5_085.217_419_357_936, 1e-8, "height");
+
+    // Test case from Vallado, 4th Ed., page 173, Example 3-4
+    let radius_km = eme2k
+        .geodetic_to_cartesian(-7.906_635_7, 345.5975, 56.0e-3)
+        .unwrap();
+    f64_eq_tol!(radius_km.x, 6_119.403_233_271_109, 1e-9, "r_i");
+    f64_eq_tol!(radius_km.y, -1_571.480_316_600_378_3, 1e-9, "r_j");
+    f64_eq_tol!(radius_km.z, -871.560_226_712_024_7, 1e-9, "r_k");
+
+    // The biaxial conversions match the latitude, longitude, altitude conversions of the orbit.
+    for (lat_deg, long_deg, height_km) in [
+        (-89.5, 10.0, 0.1),
+        (-45.0, 123.0, 500.0),
+        (0.0, 270.0, -10.0),
+        (40.4, 355.75, 0.8),
+        (89.0, 180.0, 42_000.0),
+    ] {
+        let orbit = Orbit::try_latlongalt(
+            lat_deg,
+            long_deg,
+            height_km,
+            MEAN_EARTH_ANGULAR_VELOCITY_DEG_S,
+            epoch,
+            eme2k,
+        )
+        .unwrap();
+        let radius_km = eme2k
+            .geodetic_to_cartesian(lat_deg, long_deg, height_km)
+            .unwrap();
+        assert!((orbit.radius_km - radius_km).norm() < 1e-9);
+
+        let (lat2_deg, long2_deg, height2_km) = eme2k.cartesian_to_geodetic(radius_km).unwrap();
+        let (orbit_lat_deg, orbit_long_deg, orbit_height_km) = orbit.latlongalt().unwrap();
+        f64_eq_tol!(lat2_deg, orbit_lat_deg, 1e-9, "latitude (φ)");
+        f64_eq_tol!(
+            between_0_360(long2_deg),
+            orbit_long_deg,
+            1e-9,
+            "longitude (λ)"
+        );
+        f64_eq_tol!(height2_km, orbit_height_km, 1e-6, "height");
+        f64_eq_tol!(height2_km, height_km, 1e-6, "height");
+    }
+}
+
+/// Validates the geodetic conversions of the Earth against SPICE's `georec` and `recgeo`, over the grid of the geodetic round trip unit test.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[rstest]
+fn validate_geodetic_vs_spice_georec_recgeo(almanac: Almanac) {
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let re_km = eme2k.semi_major_radius_km().unwrap();
+    let flattening = eme2k.flattening().unwrap();
+
+    let mut lats_deg: Vec<f64> = (-6..=6).map(|i| 15.0 * i as f64).collect();
+    lats_deg.extend([-89.9999, -1e-6, 1e-6, 89.9999]);
+
+    for lat_deg in &lats_deg {
+        for lon_deg in (-6..6).map(|i| 30.0 * i as f64 + 1.5) {
+            for height_km in [-100.0, -1.0, 0.0, 1e-3, 0.5, 400.0, 36_000.0] {
+                let loc = format!("{lat_deg} {lon_deg} {height_km}");
+
+                let radius_km = eme2k
+                    .geodetic_to_cartesian(*lat_deg, lon_deg, height_km)
+                    .unwrap();
+
+                let mut spice_radius_km = [0.0_f64; 3];
+                unsafe {
+                    spice::c::georec_c(
+                        lon_deg.to_radians(),
+                        lat_deg.to_radians(),
+                        height_km,
+                        re_km,
+                        flattening,
+                        spice_radius_km.as_mut_ptr(),
+                    );
+                }
+                let spice_radius_km = Vector3::from(spice_radius_km);
+                assert!(
+                    (radius_km - spice_radius_km).norm() < 1e-9,
+                    "{loc}: {radius_km} vs SPICE {spice_radius_km}"
+                );
+
+                let (lat2_deg, lon2_deg, height2_km) =
+                    eme2k.cartesian_to_geodetic(radius_km).unwrap();
+
+                let rectan = [radius_km.x, radius_km.y, radius_km.z];
+                let (mut spice_lon_rad, mut spice_lat_rad, mut spice_height_km) = (0.0, 0.0, 0.0);
+                unsafe {
+                    spice::c::recgeo_c(
+                        rectan.as_ptr(),
+                        re_km,
+                        flattening,
+                        &mut spice_lon_rad,
+                        &mut spice_lat_rad,
+                        &mut spice_height_km,
+                    );
+                }
+                f64_eq_tol!(lat2_deg, spice_lat_rad.to_degrees(), 1e-9, "latitude (φ)");
+                assert!(
+                    between_pm_180(lon2_deg - spice_lon_rad.to_degrees()).abs() < 1e-9,
+                    "{loc}: longitude {lon2_deg} vs SPICE {}",
+                    spice_lon_rad.to_degrees()
+                );
+                f64_eq_tol!(height2_km, spice_height_km, 1e-6, "height");
+            }
+        }
+    }
+}
+
 #[rstest]
 fn verif_with_init(almanac: Almanac) {
     let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
@@ -1081,3 +1202,282 @@ fn test_duration_to_radius_error_conditions(epoch: Epoch, frame: Frame) {
     // If sma_km() returns Inf, then sma_km.abs().powi(3) is Inf, then n_rad_s is 0. Caught.
     // If sma_km() returns some error that leads to NaN sma, then n_rad_s is NaN. Caught.
 }
+
+#[rstest]
+fn verif_keplerian_degenerate_round_trip(epoch: Epoch, frame: Frame) {
+    // Each case provides the elements used to initialize the orbit, and the expected RAAN, AOP, and TA with the degenerate conventions.
+    let cases = [
+        // Elliptical: all of the angles are defined.
+        (
+            "elliptical",
+            [12_000.0, 0.2, 35.0, 120.0, 80.0, 45.0],
+            [120.0, 80.0, 45.0],
+        ),
+        // Circular: the TA is the argument of latitude.
+        (
+            "circular",
+            [7_000.0, 0.0, 51.6, 120.0, 80.0, 45.0],
+            [120.0, 0.0, 125.0],
+        ),
+        // Equatorial: the AOP is the longitude of periapsis.
+        (
+            "equatorial",
+            [12_000.0, 0.2, 0.0, 120.0, 80.0, 45.0],
+            [0.0, 200.0, 45.0],
+        ),
+        // Circular and equatorial: the TA is the true longitude.
+        (
+            "circular equatorial",
+            [42_164.0, 0.0, 0.0, 120.0, 80.0, 45.0],
+            [0.0, 0.0, 245.0],
+        ),
+        // Retrograde equatorial: the longitude of periapsis is measured in the direction of motion.
+        (
+            "retrograde equatorial",
+            [12_000.0, 0.2, 180.0, 0.0, 80.0, 45.0],
+            [0.0, 80.0, 45.0],
+        ),
+        // Hyperbolic: all of the angles are defined.
+        (
+            "hyperbolic",
+            [-20_000.0, 1.5, 35.0, 120.0, 80.0, 45.0],
+            [120.0, 80.0, 45.0],
+        ),
+    ];
+
+    for (name, [sma_km, ecc, inc_deg, raan_deg, aop_deg, ta_deg], [exp_raan, exp_aop, exp_ta]) in
+        cases
+    {
+        let orbit = Orbit::try_keplerian(
+            sma_km, ecc, inc_deg, raan_deg, aop_deg, ta_deg, epoch, frame,
+        )
+        .unwrap();
+
+        f64_eq_tol!(orbit.sma_km().unwrap(), sma_km, 1e-6, format!("{name} sma"));
+        f64_eq_tol!(orbit.ecc().unwrap(), ecc, 1e-12, format!("{name} ecc"));
+        f64_eq_tol!(
+            orbit.inc_deg().unwrap(),
+            inc_deg,
+            1e-6,
+            format!("{name} inc")
+        );
+        for (value, expected, param) in [
+            (orbit.raan_deg().unwrap(), exp_raan, "raan"),
+            (orbit.aop_deg().unwrap(), exp_aop, "aop"),
+            (orbit.ta_deg().unwrap(), exp_ta, "ta"),
+        ] {
+            f64_eq_tol!(
+                between_pm_180(value - expected),
+                0.0,
+                1e-6,
+                format!("{name} {param}")
+            );
+        }
+
+        // The elements round trip to the same Cartesian state.
+        let round_trip = Orbit::try_keplerian(
+            orbit.sma_km().unwrap(),
+            orbit.ecc().unwrap(),
+            orbit.inc_deg().unwrap(),
+            orbit.raan_deg().unwrap(),
+            orbit.aop_deg().unwrap(),
+            orbit.ta_deg().unwrap(),
+            epoch,
+            frame,
+        )
+        .unwrap();
+        f64_eq_tol!(
+            round_trip.rss_radius_km(&orbit).unwrap(),
+            0.0,
+            1e-6,
+            format!("{name} radius")
+        );
+        f64_eq_tol!(
+            round_trip.rss_velocity_km_s(&orbit).unwrap(),
+            0.0,
+            1e-9,
+            format!("{name} velocity")
+        );
+
+        if ecc < 1.0 {
+            assert!(orbit.period().is_ok(), "{name} period");
+            // The mean anomaly of a circular orbit is its true anomaly.
+            if ecc < ECC_EPSILON {
+                assert!(orbit.ma_deg().is_err(), "{name} ma");
+                f64_eq_tol!(
+                    orbit.mean_anomaly_deg().unwrap(),
+                    orbit.ta_deg().unwrap(),
+                    1e-9,
+                    format!("{name} ma")
+                );
+            }
+        } else {
+            assert_eq!(
+                orbit.period(),
+                Err(PhysicsError::NotElliptical {
+                    ecc: orbit.ecc().unwrap()
+                }),
+                "{name} period"
+            );
+            // Kepler's equation for a hyperbola, from the hyperbolic anomaly.
+            let hyp_anomaly_rad =
+                between_pm_180(orbit.hyperbolic_anomaly_deg().unwrap()).to_radians();
+            f64_eq_tol!(
+                orbit.mean_anomaly_deg().unwrap().to_radians(),
+                orbit.ecc().unwrap() * hyp_anomaly_rad.sinh() - hyp_anomaly_rad,
+                1e-9,
+                format!("{name} ma")
+            );
+        }
+    }
+}
+
+#[rstest]
+fn verif_propagate_two_body(almanac: Almanac) {
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2021, 3, 4);
+
+    // Over one period, the energy and the angular momentum are conserved, and the state returns to its initial value.
+    let orbit = Orbit::keplerian(12_000.0, 0.3, 35.0, 65.0, 75.0, 15.0, epoch, eme2k);
+    let period = orbit.period().unwrap();
+    let propagated = orbit.propagate_two_body(period).unwrap();
+    assert_eq!(propagated.epoch, epoch + period);
+    assert_eq!(propagated.frame, orbit.frame);
+    f64_eq_tol!(
+        propagated.energy_km2_s2().unwrap(),
+        orbit.energy_km2_s2().unwrap(),
+        1e-12,
+        "energy"
+    );
+    assert!((propagated.hvec().unwrap() - orbit.hvec().unwrap()).norm() < 1e-8);
+    assert!(propagated.rss_radius_km(&orbit).unwrap() < TEST_EPS_RADIUS_KM);
+
+    // Half a period later, the mean anomaly has advanced by 180 degrees.
+    let half = orbit.propagate_two_body(period * 0.5).unwrap();
+    f64_eq_tol!(
+        half.ma_deg().unwrap(),
+        between_0_360(orbit.ma_deg().unwrap() + 180.0),
+        1e-6,
+        "half period mean anomaly"
+    );
+
+    // Hyperbolic and near parabolic trajectories, forward and backward.
+    let mu_km3_s2 = eme2k.mu_km3_s2().unwrap();
+    for (name, vmag_km_s) in [
+        ("hyperbolic", 12.0),
+        (
+            "near parabolic",
+            (2.0 * mu_km3_s2 / 7000.0).sqrt() * (1.0 - 1e-10),
+        ),
+    ] {
+        let (sin_fpa, cos_fpa) = 10.0_f64.to_radians().sin_cos();
+        let orbit = Orbit::new(
+            7000.0,
+            0.0,
+            0.0,
+            0.0,
+            vmag_km_s * cos_fpa,
+            vmag_km_s * sin_fpa,
+            epoch,
+            eme2k,
+        );
+        let dt = Unit::Day * 1;
+        let propagated = orbit.propagate_two_body(dt).unwrap();
+        f64_eq_tol!(
+            propagated.energy_km2_s2().unwrap(),
+            orbit.energy_km2_s2().unwrap(),
+            1e-9,
+            format!("{name} energy")
+        );
+        assert!(
+            (propagated.hvec().unwrap() - orbit.hvec().unwrap()).norm() < 1e-6,
+            "{name} angular momentum"
+        );
+        assert!(propagated.rmag_km() > orbit.rmag_km(), "{name} escapes");
+
+        let back = propagated.propagate_two_body(-dt).unwrap();
+        assert!(
+            back.rss_radius_km(&orbit).unwrap() < TEST_EPS_RADIUS_KM,
+            "{name}"
+        );
+        assert!(back.rss_velocity_km_s(&orbit).unwrap() < 1e-6, "{name}");
+    }
+}
+
+#[rstest]
+fn verif_propagate_j2_sun_synchronous(almanac: Almanac) {
+    let eme2k = almanac.frame_from_uid(EARTH_J2000).unwrap();
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2021, 3, 4);
+
+    let j2 = 1.082_626_68e-3;
+    let req_km = 6378.1363;
+    let sma_km = req_km + 700.0;
+    let ecc = 1e-3;
+    let mu_km3_s2 = eme2k.mu_km3_s2().unwrap();
+
+    // A sun-synchronous orbit regresses its node by 360 degrees per tropical year, i.e. 0.9856 deg/day.
+    let sso_rate_rad_s = 360.0_f64.to_radians() / (365.242_19 * 86_400.0);
+    let mean_motion_rad_s = (mu_km3_s2 / sma_km.powi(3)).sqrt();
+    let p_km = sma_km * (1.0 - ecc * ecc);
+    let inc_deg = (-sso_rate_rad_s / (1.5 * mean_motion_rad_s * j2 * (req_km / p_km).powi(2)))
+        .acos()
+        .to_degrees();
+    // Sun-synchronous orbits at 700 km are retrograde at about 98.2 degrees.
+    f64_eq_tol!(inc_deg, 98.19, 0.01, "sun-synchronous inclination");
+
+    let orbit = Orbit::keplerian(sma_km, ecc, inc_deg, 45.0, 90.0, 10.0, epoch, eme2k);
+    let dt = Unit::Day * 1;
+    let propagated = orbit.propagate_j2(dt, j2, req_km).unwrap();
+    assert_eq!(propagated.epoch, epoch + dt);
+
+    f64_eq_tol!(
+        propagated.raan_deg().unwrap() - orbit.raan_deg().unwrap(),
+        0.985_609,
+        1e-5,
+        "nodal regression over one day"
+    );
+    f64_eq_tol!(propagated.sma_km().unwrap(), sma_km, 1e-6, "sma");
+    f64_eq_tol!(propagated.ecc().unwrap(), ecc, 1e-9, "ecc");
+    f64_eq_tol!(propagated.inc_deg().unwrap(), inc_deg, 1e-9, "inc");
+
+    // Without J2, this is the two-body propagation.
+    let keplerian = orbit.propagate_j2(dt, 0.0, req_km).unwrap();
+    let two_body = orbit.propagate_two_body(dt).unwrap();
+    assert!(keplerian.rss_radius_km(&two_body).unwrap() < TEST_EPS_RADIUS_KM);
+
+    // Hyperbolic orbits have no mean elements.
+    let hyperbolic = Orbit::new(7000.0, 0.0, 0.0, 0.0, 12.0, 0.5, epoch, eme2k);
+    assert!(matches!(
+        hyperbolic.propagate_j2(dt, j2, req_km),
+        Err(PhysicsError::NotElliptical { .. })
+    ));
+}
+
+#[rstest]
+fn period_of_non_elliptical_orbits(epoch: Epoch, frame: Frame) {
+    // Hyperbolic orbits have no period, which is reported as an error instead of a NaN duration.
+    for ecc in [1.1, 1.5, 3.0] {
+        let orbit =
+            Orbit::try_keplerian(-20_000.0, ecc, 35.0, 120.0, 80.0, 45.0, epoch, frame).unwrap();
+        assert_eq!(
+            orbit.period(),
+            Err(PhysicsError::NotElliptical {
+                ecc: orbit.ecc().unwrap()
+            }),
+            "ecc = {ecc}"
+        );
+        // The functions which depend on the period report the same error.
+        assert_eq!(
+            orbit.ta_dot_deg_s(),
+            Err(PhysicsError::NotElliptical {
+                ecc: orbit.ecc().unwrap()
+            }),
+            "ecc = {ecc}"
+        );
+    }
+
+    // An elliptical orbit close to the parabolic limit still has a period.
+    let orbit =
+        Orbit::try_keplerian(1_000_000.0, 0.99, 35.0, 120.0, 80.0, 45.0, epoch, frame).unwrap();
+    assert!(orbit.period().unwrap().to_seconds().is_finite());
+}
@@ -10,7 +10,9 @@
 
 use std::str::FromStr;
 
-use anise::constants::celestial_objects::{EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER};
+use anise::constants::celestial_objects::{
+    EARTH, EARTH_MOON_BARYCENTER, MOON, SOLAR_SYSTEM_BARYCENTER,
+};
 use anise::constants::frames::*;
 use anise::file2heap;
 use anise::prelude::*;
@@ -95,3 +97,97 @@ fn common_root_verif() {
         );
     }
 }
+
+/// Builds an SPK with a single segment of a fixed position over [start, end] for the provided target and center.
+fn fixed_target_spk(target_id: i32, center_id: i32, start: Epoch, end: Epoch) -> SPK {
+    use anise::naif::spk::writer::SPKWriter;
+
+    let states = TimeSeries::inclusive(start, end, 1.hours())
+        .map(|epoch| Orbit::new(1000.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch, EARTH_J2000))
+        .collect::<Vec<Orbit>>();
+
+    let mut writer = SPKWriter::new("path cache test");
+    writer
+        .add_type2_segment(&states, target_id, center_id, 1, 5, 6.hours(), "PATH CACHE")
+        .unwrap();
+    writer.build().unwrap()
+}
+
+/// Tests that the cached paths are invalidated when a new SPK is loaded, or when a segment changes the path.
+#[test]
+fn path_cache_invalidation() {
+    let sc = Frame::from_ephem_j2000(-3001);
+    let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+    let epoch = start + 12.hours();
+
+    let almanac = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .with_spk(fixed_target_spk(-3001, EARTH, start, start + 1.days()))
+        .unwrap();
+    assert!(almanac.path_cache.is_empty());
+
+    // The spacecraft is orbiting the Earth, so the common node with the Moon is the EMB.
+    let path = almanac
+        .common_ephemeris_path(sc, MOON_J2000, epoch)
+        .unwrap();
+    assert_eq!(path.2, EARTH_MOON_BARYCENTER);
+    assert_eq!(almanac.path_cache.len(), 1);
+    // Cached paths are identical to computed ones
+    assert_eq!(
+        almanac
+            .common_ephemeris_path(sc, MOON_J2000, epoch)
+            .unwrap(),
+        path
+    );
+    let cached_state = almanac.translate_geometric(sc, MOON_J2000, epoch).unwrap();
+    almanac.path_cache.clear();
+    assert_eq!(
+        almanac.translate_geometric(sc, MOON_J2000, epoch).unwrap(),
+        cached_state
+    );
+
+    // Loading a new SPK where the spacecraft orbits the Moon invalidates the cache.
+    let almanac_moon = almanac
+        .with_spk(fixed_target_spk(-3001, MOON, start, start + 1.days()))
+        .unwrap();
+    assert!(almanac_moon.path_cache.is_empty());
+    assert_eq!(
+        almanac_moon
+            .common_ephemeris_path(sc, MOON_J2000, epoch)
+            .unwrap()
+            .2,
+        MOON
+    );
+    // The original almanac is unchanged
+    assert_eq!(
+        almanac
+            .common_ephemeris_path(sc, MOON_J2000, epoch)
+            .unwrap(),
+        path
+    );
+
+    // A segment later on with a different center limits the validity of the cached path.
+    let almanac_later = almanac
+        .with_spk(fixed_target_spk(
+            -3001,
+            MOON,
+            start + 1.days(),
+            start + 2.days(),
+        ))
+        .unwrap();
+    assert_eq!(
+        almanac_later
+            .common_ephemeris_path(sc, MOON_J2000, epoch)
+            .unwrap()
+            .2,
+        EARTH_MOON_BARYCENTER
+    );
+    assert_eq!(
+        almanac_later
+            .common_ephemeris_path(sc, MOON_J2000, epoch + 1.days())
+            .unwrap()
+            .2,
+        MOON
+    );
+    assert_eq!(almanac_later.path_cache.len(), 1);
+}
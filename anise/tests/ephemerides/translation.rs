@@ -9,6 +9,7 @@
  */
 
 use anise::constants::frames::{EARTH_J2000, EARTH_MOON_BARYCENTER_J2000, MOON_J2000, VENUS_J2000};
+use anise::constants::SPEED_OF_LIGHT_KM_S;
 use anise::file2heap;
 use anise::math::Vector3;
 use anise::prelude::*;
@@ -621,6 +622,157 @@ fn de440s_translation_verif_aberrations() {
     }
 }
 
+/// The light time corrected velocity includes the rate of change of the light time, so it matches SPICE much more closely than the
+/// stellar aberration corrected velocities (cf. de440s_translation_verif_aberrations for the SPICE code).
+#[test]
+fn de440s_light_time_rate() {
+    let ctx = Almanac::new("../data/de440s.bsp").unwrap();
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2002, 2, 7);
+
+    let cases = [
+        (
+            Aberration::LT.unwrap(),
+            Vector3::new(
+                9.607_084_389_002_623e-1,
+                -2.035_781_705_460_237_8e-1,
+                -1.838_032_601_966_706e-1,
+            ),
+        ),
+        (
+            Aberration::CN.unwrap(),
+            Vector3::new(
+                9.607_084_394_698_688e-1,
+                -2.035_781_706_971_668_8e-1,
+                -1.838_032_602_663_712_8e-1,
+            ),
+        ),
+        (
+            Aberration::XLT.unwrap(),
+            Vector3::new(
+                9.607_152_566_210_146e-1,
+                -2.035_882_734_212_926e-1,
+                -1.838_077_669_346_027_7e-1,
+            ),
+        ),
+        (
+            Aberration::XCN.unwrap(),
+            Vector3::new(
+                9.607_152_571_912_962e-1,
+                -2.035_882_735_719_17e-1,
+                -1.838_077_670_040_778_6e-1,
+            ),
+        ),
+    ];
+
+    for (ab_corr, vel_expct_km_s) in cases {
+        let state = ctx
+            .translate(
+                MOON_J2000,
+                EARTH_MOON_BARYCENTER_J2000,
+                epoch,
+                Some(ab_corr),
+            )
+            .unwrap();
+
+        // Without the light time rate, the error is about 5e-6 km/s.
+        let err_km_s = (state.velocity_km_s - vel_expct_km_s).norm();
+        assert!(err_km_s < 1e-7, "{ab_corr}: err = {err_km_s:.3e} km/s");
+
+        // The apparent range rate therefore also matches.
+        let range_rate_km_s = state.radius_km.dot(&state.velocity_km_s) / state.rmag_km();
+        let spice_range_rate_km_s = state.radius_km.dot(&vel_expct_km_s) / state.rmag_km();
+        assert!((range_rate_km_s - spice_range_rate_km_s).abs() < 1e-7);
+
+        // The light time matches the apparent distance, and its rate the finite difference of the light time.
+        let (lt, lt_rate) = ctx
+            .one_way_light_time(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, epoch, ab_corr)
+            .unwrap();
+        // The light time is returned as a Duration, so it is rounded to the nanosecond.
+        assert!((lt.to_seconds() - state.rmag_km() / SPEED_OF_LIGHT_KM_S).abs() < 1e-9);
+
+        let h = 60.0 * Unit::Second;
+        let lt_s_at = |epoch: Epoch| {
+            ctx.one_way_light_time(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, epoch, ab_corr)
+                .unwrap()
+                .0
+                .to_seconds()
+        };
+        let fd_lt_rate = (lt_s_at(epoch + h) - lt_s_at(epoch - h)) / (2.0 * h.to_seconds());
+        assert!(
+            (lt_rate - fd_lt_rate).abs() < 1e-10,
+            "{ab_corr}: rate = {lt_rate:e}\tfinite diff = {fd_lt_rate:e}"
+        );
+    }
+}
+
+#[test]
+fn de440s_light_time_direction() {
+    use anise::astro::LightTimeDirection;
+    use anise::constants::frames::{MARS_BARYCENTER_J2000, SSB_J2000};
+
+    let ctx = Almanac::new("../data/de440s.bsp").unwrap();
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2002, 2, 7);
+
+    // The reception and transmission light times differ by about twice the light time times the rate of change of the distance as
+    // the target moves, i.e. twice the light time times r_hat . v_tgt / c. The remaining error is due to the acceleration of the
+    // target over the light time, and to the nanosecond resolution of the durations.
+    for (target, observer, tol_s) in [
+        (MOON_J2000, EARTH_J2000, 1e-8),
+        (MARS_BARYCENTER_J2000, EARTH_J2000, 1e-4),
+    ] {
+        let (lt_rx, state_rx) = ctx
+            .light_time(target, observer, epoch, LightTimeDirection::Reception)
+            .unwrap();
+        let (lt_tx, state_tx) = ctx
+            .light_time(target, observer, epoch, LightTimeDirection::Transmission)
+            .unwrap();
+
+        for (direction, lt, state, sign) in [
+            (LightTimeDirection::Reception, lt_rx, state_rx, -1.0),
+            (LightTimeDirection::Transmission, lt_tx, state_tx, 1.0),
+        ] {
+            // The light time converged within the iteration cap: it matches the distance traveled by the signal.
+            let lt_s = lt.to_seconds();
+            assert!(
+                (lt_s - state.rmag_km() / SPEED_OF_LIGHT_KM_S).abs() < 1e-9,
+                "{direction}: light time did not converge"
+            );
+            // The state of the target is that at the emission epoch in reception, and at the reception epoch in transmission.
+            assert!(((state.epoch - epoch).to_seconds() - sign * lt_s).abs() < 2e-9);
+
+            // The position is the converged light time corrected one, without stellar aberration.
+            let ab_corr = direction.converged_aberration();
+            let corrected = ctx
+                .translate(target, observer, epoch, Some(ab_corr))
+                .unwrap();
+            assert_eq!(state.radius_km, corrected.radius_km, "{ab_corr}");
+        }
+
+        let tgt_ssb = ctx.translate(target, SSB_J2000, epoch, None).unwrap();
+        let distance_rate = state_rx.r_hat().dot(&tgt_ssb.velocity_km_s) / SPEED_OF_LIGHT_KM_S;
+        let expected_diff_s =
+            2.0 * lt_rx.to_seconds() * distance_rate / (1.0 - distance_rate.powi(2));
+        let diff_s = (lt_tx - lt_rx).to_seconds();
+        assert!(diff_s.abs() > 1e-6);
+        assert!(
+            (diff_s - expected_diff_s).abs() < tol_s,
+            "{target}: diff = {diff_s:e} s\texpected = {expected_diff_s:e} s"
+        );
+    }
+
+    // Light time leaving the coverage of the data fails.
+    assert!(ctx
+        .light_time(
+            MARS_BARYCENTER_J2000,
+            EARTH_J2000,
+            Epoch::from_gregorian_utc_at_midnight(1849, 1, 1),
+            LightTimeDirection::Transmission
+        )
+        .is_err());
+}
+
 #[cfg(feature = "metaload")]
 #[test]
 fn type9_lagrange_query() {
@@ -701,3 +853,812 @@ fn type9_lagrange_query() {
         (state.velocity_km_s - expected_vel_km_s).norm()
     );
 }
+
+#[test]
+fn de440s_stellar_aberration_mars() {
+    use anise::constants::frames::{MARS_BARYCENTER_J2000, SSB_J2000};
+    use anise::constants::SPEED_OF_LIGHT_KM_S;
+
+    let ctx = Almanac::new("../data/de440s.bsp").unwrap();
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2002, 2, 7);
+
+    // The stellar aberration is due to the velocity of the Earth-based observer with respect to the SSB.
+    let obs_ssb = ctx.translate(EARTH_J2000, SSB_J2000, epoch, None).unwrap();
+
+    for (without, with) in [
+        (Aberration::LT, Aberration::LT_S),
+        (Aberration::CN, Aberration::CN_S),
+    ] {
+        let apparent = ctx
+            .translate(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, with)
+            .unwrap();
+        let light_time_only = ctx
+            .translate(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, without)
+            .unwrap();
+
+        // The stellar aberration only rotates the position.
+        assert!((apparent.rmag_km() - light_time_only.rmag_km()).abs() < 1e-6);
+        assert_eq!(apparent.velocity_km_s, light_time_only.velocity_km_s);
+
+        let shift_arcsec = apparent
+            .radius_km
+            .angle(&light_time_only.radius_km)
+            .to_degrees()
+            * 3600.0;
+        // sin(phi) = v sin(w) / c
+        let expected_arcsec = (light_time_only
+            .radius_km
+            .normalize()
+            .cross(&obs_ssb.velocity_km_s)
+            .norm()
+            / SPEED_OF_LIGHT_KM_S)
+            .asin()
+            .to_degrees()
+            * 3600.0;
+
+        println!("{with:?}: stellar aberration of {shift_arcsec:.6} arcsec");
+        // About 20 arcsec at most given the orbital velocity of the Earth
+        assert!(shift_arcsec > 1.0 && shift_arcsec < 21.0);
+        assert!(
+            (shift_arcsec - expected_arcsec).abs() < 1e-6,
+            "{with:?}: {shift_arcsec} != {expected_arcsec} arcsec"
+        );
+    }
+
+    // The converged light time is a fixed point of the light time equation, unlike the unconverged one.
+    let residual_km = |ab_corr: Option<Aberration>| {
+        let state = ctx
+            .translate(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, ab_corr)
+            .unwrap();
+        let lt = (state.rmag_km() / SPEED_OF_LIGHT_KM_S).seconds();
+        let tgt_ssb = ctx
+            .translate(MARS_BARYCENTER_J2000, SSB_J2000, epoch - lt, None)
+            .unwrap();
+        (tgt_ssb.radius_km - obs_ssb.radius_km - state.radius_km).norm()
+    };
+
+    assert!(residual_km(Aberration::CN) < 1e-6);
+    assert!(residual_km(Aberration::LT) > 1e-3);
+}
+
+#[test]
+fn type2_translation_acceleration() {
+    use anise::astro::utils::propagate_universal_variable;
+    use anise::math::cartesian::CartesianState;
+    use anise::naif::spk::writer::SPKWriter;
+
+    const GM: f64 = 398600.4418;
+    let two_body = |et_s: f64| {
+        propagate_universal_variable(
+            Vector3::new(7000.0, 100.0, -50.0),
+            Vector3::new(0.1, 7.2, 1.5),
+            GM,
+            et_s,
+        )
+        .unwrap()
+    };
+
+    let states: Vec<CartesianState> = (0..=6 * 60)
+        .map(|i| {
+            let (radius_km, velocity_km_s) = two_body(60.0 * i as f64);
+            CartesianState {
+                radius_km,
+                velocity_km_s,
+                epoch: Epoch::from_et_seconds(60.0 * i as f64),
+                frame: EARTH_J2000,
+            }
+        })
+        .collect();
+
+    let mut writer = SPKWriter::new("ANISE acceleration test");
+    writer
+        .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+        .unwrap();
+    let almanac = Almanac::default()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+
+    let sc_j2000 = Frame::from_ephem_j2000(-10000001);
+    let h = 1.seconds();
+
+    // Avoid the interval boundaries, where the central difference would straddle two polynomials.
+    for et_s in [600.0, 4321.0, 9000.0, 17_100.0] {
+        let epoch = Epoch::from_et_seconds(et_s);
+
+        let (state, acc_km_s2) = almanac
+            .translate_with_derivatives(sc_j2000, EARTH_J2000, epoch)
+            .unwrap();
+
+        // The position and velocity match the regular translation.
+        let geometric = almanac
+            .translate_geometric(sc_j2000, EARTH_J2000, epoch)
+            .unwrap();
+        assert!((state.radius_km - geometric.radius_km).norm() < 1e-12);
+        assert!((state.velocity_km_s - geometric.velocity_km_s).norm() < 1e-15);
+        assert_eq!(state.frame, geometric.frame);
+
+        // Central finite difference of the velocity
+        let vel_after = almanac
+            .translate_geometric(sc_j2000, EARTH_J2000, epoch + h)
+            .unwrap()
+            .velocity_km_s;
+        let vel_before = almanac
+            .translate_geometric(sc_j2000, EARTH_J2000, epoch - h)
+            .unwrap()
+            .velocity_km_s;
+        let finite_diff_km_s2 = (vel_after - vel_before) / (2.0 * h.to_seconds());
+
+        let err_km_s2 = (acc_km_s2 - finite_diff_km_s2).norm();
+        println!("{epoch:E}: |a - finite diff| = {err_km_s2:e} km/s^2");
+        assert!(err_km_s2 < 1e-9);
+
+        // And the fitted polynomials follow the two-body dynamics they were built from.
+        let two_body_km_s2 = -GM * state.radius_km / state.rmag_km().powi(3);
+        assert!((acc_km_s2 - two_body_km_s2).norm() < 1e-8);
+
+        // Swapping the target and the observer negates the acceleration.
+        let (_, reverse_acc_km_s2) = almanac
+            .translate_with_derivatives(EARTH_J2000, sc_j2000, epoch)
+            .unwrap();
+        assert!((acc_km_s2 + reverse_acc_km_s2).norm() < 1e-15);
+    }
+}
+
+#[test]
+fn de440s_translate_many() {
+    use anise::ephemerides::EphemerisError;
+    use anise::naif::spk::writer::SPKWriter;
+
+    let almanac = Almanac::new("../data/de440s.bsp").unwrap();
+
+    // Overlay a Moon segment over two days in the middle of the batch, offset by one kilometer so that it can be distinguished
+    // from DE440s: it has a higher priority than DE440s, so the batch must switch segments at its bounds.
+    let overlay_start = Epoch::from_gregorian_utc_at_midnight(2024, 6, 2);
+    let states = TimeSeries::inclusive(overlay_start, overlay_start + 2.days(), 10.minutes())
+        .map(|epoch| {
+            let mut state = almanac.translate_to_parent(MOON_J2000, epoch).unwrap();
+            state.radius_km.x += 1.0;
+            state
+        })
+        .collect::<Vec<_>>();
+    let mut writer = SPKWriter::new("Moon overlay");
+    writer
+        .add_type2_segment(&states, 301, 3, 1, 13, 6.hours(), "MOON OVERLAY")
+        .unwrap();
+    let almanac = almanac.with_spk(writer.build().unwrap()).unwrap();
+
+    let start = Epoch::from_gregorian_utc_at_midnight(2024, 6, 1);
+    let epochs = TimeSeries::inclusive(start, start + 5.days(), 17.minutes())
+        .chain([overlay_start, overlay_start + 2.days()])
+        .collect::<Vec<_>>();
+    let mut reversed = epochs.clone();
+    reversed.reverse();
+
+    for ab_corr in [None, Aberration::LT, Aberration::XCN] {
+        for (target, observer) in [
+            (MOON_J2000, EARTH_J2000),
+            (VENUS_J2000, MOON_J2000),
+            (EARTH_J2000, EARTH_MOON_BARYCENTER_J2000),
+        ] {
+            for epochs in [&epochs, &reversed] {
+                let states = almanac
+                    .translate_many(target, observer, epochs, ab_corr)
+                    .unwrap();
+                assert_eq!(states.len(), epochs.len());
+                for (state, epoch) in states.iter().zip(epochs.iter()) {
+                    let expected = almanac
+                        .translate(target, observer, *epoch, ab_corr)
+                        .unwrap();
+                    assert_eq!(state.epoch, *epoch);
+                    assert_eq!(state.frame, expected.frame);
+                    assert_eq!(state.radius_km, expected.radius_km, "{target} {epoch}");
+                    assert_eq!(
+                        state.velocity_km_s, expected.velocity_km_s,
+                        "{target} {epoch}"
+                    );
+                }
+            }
+        }
+    }
+
+    // The overlay is used in the batch.
+    let inside = overlay_start + 1.days();
+    let moon = almanac
+        .translate_many(MOON_J2000, EARTH_J2000, &[start, inside], None)
+        .unwrap();
+    let de440s = Almanac::new("../data/de440s.bsp").unwrap();
+    let expected = de440s
+        .translate(MOON_J2000, EARTH_J2000, inside, None)
+        .unwrap();
+    assert!(((moon[1].radius_km - expected.radius_km).x - 1.0).abs() < 1e-6);
+
+    // The error reports the first epoch which is not covered.
+    let uncovered = Epoch::from_gregorian_utc_at_midnight(2200, 1, 1);
+    let batch = [start, inside, uncovered, uncovered + 1.days()];
+    for ab_corr in [None, Aberration::LT] {
+        match almanac.translate_many(MOON_J2000, EARTH_J2000, &batch, ab_corr) {
+            Err(EphemerisError::BatchTranslation { index, epoch, .. }) => {
+                assert_eq!(index, 2);
+                assert_eq!(epoch, uncovered);
+            }
+            other => panic!("expected a batch error, got {other:?}"),
+        }
+    }
+
+    assert!(almanac
+        .translate_many(MOON_J2000, EARTH_J2000, &[], None)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn segment_boundary_discontinuity() {
+    use anise::naif::spk::writer::SPKWriter;
+
+    let de440s = Almanac::new("../data/de440s.bsp").unwrap();
+
+    // Two Moon segments of one day each, where the second one is offset by one kilometer.
+    let start = Epoch::from_gregorian_utc_at_midnight(2024, 6, 1);
+    let boundary = start + 1.days();
+    let moon_states = |from: Epoch, offset_km: f64| {
+        TimeSeries::inclusive(from, from + 1.days(), 10.minutes())
+            .map(|epoch| {
+                let mut state = de440s.translate_to_parent(MOON_J2000, epoch).unwrap();
+                state.radius_km.x += offset_km;
+                state
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut writer = SPKWriter::new("stitching error");
+    writer
+        .add_type2_segment(&moon_states(start, 0.0), 301, 3, 1, 13, 6.hours(), "FIRST")
+        .unwrap();
+    writer
+        .add_type2_segment(
+            &moon_states(boundary, 1.0),
+            301,
+            3,
+            1,
+            13,
+            6.hours(),
+            "SECOND",
+        )
+        .unwrap();
+    let almanac = Almanac::default()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+
+    // The jump is reported at and near the boundary between the segments.
+    for epoch in [boundary, boundary - 0.5.seconds(), boundary + 0.2.seconds()] {
+        let jump = almanac
+            .segment_boundary_discontinuity(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, epoch)
+            .unwrap()
+            .unwrap();
+        println!("{jump}");
+        assert_eq!(jump.epoch, boundary);
+        assert_eq!(jump.id, 301);
+        assert!(
+            (jump.position_km - Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-6,
+            "{jump}"
+        );
+        assert!(jump.velocity_km_s.norm() < 1e-9, "{jump}");
+
+        // The jump is the same when seen from the other side.
+        let reversed = almanac
+            .segment_boundary_discontinuity(EARTH_MOON_BARYCENTER_J2000, MOON_J2000, epoch)
+            .unwrap()
+            .unwrap();
+        assert!((reversed.position_km + jump.position_km).norm() < 1e-9);
+    }
+
+    // The records within a segment are continuous up to the precision of the fit.
+    let record_boundary = start + 6.hours();
+    let jump = almanac
+        .segment_boundary_discontinuity(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, record_boundary)
+        .unwrap()
+        .unwrap();
+    assert_eq!(jump.epoch, record_boundary);
+    assert!(jump.position_km.norm() < 1e-6, "{jump}");
+    assert!(jump.velocity_km_s.norm() < 1e-9, "{jump}");
+
+    // Away from boundaries, or at the end of the coverage, there is nothing to report.
+    for epoch in [start + 3.hours(), start, boundary + 1.days()] {
+        assert_eq!(
+            almanac
+                .segment_boundary_discontinuity(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, epoch)
+                .unwrap(),
+            None,
+            "{epoch}"
+        );
+    }
+
+    // Outside of the coverage, the translation is not available.
+    assert!(almanac
+        .segment_boundary_discontinuity(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, start - 1.days())
+        .is_err());
+}
+
+#[test]
+fn translation_error_variants() {
+    use anise::constants::celestial_objects::EARTH;
+    use anise::ephemerides::EphemerisError;
+    use anise::naif::daf::DafDataType;
+    use anise::naif::spk::summary::SPKSummaryRecord;
+    use anise::naif::spk::writer::SPKWriter;
+
+    let almanac = Almanac::new("../data/de440s.bsp").unwrap();
+    let epoch = Epoch::from_gregorian_utc_at_noon(2025, 1, 1);
+
+    // Forgot to load the kernel of this spacecraft.
+    let sc = Frame::from_ephem_j2000(-3001);
+    let err = almanac.translate(sc, MOON_J2000, epoch, None).unwrap_err();
+    println!("{err}");
+    assert_eq!(
+        err,
+        EphemerisError::NoConnectingPath {
+            from: MOON_J2000.into(),
+            to: sc.into(),
+            id: -3001
+        }
+    );
+
+    // The Earth is loaded, but not at this epoch.
+    let too_early = Epoch::from_gregorian_utc_at_midnight(1800, 1, 1);
+    let err = almanac
+        .translate(MOON_J2000, EARTH_J2000, too_early, None)
+        .unwrap_err();
+    println!("{err}");
+    match err {
+        EphemerisError::EpochOutOfCoverage {
+            id,
+            requested,
+            available,
+        } => {
+            assert_eq!(id, EARTH);
+            assert_eq!(requested, too_early);
+            assert!(available.0 > too_early && available.1 > available.0);
+            assert_eq!(almanac.spk_domain(EARTH).unwrap(), available);
+        }
+        _ => panic!("expected EpochOutOfCoverage, got {err:?}"),
+    }
+
+    // Precessing conics are not supported, even though the segment covers the epoch.
+    let summary = SPKSummaryRecord {
+        start_epoch_et_s: (epoch - 1.days()).to_et_seconds(),
+        end_epoch_et_s: (epoch + 1.days()).to_et_seconds(),
+        target_id: -3001,
+        center_id: EARTH,
+        frame_id: 1,
+        data_type_i: 15,
+        ..Default::default()
+    };
+    let mut writer = SPKWriter::new("unsupported type");
+    writer
+        .add_segment_data(summary, "PRECESSING CONICS", vec![0.0; 16])
+        .unwrap();
+    let almanac = almanac.with_spk(writer.build().unwrap()).unwrap();
+    let err = almanac.translate(sc, MOON_J2000, epoch, None).unwrap_err();
+    println!("{err}");
+    assert_eq!(
+        err,
+        EphemerisError::UnsupportedDataType {
+            id: -3001,
+            kind: DafDataType::Type15PrecessingConics
+        }
+    );
+}
+
+#[test]
+fn translation_gap_tolerance() {
+    use anise::ephemerides::EphemerisError;
+    use anise::naif::spk::writer::SPKWriter;
+
+    let _ = pretty_env_logger::try_init();
+
+    // Two segments of a target in uniform motion, separated by a gap of half a second.
+    let start = Epoch::from_gregorian_utc_at_noon(2025, 1, 1);
+    let gap = Unit::Millisecond * 500;
+    let target = Frame::from_ephem_j2000(-3002);
+    let state_at = |epoch: Epoch| {
+        let dt_s = (epoch - start).to_seconds();
+        Orbit::new(7000.0 + dt_s, 0.0, 0.0, 1.0, 0.0, 0.0, epoch, EARTH_J2000)
+    };
+
+    let mut writer = SPKWriter::new("ANISE gap test");
+    for (segment_start, name) in [
+        (start, "BEFORE GAP"),
+        (start + Unit::Hour + gap, "AFTER GAP"),
+    ] {
+        let states = (0..=60)
+            .map(|minute| state_at(segment_start + Unit::Minute * minute))
+            .collect::<Vec<Orbit>>();
+        writer
+            .add_type2_segment(&states, -3002, 399, 1, 5, 10 * Unit::Minute, name)
+            .unwrap();
+    }
+    let strict = Almanac::new("../data/de440s.bsp")
+        .unwrap()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+    let end_of_first = start + Unit::Hour;
+
+    // By default, the gap is a coverage error.
+    let in_gap = end_of_first + Unit::Millisecond * 250;
+    assert!(matches!(
+        strict.translate_geometric(target, EARTH_J2000, in_gap),
+        Err(EphemerisError::EpochOutOfCoverage { id: -3002, .. })
+    ));
+
+    // With a tolerance of one second, the nearer segment is extrapolated across the gap.
+    let tolerant = strict.with_gap_tolerance(Unit::Second * 1);
+    for offset_ms in [100, 250, 400] {
+        let epoch = end_of_first + Unit::Millisecond * offset_ms;
+        let state = tolerant
+            .translate_geometric(target, EARTH_J2000, epoch)
+            .unwrap();
+        let expected = state_at(epoch);
+        assert!(
+            (state.radius_km - expected.radius_km).norm() < 1e-6,
+            "{offset_ms} ms: {state}"
+        );
+        assert!((state.velocity_km_s - expected.velocity_km_s).norm() < 1e-9);
+        assert_eq!(state.epoch, epoch);
+
+        // The translation with derivatives extrapolates the same way, and the linear extrapolation has no acceleration.
+        let (state_with_derivatives, acc_km_s2) = tolerant
+            .translate_with_derivatives(target, EARTH_J2000, epoch)
+            .unwrap();
+        assert!((state_with_derivatives.radius_km - state.radius_km).norm() < 1e-12);
+        assert!((state_with_derivatives.velocity_km_s - state.velocity_km_s).norm() < 1e-12);
+        assert_eq!(acc_km_s2, Vector3::zeros());
+    }
+
+    // The covered epochs are unaffected.
+    let epoch = start + Unit::Minute * 30;
+    assert_eq!(
+        tolerant
+            .translate_geometric(target, EARTH_J2000, epoch)
+            .unwrap(),
+        strict
+            .translate_geometric(target, EARTH_J2000, epoch)
+            .unwrap()
+    );
+
+    // A tolerance shorter than the gap, and epochs outside of the coverage, are still errors.
+    assert!(strict
+        .with_gap_tolerance(Unit::Millisecond * 250)
+        .translate_geometric(target, EARTH_J2000, in_gap)
+        .is_err());
+    assert!(tolerant
+        .translate_geometric(target, EARTH_J2000, start - Unit::Millisecond * 250)
+        .is_err());
+    assert!(tolerant
+        .translate_geometric(
+            target,
+            EARTH_J2000,
+            end_of_first + gap + Unit::Hour + Unit::Millisecond * 250
+        )
+        .is_err());
+}
+
+#[test]
+fn de440s_shapiro_delay_earth_mars() {
+    use anise::astro::{shapiro_delay_s, AberrationConfig};
+    use anise::constants::frames::{MARS_BARYCENTER_J2000, SUN_J2000};
+    use anise::constants::SUN_GM_KM3_S2;
+
+    let ctx = Almanac::new("../data/de440s.bsp").unwrap();
+    let ab_corr = Aberration::CN.unwrap();
+    let shapiro = AberrationConfig {
+        shapiro: true,
+        ..Default::default()
+    };
+
+    // Angle between the Sun and Mars as seen from the Earth
+    let elongation_deg = |epoch: Epoch| {
+        let sun_km = ctx
+            .translate(SUN_J2000, EARTH_J2000, epoch, None)
+            .unwrap()
+            .radius_km;
+        let mars_km = ctx
+            .translate(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, None)
+            .unwrap()
+            .radius_km;
+        sun_km.angle(&mars_km).to_degrees()
+    };
+
+    let delay_s = |epoch: Epoch| {
+        let (lt, _) = ctx
+            .one_way_light_time(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, ab_corr)
+            .unwrap();
+        let (lt_shapiro, _) = ctx
+            .one_way_light_time_cfg(MARS_BARYCENTER_J2000, EARTH_J2000, epoch, ab_corr, shapiro)
+            .unwrap();
+        (lt_shapiro - lt).to_seconds()
+    };
+
+    let days = |start: Epoch, num_days: i64| (0..num_days).map(move |day| start + Unit::Day * day);
+    let min_by_key = |epochs: Vec<Epoch>, key: &dyn Fn(Epoch) -> f64| {
+        epochs
+            .into_iter()
+            .min_by(|a, b| key(*a).total_cmp(&key(*b)))
+            .unwrap()
+    };
+
+    // Solar conjunction of Mars in November 2023: the signal passes close to the Sun.
+    let conjunction = min_by_key(
+        days(Epoch::from_gregorian_utc_at_midnight(2023, 10, 15), 60).collect(),
+        &elongation_deg,
+    );
+    assert!(elongation_deg(conjunction) < 2.0);
+    let conjunction_delay_s = delay_s(conjunction);
+    println!(
+        "conjunction on {conjunction} ({:.3} deg): {:.3} us",
+        elongation_deg(conjunction),
+        conjunction_delay_s * 1e6
+    );
+    assert!(
+        (30e-6..300e-6).contains(&conjunction_delay_s),
+        "{conjunction_delay_s:e} s"
+    );
+
+    // It matches the delay of the geometry at the light time corrected epoch.
+    let (lt, mars_wrt_earth) = ctx
+        .light_time(
+            MARS_BARYCENTER_J2000,
+            EARTH_J2000,
+            conjunction,
+            anise::astro::LightTimeDirection::Reception,
+        )
+        .unwrap();
+    let sun_km = ctx
+        .translate(SUN_J2000, EARTH_J2000, conjunction, None)
+        .unwrap()
+        .radius_km;
+    let expected_s = shapiro_delay_s(-sun_km, mars_wrt_earth.radius_km - sun_km, SUN_GM_KM3_S2);
+    assert!((conjunction_delay_s - expected_s).abs() < 1e-8);
+    assert!(lt.to_seconds() > 1_000.0);
+
+    // At quadrature, the signal is far from the Sun and the delay is much smaller.
+    let quadrature = min_by_key(
+        days(Epoch::from_gregorian_utc_at_midnight(2023, 1, 1), 300).collect(),
+        &|epoch| (elongation_deg(epoch) - 90.0).abs(),
+    );
+    assert!((elongation_deg(quadrature) - 90.0).abs() < 1.0);
+    let quadrature_delay_s = delay_s(quadrature);
+    println!(
+        "quadrature on {quadrature}: {:.3} us",
+        quadrature_delay_s * 1e6
+    );
+    assert!(quadrature_delay_s > 0.0);
+    assert!(quadrature_delay_s < 15e-6);
+    assert!(quadrature_delay_s < conjunction_delay_s / 4.0);
+
+    // Without the Shapiro delay, the configuration is the default one.
+    let (lt_default, _) = ctx
+        .one_way_light_time_cfg(
+            MARS_BARYCENTER_J2000,
+            EARTH_J2000,
+            quadrature,
+            ab_corr,
+            AberrationConfig::default(),
+        )
+        .unwrap();
+    assert_eq!(
+        lt_default,
+        ctx.one_way_light_time(MARS_BARYCENTER_J2000, EARTH_J2000, quadrature, ab_corr)
+            .unwrap()
+            .0
+    );
+}
+
+#[test]
+fn translation_segment_frame_rotation() {
+    use anise::astro::utils::propagate_universal_variable;
+    use anise::constants::orientations::{B1950, ECLIPJ2000, J2000, J2000_TO_ECLIPJ2000_ANGLE_RAD};
+    use anise::math::cartesian::CartesianState;
+    use anise::math::rotation::r1;
+    use anise::math::Matrix3;
+    use anise::naif::spk::writer::SPKWriter;
+
+    const GM: f64 = 398600.4418;
+    let states: Vec<CartesianState> = (0..=6 * 60)
+        .map(|i| {
+            let (radius_km, velocity_km_s) = propagate_universal_variable(
+                Vector3::new(7000.0, 100.0, -50.0),
+                Vector3::new(0.1, 7.2, 1.5),
+                GM,
+                60.0 * i as f64,
+            )
+            .unwrap();
+            CartesianState {
+                radius_km,
+                velocity_km_s,
+                epoch: Epoch::from_et_seconds(60.0 * i as f64),
+                frame: EARTH_J2000,
+            }
+        })
+        .collect();
+
+    // Rotation from B1950 to J2000, from SPICE:
+    // >>> sp.pxform('B1950', 'J2000', 0.0)
+    let b1950_to_j2000 = Matrix3::new(
+        0.999_925_707_952_362_9,
+        -0.011_178_938_137_770_0,
+        -0.004_859_003_815_359_2,
+        0.011_178_938_126_427_6,
+        0.999_937_513_349_988_8,
+        -0.000_027_162_594_714_2,
+        0.004_859_003_841_454_4,
+        -0.000_027_157_926_258_5,
+        0.999_988_194_602_374_2,
+    );
+
+    // Write the same states stored in J2000, in the ecliptic J2000, and in B1950.
+    let mut writer = SPKWriter::new("ANISE segment frames");
+    for (target_id, frame_id, j2000_to_frame) in [
+        (-10000001, J2000, Matrix3::identity()),
+        (-10000002, ECLIPJ2000, r1(J2000_TO_ECLIPJ2000_ANGLE_RAD)),
+        (-10000003, B1950, b1950_to_j2000.transpose()),
+    ] {
+        let rotated: Vec<CartesianState> = states
+            .iter()
+            .map(|state| CartesianState {
+                radius_km: j2000_to_frame * state.radius_km,
+                velocity_km_s: j2000_to_frame * state.velocity_km_s,
+                ..*state
+            })
+            .collect();
+        writer
+            .add_type2_segment(&rotated, target_id, 399, frame_id, 13, 1.hours(), "TEST SC")
+            .unwrap();
+    }
+    let almanac = Almanac::default()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+
+    let reference = Frame::from_ephem_j2000(-10000001);
+    for et_s in [600.0, 4321.0, 9000.0, 17_100.0] {
+        let epoch = Epoch::from_et_seconds(et_s);
+        let expected = almanac
+            .translate_geometric(reference, EARTH_J2000, epoch)
+            .unwrap();
+        let (_, expected_acc_km_s2) = almanac
+            .translate_with_derivatives(reference, EARTH_J2000, epoch)
+            .unwrap();
+
+        for target_id in [-10000002, -10000003] {
+            let target = Frame::from_ephem_j2000(target_id);
+            let state = almanac
+                .translate_geometric(target, EARTH_J2000, epoch)
+                .unwrap();
+            assert!(
+                (state.radius_km - expected.radius_km).norm() < 1e-8,
+                "{target_id} @ {epoch}: {} km",
+                (state.radius_km - expected.radius_km).norm()
+            );
+            assert!((state.velocity_km_s - expected.velocity_km_s).norm() < 1e-11);
+
+            let (state, acc_km_s2) = almanac
+                .translate_with_derivatives(target, EARTH_J2000, epoch)
+                .unwrap();
+            assert!((state.radius_km - expected.radius_km).norm() < 1e-8);
+            assert!((acc_km_s2 - expected_acc_km_s2).norm() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn state_wrt_ssb_chain() {
+    use anise::astro::utils::propagate_universal_variable;
+    use anise::constants::frames::SSB_J2000;
+    use anise::ephemerides::EphemerisError;
+    use anise::math::cartesian::CartesianState;
+    use anise::naif::spk::writer::SPKWriter;
+
+    let ctx = Almanac::new("../data/de440s.bsp").unwrap();
+
+    for epoch in [
+        Epoch::from_gregorian_utc_at_midnight(2002, 2, 7),
+        Epoch::from_gregorian_utc_at_noon(2024, 4, 8),
+        Epoch::from_gregorian_tai_hms(2035, 11, 30, 6, 25, 13),
+    ] {
+        let earth_ssb = ctx.state_wrt_ssb(EARTH_J2000, epoch, None).unwrap();
+        let moon_ssb = ctx.state_wrt_ssb(MOON_J2000, epoch, None).unwrap();
+        let moon_earth = ctx.translate(MOON_J2000, EARTH_J2000, epoch, None).unwrap();
+
+        for state in [earth_ssb, moon_ssb] {
+            assert_eq!(state.epoch, epoch);
+            assert_eq!(state.frame.ephemeris_id, SSB_J2000.ephemeris_id);
+            assert_eq!(state.frame.orientation_id, SSB_J2000.orientation_id);
+        }
+
+        let pos_err_km = (earth_ssb.radius_km + moon_earth.radius_km - moon_ssb.radius_km).norm();
+        let vel_err_km_s =
+            (earth_ssb.velocity_km_s + moon_earth.velocity_km_s - moon_ssb.velocity_km_s).norm();
+        assert!(pos_err_km < POSITION_EPSILON_KM, "{epoch}: {pos_err_km} km");
+        assert!(
+            vel_err_km_s < VELOCITY_EPSILON_KM_S,
+            "{epoch}: {vel_err_km_s} km/s"
+        );
+
+        // Same as translating with the SSB as observer
+        let expected = ctx.translate(MOON_J2000, SSB_J2000, epoch, None).unwrap();
+        assert!((moon_ssb.radius_km - expected.radius_km).norm() < POSITION_EPSILON_KM);
+        assert!((moon_ssb.velocity_km_s - expected.velocity_km_s).norm() < VELOCITY_EPSILON_KM_S);
+
+        // The aberration corrections are those of an observer at the SSB.
+        let moon_ssb_lt = ctx
+            .state_wrt_ssb(MOON_J2000, epoch, Aberration::LT)
+            .unwrap();
+        let expected = ctx
+            .translate(MOON_J2000, SSB_J2000, epoch, Aberration::LT)
+            .unwrap();
+        assert_eq!(moon_ssb_lt.radius_km, expected.radius_km);
+        assert_eq!(moon_ssb_lt.velocity_km_s, expected.velocity_km_s);
+        assert!((moon_ssb_lt.radius_km - moon_ssb.radius_km).norm() > 1.0);
+    }
+
+    // The SSB itself
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2002, 2, 7);
+    let ssb = ctx.state_wrt_ssb(SSB_J2000, epoch, None).unwrap();
+    assert_eq!(ssb.radius_km, Vector3::zeros());
+    assert_eq!(ssb.velocity_km_s, Vector3::zeros());
+
+    // A spacecraft around the Earth without any planetary ephemeris stops at the Earth.
+    let states: Vec<CartesianState> = (0..=6 * 60)
+        .map(|i| {
+            let (radius_km, velocity_km_s) = propagate_universal_variable(
+                Vector3::new(7000.0, 100.0, -50.0),
+                Vector3::new(0.1, 7.2, 1.5),
+                398600.4418,
+                60.0 * i as f64,
+            )
+            .unwrap();
+            CartesianState {
+                radius_km,
+                velocity_km_s,
+                epoch: Epoch::from_et_seconds(60.0 * i as f64),
+                frame: EARTH_J2000,
+            }
+        })
+        .collect();
+    let mut writer = SPKWriter::new("ANISE SSB chain");
+    writer
+        .add_type2_segment(&states, -10000001, 399, 1, 13, 1.hours(), "TEST SC")
+        .unwrap();
+    let sc_only = Almanac::default()
+        .with_spk(writer.build().unwrap())
+        .unwrap();
+
+    let spacecraft = Frame::from_ephem_j2000(-10000001);
+    let err = sc_only
+        .state_wrt_ssb(spacecraft, Epoch::from_et_seconds(600.0), None)
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            EphemerisError::ChainToSsb {
+                target: -10000001,
+                root: 399,
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    // With the planetary ephemeris, the chain reaches the SSB through the Earth.
+    let with_planets = sc_only.load("../data/de440s.bsp").unwrap();
+    let epoch = Epoch::from_et_seconds(600.0);
+    let sc_ssb = with_planets.state_wrt_ssb(spacecraft, epoch, None).unwrap();
+    let earth_ssb = with_planets
+        .state_wrt_ssb(EARTH_J2000, epoch, None)
+        .unwrap();
+    let sc_earth = with_planets
+        .translate(spacecraft, EARTH_J2000, epoch, None)
+        .unwrap();
+    assert!(
+        (earth_ssb.radius_km + sc_earth.radius_km - sc_ssb.radius_km).norm() < POSITION_EPSILON_KM
+    );
+}
@@ -8,8 +8,10 @@
  * Documentation: https://nyxspace.com/
  */
 
+mod type01_modified_difference;
 mod type02_chebyshev_jpl_de;
 mod type03_chebyshev_jpl_de;
+mod type05_discrete_states;
 mod type09_lagrange;
 mod type13_hermite;
 
@@ -0,0 +1,50 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use anise::naif::daf::DafDataType;
+use anise::prelude::*;
+
+use super::{compare::*, validate::Validation};
+
+/// Validates the Type 1 evaluator with the JPL small body ephemeris of comet C/2013 A1 (Siding Spring) against SPICE.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_mda_type01_siding_spring() {
+    let path = "../data/siding_spring_8-19-14.bsp";
+
+    // Ensure that this kernel only has Type 1 segments, so that the comparison validates the Type 1 evaluator.
+    let spk = SPK::load(path).unwrap();
+    for summary in spk.data_summaries().unwrap() {
+        if summary.is_empty() {
+            break;
+        }
+        assert_eq!(
+            summary.data_type().unwrap(),
+            DafDataType::Type1ModifiedDifferenceArray
+        );
+    }
+
+    let file_name = "spk-type01-validation-siding-spring".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    // Sub-meter accuracy, in km and km/s
+    let validator = Validation {
+        file_name,
+        max_q75_err: 1e-6,
+        max_q99_err: 1e-5,
+        max_abs_err: 1e-4,
+    };
+
+    validator.validate();
+}
@@ -0,0 +1,149 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use anise::constants::frames::EARTH_J2000;
+use anise::math::Vector3;
+use anise::naif::daf::DafDataType;
+use anise::prelude::*;
+use spice::cstr;
+
+const SPACECRAFT: i32 = -1005;
+const GM_KM3_S2: f64 = 398_600.435_436;
+
+/// Writes a Type 5 kernel with SPICE from the states of a J2 propagation, so that the two-body propagation between the states differs
+/// from the stored states, and validates the Type 5 evaluator against spkezr, both between and outside of the stored states.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_discrete_states_type05() {
+    let path = "../target/spk-type05-validation.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+    let orbit = Orbit::keplerian(
+        8_191.93,
+        0.024,
+        12.85,
+        306.614,
+        314.19,
+        99.887_7,
+        epoch,
+        EARTH_J2000.with_mu_km3_s2(GM_KM3_S2),
+    );
+
+    // One state every ten minutes for a day.
+    let epochs_et_s: Vec<f64> = (0..145)
+        .map(|i| (epoch + (10 * i).minutes()).to_et_seconds())
+        .collect();
+    let states: Vec<[f64; 6]> = epochs_et_s
+        .iter()
+        .map(|et_s| {
+            let state = orbit
+                .propagate_j2(
+                    Epoch::from_et_seconds(*et_s) - epoch,
+                    1.082_63e-3,
+                    6_378.136_3,
+                )
+                .unwrap();
+            let mut data = [0.0; 6];
+            data.copy_from_slice(state.to_cartesian_pos_vel().as_slice());
+            data
+        })
+        .collect();
+
+    // The segment extends half an hour on either side of the stored states.
+    let first_et_s = epochs_et_s[0] - 1800.0;
+    let last_et_s = epochs_et_s[epochs_et_s.len() - 1] + 1800.0;
+
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 5 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw05_c(
+            handle,
+            SPACECRAFT,
+            399,
+            cstr!("J2000"),
+            first_et_s,
+            last_et_s,
+            cstr!("ANISE Type 5 validation"),
+            GM_KM3_S2,
+            states.len() as _,
+            states.as_ptr(),
+            epochs_et_s.as_ptr(),
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    spice::furnsh(path);
+
+    let spk = SPK::load(path).unwrap();
+    assert_eq!(
+        spk.data_summaries().unwrap()[0].data_type().unwrap(),
+        DafDataType::Type5DiscreteStates
+    );
+    let almanac = Almanac::default().with_spk(spk).unwrap();
+    let sc_frame = Frame::from_ephem_j2000(SPACECRAFT);
+
+    // At the epochs of the stored states, the stored states are returned.
+    for (et_s, stored) in epochs_et_s.iter().zip(&states) {
+        let state = almanac
+            .translate(sc_frame, EARTH_J2000, Epoch::from_et_seconds(*et_s), None)
+            .unwrap();
+        assert_eq!(state.to_cartesian_pos_vel().as_slice(), stored);
+    }
+
+    // Between and outside of the stored states, the states are propagated and blended as SPICE does.
+    let mut max_pos_err_km = 0.0_f64;
+    let mut max_vel_err_km_s = 0.0_f64;
+    for epoch in TimeSeries::inclusive(
+        Epoch::from_et_seconds(first_et_s),
+        Epoch::from_et_seconds(last_et_s),
+        17.seconds(),
+    ) {
+        let state = almanac
+            .translate(sc_frame, EARTH_J2000, epoch, None)
+            .unwrap();
+
+        let (spice_state, _) = spice::spkezr(
+            &format!("{SPACECRAFT}"),
+            epoch.to_et_seconds(),
+            "J2000",
+            "NONE",
+            "EARTH",
+        );
+
+        let pos_err_km =
+            (state.radius_km - Vector3::new(spice_state[0], spice_state[1], spice_state[2])).norm();
+        let vel_err_km_s = (state.velocity_km_s
+            - Vector3::new(spice_state[3], spice_state[4], spice_state[5]))
+        .norm();
+
+        max_pos_err_km = max_pos_err_km.max(pos_err_km);
+        max_vel_err_km_s = max_vel_err_km_s.max(vel_err_km_s);
+    }
+
+    println!("max errors: {max_pos_err_km:e} km\t{max_vel_err_km_s:e} km/s");
+
+    // Both propagate with different implementations of the two-body problem: allow up to one millimeter and one micrometer per second.
+    assert!(
+        max_pos_err_km < 1e-6,
+        "position error of {max_pos_err_km} km"
+    );
+    assert!(
+        max_vel_err_km_s < 1e-9,
+        "velocity error of {max_vel_err_km_s} km/s"
+    );
+
+    spice::unload(path);
+}
@@ -78,7 +78,12 @@ fn test_spk_load_bytes() {
     assert_eq!(de421.daf_summary().unwrap().next_record(), 0);
     assert_eq!(de421.daf_summary().unwrap().prev_record(), 0);
 
-    println!("{}", de421.comments().unwrap().unwrap());
+    let comments = de421.comments().unwrap().unwrap();
+    println!("{comments}");
+    // The control characters of the comment area are decoded into new lines.
+    assert!(comments.to_lowercase().contains("de421"));
+    assert!(comments.lines().count() > 10);
+    assert!(!comments.contains(['\u{0}', '\u{4}']));
 
     // From Python jplephem, an inspection of the coefficients of the DE421 file shows the number of segments we should have.
     // // So let's add it here as a test.
@@ -262,3 +267,38 @@ fn test_spk_truncate_cheby() {
         "summary 301 not removed"
     );
 }
+
+#[test]
+fn test_spk_segment_states_iter() {
+    let _ = pretty_env_logger::try_init();
+
+    // Type 13 and Type 9 segments store their nodes, so these span the whole coverage of the segment.
+    for path in ["../data/gmat-hermite.bsp", "../data/gmat-lagrange.bsp"] {
+        let spk = SPK::load(path).unwrap();
+        let mut num_segments = 0;
+        for segment in spk.segments().unwrap() {
+            num_segments += 1;
+            let summary = segment.summary;
+            let states: Vec<_> = segment.states_iter().unwrap().collect();
+            assert!(states.len() > 1, "{path}: {summary}");
+
+            let (first_epoch, first_state) = states.first().unwrap();
+            let (last_epoch, _) = states.last().unwrap();
+            assert_eq!(
+                *first_epoch,
+                Epoch::from_et_seconds(summary.start_epoch_et_s),
+                "{path}: {summary}"
+            );
+            assert_eq!(
+                *last_epoch,
+                Epoch::from_et_seconds(summary.end_epoch_et_s),
+                "{path}: {summary}"
+            );
+
+            // The states are of the target with respect to the center of the segment, in its frame.
+            assert_eq!(first_state.frame, summary.center_frame());
+            assert!(states.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+        assert!(num_segments > 0, "{path}");
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use anise::constants::frames::{
-    EARTH_ITRF93, EARTH_J2000, EME2000, IAU_JUPITER_FRAME, IAU_MOON_FRAME,
+    EARTH_ITRF93, EARTH_J2000, EME2000, IAU_EARTH_FRAME, IAU_JUPITER_FRAME, IAU_MOON_FRAME,
     JUPITER_BARYCENTER_J2000, MOON_J2000, MOON_ME_DE440_ME421_FRAME, MOON_PA_DE421_FRAME,
     MOON_PA_DE440_FRAME,
 };
@@ -170,6 +170,82 @@ fn test_itrf93_to_j2k() {
     );
 }
 
+#[test]
+fn test_itrf93_station_requires_bpc() {
+    use anise::orientations::OrientationError;
+    use core::str::FromStr;
+
+    let epoch = Epoch::from_str("2019-03-01T04:02:51.0 ET").unwrap();
+    // Approximate ECEF position of the DSS-65 antenna in Madrid, in km.
+    let station_km = Vector3::new(4849.336, -360.428, 4114.751);
+
+    // Only the low precision IAU_EARTH model is loaded: ITRF93 must not silently use it.
+    let almanac = Almanac::new("../data/pck08.pca").unwrap();
+    assert!(almanac.rotate(IAU_EARTH_FRAME, EARTH_J2000, epoch).is_ok());
+    match almanac.rotate(EARTH_ITRF93, EARTH_J2000, epoch) {
+        Err(OrientationError::HighPrecisionUnavailable { id, .. }) => assert_eq!(id, ITRF93),
+        other => panic!("expected high precision error, got {other:?}"),
+    }
+    assert!(almanac.rotate(EARTH_J2000, EARTH_ITRF93, epoch).is_err());
+
+    // With the high precision BPC, the rotation uses the BPC Euler angles, but only within its coverage.
+    let almanac = almanac
+        .with_bpc(BPC::load("../data/earth_latest_high_prec.bpc").unwrap())
+        .unwrap();
+    match almanac.rotate(
+        EARTH_ITRF93,
+        EARTH_J2000,
+        Epoch::from_gregorian_utc_at_midnight(1990, 1, 1),
+    ) {
+        Err(OrientationError::HighPrecisionUnavailable { id, .. }) => assert_eq!(id, ITRF93),
+        other => panic!("expected high precision error, got {other:?}"),
+    }
+
+    let dcm = almanac.rotate(EARTH_ITRF93, EARTH_J2000, epoch).unwrap();
+    assert_eq!(dcm.from, ITRF93);
+    assert_eq!(dcm.to, J2000);
+
+    // sxform_c("ITRF93", "J2000") from SPICE with the same BPC.
+    let spice_rot_mat = Matrix3::new(
+        -0.7787074378266214,
+        0.6273845404742724,
+        0.0018342975179237739,
+        -0.6273856264104672,
+        -0.7787087230243394,
+        -0.000021432407757815408,
+        0.0014149371165367297,
+        -0.0011675014726372779,
+        0.9999983174452183,
+    );
+    let spice_rot_mat_dt = Matrix3::new(
+        0.000045749603091397784,
+        0.00005678424274353827,
+        0.00000000008998156330541006,
+        -0.000056784336444384685,
+        0.00004574968205088016,
+        0.00000000008643799681544929,
+        -0.0000000850112519852614,
+        -0.00000010316798647710046,
+        -0.00000000000016320065843054112,
+    );
+
+    let pos_err_km = (dcm.rot_mat * station_km - spice_rot_mat * station_km).norm();
+    let vel_err_km_s =
+        (dcm.rot_mat_dt.unwrap() * station_km - spice_rot_mat_dt * station_km).norm();
+    println!(
+        "station error: {:.3} mm\t{:.3e} mm/s",
+        pos_err_km * 1e6,
+        vel_err_km_s * 1e6
+    );
+    assert!(pos_err_km < 2e-5, "position error: {pos_err_km:e} km");
+    assert!(vel_err_km_s < 2e-9, "velocity error: {vel_err_km_s:e} km/s");
+
+    // The IAU_EARTH model differs from ITRF93 at the station by far more than that.
+    let iau_dcm = almanac.rotate(IAU_EARTH_FRAME, EARTH_J2000, epoch).unwrap();
+    let iau_diff_km = (iau_dcm.rot_mat * station_km - dcm.rot_mat * station_km).norm();
+    assert!(iau_diff_km > 1e-3, "{iau_diff_km} km");
+}
+
 #[test]
 fn test_j2k_to_itrf93() {
     use core::str::FromStr;
@@ -320,6 +396,171 @@ fn regression_test_issue_112_test_iau_jupiter() {
     );
 }
 
+/// Reproduces the IAU_MARS and IAU_PHOBOS orientations of pck00011, including their nutation and precession terms, by evaluating the
+/// IAU model directly. The fifth phase angle of the Mars system and the prime meridian of Phobos are quadratic, so the epochs are far
+/// from J2000 for their T^2 and d^2 terms to be significant.
+#[test]
+fn test_iau_mars_from_tpc() {
+    use anise::constants::frames::{IAU_MARS_FRAME, MARS_J2000};
+    use anise::constants::orientations::IAU_MARS;
+    use anise::math::rotation::{r1, r3};
+    use core::str::FromStr;
+
+    let planetary_data = convert_tpc("../data/pck00011.tpc", "../data/gm_de440.tpc").unwrap();
+
+    // The Mars system uses quadratic phase angles, which must be kept.
+    let mars_system = planetary_data.get_by_id(4).unwrap();
+    assert_eq!(mars_system.num_nut_prec_angles, 26);
+    assert_eq!(mars_system.nut_prec_angles[4].accel_deg, 12.711923222);
+
+    let almanac = Almanac::default().with_planetary_data(planetary_data);
+
+    // Phase angles of the Mars system used by Phobos and Mars: (offset, rate, acceleration) in degrees, degrees per century, and
+    // degrees per century squared.
+    let phobos_angles = [
+        (190.72646643, 15917.10818695, 0.0),
+        (21.46892470, 31834.27934054, 0.0),
+        (332.86082793, 19139.89694742, 0.0),
+        (394.93256437, 38280.79631835, 0.0),
+        (189.63271560, 41215158.18420050, 12.711923222),
+    ];
+    let mars_ra_angles = [
+        (198.991226, 19139.4819985, 0.0),
+        (226.292679, 38280.8511281, 0.0),
+        (249.663391, 57420.7251593, 0.0),
+        (266.183510, 76560.6367950, 0.0),
+        (79.398797, 0.5042615, 0.0),
+    ];
+    let mars_dec_angles = [
+        (122.433576, 19139.9407476, 0.0),
+        (43.058401, 38280.8753272, 0.0),
+        (57.663379, 57420.7517205, 0.0),
+        (79.476401, 76560.6495004, 0.0),
+        (166.325722, 0.5042615, 0.0),
+    ];
+    let mars_pm_angles = [
+        (129.071773, 19140.0328244, 0.0),
+        (36.352167, 38281.0473591, 0.0),
+        (56.668646, 57420.9295360, 0.0),
+        (67.364003, 76560.2552215, 0.0),
+        (104.792680, 95700.4387578, 0.0),
+        (95.391654, 0.5042615, 0.0),
+    ];
+
+    // Angle of the rotation between both DCMs, in arcseconds
+    let err_arcsec = |dcm: &Matrix3, expected: &Matrix3| {
+        (((dcm * expected.transpose()).trace() - 1.0) / 2.0)
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+            * 3600.0
+    };
+
+    for epoch_str in [
+        "1900-01-01 00:00:00 TDB",
+        "2060-01-01 00:00:00 TDB",
+        "2099-12-01 00:00:00 TDB",
+    ] {
+        let epoch = Epoch::from_str(epoch_str).unwrap();
+        let t = epoch.to_tdb_duration().to_unit(Unit::Century);
+        let d = epoch.to_tdb_duration().to_unit(Unit::Day);
+
+        // Sum of the nutation and precession terms: the coefficient of each (offset, rate, acceleration) phase angle, in degrees.
+        let nut_prec = |coeffs: &[f64], angles: &[(f64, f64, f64)], trig: fn(f64) -> f64| -> f64 {
+            coeffs
+                .iter()
+                .zip(angles)
+                .map(|(coeff, (offset_deg, rate_deg, accel_deg))| {
+                    coeff * trig((offset_deg + rate_deg * t + accel_deg * t.powi(2)).to_radians())
+                })
+                .sum()
+        };
+        let iau_dcm = |ra_deg: f64, dec_deg: f64, w_deg: f64| {
+            r3(w_deg.to_radians())
+                * r1((90.0 - dec_deg).to_radians())
+                * r3((90.0 + ra_deg).to_radians())
+        };
+
+        let ra_deg = 317.269202 - 0.10927547 * t
+            + nut_prec(
+                &[0.000068, 0.000238, 0.000052, 0.000009, 0.419057],
+                &mars_ra_angles,
+                f64::sin,
+            );
+        let dec_deg = 54.432516 - 0.05827105 * t
+            + nut_prec(
+                &[0.000051, 0.000141, 0.000031, 0.000005, 1.591274],
+                &mars_dec_angles,
+                f64::cos,
+            );
+        let w_deg = 176.049863
+            + 350.891982443297 * d
+            + nut_prec(
+                &[0.000145, 0.000157, 0.000040, 0.000001, 0.000001, 0.584542],
+                &mars_pm_angles,
+                f64::sin,
+            );
+
+        let dcm = almanac.rotate(MARS_J2000, IAU_MARS_FRAME, epoch).unwrap();
+        assert_eq!(dcm.from, J2000);
+        assert_eq!(dcm.to, IAU_MARS);
+
+        let err = err_arcsec(&dcm.rot_mat, &iau_dcm(ra_deg, dec_deg, w_deg));
+        assert!(err < 1.0, "{epoch_str}: IAU_MARS error of {err} arcsec");
+
+        // Phobos is the only body whose orientation uses the quadratic phase angle of the Mars system.
+        let phobos_pm = |angles: &[(f64, f64, f64)], accel_deg_day2: f64| {
+            35.18774440
+                + 1128.84475928 * d
+                + accel_deg_day2 * d.powi(2)
+                + nut_prec(
+                    &[1.42421769, -0.02273783, 0.00410711, 0.00631964, -1.143],
+                    angles,
+                    f64::sin,
+                )
+        };
+        let ra_deg = 317.67071657 - 0.10844326 * t
+            + nut_prec(
+                &[-1.78428399, 0.02212824, -0.01028251, -0.00475595],
+                &phobos_angles,
+                f64::sin,
+            );
+        let dec_deg = 52.88627266 - 0.06134706 * t
+            + nut_prec(
+                &[-1.07516537, 0.00668626, -0.00648740, 0.00281576],
+                &phobos_angles,
+                f64::cos,
+            );
+        let w_deg = phobos_pm(&phobos_angles, 9.536137031212154e-09);
+
+        let dcm = almanac
+            .rotate(Frame::new(401, J2000), Frame::new(401, 401), epoch)
+            .unwrap();
+        assert_eq!(dcm.to, 401);
+
+        let err = err_arcsec(&dcm.rot_mat, &iau_dcm(ra_deg, dec_deg, w_deg));
+        assert!(err < 1.0, "{epoch_str}: IAU_PHOBOS error of {err} arcsec");
+
+        // Ensure that this test would catch the omission of either quadratic term at these epochs: the quadratic phase angle term
+        // changes the prime meridian by 261 to 845 arcsec, and the quadratic prime meridian term by 4.6 to 12.7 deg.
+        let mut linear_angles = phobos_angles;
+        linear_angles[4].2 = 0.0;
+        for (name, w_deg) in [
+            (
+                "phase angle",
+                phobos_pm(&linear_angles, 9.536137031212154e-09),
+            ),
+            ("prime meridian", phobos_pm(&phobos_angles, 0.0)),
+        ] {
+            let err = err_arcsec(&dcm.rot_mat, &iau_dcm(ra_deg, dec_deg, w_deg));
+            assert!(
+                err > 10.0,
+                "{epoch_str}: IAU_PHOBOS without the quadratic {name} term is only {err} arcsec off"
+            );
+        }
+    }
+}
+
 #[test]
 fn regression_test_issue_357_test_moon_me_j2k() {
     use core::str::FromStr;
@@ -542,3 +783,246 @@ fn regression_test_issue_431_test() {
 
     assert_eq!(expected, computed);
 }
+
+#[test]
+fn rotation_error_variants() {
+    use anise::constants::celestial_objects::EARTH;
+    use anise::orientations::OrientationError;
+
+    let epoch = Epoch::from_gregorian_utc_at_noon(2025, 1, 1);
+    let almanac = Almanac::new("../data/pck08.pca").unwrap();
+
+    // No loaded data defines this orientation.
+    let unknown = Frame::new(EARTH, 123_456);
+    let err = almanac.rotate(unknown, IAU_EARTH_FRAME, epoch).unwrap_err();
+    println!("{err}");
+    assert_eq!(
+        err,
+        OrientationError::NoConnectingPath {
+            from: unknown.into(),
+            to: IAU_EARTH_FRAME.into(),
+            id: 123_456
+        }
+    );
+
+    // The Moon principal axes are loaded, but not at this epoch.
+    let almanac = almanac.load("../data/moon_pa_de440_200625.bpc").unwrap();
+    assert!(almanac
+        .rotate(MOON_PA_DE440_FRAME, MOON_J2000, epoch)
+        .is_ok());
+    let too_early = Epoch::from_gregorian_utc_at_midnight(1400, 1, 1);
+    let err = almanac
+        .rotate(MOON_PA_DE440_FRAME, MOON_J2000, too_early)
+        .unwrap_err();
+    println!("{err}");
+    match err {
+        OrientationError::EpochOutOfCoverage {
+            id,
+            requested,
+            available,
+        } => {
+            assert_eq!(id, MOON_PA_DE440);
+            assert_eq!(requested, too_early);
+            assert_eq!(almanac.bpc_domain(MOON_PA_DE440).unwrap(), available);
+        }
+        _ => panic!("expected EpochOutOfCoverage, got {err:?}"),
+    }
+
+    // High precision orientations report why the BPC could not be used.
+    let almanac = almanac.load("../data/earth_latest_high_prec.bpc").unwrap();
+    match almanac.rotate(
+        EARTH_ITRF93,
+        EARTH_J2000,
+        Epoch::from_gregorian_utc_at_midnight(1990, 1, 1),
+    ) {
+        Err(OrientationError::HighPrecisionUnavailable { id, source, .. }) => {
+            assert_eq!(id, ITRF93);
+            assert!(matches!(
+                *source,
+                OrientationError::EpochOutOfCoverage { id: ITRF93, .. }
+            ));
+        }
+        other => panic!("expected high precision error, got {other:?}"),
+    }
+}
+
+#[test]
+fn estimated_rate_of_constant_bpc_records() {
+    use anise::math::rotation::{r1, r3};
+    use anise::naif::bpc::writer::BPCWriter;
+
+    const FRAME_ID: i32 = 1_234_567;
+    const SPIN_RAD_S: f64 = 7.292115e-5;
+
+    // A frame spinning about its tilted pole, sampled every minute for a day.
+    let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+    let rotations: Vec<(Epoch, DCM)> = (0..=1440)
+        .map(|minute| {
+            let dt_s = minute as f64 * 60.0;
+            let mut dcm = DCM::identity(J2000, FRAME_ID);
+            dcm.rot_mat = r3(1.0 + SPIN_RAD_S * dt_s) * r1(0.4) * r3(0.3);
+            (start + Unit::Second * dt_s, dcm)
+        })
+        .collect();
+
+    let body_fixed = Frame::new(399, FRAME_ID);
+    let epochs: Vec<Epoch> = (1..48)
+        .map(|half_hour| start + Unit::Minute * (30 * half_hour + 7))
+        .collect();
+
+    // Records of degree zero only store the angles, so the rate is estimated from the neighboring records.
+    let mut writer = BPCWriter::new("constant records");
+    writer
+        .add_type2_segment(&rotations, FRAME_ID, J2000, 0, Unit::Minute * 10, "SPIN")
+        .unwrap();
+    let almanac = Almanac::default()
+        .with_bpc(writer.build().unwrap())
+        .unwrap();
+
+    for epoch in &epochs {
+        let (dcm, rate_estimated) = almanac
+            .rotation_to_parent_with_rate_flag(body_fixed, *epoch)
+            .unwrap();
+        assert!(rate_estimated);
+        let omega = dcm.angular_velocity().unwrap();
+        assert!(
+            (omega.norm() - SPIN_RAD_S).abs() < 1e-6 * SPIN_RAD_S,
+            "{epoch}: {omega}"
+        );
+        // The flag is kept through the rotations composed with it.
+        let (_, rate_estimated) = almanac
+            .rotate_with_rate_flag(body_fixed, EARTH_J2000, *epoch)
+            .unwrap();
+        assert!(rate_estimated);
+        let (_, rate_estimated) = almanac
+            .rotate_with_rate_flag(EARTH_J2000, body_fixed, *epoch)
+            .unwrap();
+        assert!(rate_estimated);
+    }
+
+    // Polynomial records define the rate, which is not flagged.
+    let mut writer = BPCWriter::new("polynomial records");
+    writer
+        .add_type2_segment(&rotations, FRAME_ID, J2000, 5, Unit::Hour * 2, "SPIN")
+        .unwrap();
+    let almanac = Almanac::default()
+        .with_bpc(writer.build().unwrap())
+        .unwrap();
+
+    for epoch in &epochs {
+        let (dcm, rate_estimated) = almanac
+            .rotation_to_parent_with_rate_flag(body_fixed, *epoch)
+            .unwrap();
+        assert!(!rate_estimated);
+        let omega = dcm.angular_velocity().unwrap();
+        assert!(
+            (omega.norm() - SPIN_RAD_S).abs() < 1e-9 * SPIN_RAD_S,
+            "{epoch}: {omega}"
+        );
+    }
+}
+
+#[test]
+fn angular_acceleration_of_bpc_records() {
+    use anise::math::rotation::{r1, r3};
+    use anise::naif::bpc::writer::BPCWriter;
+    use anise::orientations::OrientationError;
+
+    const FRAME_ID: i32 = 1_234_567;
+    const SPIN_RAD_S: f64 = 7.292115e-5;
+    const SPIN_UP_RAD_S2: f64 = 1e-9;
+
+    let start = Epoch::from_gregorian_utc_at_midnight(2025, 1, 1);
+    let body_fixed = Frame::new(399, FRAME_ID);
+    let epochs: Vec<Epoch> = (1..48)
+        .map(|half_hour| start + Unit::Minute * (30 * half_hour + 7))
+        .collect();
+
+    let build = |spin_up_rad_s2: f64, degree: usize| {
+        // A frame spinning about its tilted pole, sampled every minute for a day.
+        let rotations: Vec<(Epoch, DCM)> = (0..=1440)
+            .map(|minute| {
+                let dt_s = minute as f64 * 60.0;
+                let mut dcm = DCM::identity(J2000, FRAME_ID);
+                dcm.rot_mat = r3(1.0 + SPIN_RAD_S * dt_s + 0.5 * spin_up_rad_s2 * dt_s.powi(2))
+                    * r1(0.4)
+                    * r3(0.3);
+                (start + Unit::Second * dt_s, dcm)
+            })
+            .collect();
+
+        let mut writer = BPCWriter::new("spin");
+        writer
+            .add_type2_segment(&rotations, FRAME_ID, J2000, degree, Unit::Hour * 2, "SPIN")
+            .unwrap();
+        Almanac::default()
+            .with_bpc(writer.build().unwrap())
+            .unwrap()
+    };
+
+    // A uniform spin has no angular acceleration.
+    let almanac = build(0.0, 5);
+    for epoch in &epochs {
+        let (dcm, rot_mat_ddt) = almanac
+            .rotation_to_parent_with_acceleration(body_fixed, *epoch)
+            .unwrap();
+        let alpha = dcm.angular_acceleration(&rot_mat_ddt).unwrap();
+        assert!(alpha.norm() < 1e-15, "{epoch}: {alpha}");
+        // The second derivative does not change the rotation itself.
+        assert_eq!(dcm, almanac.rotation_to_parent(body_fixed, *epoch).unwrap());
+    }
+
+    // A constant spin up about the pole has a constant angular acceleration.
+    let almanac = build(SPIN_UP_RAD_S2, 5);
+    for epoch in &epochs {
+        let (dcm, rot_mat_ddt) = almanac
+            .rotate_with_derivatives(EARTH_J2000, body_fixed, *epoch)
+            .unwrap();
+        let alpha = dcm.angular_acceleration(&rot_mat_ddt).unwrap();
+        assert!(
+            (alpha.norm() - SPIN_UP_RAD_S2).abs() < 1e-6 * SPIN_UP_RAD_S2,
+            "{epoch}: {alpha}"
+        );
+        // And the angular velocity about the same axis.
+        let omega = dcm.angular_velocity().unwrap();
+        assert!(omega.normalize().dot(&alpha.normalize()) > 1.0 - 1e-9);
+
+        // A point at rest in the inertial frame has the fictitious accelerations of the rotating frame, matching
+        // the finite differences of its velocity in that frame.
+        let state = CartesianState::new(7000.0, 1000.0, -500.0, 0.0, 0.0, 0.0, *epoch, EARTH_J2000);
+        let (in_body, acc_km_s2) = almanac
+            .transform_state_with_acceleration(state, Vector3::zeros(), body_fixed, *epoch)
+            .unwrap();
+        assert_eq!(
+            in_body,
+            almanac.transform_state(state, body_fixed, *epoch).unwrap()
+        );
+
+        let h = Unit::Second * 1.0;
+        let vel_km_s = |epoch: Epoch| {
+            almanac
+                .transform_state(CartesianState { epoch, ..state }, body_fixed, epoch)
+                .unwrap()
+                .velocity_km_s
+        };
+        let fd_acc_km_s2 = (vel_km_s(*epoch + h) - vel_km_s(*epoch - h)) / 2.0;
+        assert!(
+            (acc_km_s2 - fd_acc_km_s2).norm() < 1e-6 * fd_acc_km_s2.norm(),
+            "{epoch}: {acc_km_s2} vs {fd_acc_km_s2}"
+        );
+
+        // Transforming back recovers the zero inertial acceleration.
+        let (back, back_acc_km_s2) = almanac
+            .transform_state_with_acceleration(in_body, acc_km_s2, EARTH_J2000, *epoch)
+            .unwrap();
+        assert!((back.radius_km - state.radius_km).norm() < 1e-9);
+        assert!(back_acc_km_s2.norm() < 1e-12, "{back_acc_km_s2}");
+    }
+
+    // Constant records do not define the second derivative of their angles.
+    let almanac = build(0.0, 0);
+    assert!(matches!(
+        almanac.rotation_to_parent_with_acceleration(body_fixed, epochs[0]),
+        Err(OrientationError::AccelerationUnavailable { id: FRAME_ID, .. })
+    ));
+}